@@ -1,3 +1,8 @@
+pub mod bfe;
+pub mod i32;
 pub mod u128;
+pub mod u16;
 pub mod u32;
+pub mod u32s_n;
 pub mod u64;
+pub mod xfe;
@@ -0,0 +1,2 @@
+pub mod bfe_pow;
+pub mod sum_list;
@@ -0,0 +1,191 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::traits::ModPowU32;
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Raise a base field element to a `u32` power using square-and-multiply.
+pub struct BfePow;
+
+impl BasicSnippet for BfePow {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![
+            (DataType::BFE, "base".to_string()),
+            (DataType::U32, "exponent".to_string()),
+        ]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::BFE, "power".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_bfe_pow".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+
+        triton_asm!(
+            // BEFORE: _ base exponent
+            // AFTER: _ (base^exponent)
+            {entrypoint}:
+                push 1
+                // _ bpow2 i acc
+
+                call {entrypoint}_loop
+                // _ bpow2 0 acc
+
+                swap 2
+                pop
+                pop
+                return
+
+            // INVARIANT: _ bpow2 i acc
+            {entrypoint}_loop:
+                dup 1
+                push 0
+                eq
+                skiz
+                    return
+
+                push 2
+                dup 2
+                div
+                // _ bpow2 i acc (i >> 1) (i & 1)
+
+                skiz
+                    call {entrypoint}_mul_acc_with_bpow2
+                // _ bpow2 i acc (i >> 1)
+
+                swap 2
+                pop
+                // _ bpow2 (i >> 1) acc
+
+                swap 2
+                dup 0
+                mul
+                swap 2
+                // _ (bpow2 * bpow2) (i >> 1) acc
+
+                recurse
+
+            // BEFORE: _ bpow2 i acc (i >> 1)
+            // AFTER: _ bpow2 i (acc * bpow2) (i >> 1)
+            {entrypoint}_mul_acc_with_bpow2:
+                swap 1
+                dup 3
+                mul
+                swap 1
+                return
+        )
+    }
+}
+
+impl Closure for BfePow {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let exponent: u32 = stack.pop().unwrap().try_into().unwrap();
+        let base = stack.pop().unwrap();
+        stack.push(base.mod_pow_u32(exponent));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let base: BFieldElement = rng.gen();
+        let exponent: u32 = match bench_case {
+            Some(BenchmarkCase::CommonCase) => 1 << 10,
+            Some(BenchmarkCase::WorstCase) => u32::MAX,
+            Some(BenchmarkCase::Custom(_)) | None => rng.gen(),
+        };
+
+        [
+            get_init_tvm_stack(),
+            vec![base, BFieldElement::new(exponent as u64)],
+        ]
+        .concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::One;
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn bfe_pow_pbt() {
+        ShadowedClosure::new(BfePow).test()
+    }
+
+    fn prop_bfe_pow(base: BFieldElement, exponent: u32, expected: BFieldElement) {
+        let init_stack = [
+            get_init_tvm_stack(),
+            vec![base, BFieldElement::new(exponent as u64)],
+        ]
+        .concat();
+        let expected_final_stack = [get_init_tvm_stack(), vec![expected]].concat();
+
+        test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(BfePow),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            Some(&expected_final_stack),
+        );
+    }
+
+    #[test]
+    fn bfe_pow_zero_exponent_unit_test() {
+        prop_bfe_pow(random(), 0, BFieldElement::one());
+        prop_bfe_pow(BFieldElement::new(0), 0, BFieldElement::one());
+    }
+
+    #[test]
+    fn bfe_pow_one_exponent_unit_test() {
+        let base: BFieldElement = random();
+        prop_bfe_pow(base, 1, base);
+    }
+
+    #[test]
+    fn bfe_pow_large_exponent_unit_test() {
+        let base: BFieldElement = random();
+        for exponent in [2, 3, 0b1011_0110, 1 << 31, u32::MAX] {
+            prop_bfe_pow(base, exponent, base.mod_pow(exponent as u64));
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn bfe_pow_bench() {
+        ShadowedClosure::new(BfePow).bench()
+    }
+}
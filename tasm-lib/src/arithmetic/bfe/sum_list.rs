@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_insert,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Sum the elements of a safe list of `BFE`s. The sum of the empty list is zero.
+#[derive(Clone, Debug)]
+pub struct BfeSumList;
+
+impl BfeSumList {
+    fn prepare_state(&self, elements: Vec<BFieldElement>) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64 + 1);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, elements.len() as u32, elements, &mut memory);
+
+        let stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    fn random_state(&self, length: usize) -> ExecutionState {
+        self.prepare_state((0..length).map(|_| random()).collect_vec())
+    }
+}
+
+impl DeprecatedSnippet for BfeSumList {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_bfe_sum_list".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::BFE))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["sum".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::BFE]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+
+        format!(
+            "
+            // BEFORE: _ *list
+            // AFTER: _ sum
+            {entrypoint}:
+                dup 0
+                read_mem
+                swap 1
+                pop
+                // _ *list length
+
+                swap 1
+                push 2
+                add
+                // _ length *first_element
+
+                swap 1
+                dup 1
+                add
+                // _ *first_element *end
+
+                push 0
+                // _ *first_element *end 0
+
+                call {entrypoint}_loop
+                // _ *end *end sum
+
+                swap 2
+                pop
+                pop
+                // _ sum
+
+                return
+
+            // INVARIANT: _ *element *end acc
+            {entrypoint}_loop:
+                dup 2
+                dup 2
+                eq
+                skiz
+                    return
+
+                dup 2
+                read_mem
+                swap 1
+                pop
+                // _ *element *end acc element
+
+                add
+                // _ *element *end (acc + element)
+
+                swap 2
+                push 1
+                add
+                swap 2
+                // _ (*element + 1) *end (acc + element)
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.random_state(0),
+            self.random_state(1),
+            self.random_state(2),
+            self.random_state(rng.gen_range(3..50)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.random_state(32)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.random_state(1000)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let list_pointer = stack.pop().unwrap();
+        let length = safe_list_get_length(list_pointer, memory);
+        let sum: BFieldElement = (0..length)
+            .map(|i| safe_list_get(list_pointer, i, memory, DataType::BFE.get_size())[0])
+            .sum();
+
+        stack.push(sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn bfe_sum_list_test() {
+        test_rust_equivalence_multiple_deprecated(&BfeSumList, true);
+    }
+
+    fn prop_bfe_sum_list(elements: Vec<BFieldElement>) {
+        let expected_sum: BFieldElement = elements.iter().copied().sum();
+        let expected_stack = [get_init_tvm_stack(), vec![expected_sum]].concat();
+
+        let mut init_state = BfeSumList.prepare_state(elements);
+        test_rust_equivalence_given_input_values_deprecated(
+            &BfeSumList,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn empty_list_sums_to_zero() {
+        prop_bfe_sum_list(vec![]);
+    }
+
+    #[test]
+    fn single_element_list() {
+        prop_bfe_sum_list(vec![random()]);
+    }
+
+    #[test]
+    fn multi_element_list() {
+        prop_bfe_sum_list((0..17).map(|_| random()).collect_vec());
+        prop_bfe_sum_list(vec![BFieldElement::new(BFieldElement::MAX); 3]);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn bfe_sum_list_benchmark() {
+        bench_and_write(BfeSumList);
+    }
+}
@@ -0,0 +1 @@
+pub mod assert_i32;
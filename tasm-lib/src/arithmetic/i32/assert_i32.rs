@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u32::assert_u32::AssertU32;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Crash the VM unless the top element of the stack is a valid encoding of an `I32`,
+/// i.e., smaller than `2^32`. Every such element is the two's-complement bit pattern of
+/// exactly one `i32`, see [`DataType::i32_encoding`], so this is [`AssertU32`] on a
+/// differently typed value. The value is left on the stack.
+#[derive(Clone, Debug)]
+pub struct AssertI32;
+
+impl AssertI32 {
+    fn prepare_state(value: BFieldElement) -> ExecutionState {
+        let stack = [get_init_tvm_stack(), vec![value]].concat();
+        ExecutionState::with_stack(stack)
+    }
+}
+
+impl DeprecatedSnippet for AssertI32 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_i32_assert_i32".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::I32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::I32]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let assert_u32 = library.import(Box::new(AssertU32));
+        format!(
+            "
+            // BEFORE: _ value
+            // AFTER: _ value
+            {entrypoint}:
+                call {assert_u32}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["value is not the encoding of an i32".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            Self::prepare_state(DataType::i32_encoding(0)),
+            Self::prepare_state(DataType::i32_encoding(-1)),
+            Self::prepare_state(DataType::i32_encoding(i32::MIN)),
+            Self::prepare_state(DataType::i32_encoding(i32::MAX)),
+            Self::prepare_state(DataType::i32_encoding(rng.next_u32() as i32)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(DataType::i32_encoding(-(1 << 16)))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(DataType::i32_encoding(i32::MIN))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        assert!(
+            DataType::i32_from_encoding(*stack.last().unwrap()).is_some(),
+            "value is not the encoding of an i32"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn assert_i32_test() {
+        test_rust_equivalence_multiple_deprecated(&AssertI32, true);
+    }
+
+    #[test]
+    fn valid_i32s_are_left_untouched() {
+        for value in [0, 1, -1, i32::MIN, i32::MAX] {
+            let init_state = AssertI32::prepare_state(DataType::i32_encoding(value));
+            test_rust_equivalence_given_input_values_deprecated(
+                &AssertI32,
+                &init_state.stack,
+                &[],
+                &mut HashMap::default(),
+                0,
+                Some(&init_state.stack),
+            );
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn two_pow_32_crashes() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AssertI32,
+            AssertI32::prepare_state(BFieldElement::new(1 << 32)),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn negative_field_element_crashes() {
+        // -1 as a field element is not the encoding of the i32 -1
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AssertI32,
+            AssertI32::prepare_state(-BFieldElement::new(1)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn assert_i32_benchmark() {
+        bench_and_write(AssertI32);
+    }
+}
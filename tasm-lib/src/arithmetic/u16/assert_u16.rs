@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Crash the VM unless the top element of the stack is a valid `U16`, i.e., smaller
+/// than `2^16`. The value is left on the stack.
+#[derive(Clone, Debug)]
+pub struct AssertU16;
+
+impl AssertU16 {
+    fn prepare_state(value: u64) -> ExecutionState {
+        let stack = [get_init_tvm_stack(), vec![BFieldElement::new(value)]].concat();
+        ExecutionState::with_stack(stack)
+    }
+}
+
+impl DeprecatedSnippet for AssertU16 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u16_assert_u16".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U16]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["value".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U16]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        format!(
+            "
+            // BEFORE: _ value
+            // AFTER: _ value
+            {entrypoint}:
+                dup 0   // _ value value
+                split   // _ value hi lo
+                push 65536
+                dup 1   // _ value hi lo 2^16 lo
+                lt      // _ value hi lo (lo < 2^16)
+                assert  // _ value hi lo
+                pop     // _ value hi
+                push 0
+                eq
+                assert  // _ value
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["value exceeds u16::MAX".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            Self::prepare_state(0),
+            Self::prepare_state(u16::MAX as u64),
+            Self::prepare_state((rng.next_u32() >> 16) as u64),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(1 << 8)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(u16::MAX as u64)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        assert!(
+            DataType::u16_from_encoding(*stack.last().unwrap()).is_some(),
+            "value exceeds u16::MAX"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn assert_u16_test() {
+        test_rust_equivalence_multiple_deprecated(&AssertU16, true);
+    }
+
+    #[test]
+    fn valid_u16s_are_left_untouched() {
+        for value in [0, 1 << 10, u16::MAX as u64] {
+            let init_state = AssertU16::prepare_state(value);
+            test_rust_equivalence_given_input_values_deprecated(
+                &AssertU16,
+                &init_state.stack,
+                &[],
+                &mut HashMap::default(),
+                0,
+                Some(&init_state.stack),
+            );
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn two_pow_16_crashes() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AssertU16,
+            AssertU16::prepare_state(1 << 16),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn non_u32_crashes() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AssertU16,
+            AssertU16::prepare_state(1 << 32),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn assert_u16_benchmark() {
+        bench_and_write(AssertU16);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod assert_u32;
 pub mod isodd;
 pub mod isu32;
 pub mod leadingzeros;
@@ -64,7 +64,7 @@ impl Closure for Overflowingadd {
                 (1u32 << 31, (1u32 << 31) - 1)
             }
             Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => (1u32 << 31, 1u32 << 31),
-            None => {
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => {
                 let mut rng = StdRng::from_seed(seed);
                 (rng.next_u32(), rng.next_u32())
             }
@@ -169,7 +169,7 @@ impl Closure for Safepow {
         let (base, exponent): (u32, u32) = match bench_case {
             Some(BenchmarkCase::CommonCase) => (10, 5),
             Some(BenchmarkCase::WorstCase) => (2, 31),
-            None => {
+            Some(BenchmarkCase::Custom(_)) | None => {
                 let mut seeded_rng = StdRng::from_seed(seed);
                 let base: u32 = seeded_rng.gen_range(0..0x10);
                 let exponent: u32 = seeded_rng.gen_range(0..0x8);
@@ -0,0 +1,2 @@
+pub mod add_u32s_n;
+pub mod sub_u32s_n;
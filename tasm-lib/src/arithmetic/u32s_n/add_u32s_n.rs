@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{thread_rng, Rng};
+use twenty_first::{
+    amount::u32s::U32s,
+    shared_math::{b_field_element::BFieldElement, bfield_codec::BFieldCodec},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    library::Library,
+    snippet::{DataType, DeprecatedSnippet},
+    ExecutionState,
+};
+
+/// Add two `U32s<N>`, i.e., integers of `N` limbs of 32 bits each, propagating the carry
+/// from the least to the most significant limb. Crashes if the sum overflows. The limbs
+/// are assumed to be valid u32s.
+#[derive(Clone, Debug)]
+pub struct AddU32s<const N: usize>;
+
+impl<const N: usize> DeprecatedSnippet for AddU32s<N> {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_arithmetic_u32s_{N}_add")
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        let rhs = (0..N).rev().map(|i| format!("rhs_{i}"));
+        let lhs = (0..N).rev().map(|i| format!("lhs_{i}"));
+        rhs.chain(lhs).collect()
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32; 2 * N]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        (0..N).rev().map(|i| format!("(lhs + rhs)_{i}")).collect()
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U32; N]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -(N as isize)
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        assert!(
+            (1..=14).contains(&N),
+            "Number of limbs must be between 1 and 14"
+        );
+        let entrypoint = self.entrypoint_name();
+
+        // INVARIANT: _ rhs_{N-1} .. rhs_i sum_{i-1} .. sum_0 lhs_{N-1} .. lhs_i carry
+        // The limb sum is written into the slot of rhs_i, which is then discarded.
+        let add_limb = format!(
+            "
+                add
+                dup {N}
+                add
+                split
+                // _ rhs_{{N-1}} .. rhs_i sum_{{i-1}} .. sum_0 lhs_{{N-1}} .. lhs_{{i+1}} carry sum_i
+
+                swap {}
+                pop
+                // _ rhs_{{N-1}} .. rhs_{{i+1}} sum_i .. sum_0 lhs_{{N-1}} .. lhs_{{i+1}} carry
+            ",
+            N + 1
+        );
+        let add_all_limbs = vec![add_limb; N].concat();
+
+        format!(
+            "
+            // BEFORE: _ rhs_{{N-1}} .. rhs_0 lhs_{{N-1}} .. lhs_0
+            // AFTER: _ sum_{{N-1}} .. sum_0
+            {entrypoint}:
+                push 0
+                {add_all_limbs}
+                // _ sum_{{N-1}} .. sum_0 overflow
+
+                push 0
+                eq
+                assert
+                // _ sum_{{N-1}} .. sum_0
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![format!("if (lhs + rhs) overflows U32s<{N}>")]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let mut random_limbs = |top_limb_bound: u32| {
+            let mut limbs = (0..N).map(|_| rng.gen::<u32>()).collect_vec();
+            limbs[N - 1] = rng.gen_range(0..top_limb_bound);
+            limbs
+        };
+
+        let mut states = vec![];
+        for _ in 0..20 {
+            let lhs = random_limbs(1 << 31);
+            let rhs = random_limbs(1 << 31);
+            states.push(prepare_state(&lhs, &rhs));
+        }
+
+        // carry propagating through all limbs
+        let all_ones = [vec![u32::MAX; N - 1], vec![0]].concat();
+        let one = [vec![1], vec![0; N - 1]].concat();
+        states.push(prepare_state(&all_ones, &one));
+
+        states
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        let half = [vec![0; N - 1], vec![1 << 30]].concat();
+        prepare_state(&half, &half)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        let all_ones = [vec![u32::MAX; N - 1], vec![1 << 30]].concat();
+        prepare_state(&all_ones, &all_ones)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let mut pop_u32s = || {
+            let limbs = (0..N)
+                .map(|_| stack.pop().unwrap().try_into().unwrap())
+                .collect_vec();
+            U32s::<N>::new(limbs.try_into().unwrap())
+        };
+        let lhs = pop_u32s();
+        let rhs = pop_u32s();
+
+        let mut res = (lhs + rhs).encode();
+        for _ in 0..res.len() {
+            stack.push(res.pop().unwrap());
+        }
+    }
+}
+
+/// Limbs are given least significant first.
+fn prepare_state(lhs: &[u32], rhs: &[u32]) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    for limb in rhs.iter().rev().chain(lhs.iter().rev()) {
+        stack.push(BFieldElement::new(*limb as u64));
+    }
+
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn add_u32s_n_test() {
+        test_rust_equivalence_multiple_deprecated(&AddU32s::<2>, true);
+        test_rust_equivalence_multiple_deprecated(&AddU32s::<3>, true);
+        test_rust_equivalence_multiple_deprecated(&AddU32s::<4>, true);
+    }
+
+    fn prop_add<const N: usize>(lhs: &[u32], rhs: &[u32], expected_sum: &[u32]) {
+        let mut expected_stack = get_init_tvm_stack();
+        for limb in expected_sum.iter().rev() {
+            expected_stack.push(BFieldElement::new(*limb as u64));
+        }
+
+        let mut init_state = prepare_state(lhs, rhs);
+        test_rust_equivalence_given_input_values_deprecated(
+            &AddU32s::<N>,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    fn prop_max_carry<const N: usize>() {
+        let all_ones = [vec![u32::MAX; N - 1], vec![0]].concat();
+        let one = [vec![1], vec![0; N - 1]].concat();
+        let expected_sum = [vec![0; N - 1], vec![1]].concat();
+        prop_add::<N>(&all_ones, &one, &expected_sum);
+        prop_add::<N>(&one, &all_ones, &expected_sum);
+    }
+
+    #[test]
+    fn max_carry_test() {
+        prop_max_carry::<2>();
+        prop_max_carry::<3>();
+        prop_max_carry::<4>();
+    }
+
+    #[test]
+    fn largest_sum_without_overflow_test() {
+        let max = vec![u32::MAX; 4];
+        let zero = vec![0; 4];
+        prop_add::<4>(&max, &zero, &max);
+        prop_add::<4>(&zero, &max, &max);
+    }
+
+    fn prop_overflow<const N: usize>() {
+        let max = vec![u32::MAX; N];
+        let one = [vec![1], vec![0; N - 1]].concat();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AddU32s::<N>,
+            prepare_state(&max, &one),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn overflow_u32s_2_test() {
+        prop_overflow::<2>();
+    }
+
+    #[should_panic]
+    #[test]
+    fn overflow_u32s_3_test() {
+        prop_overflow::<3>();
+    }
+
+    #[should_panic]
+    #[test]
+    fn overflow_u32s_4_test() {
+        prop_overflow::<4>();
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn add_u32s_n_benchmark() {
+        bench_and_write(AddU32s::<4>);
+    }
+}
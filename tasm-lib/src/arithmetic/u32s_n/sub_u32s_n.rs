@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{thread_rng, Rng};
+use twenty_first::{
+    amount::u32s::U32s,
+    shared_math::{b_field_element::BFieldElement, bfield_codec::BFieldCodec},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    library::Library,
+    snippet::{DataType, DeprecatedSnippet},
+    ExecutionState,
+};
+
+/// Subtract two `U32s<N>`, i.e., integers of `N` limbs of 32 bits each, propagating the
+/// borrow from the least to the most significant limb. Crashes if `rhs > lhs`. The limbs
+/// are assumed to be valid u32s.
+#[derive(Clone, Debug)]
+pub struct SubU32s<const N: usize>;
+
+impl<const N: usize> DeprecatedSnippet for SubU32s<N> {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_arithmetic_u32s_{N}_sub")
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        let rhs = (0..N).rev().map(|i| format!("rhs_{i}"));
+        let lhs = (0..N).rev().map(|i| format!("lhs_{i}"));
+        rhs.chain(lhs).collect()
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32; 2 * N]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        (0..N).rev().map(|i| format!("(lhs - rhs)_{i}")).collect()
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U32; N]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -(N as isize)
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        assert!(
+            (1..=14).contains(&N),
+            "Number of limbs must be between 1 and 14"
+        );
+        let entrypoint = self.entrypoint_name();
+
+        const TWO_POW_32_MINUS_ONE: u64 = u32::MAX as u64;
+
+        // INVARIANT: _ rhs_{N-1} .. rhs_i diff_{i-1} .. diff_0 lhs_{N-1} .. lhs_i no_borrow
+        // The limb difference is written into the slot of rhs_i, which is then discarded.
+        let sub_limb = format!(
+            "
+                add
+                dup {N}
+                push -1
+                mul
+                add
+                // _ rhs_{{N-1}} .. rhs_i diff_{{i-1}} .. diff_0 lhs_{{N-1}} .. lhs_{{i+1}} (lhs_i + no_borrow - rhs_i)
+
+                push {TWO_POW_32_MINUS_ONE}
+                add
+                split
+                // _ rhs_{{N-1}} .. rhs_i diff_{{i-1}} .. diff_0 lhs_{{N-1}} .. lhs_{{i+1}} no_borrow diff_i
+
+                swap {}
+                pop
+                // _ rhs_{{N-1}} .. rhs_{{i+1}} diff_i .. diff_0 lhs_{{N-1}} .. lhs_{{i+1}} no_borrow
+            ",
+            N + 1
+        );
+        let sub_all_limbs = vec![sub_limb; N].concat();
+
+        format!(
+            "
+            // BEFORE: _ rhs_{{N-1}} .. rhs_0 lhs_{{N-1}} .. lhs_0
+            // AFTER: _ diff_{{N-1}} .. diff_0
+            {entrypoint}:
+                push 1
+                {sub_all_limbs}
+                // _ diff_{{N-1}} .. diff_0 no_borrow
+
+                assert
+                // _ diff_{{N-1}} .. diff_0
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![format!("if (lhs - rhs) overflows U32s<{N}>")]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+
+        let mut states = vec![];
+        for _ in 0..20 {
+            let a = (0..N).map(|_| rng.gen::<u32>()).collect_vec();
+            let b = (0..N).map(|_| rng.gen::<u32>()).collect_vec();
+
+            // Limbs are least significant first, so compare them most significant first.
+            if a.iter().rev().cmp(b.iter().rev()).is_ge() {
+                states.push(prepare_state(&a, &b));
+            } else {
+                states.push(prepare_state(&b, &a));
+            }
+        }
+
+        // borrow propagating through all limbs
+        let top_one = [vec![0; N - 1], vec![1]].concat();
+        let one = [vec![1], vec![0; N - 1]].concat();
+        states.push(prepare_state(&top_one, &one));
+
+        states
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        let lhs = [vec![0; N - 1], vec![1 << 31]].concat();
+        let rhs = [vec![0; N - 1], vec![1 << 30]].concat();
+        prepare_state(&lhs, &rhs)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        let lhs = [vec![0; N - 1], vec![1 << 31]].concat();
+        let rhs = [vec![u32::MAX; N - 1], vec![1 << 30]].concat();
+        prepare_state(&lhs, &rhs)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let mut pop_u32s = || {
+            let limbs = (0..N)
+                .map(|_| stack.pop().unwrap().try_into().unwrap())
+                .collect_vec();
+            U32s::<N>::new(limbs.try_into().unwrap())
+        };
+        let lhs = pop_u32s();
+        let rhs = pop_u32s();
+
+        let mut res = (lhs - rhs).encode();
+        for _ in 0..res.len() {
+            stack.push(res.pop().unwrap());
+        }
+    }
+}
+
+/// Limbs are given least significant first.
+fn prepare_state(lhs: &[u32], rhs: &[u32]) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    for limb in rhs.iter().rev().chain(lhs.iter().rev()) {
+        stack.push(BFieldElement::new(*limb as u64));
+    }
+
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn sub_u32s_n_test() {
+        test_rust_equivalence_multiple_deprecated(&SubU32s::<2>, true);
+        test_rust_equivalence_multiple_deprecated(&SubU32s::<3>, true);
+        test_rust_equivalence_multiple_deprecated(&SubU32s::<4>, true);
+    }
+
+    fn prop_sub<const N: usize>(lhs: &[u32], rhs: &[u32], expected_diff: &[u32]) {
+        let mut expected_stack = get_init_tvm_stack();
+        for limb in expected_diff.iter().rev() {
+            expected_stack.push(BFieldElement::new(*limb as u64));
+        }
+
+        let mut init_state = prepare_state(lhs, rhs);
+        test_rust_equivalence_given_input_values_deprecated(
+            &SubU32s::<N>,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    fn prop_max_borrow<const N: usize>() {
+        let top_one = [vec![0; N - 1], vec![1]].concat();
+        let one = [vec![1], vec![0; N - 1]].concat();
+        let expected_diff = [vec![u32::MAX; N - 1], vec![0]].concat();
+        prop_sub::<N>(&top_one, &one, &expected_diff);
+    }
+
+    #[test]
+    fn max_borrow_test() {
+        prop_max_borrow::<2>();
+        prop_max_borrow::<3>();
+        prop_max_borrow::<4>();
+    }
+
+    #[test]
+    fn subtraction_involving_extremes_test() {
+        let max = vec![u32::MAX; 4];
+        let zero = vec![0; 4];
+        prop_sub::<4>(&max, &zero, &max);
+        prop_sub::<4>(&max, &max, &zero);
+        prop_sub::<4>(&zero, &zero, &zero);
+    }
+
+    fn prop_underflow<const N: usize>() {
+        let zero = vec![0; N];
+        let one = [vec![1], vec![0; N - 1]].concat();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &SubU32s::<N>,
+            prepare_state(&zero, &one),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn underflow_u32s_2_test() {
+        prop_underflow::<2>();
+    }
+
+    #[should_panic]
+    #[test]
+    fn underflow_u32s_3_test() {
+        prop_underflow::<3>();
+    }
+
+    #[should_panic]
+    #[test]
+    fn underflow_u32s_4_test() {
+        prop_underflow::<4>();
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn sub_u32s_n_benchmark() {
+        bench_and_write(SubU32s::<4>);
+    }
+}
@@ -1,25 +1,37 @@
 pub mod add_u64;
 pub mod and_u64;
+pub mod bits;
+pub mod checked_sub_u64;
+pub mod clamp;
 pub mod decr_u64;
 pub mod div2_u64;
 pub mod div_mod_u64;
 pub mod double_pow2_u64;
 pub mod eq_u64;
+pub mod gcd_u64;
 pub mod incr_u64;
 pub mod index_of_last_nonzero_bit;
 pub mod leading_zeros_u64;
 pub mod log_2_floor_u64;
 pub mod lt_u64;
+pub mod max_u64;
+pub mod min_u64;
 pub mod mul_two_u64s_to_u128_u64;
+pub mod neg_u64;
 pub mod or_u64;
 pub mod overflowing_add_u64;
 pub mod overflowing_sub_u64;
 pub mod popcount_u64;
 pub mod pow2_u64;
 pub mod safe_mul_u64;
+pub mod saturating_add_u64;
+pub mod saturating_decr_u64;
+pub mod saturating_incr_u64;
+pub mod saturating_sub_u64;
 pub mod shift_left_u64;
 pub mod shift_right_u64;
 pub mod sub_u64;
+pub mod validate_u64;
 pub mod wrapping_mul_u64;
 pub mod wrapping_sub_u64;
 pub mod xor_u64;
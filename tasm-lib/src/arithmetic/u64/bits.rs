@@ -0,0 +1,544 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, RngCore};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::or_u64::OrU64;
+use crate::arithmetic::u64::shift_left_u64::ShiftLeftU64;
+use crate::arithmetic::u64::shift_right_u64::ShiftRightU64;
+use crate::library::Library;
+use crate::list::safeimplu32::{
+    get::SafeGet, length::Length as SafeLength, new::SafeNew, push::SafePush,
+};
+use crate::list::unsafeimplu32::{
+    get::UnsafeGet, length::Length as UnsafeLength, new::UnsafeNew, push::UnsafePush,
+};
+use crate::list::ListType;
+use crate::pseudo::lsb::Lsb;
+use crate::rust_shadowing_helper_functions::{safe_list, unsafe_list};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Number of bits in a `U64`, and thus the length of the bit lists used here.
+const NUM_BITS: usize = 64;
+
+/// Explode a `U64` into a new list of 64 `Bool`s. The list starts with the least
+/// significant bit, i.e., element `i` is bit `i` of the value.
+#[derive(Clone, Debug)]
+pub struct U64ToBits {
+    pub list_type: ListType,
+}
+
+/// Recombine a list of 64 `Bool`s, least significant bit first, into a `U64`. This is
+/// the inverse of [`U64ToBits`]. Crashes if the list does not have exactly 64 elements,
+/// or if any element is not 0 or 1.
+#[derive(Clone, Debug)]
+pub struct BitsToU64 {
+    pub list_type: ListType,
+}
+
+fn u64_to_stack(value: u64) -> Vec<BFieldElement> {
+    vec![
+        BFieldElement::new(value >> 32),
+        BFieldElement::new(value & u32::MAX as u64),
+    ]
+}
+
+fn bits_lsb_first(value: u64) -> Vec<BFieldElement> {
+    (0..NUM_BITS)
+        .map(|i| BFieldElement::new((value >> i) & 1))
+        .collect_vec()
+}
+
+impl U64ToBits {
+    fn prepare_state(value: u64) -> ExecutionState {
+        let stack = [get_init_tvm_stack(), u64_to_stack(value)].concat();
+        ExecutionState::with_stack(stack)
+    }
+}
+
+impl DeprecatedSnippet for U64ToBits {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_arithmetic_u64_to_bits_in_{}_list", self.list_type)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*bits".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::Bool))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let (new_list, push_bit) = match self.list_type {
+            ListType::Safe => (
+                library.import(Box::new(SafeNew(DataType::Bool))),
+                library.import(Box::new(SafePush(DataType::Bool))),
+            ),
+            ListType::Unsafe => (
+                library.import(Box::new(UnsafeNew(DataType::Bool))),
+                library.import(Box::new(UnsafePush(DataType::Bool))),
+            ),
+        };
+        let lsb = library.import(Box::new(Lsb));
+        let shift_right = library.import(Box::new(ShiftRightU64));
+
+        format!(
+            "
+            // BEFORE: _ value_hi value_lo
+            // AFTER: _ *bits
+            {entrypoint}:
+                push {NUM_BITS}
+                call {new_list}
+                // _ value_hi value_lo *bits
+
+                swap 2
+                swap 1
+                push {NUM_BITS}
+                // _ *bits value_hi value_lo {NUM_BITS}
+
+                call {entrypoint}_loop
+                // _ *bits 0 0 0
+
+                pop
+                pop
+                pop
+                return
+
+            // INVARIANT: _ *bits value_hi value_lo remaining
+            {entrypoint}_loop:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                push -1
+                add
+                dup 3
+                dup 2
+                call {lsb}
+                // _ *bits value_hi value_lo remaining *bits (value_lo / 2) (value_lo % 2)
+
+                swap 1
+                pop
+                call {push_bit}
+                // _ *bits value_hi value_lo remaining
+
+                swap 2
+                swap 1
+                push 1
+                call {shift_right}
+                // _ *bits remaining (value >> 1)_hi (value >> 1)_lo
+
+                swap 1
+                swap 2
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            Self::prepare_state(0),
+            Self::prepare_state(1),
+            Self::prepare_state(1 << 63),
+            Self::prepare_state(0x5555_5555_5555_5555),
+            Self::prepare_state(u64::MAX),
+            Self::prepare_state(rng.next_u64()),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(1 << 40)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(u64::MAX)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let value_lo = stack.pop().unwrap().value();
+        let value_hi = stack.pop().unwrap().value();
+        let value = (value_hi << 32) + value_lo;
+
+        stack.push(BFieldElement::new(NUM_BITS as u64));
+        match self.list_type {
+            ListType::Safe => {
+                SafeNew(DataType::Bool).rust_shadowing(stack, std_in, secret_in, memory)
+            }
+            ListType::Unsafe => {
+                UnsafeNew(DataType::Bool).rust_shadowing(stack, std_in, secret_in, memory)
+            }
+        }
+        let list_pointer = *stack.last().unwrap();
+
+        let list_push = match self.list_type {
+            ListType::Safe => safe_list::safe_list_push,
+            ListType::Unsafe => unsafe_list::unsafe_list_push,
+        };
+        for bit in bits_lsb_first(value) {
+            list_push(list_pointer, vec![bit], memory, 1);
+        }
+    }
+}
+
+impl BitsToU64 {
+    fn prepare_state(&self, bits: Vec<BFieldElement>) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut memory = HashMap::default();
+        match self.list_type {
+            ListType::Safe => {
+                safe_list::safe_list_new(list_pointer, bits.len() as u32, &mut memory);
+                for bit in bits {
+                    safe_list::safe_list_push(list_pointer, vec![bit], &mut memory, 1);
+                }
+            }
+            ListType::Unsafe => {
+                unsafe_list::unsafe_list_new(list_pointer, &mut memory);
+                for bit in bits {
+                    unsafe_list::unsafe_list_push(list_pointer, vec![bit], &mut memory, 1);
+                }
+            }
+        }
+
+        let stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for BitsToU64 {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_arithmetic_u64_from_bits_in_{}_list", self.list_type)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*bits".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::Bool))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let (get_length, get_bit) = match self.list_type {
+            ListType::Safe => (
+                library.import(Box::new(SafeLength(DataType::Bool))),
+                library.import(Box::new(SafeGet(DataType::Bool))),
+            ),
+            ListType::Unsafe => (
+                library.import(Box::new(UnsafeLength(DataType::Bool))),
+                library.import(Box::new(UnsafeGet(DataType::Bool))),
+            ),
+        };
+        let shift_left = library.import(Box::new(ShiftLeftU64));
+        let or = library.import(Box::new(OrU64));
+
+        format!(
+            "
+            // BEFORE: _ *bits
+            // AFTER: _ value_hi value_lo
+            {entrypoint}:
+                dup 0
+                call {get_length}
+                push {NUM_BITS}
+                eq
+                assert
+                // _ *bits
+
+                push 0
+                push 0
+                push {NUM_BITS}
+                call {entrypoint}_loop
+                // _ *bits value_hi value_lo 0
+
+                pop
+                swap 1
+                swap 2
+                pop
+                return
+
+            // Shift in the bits, starting with the most significant one.
+            // INVARIANT: _ *bits acc_hi acc_lo remaining
+            {entrypoint}_loop:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                push -1
+                add
+                swap 2
+                swap 1
+                push 1
+                call {shift_left}
+                // _ *bits remaining (acc << 1)_hi (acc << 1)_lo
+
+                push 0
+                dup 4
+                dup 4
+                call {get_bit}
+                // _ *bits remaining (acc << 1)_hi (acc << 1)_lo 0 bit
+
+                push 2
+                dup 1
+                lt
+                assert
+                call {or}
+                // _ *bits remaining acc'_hi acc'_lo
+
+                swap 1
+                swap 2
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "list does not contain exactly 64 elements".to_string(),
+            "list element is not a bit".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            self.prepare_state(bits_lsb_first(0)),
+            self.prepare_state(bits_lsb_first(1)),
+            self.prepare_state(bits_lsb_first(1 << 63)),
+            self.prepare_state(bits_lsb_first(0x5555_5555_5555_5555)),
+            self.prepare_state(bits_lsb_first(u64::MAX)),
+            self.prepare_state(bits_lsb_first(rng.next_u64())),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(bits_lsb_first(1 << 40))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(bits_lsb_first(u64::MAX))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let list_pointer = stack.pop().unwrap();
+        let list_length = match self.list_type {
+            ListType::Safe => safe_list::safe_list_get_length(list_pointer, memory),
+            ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, memory),
+        };
+        let list_get = match self.list_type {
+            ListType::Safe => safe_list::safe_list_get,
+            ListType::Unsafe => unsafe_list::unsafe_list_get,
+        };
+        assert_eq!(
+            NUM_BITS, list_length,
+            "list does not contain exactly 64 elements"
+        );
+
+        let mut value = 0u64;
+        for i in (0..NUM_BITS).rev() {
+            let bit = list_get(list_pointer, i, memory, 1)[0].value();
+            assert!(bit < 2, "list element is not a bit");
+            value = (value << 1) | bit;
+        }
+
+        stack.append(&mut u64_to_stack(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn u64_to_bits_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(&U64ToBits { list_type }, true);
+        }
+    }
+
+    #[test]
+    fn bits_to_u64_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(&BitsToU64 { list_type }, true);
+        }
+    }
+
+    fn read_bits(
+        list_type: &ListType,
+        list_pointer: BFieldElement,
+        memory: &HashMap<BFieldElement, BFieldElement>,
+    ) -> Vec<BFieldElement> {
+        (0..NUM_BITS)
+            .map(|i| match list_type {
+                ListType::Safe => safe_list::safe_list_get(list_pointer, i, memory, 1)[0],
+                ListType::Unsafe => unsafe_list::unsafe_list_get(list_pointer, i, memory, 1)[0],
+            })
+            .collect_vec()
+    }
+
+    fn prop_round_trip(value: u64) {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let to_bits = U64ToBits {
+                list_type: list_type.clone(),
+            };
+            let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+                &to_bits,
+                U64ToBits::prepare_state(value),
+            );
+            let list_pointer = *vm_output_state.final_stack.last().unwrap();
+            let mut memory = vm_output_state.final_ram;
+            assert_eq!(
+                bits_lsb_first(value),
+                read_bits(&list_type, list_pointer, &memory)
+            );
+
+            let init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+            let expected_stack = [get_init_tvm_stack(), u64_to_stack(value)].concat();
+            test_rust_equivalence_given_input_values_deprecated(
+                &BitsToU64 { list_type },
+                &init_stack,
+                &[],
+                &mut memory,
+                0,
+                Some(&expected_stack),
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_random_values() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..5 {
+            prop_round_trip(rng.next_u64());
+        }
+    }
+
+    #[test]
+    fn round_trip_bit_patterns() {
+        for value in [
+            0,
+            1,
+            1 << 31,
+            1 << 32,
+            1 << 63,
+            0x5555_5555_5555_5555,
+            0xaaaa_aaaa_aaaa_aaaa,
+            u64::MAX,
+        ] {
+            prop_round_trip(value);
+        }
+    }
+
+    #[test]
+    fn bits_are_least_significant_first() {
+        let bits = bits_lsb_first(0x5555_5555_5555_5555);
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!((i % 2 == 0) as u64, bit.value(), "bit {i}");
+        }
+
+        let bits = bits_lsb_first(1 << 32);
+        assert_eq!(Some(32), bits.iter().position(|bit| bit.value() == 1));
+        assert_eq!(1, bits.iter().filter(|bit| bit.value() == 1).count());
+    }
+
+    #[should_panic]
+    #[test]
+    fn bits_to_u64_crashes_on_63_bits() {
+        let snippet = BitsToU64 {
+            list_type: ListType::Safe,
+        };
+        let mut bits = bits_lsb_first(u64::MAX);
+        bits.pop();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &snippet,
+            snippet.prepare_state(bits),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn bits_to_u64_crashes_on_non_bit_element() {
+        let snippet = BitsToU64 {
+            list_type: ListType::Unsafe,
+        };
+        let mut bits = bits_lsb_first(0);
+        bits[17] = BFieldElement::new(2);
+        test_rust_equivalence_given_execution_state_deprecated(
+            &snippet,
+            snippet.prepare_state(bits),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn u64_to_bits_benchmark() {
+        bench_and_write(U64ToBits {
+            list_type: ListType::Unsafe,
+        });
+    }
+
+    #[test]
+    fn bits_to_u64_benchmark() {
+        bench_and_write(BitsToU64 {
+            list_type: ListType::Unsafe,
+        });
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::lt_u64::LtU64;
+use crate::arithmetic::u64::max_u64::MaxU64;
+use crate::arithmetic::u64::min_u64::MinU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// Restrict a `U64` to the inclusive range `[lo, hi]`, i.e. `min(max(value, lo), hi)`.
+/// Crashes if `lo > hi`.
+#[derive(Clone, Debug)]
+pub struct U64Clamp;
+
+impl DeprecatedSnippet for U64Clamp {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_clamp".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "value_hi".to_string(),
+            "value_lo".to_string(),
+            "lo_hi".to_string(),
+            "lo_lo".to_string(),
+            "hi_hi".to_string(),
+            "hi_lo".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64, DataType::U64, DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["clamped_hi".to_string(), "clamped_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -4
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let lt_u64 = library.import(Box::new(LtU64));
+        let max_u64 = library.import(Box::new(MaxU64));
+        let min_u64 = library.import(Box::new(MinU64));
+
+        format!(
+            "
+            // BEFORE: _ value_hi value_lo lo_hi lo_lo hi_hi hi_lo
+            // AFTER: _ clamped_hi clamped_lo
+            {entrypoint}:
+                call {lt_u64}
+                // _ value_hi value_lo lo_hi lo_lo hi_hi hi_lo (hi < lo)
+
+                push 0
+                eq
+                assert
+                // _ value_hi value_lo lo_hi lo_lo hi_hi hi_lo
+
+                swap 4
+                swap 1
+                swap 5
+                swap 1
+                // _ hi_hi hi_lo lo_hi lo_lo value_hi value_lo
+
+                call {max_u64}
+                // _ hi_hi hi_lo max_hi max_lo
+
+                call {min_u64}
+                // _ clamped_hi clamped_lo
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "Inputs are not u32s".to_string(),
+            "lower bound exceeds upper bound".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let mut ret = vec![prepare_state(0, 0, 0), prepare_state(u64::MAX, 0, u64::MAX)];
+        for _ in 0..20 {
+            let mut bounds = [rng.next_u64(), rng.next_u64()];
+            bounds.sort();
+            ret.push(prepare_state(rng.next_u64(), bounds[0], bounds[1]));
+            ret.push(prepare_state(bounds[0], bounds[0], bounds[1]));
+            ret.push(prepare_state(bounds[1], bounds[0], bounds[1]));
+        }
+
+        ret
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(1 << 35, 1 << 32, 1 << 40)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(1 << 50, 1 << 32, 1 << 40)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let mut pop_u64 = || {
+            let lo: u32 = stack.pop().unwrap().try_into().unwrap();
+            let hi: u32 = stack.pop().unwrap().try_into().unwrap();
+            ((hi as u64) << 32) + lo as u64
+        };
+        let hi = pop_u64();
+        let lo = pop_u64();
+        let value = pop_u64();
+
+        push_encodable(stack, &value.clamp(lo, hi));
+    }
+}
+
+fn prepare_state(value: u64, lo: u64, hi: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &value);
+    push_encodable(&mut stack, &lo);
+    push_encodable(&mut stack, &hi);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn clamp_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&U64Clamp, true);
+    }
+
+    #[test]
+    fn clamp_below_range() {
+        prop_clamp(0, 1, 10, 1);
+        prop_clamp((1 << 32) - 1, 1 << 32, u64::MAX, 1 << 32);
+    }
+
+    #[test]
+    fn clamp_inside_range() {
+        prop_clamp(5, 1, 10, 5);
+        prop_clamp((3 << 32) + 7, 3 << 32, 4 << 32, (3 << 32) + 7);
+    }
+
+    #[test]
+    fn clamp_above_range() {
+        prop_clamp(11, 1, 10, 10);
+        prop_clamp(u64::MAX, 0, (1 << 32) + 1, (1 << 32) + 1);
+    }
+
+    #[test]
+    fn clamp_on_boundaries() {
+        prop_clamp(1, 1, 10, 1);
+        prop_clamp(10, 1, 10, 10);
+        prop_clamp(1 << 40, 1 << 40, 1 << 40, 1 << 40);
+        prop_clamp(0, 0, u64::MAX, 0);
+        prop_clamp(u64::MAX, 0, u64::MAX, u64::MAX);
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_with_lower_bound_above_upper_bound() {
+        prop_clamp(5, 10, 1, 5);
+    }
+
+    fn prop_clamp(value: u64, lo: u64, hi: u64, expected_clamped: u64) {
+        let mut expected = get_init_tvm_stack();
+        push_encodable(&mut expected, &expected_clamped);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &U64Clamp,
+            &prepare_state(value, lo, hi).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn clamp_u64_benchmark() {
+        bench_and_write(U64Clamp);
+    }
+}
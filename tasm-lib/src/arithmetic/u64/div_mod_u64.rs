@@ -57,6 +57,11 @@ impl DeprecatedSnippet for DivModU64 {
         0
     }
 
+    fn static_allocation_size(&self) -> usize {
+        // The divisor is spilled to memory
+        2
+    }
+
     fn function_code(&self, library: &mut Library) -> String {
         let entrypoint = self.entrypoint_name();
         let shift_right_u64 = library.import(Box::new(ShiftRightU64));
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u32::isodd::Isodd;
+use crate::arithmetic::u64::eq_u64::EqU64;
+use crate::arithmetic::u64::lt_u64::LtU64;
+use crate::arithmetic::u64::shift_left_u64::ShiftLeftU64;
+use crate::arithmetic::u64::shift_right_u64::ShiftRightU64;
+use crate::arithmetic::u64::sub_u64::SubU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// The greatest common divisor of two `U64`s, computed with the binary (Stein's)
+/// algorithm. `gcd(0, n) = gcd(n, 0) = n`, so in particular `gcd(0, 0) = 0`.
+#[derive(Clone, Debug)]
+pub struct GcdU64;
+
+impl DeprecatedSnippet for GcdU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_gcd".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "a_hi".to_string(),
+            "a_lo".to_string(),
+            "b_hi".to_string(),
+            "b_lo".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64, DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["gcd_hi".to_string(), "gcd_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let eq_u64 = library.import(Box::new(EqU64));
+        let lt_u64 = library.import(Box::new(LtU64));
+        let sub_u64 = library.import(Box::new(SubU64));
+        let shift_left_u64 = library.import(Box::new(ShiftLeftU64));
+        let shift_right_u64 = library.import(Box::new(ShiftRightU64));
+        let isodd = library.import(Box::new(Isodd));
+
+        format!(
+            "
+            // BEFORE: _ a_hi a_lo b_hi b_lo
+            // AFTER: _ gcd_hi gcd_lo
+            {entrypoint}:
+                dup 3
+                dup 3
+                push 0
+                push 0
+                call {eq_u64}
+                // _ a_hi a_lo b_hi b_lo (a == 0)
+
+                skiz
+                    call {entrypoint}_swap
+                // _ a_hi a_lo b_hi b_lo, where b == 0 if either input is 0
+
+                dup 1
+                dup 1
+                push 0
+                push 0
+                call {eq_u64}
+                push 0
+                eq
+                // _ a_hi a_lo b_hi b_lo (b != 0)
+
+                skiz
+                    call {entrypoint}_nonzero
+                // _ gcd_hi gcd_lo 0 0
+
+                pop
+                pop
+                return
+
+            // BEFORE: _ a_hi a_lo b_hi b_lo
+            // AFTER: _ b_hi b_lo a_hi a_lo
+            {entrypoint}_swap:
+                swap 2
+                swap 1
+                swap 3
+                swap 1
+                return
+
+            // BEFORE: _ a_hi a_lo b_hi b_lo, where a != 0 and b != 0
+            // AFTER: _ gcd_hi gcd_lo 0 0
+            {entrypoint}_nonzero:
+                push 0
+                call {entrypoint}_remove_common_twos
+                // _ a_hi a_lo b_hi b_lo k, where a or b is odd and gcd = gcd(a, b) << k
+
+                swap 4
+                swap 3
+                swap 2
+                swap 1
+                // _ k a_hi a_lo b_hi b_lo
+
+                call {entrypoint}_swap
+                call {entrypoint}_remove_twos
+                call {entrypoint}_swap
+                // _ k a_hi a_lo b_hi b_lo, where a is odd
+
+                call {entrypoint}_loop
+                // _ k g_hi g_lo 0 0
+
+                pop
+                pop
+                swap 2
+                swap 1
+                swap 2
+                swap 1
+                // _ g_hi g_lo k
+
+                call {shift_left_u64}
+                // _ gcd_hi gcd_lo
+
+                push 0
+                push 0
+                return
+
+            // BEFORE: _ a_hi a_lo b_hi b_lo k
+            // AFTER: _ (a >> j)_hi (a >> j)_lo (b >> j)_hi (b >> j)_lo (k + j), where j is
+            // the number of trailing zeros that a and b have in common
+            {entrypoint}_remove_common_twos:
+                dup 1
+                call {isodd}
+                dup 4
+                call {isodd}
+                add
+                // _ a_hi a_lo b_hi b_lo k (a_lo % 2 + b_lo % 2)
+
+                push 0
+                eq
+                push 0
+                eq
+                // _ a_hi a_lo b_hi b_lo k (a or b is odd)
+
+                skiz
+                    return
+
+                push 1
+                add
+                swap 4
+                swap 3
+                swap 2
+                swap 1
+                // _ (k + 1) a_hi a_lo b_hi b_lo
+
+                push 1
+                call {shift_right_u64}
+                call {entrypoint}_swap
+                push 1
+                call {shift_right_u64}
+                call {entrypoint}_swap
+                // _ (k + 1) (a >> 1)_hi (a >> 1)_lo (b >> 1)_hi (b >> 1)_lo
+
+                swap 1
+                swap 2
+                swap 3
+                swap 4
+                // _ (a >> 1)_hi (a >> 1)_lo (b >> 1)_hi (b >> 1)_lo (k + 1)
+
+                recurse
+
+            // BEFORE: _ x_hi x_lo, where x != 0
+            // AFTER: _ y_hi y_lo, where y is x with all trailing zeros removed
+            {entrypoint}_remove_twos:
+                dup 0
+                call {isodd}
+                skiz
+                    return
+
+                push 1
+                call {shift_right_u64}
+                recurse
+
+            // BEFORE: _ k a_hi a_lo b_hi b_lo, where a is odd and b != 0
+            // AFTER: _ k gcd(a, b)_hi gcd(a, b)_lo 0 0
+            {entrypoint}_loop:
+                call {entrypoint}_remove_twos
+                // _ k a_hi a_lo b_hi b_lo, where a and b are odd
+
+                call {lt_u64}
+                skiz
+                    call {entrypoint}_swap
+                // _ k a_hi a_lo b_hi b_lo, where a <= b
+
+                dup 3
+                dup 3
+                call {entrypoint}_swap
+                call {sub_u64}
+                // _ k a_hi a_lo (b - a)_hi (b - a)_lo
+
+                dup 1
+                dup 1
+                push 0
+                push 0
+                call {eq_u64}
+                skiz
+                    return
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Inputs are not u64s".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let common_factor = (rng.next_u32() as u64) << 8;
+        vec![
+            prepare_state(0, 0),
+            prepare_state(0, rng.next_u64()),
+            prepare_state(rng.next_u64(), 0),
+            prepare_state(rng.next_u64(), rng.next_u64()),
+            prepare_state(rng.next_u32() as u64, rng.next_u64()),
+            prepare_state(
+                common_factor * (rng.next_u32() >> 8) as u64,
+                common_factor * (rng.next_u32() >> 8) as u64,
+            ),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(3 << 40, 5 << 33)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(u64::MAX, 1 << 63)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let b_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let b_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let a_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let a_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let a = ((a_hi as u64) << 32) + a_lo as u64;
+        let b = ((b_hi as u64) << 32) + b_lo as u64;
+
+        push_encodable(stack, &num::integer::gcd(a, b));
+    }
+}
+
+fn prepare_state(a: u64, b: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &a);
+    push_encodable(&mut stack, &b);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn gcd_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&GcdU64, true);
+    }
+
+    fn prop_gcd(a: u64, b: u64, expected_gcd: u64) {
+        let mut expected = get_init_tvm_stack();
+        push_encodable(&mut expected, &expected_gcd);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &GcdU64,
+            &prepare_state(a, b).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
+
+    #[test]
+    fn gcd_with_zero() {
+        for n in [0, 1, 2, 17, 1 << 32, (1 << 32) + 1, u64::MAX] {
+            prop_gcd(0, n, n);
+            prop_gcd(n, 0, n);
+        }
+    }
+
+    #[test]
+    fn gcd_of_coprime_pairs() {
+        prop_gcd(1, 1, 1);
+        prop_gcd(2, 3, 1);
+        prop_gcd(35, 64, 1);
+        prop_gcd(1 << 63, u64::MAX, 1);
+        prop_gcd(u64::MAX, u64::MAX - 1, 1);
+        prop_gcd((1 << 32) + 1, 1 << 32, 1);
+    }
+
+    #[test]
+    fn gcd_with_large_common_factor() {
+        let common_factor: u64 = (1 << 40) + 15;
+        prop_gcd(3 * common_factor, 7 * common_factor, common_factor);
+        prop_gcd(common_factor, 12 * common_factor, common_factor);
+        prop_gcd(1 << 40, 3 << 50, 1 << 40);
+        prop_gcd(u64::MAX, u64::MAX, u64::MAX);
+        prop_gcd(6 << 36, 4 << 36, 2 << 36);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn gcd_u64_benchmark() {
+        bench_and_write(GcdU64);
+    }
+}
@@ -133,6 +133,10 @@ impl DeprecatedSnippet for IncrU64 {
             .concat(),
         )
     }
+
+    fn source_location(&self) -> Option<&'static str> {
+        Some(crate::source_location!())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::lt_u64::LtU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// The smaller of two `U64`s.
+#[derive(Clone, Debug)]
+pub struct MinU64;
+
+impl DeprecatedSnippet for MinU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_min".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "rhs_hi".to_string(),
+            "rhs_lo".to_string(),
+            "lhs_hi".to_string(),
+            "lhs_lo".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64, DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["min_hi".to_string(), "min_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let lt_u64 = library.import(Box::new(LtU64));
+
+        format!(
+            "
+            // BEFORE: _ rhs_hi rhs_lo lhs_hi lhs_lo
+            // AFTER: _ min_hi min_lo
+            {entrypoint}:
+                call {lt_u64}
+                // _ rhs_hi rhs_lo lhs_hi lhs_lo (lhs < rhs)
+
+                push 0
+                eq
+                // _ rhs_hi rhs_lo lhs_hi lhs_lo (lhs >= rhs)
+
+                skiz
+                    call {entrypoint}_swap
+                // _ other_hi other_lo min_hi min_lo
+
+                swap 2
+                pop
+                swap 2
+                pop
+                // _ min_hi min_lo
+
+                return
+
+            // BEFORE: _ a_hi a_lo b_hi b_lo
+            // AFTER: _ b_hi b_lo a_hi a_lo
+            {entrypoint}_swap:
+                swap 2
+                swap 1
+                swap 3
+                swap 1
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Inputs are not u32s".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let mut ret = vec![prepare_state(0, 0), prepare_state(u64::MAX, u64::MAX)];
+        for _ in 0..20 {
+            ret.push(prepare_state(rng.next_u64(), rng.next_u64()));
+            let value = rng.next_u64();
+            ret.push(prepare_state(value, value));
+        }
+
+        ret
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(1 << 40, (1 << 40) + 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state((1 << 40) + 1, 1 << 40)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let lhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs = ((lhs_hi as u64) << 32) + lhs_lo as u64;
+        let rhs = ((rhs_hi as u64) << 32) + rhs_lo as u64;
+
+        push_encodable(stack, &lhs.min(rhs));
+    }
+}
+
+fn prepare_state(lhs: u64, rhs: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &rhs);
+    push_encodable(&mut stack, &lhs);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn min_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&MinU64, true);
+    }
+
+    #[test]
+    fn min_u64_unit_test() {
+        for (lhs, rhs) in [
+            (0, 1),
+            (1, 0),
+            (14, 14),
+            ((1 << 32) - 1, 1 << 32),
+            (1 << 32, (1 << 32) - 1),
+            ((1 << 32) + 1, 2 << 32),
+            (u64::MAX, 0),
+            (0, u64::MAX),
+        ] {
+            let mut expected = get_init_tvm_stack();
+            push_encodable(&mut expected, &u64::min(lhs, rhs));
+
+            test_rust_equivalence_given_input_values_deprecated(
+                &MinU64,
+                &prepare_state(lhs, rhs).stack,
+                &[],
+                &mut HashMap::default(),
+                0,
+                Some(&expected),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn min_u64_benchmark() {
+        bench_and_write(MinU64);
+    }
+}
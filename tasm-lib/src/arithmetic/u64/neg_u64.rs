@@ -0,0 +1,164 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack,
+    snippet::{BasicSnippet, DataType},
+};
+
+/// Two's-complement negation of a u64, i.e., `value.wrapping_neg()`. Computed as
+/// `(u64::MAX - value) + 1`, where the bitwise complement `u64::MAX - value` is taken
+/// limb by limb and the final increment wraps around, so `0` negates to `0`.
+/// `IncrU64` is not used for the increment since it crashes on `u64::MAX`.
+pub struct NegU64;
+
+impl BasicSnippet for NegU64 {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::U64, "value".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::U64, "neg".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_u64_neg".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let u32_max = u32::MAX;
+
+        triton_asm!(
+            {entrypoint}:
+                // _ value_hi value_lo
+
+                push -1
+                mul
+                push {u32_max}
+                add
+                // _ value_hi !value_lo
+
+                swap 1
+                push -1
+                mul
+                push {u32_max}
+                add
+                swap 1
+                // _ !value_hi !value_lo
+
+                push 1
+                add
+                split
+                // _ !value_hi carry neg_lo
+
+                swap 2
+                add
+                split
+                // _ neg_lo overflow neg_hi
+
+                swap 1
+                pop
+                swap 1
+                // _ neg_hi neg_lo
+
+                return
+        )
+    }
+}
+
+impl Closure for NegU64 {
+    fn rust_shadow(&self, stack: &mut Vec<triton_vm::BFieldElement>) {
+        let value_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value: u64 = value_lo as u64 + ((value_hi as u64) << 32);
+
+        let neg = value.wrapping_neg();
+        stack.push(BFieldElement::new(neg >> 32));
+        stack.push(BFieldElement::new(neg & (u32::MAX as u64)));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<crate::snippet_bencher::BenchmarkCase>,
+    ) -> Vec<triton_vm::BFieldElement> {
+        let value = match bench_case {
+            Some(crate::snippet_bencher::BenchmarkCase::CommonCase) => 1u64 << 40,
+            Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => 0,
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => {
+                StdRng::from_seed(seed).next_u64()
+            }
+        };
+
+        [get_init_tvm_stack(), value.encode()].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn u64_neg_pbt() {
+        ShadowedClosure::new(NegU64).test()
+    }
+
+    #[test]
+    fn u64_neg_unit_test() {
+        for (value, expected) in [
+            (0u64, 0u64),
+            (1, u64::MAX),
+            (1 << 63, 1 << 63),
+            (u64::MAX, 1),
+            (1 << 32, u64::MAX - u32::MAX as u64),
+            (u32::MAX as u64, u64::MAX - u32::MAX as u64 + 1),
+        ] {
+            assert_eq!(value.wrapping_neg(), expected);
+
+            let u64_to_stack = |value: u64| {
+                vec![
+                    BFieldElement::new(value >> 32),
+                    BFieldElement::new(value & u32::MAX as u64),
+                ]
+            };
+            let init_stack = [get_init_tvm_stack(), u64_to_stack(value)].concat();
+            let expected_final_stack = [get_init_tvm_stack(), u64_to_stack(expected)].concat();
+            let _vm_output_state = test_rust_equivalence_given_complete_state(
+                &ShadowedClosure::new(NegU64),
+                &init_stack,
+                &[],
+                &NonDeterminism::new(vec![]),
+                &HashMap::default(),
+                &VmHasherState::new(Domain::VariableLength),
+                1,
+                Some(&expected_final_stack),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn u64_neg_bench() {
+        ShadowedClosure::new(NegU64).bench()
+    }
+}
@@ -89,7 +89,7 @@ impl Closure for OverflowingAdd {
                 (1u64 << 63, (1u64 << 63) - 1)
             }
             Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => (1u64 << 63, 1u64 << 50),
-            None => {
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => {
                 let mut rng = StdRng::from_seed(seed);
                 (rng.next_u64(), rng.next_u64())
             }
@@ -126,6 +126,10 @@ mod tests {
             (1, 1),
             (1 << 32, 1 << 32),
             (1 << 63, 1 << 63),
+            (u64::MAX - 1, 1),
+            (u64::MAX, 1),
+            (1, u64::MAX),
+            (u64::MAX, u64::MAX),
         ] {
             let init_stack = [
                 get_init_tvm_stack(),
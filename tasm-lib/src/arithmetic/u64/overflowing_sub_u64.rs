@@ -122,7 +122,7 @@ impl Closure for OverflowingSub {
                 (1u64 << 63, (1u64 << 63) - 1)
             }
             Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => (1u64 << 63, 1u64 << 50),
-            None => {
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => {
                 let mut rng = StdRng::from_seed(seed);
                 (rng.next_u64(), rng.next_u64())
             }
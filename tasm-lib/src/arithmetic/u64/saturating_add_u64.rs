@@ -0,0 +1,164 @@
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack, push_encodable,
+    snippet::{BasicSnippet, DataType},
+};
+
+use super::overflowing_add_u64::OverflowingAdd;
+
+/// Add two `U64`s, returning `u64::MAX` if the sum overflows.
+pub struct SaturatingAddU64;
+
+impl BasicSnippet for SaturatingAddU64 {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![
+            (DataType::U64, "lhs".to_string()),
+            (DataType::U64, "rhs".to_string()),
+        ]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::U64, "saturated_sum".to_owned())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_u64_saturating_add".to_string()
+    }
+
+    fn code(
+        &self,
+        library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let overflowing_add = library.import(Box::new(OverflowingAdd));
+        let u32_max = u32::MAX;
+
+        triton_asm!(
+        // BEFORE: _ lhs_hi lhs_lo rhs_hi rhs_lo
+        // AFTER: _ sum_hi sum_lo
+        {entrypoint}:
+            call {overflowing_add}
+            // _ wrapped_sum_hi wrapped_sum_lo overflow
+
+            skiz
+                call {entrypoint}_saturate
+            // _ sum_hi sum_lo
+
+            return
+
+        // BEFORE: _ wrapped_sum_hi wrapped_sum_lo
+        // AFTER: _ u32::MAX u32::MAX
+        {entrypoint}_saturate:
+            pop
+            pop
+            push {u32_max}
+            push {u32_max}
+            return
+        )
+    }
+}
+
+impl Closure for SaturatingAddU64 {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let rhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs: u64 = rhs_lo as u64 + ((rhs_hi as u64) << 32);
+        let lhs: u64 = lhs_lo as u64 + ((lhs_hi as u64) << 32);
+
+        let saturated_sum = lhs.saturating_add(rhs);
+        stack.push(BFieldElement::new(saturated_sum >> 32));
+        stack.push(BFieldElement::new(saturated_sum & (u32::MAX as u64)));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<crate::snippet_bencher::BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let (lhs, rhs) = match bench_case {
+            Some(crate::snippet_bencher::BenchmarkCase::CommonCase) => {
+                (1u64 << 62, (1u64 << 62) - 1)
+            }
+            Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => (1u64 << 63, 1u64 << 63),
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => {
+                let mut rng = StdRng::from_seed(seed);
+                (rng.next_u64(), rng.next_u64())
+            }
+        };
+
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &lhs);
+        push_encodable(&mut stack, &rhs);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn u64_saturating_add_pbt() {
+        ShadowedClosure::new(SaturatingAddU64).test()
+    }
+
+    #[test]
+    fn u64_saturating_add_unit_test() {
+        for (lhs, rhs) in [
+            (0u64, 0u64),
+            (1, 1),
+            (u32::MAX as u64, 1),
+            (u64::MAX - 1, 1),
+            (u64::MAX, 0),
+            (u64::MAX, 1),
+            (1, u64::MAX),
+            (u64::MAX, u64::MAX),
+            (1 << 63, 1 << 63),
+        ] {
+            let mut init_stack = get_init_tvm_stack();
+            push_encodable(&mut init_stack, &lhs);
+            push_encodable(&mut init_stack, &rhs);
+            let expected = lhs.saturating_add(rhs);
+            let expected_final_stack = [
+                get_init_tvm_stack(),
+                vec![(expected >> 32).into(), (expected & u32::MAX as u64).into()],
+            ]
+            .concat();
+            let _vm_output_state = test_rust_equivalence_given_complete_state(
+                &ShadowedClosure::new(SaturatingAddU64),
+                &init_stack,
+                &[],
+                &NonDeterminism::new(vec![]),
+                &HashMap::default(),
+                &VmHasherState::new(Domain::VariableLength),
+                1,
+                Some(&expected_final_stack),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn u64_saturating_add_bench() {
+        ShadowedClosure::new(SaturatingAddU64).bench()
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::decr_u64::DecrU64;
+use crate::arithmetic::u64::eq_u64::EqU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// Decrement a `U64` by one, leaving `0` unchanged instead of crashing like
+/// [`DecrU64`] does.
+#[derive(Clone, Debug)]
+pub struct SaturatingDecrU64;
+
+impl DeprecatedSnippet for SaturatingDecrU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_saturating_decr".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["(value -| 1)_hi".to_string(), "(value -| 1)_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let eq_u64 = library.import(Box::new(EqU64));
+        let decr_u64 = library.import(Box::new(DecrU64));
+
+        format!(
+            "
+            // BEFORE: _ value_hi value_lo
+            // AFTER: _ (value -| 1)_hi (value -| 1)_lo
+            {entrypoint}:
+                dup 1
+                dup 1
+                push 0
+                push 0
+                call {eq_u64}
+                // _ value_hi value_lo (value == 0)
+
+                push 0
+                eq
+                skiz
+                    call {decr_u64}
+                // _ (value -| 1)_hi (value -| 1)_lo
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            prepare_state(0),
+            prepare_state(1),
+            prepare_state(1 << 32),
+            prepare_state(u64::MAX),
+            prepare_state(rng.next_u32() as u64),
+            prepare_state(rng.next_u64()),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state((1000 << 32) + 7)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(1000 << 32)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let value_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value = ((value_hi as u64) << 32) + value_lo as u64;
+
+        push_encodable(stack, &value.saturating_sub(1));
+    }
+}
+
+fn prepare_state(value: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &value);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+    use crate::DIGEST_LENGTH;
+
+    #[test]
+    fn saturating_decr_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&SaturatingDecrU64, true);
+    }
+
+    fn prop_saturating_decr(value: u64, expected: u64) {
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &expected);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SaturatingDecrU64,
+            &prepare_state(value).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn zero_stays_zero() {
+        prop_saturating_decr(0, 0);
+    }
+
+    #[test]
+    fn ordinary_values_match_decr_u64() {
+        for value in [1, 2, u32::MAX as u64, 1 << 32, (1 << 40) + 3, u64::MAX] {
+            prop_saturating_decr(value, value - 1);
+
+            let saturating = test_rust_equivalence_given_execution_state_deprecated(
+                &SaturatingDecrU64,
+                prepare_state(value),
+            );
+            let plain = test_rust_equivalence_given_execution_state_deprecated(
+                &DecrU64,
+                prepare_state(value),
+            );
+            // The bottom of the stack holds the program hash, which differs between snippets
+            assert_eq!(
+                plain.final_stack[DIGEST_LENGTH..],
+                saturating.final_stack[DIGEST_LENGTH..]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn saturating_decr_u64_benchmark() {
+        bench_and_write(SaturatingDecrU64);
+    }
+}
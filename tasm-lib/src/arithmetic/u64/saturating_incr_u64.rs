@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::eq_u64::EqU64;
+use crate::arithmetic::u64::incr_u64::IncrU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// Increment a `U64` by one, leaving `u64::MAX` unchanged instead of crashing like
+/// [`IncrU64`] does.
+#[derive(Clone, Debug)]
+pub struct SaturatingIncrU64;
+
+impl DeprecatedSnippet for SaturatingIncrU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_saturating_incr".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["(value +| 1)_hi".to_string(), "(value +| 1)_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let eq_u64 = library.import(Box::new(EqU64));
+        let incr_u64 = library.import(Box::new(IncrU64));
+        let u32_max = u32::MAX;
+
+        format!(
+            "
+            // BEFORE: _ value_hi value_lo
+            // AFTER: _ (value +| 1)_hi (value +| 1)_lo
+            {entrypoint}:
+                dup 1
+                dup 1
+                push {u32_max}
+                push {u32_max}
+                call {eq_u64}
+                // _ value_hi value_lo (value == u64::MAX)
+
+                push 0
+                eq
+                skiz
+                    call {incr_u64}
+                // _ (value +| 1)_hi (value +| 1)_lo
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            prepare_state(0),
+            prepare_state(u32::MAX as u64),
+            prepare_state(u64::MAX - 1),
+            prepare_state(u64::MAX),
+            prepare_state(rng.next_u32() as u64),
+            prepare_state(rng.next_u64()),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state((1000 << 32) + 7)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state((1000 << 32) + u32::MAX as u64)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let value_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let value = ((value_hi as u64) << 32) + value_lo as u64;
+
+        push_encodable(stack, &value.saturating_add(1));
+    }
+}
+
+fn prepare_state(value: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &value);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+    use crate::DIGEST_LENGTH;
+
+    #[test]
+    fn saturating_incr_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&SaturatingIncrU64, true);
+    }
+
+    fn prop_saturating_incr(value: u64, expected: u64) {
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &expected);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SaturatingIncrU64,
+            &prepare_state(value).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn u64_max_stays_max() {
+        prop_saturating_incr(u64::MAX, u64::MAX);
+    }
+
+    #[test]
+    fn ordinary_values_match_incr_u64() {
+        for value in [0, 1, u32::MAX as u64, 1 << 32, (1 << 40) + 3, u64::MAX - 1] {
+            prop_saturating_incr(value, value + 1);
+
+            let saturating = test_rust_equivalence_given_execution_state_deprecated(
+                &SaturatingIncrU64,
+                prepare_state(value),
+            );
+            let plain = test_rust_equivalence_given_execution_state_deprecated(
+                &IncrU64,
+                prepare_state(value),
+            );
+            // The bottom of the stack holds the program hash, which differs between snippets
+            assert_eq!(
+                plain.final_stack[DIGEST_LENGTH..],
+                saturating.final_stack[DIGEST_LENGTH..]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn saturating_incr_u64_benchmark() {
+        bench_and_write(SaturatingIncrU64);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::lt_u64::LtU64;
+use crate::arithmetic::u64::sub_u64::SubU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// Subtract two `U64`s, returning 0 if the difference would be negative.
+#[derive(Clone, Debug)]
+pub struct SaturatingSubU64;
+
+impl DeprecatedSnippet for SaturatingSubU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_saturating_sub".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "rhs_hi".to_string(),
+            "rhs_lo".to_string(),
+            "lhs_hi".to_string(),
+            "lhs_lo".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64, DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["(lhs - rhs)_hi".to_string(), "(lhs - rhs)_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let lt_u64 = library.import(Box::new(LtU64));
+        let sub_u64 = library.import(Box::new(SubU64));
+
+        format!(
+            "
+            // BEFORE: _ rhs_hi rhs_lo lhs_hi lhs_lo
+            // AFTER: _ diff_hi diff_lo
+            {entrypoint}:
+                call {lt_u64}
+                // _ rhs_hi rhs_lo lhs_hi lhs_lo (lhs < rhs)
+
+                skiz
+                    call {entrypoint}_clamp
+                // _ rhs_hi rhs_lo lhs_hi' lhs_lo'
+
+                call {sub_u64}
+                // _ diff_hi diff_lo
+
+                return
+
+            // Replace `lhs` with `rhs` such that the difference becomes 0.
+            // BEFORE: _ rhs_hi rhs_lo lhs_hi lhs_lo
+            // AFTER: _ rhs_hi rhs_lo rhs_hi rhs_lo
+            {entrypoint}_clamp:
+                pop
+                pop
+                dup 1
+                dup 1
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Inputs are not u32s".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let mut ret = vec![prepare_state(0, 1), prepare_state(u64::MAX, u64::MAX)];
+        for _ in 0..20 {
+            ret.push(prepare_state(rng.gen(), rng.gen()));
+            let lhs: u64 = rng.gen();
+            ret.push(prepare_state(lhs, rng.gen_range(0..=lhs)));
+        }
+
+        ret
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(1 << 40, (1 << 40) - 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state((1 << 40) - 1, 1 << 40)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let lhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let rhs_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let lhs = ((lhs_hi as u64) << 32) + lhs_lo as u64;
+        let rhs = ((rhs_hi as u64) << 32) + rhs_lo as u64;
+
+        push_encodable(stack, &lhs.saturating_sub(rhs));
+    }
+}
+
+fn prepare_state(lhs: u64, rhs: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &rhs);
+    push_encodable(&mut stack, &lhs);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn saturating_sub_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&SaturatingSubU64, true);
+    }
+
+    #[test]
+    fn saturating_sub_u64_in_range() {
+        prop_saturating_sub(0, 0);
+        prop_saturating_sub(256, 129);
+        prop_saturating_sub(1 << 32, 1);
+        prop_saturating_sub(u64::MAX, 1);
+        prop_saturating_sub(u64::MAX, u64::MAX);
+    }
+
+    #[test]
+    fn saturating_sub_u64_underflow_gives_zero() {
+        prop_saturating_sub(0, 1);
+        prop_saturating_sub(1 << 32, (1 << 32) + 1);
+        prop_saturating_sub((1 << 32) - 1, 1 << 32);
+        prop_saturating_sub(0, u64::MAX);
+    }
+
+    fn prop_saturating_sub(lhs: u64, rhs: u64) {
+        let mut expected = get_init_tvm_stack();
+        push_encodable(&mut expected, &lhs.saturating_sub(rhs));
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SaturatingSubU64,
+            &prepare_state(lhs, rhs).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn saturating_sub_u64_benchmark() {
+        bench_and_write(SaturatingSubU64);
+    }
+}
@@ -171,6 +171,17 @@ fn prepare_state(value: u64, shift_amount: u32) -> ExecutionState {
     ExecutionState::with_stack(init_stack)
 }
 
+#[cfg(feature = "proptest")]
+impl crate::test_helpers::ArbitraryInputState for ShiftRightU64 {
+    fn arbitrary_input_state(&self) -> proptest::strategy::BoxedStrategy<ExecutionState> {
+        use proptest::prelude::*;
+
+        (any::<u64>(), 0u32..64)
+            .prop_map(|(value, shift_amount)| prepare_state(value, shift_amount))
+            .boxed()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -231,6 +242,106 @@ mod tests {
             Some(&expected_stack),
         );
     }
+
+    #[cfg(feature = "proptest")]
+    #[test]
+    fn shift_right_u64_proptest() {
+        crate::test_helpers::proptest_rust_equivalence(&ShiftRightU64);
+    }
+}
+
+/// A `ShiftRightU64` whose rust shadowing wrongly reduces the shift amount modulo 32, used
+/// to check that property tests shrink a failure to a minimal counterexample.
+#[cfg(all(test, feature = "proptest"))]
+mod broken_shadow_tests {
+    use std::collections::HashMap;
+
+    use proptest::test_runner::TestError;
+
+    use super::*;
+    use crate::test_helpers::{proptest_rust_equivalence_with_seed, ArbitraryInputState};
+
+    #[derive(Clone, Debug)]
+    struct BrokenShiftRightU64;
+
+    impl DeprecatedSnippet for BrokenShiftRightU64 {
+        fn entrypoint_name(&self) -> String {
+            ShiftRightU64.entrypoint_name()
+        }
+
+        fn input_field_names(&self) -> Vec<String> {
+            ShiftRightU64.input_field_names()
+        }
+
+        fn input_types(&self) -> Vec<DataType> {
+            ShiftRightU64.input_types()
+        }
+
+        fn output_types(&self) -> Vec<DataType> {
+            ShiftRightU64.output_types()
+        }
+
+        fn output_field_names(&self) -> Vec<String> {
+            ShiftRightU64.output_field_names()
+        }
+
+        fn stack_diff(&self) -> isize {
+            ShiftRightU64.stack_diff()
+        }
+
+        fn function_code(&self, library: &mut Library) -> String {
+            ShiftRightU64.function_code(library)
+        }
+
+        fn crash_conditions(&self) -> Vec<String> {
+            ShiftRightU64.crash_conditions()
+        }
+
+        fn gen_input_states(&self) -> Vec<ExecutionState> {
+            ShiftRightU64.gen_input_states()
+        }
+
+        fn common_case_input_state(&self) -> ExecutionState {
+            ShiftRightU64.common_case_input_state()
+        }
+
+        fn worst_case_input_state(&self) -> ExecutionState {
+            ShiftRightU64.worst_case_input_state()
+        }
+
+        fn rust_shadowing(
+            &self,
+            stack: &mut Vec<BFieldElement>,
+            std_in: Vec<BFieldElement>,
+            secret_in: Vec<BFieldElement>,
+            memory: &mut HashMap<BFieldElement, BFieldElement>,
+        ) {
+            let shift_amount = stack.pop().unwrap().value() % 32;
+            stack.push(BFieldElement::new(shift_amount));
+            ShiftRightU64.rust_shadowing(stack, std_in, secret_in, memory);
+        }
+    }
+
+    impl ArbitraryInputState for BrokenShiftRightU64 {
+        fn arbitrary_input_state(&self) -> proptest::strategy::BoxedStrategy<ExecutionState> {
+            ShiftRightU64.arbitrary_input_state()
+        }
+    }
+
+    #[test]
+    fn broken_shadow_is_shrunk_to_minimal_shift_amount() {
+        let Err(TestError::Fail(_reason, counterexample)) =
+            proptest_rust_equivalence_with_seed(&BrokenShiftRightU64, [42; 32])
+        else {
+            panic!("property test must find the broken shadow");
+        };
+
+        // The smallest shift amount that the broken shadow gets wrong is 32.
+        assert_eq!(
+            BFieldElement::new(32),
+            *counterexample.stack.last().unwrap()
+        );
+    }
 }
 
 #[cfg(test)]
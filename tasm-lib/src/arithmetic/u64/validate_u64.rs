@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Crash the VM unless both limbs of the u64 on top of the stack are valid u32s. The
+/// value is left on the stack, so a u64 read from an untrusted source can be validated
+/// in place before any arithmetic relies on its limbs.
+#[derive(Clone, Debug)]
+pub struct ValidateU64;
+
+impl ValidateU64 {
+    fn prepare_state(value_hi: u64, value_lo: u64) -> ExecutionState {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(value_hi));
+        stack.push(BFieldElement::new(value_lo));
+        ExecutionState::with_stack(stack)
+    }
+}
+
+impl DeprecatedSnippet for ValidateU64 {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_u64_validate".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+
+        format!(
+            "
+            // BEFORE: _ value_hi value_lo
+            // AFTER: _ value_hi value_lo
+            {entrypoint}:
+                dup 1
+                split
+                pop
+                push 0
+                eq
+                assert
+                // _ value_hi value_lo
+
+                dup 0
+                split
+                pop
+                push 0
+                eq
+                assert
+                // _ value_hi value_lo
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "value_hi is not a u32".to_string(),
+            "value_lo is not a u32".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            Self::prepare_state(0, 0),
+            Self::prepare_state(u32::MAX as u64, u32::MAX as u64),
+            Self::prepare_state(rng.next_u32() as u64, rng.next_u32() as u64),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(1 << 20, 1 << 31)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(u32::MAX as u64, u32::MAX as u64)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let value_lo = stack[stack.len() - 1];
+        let value_hi = stack[stack.len() - 2];
+        let _: u32 = value_hi.value().try_into().expect("value_hi must be a u32");
+        let _: u32 = value_lo.value().try_into().expect("value_lo must be a u32");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn validate_u64_test() {
+        test_rust_equivalence_multiple_deprecated(&ValidateU64, true);
+    }
+
+    #[test]
+    fn validate_u64_leaves_value_on_stack() {
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &ValidateU64,
+            ValidateU64::prepare_state(14, u32::MAX as u64),
+        );
+        let final_stack = vm_output_state.final_stack;
+        assert_eq!(
+            vec![BFieldElement::new(14), BFieldElement::new(u32::MAX as u64)],
+            final_stack[final_stack.len() - 2..].to_vec()
+        );
+    }
+
+    #[test]
+    fn validate_u64_non_canonical_limb_tasm_test() {
+        for (value_hi, value_lo) in [(1 << 32, 0), (0, 1 << 32)] {
+            let mut stack = ValidateU64::prepare_state(value_hi, value_lo).stack;
+            assert!(ValidateU64
+                .link_and_run_tasm_for_test(
+                    &mut stack,
+                    vec![],
+                    vec![],
+                    &mut HashMap::default(),
+                    None
+                )
+                .is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn validate_u64_non_canonical_hi_rust_test() {
+        let mut stack = ValidateU64::prepare_state(1 << 32, 0).stack;
+        ValidateU64.rust_shadowing(&mut stack, vec![], vec![], &mut HashMap::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn validate_u64_non_canonical_lo_rust_test() {
+        let mut stack = ValidateU64::prepare_state(0, 1 << 32).stack;
+        ValidateU64.rust_shadowing(&mut stack, vec![], vec![], &mut HashMap::default());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn validate_u64_benchmark() {
+        bench_and_write(ValidateU64);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bfe_to_xfe;
+pub mod horner;
+pub mod inner_product;
+pub mod xfe_inverse;
+pub mod xfe_mul;
+pub mod xfe_pow;
+pub mod xfe_try_to_bfe;
@@ -0,0 +1,129 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack, push_encodable,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Lift a base field element into the extension field, making it the constant
+/// coefficient. The inverse is [`XfeTryToBfe`](super::xfe_try_to_bfe::XfeTryToBfe).
+pub struct BfeToXfe;
+
+impl BasicSnippet for BfeToXfe {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::BFE, "value".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "lifted".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_xfe_bfe_to_xfe".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        triton_asm!(
+            // BEFORE: _ value
+            // AFTER: _ 0 0 value
+            { self.entrypoint() }:
+                push 0
+                push 0
+                swap 2
+                return
+        )
+    }
+}
+
+impl Closure for BfeToXfe {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let value = stack.pop().unwrap();
+        push_encodable(stack, &XFieldElement::new_const(value));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let mut stack = get_init_tvm_stack();
+        stack.push(rng.gen());
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::arithmetic::xfe::xfe_try_to_bfe::XfeTryToBfe;
+    use crate::closure::ShadowedClosure;
+    use crate::pop_encodable;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn bfe_to_xfe_pbt() {
+        ShadowedClosure::new(BfeToXfe).test()
+    }
+
+    #[test]
+    fn lift_random_bfe_and_project_back() {
+        let value: BFieldElement = random();
+        let init_stack = [get_init_tvm_stack(), vec![value]].concat();
+        let mut expected_lifted_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_lifted_stack, &XFieldElement::new_const(value));
+
+        let lifted_stack = test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(BfeToXfe),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            Some(&expected_lifted_stack),
+        )
+        .final_stack;
+        assert_eq!(
+            XFieldElement::new_const(value),
+            pop_encodable::<XFieldElement>(&mut lifted_stack.clone())
+        );
+
+        test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(XfeTryToBfe),
+            &lifted_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            Some(&init_stack),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn bfe_to_xfe_bench() {
+        ShadowedClosure::new(BfeToXfe).bench()
+    }
+}
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::Zero;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+use crate::arithmetic::xfe::xfe_mul::XfeMul;
+use crate::library::Library;
+use crate::list::safeimplu32::get::SafeGet;
+use crate::list::safeimplu32::length::Length as SafeLength;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_insert,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, pop_encodable, push_encodable, ExecutionState};
+
+/// Evaluate a polynomial, given as a safe list of `XFE` coefficients in order of
+/// increasing degree, in an `XFE` point using Horner's rule. The empty polynomial
+/// evaluates to zero.
+#[derive(Clone, Debug)]
+pub struct HornerEvaluation;
+
+impl HornerEvaluation {
+    fn prepare_state(
+        &self,
+        coefficients: Vec<XFieldElement>,
+        point: XFieldElement,
+    ) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64 + 1);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            list_pointer,
+            coefficients.len() as u32,
+            coefficients,
+            &mut memory,
+        );
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        push_encodable(&mut stack, &point);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    fn random_state(&self, num_coefficients: usize) -> ExecutionState {
+        let coefficients = (0..num_coefficients).map(|_| random()).collect_vec();
+        self.prepare_state(coefficients, random())
+    }
+}
+
+impl DeprecatedSnippet for HornerEvaluation {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_xfe_horner_evaluation".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "*coefficients".to_string(),
+            "point_2".to_string(),
+            "point_1".to_string(),
+            "point_0".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::XFE)), DataType::XFE]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![
+            "result_2".to_string(),
+            "result_1".to_string(),
+            "result_0".to_string(),
+        ]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::XFE]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let get_length = library.import(Box::new(SafeLength(DataType::XFE)));
+        let get_element = library.import(Box::new(SafeGet(DataType::XFE)));
+        let xfe_mul = library.import(Box::new(XfeMul));
+
+        format!(
+            "
+            // BEFORE: _ *coefficients point_2 point_1 point_0
+            // AFTER: _ result_2 result_1 result_0
+            {entrypoint}:
+                dup 3
+                call {get_length}
+                // _ *coefficients [point] i
+
+                push 0
+                push 0
+                push 0
+                // _ *coefficients [point] i [acc]
+
+                call {entrypoint}_loop
+                // _ *coefficients [point] 0 [acc]
+
+                swap 5
+                pop
+                swap 5
+                pop
+                swap 5
+                pop
+                pop
+                pop
+                // _ [acc]
+
+                return
+
+            // INVARIANT: _ *coefficients [point] i [acc]
+            {entrypoint}_loop:
+                dup 3
+                push 0
+                eq
+                skiz
+                    return
+
+                dup 6
+                dup 6
+                dup 6
+                call {xfe_mul}
+                // _ *coefficients [point] i [acc * point]
+
+                swap 3
+                push -1
+                add
+                swap 3
+                // _ *coefficients [point] (i - 1) [acc * point]
+
+                dup 7
+                dup 4
+                call {get_element}
+                // _ *coefficients [point] (i - 1) [acc * point] [coefficient]
+
+                xxadd
+                swap 3
+                pop
+                swap 3
+                pop
+                swap 3
+                pop
+                // _ *coefficients [point] (i - 1) [acc * point + coefficient]
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.random_state(0),
+            self.random_state(1),
+            self.random_state(2),
+            self.random_state(rng.gen_range(3..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.random_state(16)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.random_state(256)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let point = pop_encodable::<XFieldElement>(stack);
+        let list_pointer = stack.pop().unwrap();
+
+        let length = safe_list_get_length(list_pointer, memory);
+        let mut acc = XFieldElement::zero();
+        for i in (0..length).rev() {
+            let words = safe_list_get(list_pointer, i, memory, DataType::XFE.get_size());
+            let coefficient = XFieldElement::new(words.try_into().unwrap());
+            acc = acc * point + coefficient;
+        }
+
+        push_encodable(stack, &acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::polynomial::Polynomial;
+
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn horner_evaluation_test() {
+        test_rust_equivalence_multiple_deprecated(&HornerEvaluation, true);
+    }
+
+    fn prop_horner_evaluation(coefficients: Vec<XFieldElement>, point: XFieldElement) {
+        let expected_result = Polynomial::new(coefficients.clone()).evaluate(&point);
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &expected_result);
+
+        let mut init_state = HornerEvaluation.prepare_state(coefficients, point);
+        test_rust_equivalence_given_input_values_deprecated(
+            &HornerEvaluation,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn empty_polynomial_evaluates_to_zero() {
+        let mut init_state = HornerEvaluation.prepare_state(vec![], random());
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &XFieldElement::zero());
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &HornerEvaluation,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn constant_polynomial() {
+        prop_horner_evaluation(vec![random()], random());
+    }
+
+    #[test]
+    fn linear_polynomial() {
+        prop_horner_evaluation(vec![random(), random()], random());
+    }
+
+    #[test]
+    fn degree_five_polynomial() {
+        let coefficients = (0..6).map(|_| random()).collect_vec();
+        prop_horner_evaluation(coefficients, random());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn horner_evaluation_benchmark() {
+        bench_and_write(HornerEvaluation);
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::Zero;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+use crate::arithmetic::xfe::xfe_mul::XfeMul;
+use crate::library::Library;
+use crate::list::safeimplu32::get::SafeGet;
+use crate::list::safeimplu32::length::Length as SafeLength;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_insert,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// Compute the inner product of two safe lists of `XFE`s of equal length. The inner
+/// product of two empty lists is zero. Crashes if the lengths differ.
+#[derive(Clone, Debug)]
+pub struct XfeInnerProduct;
+
+impl XfeInnerProduct {
+    fn prepare_state(&self, a: Vec<XFieldElement>, b: Vec<XFieldElement>) -> ExecutionState {
+        let a_pointer = BFieldElement::new(random::<u32>() as u64 + 1);
+        let b_pointer = BFieldElement::new(random::<u32>() as u64 + (1 << 33));
+        let mut memory = HashMap::default();
+        safe_list_insert(a_pointer, a.len() as u32, a, &mut memory);
+        safe_list_insert(b_pointer, b.len() as u32, b, &mut memory);
+
+        let stack = [get_init_tvm_stack(), vec![a_pointer, b_pointer]].concat();
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    fn random_state(&self, length: usize) -> ExecutionState {
+        let a = (0..length).map(|_| random()).collect_vec();
+        let b = (0..length).map(|_| random()).collect_vec();
+        self.prepare_state(a, b)
+    }
+}
+
+impl DeprecatedSnippet for XfeInnerProduct {
+    fn entrypoint_name(&self) -> String {
+        "tasm_arithmetic_xfe_inner_product".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list_a".to_string(), "*list_b".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(DataType::XFE)),
+            DataType::List(Box::new(DataType::XFE)),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![
+            "result_2".to_string(),
+            "result_1".to_string(),
+            "result_0".to_string(),
+        ]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::XFE]
+    }
+
+    fn stack_diff(&self) -> isize {
+        1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let get_length = library.import(Box::new(SafeLength(DataType::XFE)));
+        let get_element = library.import(Box::new(SafeGet(DataType::XFE)));
+        let xfe_mul = library.import(Box::new(XfeMul));
+
+        format!(
+            "
+            // BEFORE: _ *list_a *list_b
+            // AFTER: _ result_2 result_1 result_0
+            {entrypoint}:
+                dup 1
+                call {get_length}
+                dup 1
+                call {get_length}
+                // _ *list_a *list_b length_a length_b
+
+                dup 1
+                eq
+                assert
+                // _ *list_a *list_b i
+
+                push 0
+                push 0
+                push 0
+                // _ *list_a *list_b i [acc]
+
+                call {entrypoint}_loop
+                // _ *list_a *list_b 0 [acc]
+
+                swap 3
+                pop
+                swap 3
+                pop
+                swap 3
+                pop
+                // _ [acc]
+
+                return
+
+            // INVARIANT: _ *list_a *list_b i [acc]
+            {entrypoint}_loop:
+                dup 3
+                push 0
+                eq
+                skiz
+                    return
+
+                swap 3
+                push -1
+                add
+                swap 3
+                // _ *list_a *list_b (i - 1) [acc]
+
+                dup 5
+                dup 4
+                call {get_element}
+                // _ *list_a *list_b (i - 1) [acc] [a_i]
+
+                dup 7
+                dup 7
+                call {get_element}
+                // _ *list_a *list_b (i - 1) [acc] [a_i] [b_i]
+
+                call {xfe_mul}
+                // _ *list_a *list_b (i - 1) [acc] [a_i * b_i]
+
+                xxadd
+                swap 3
+                pop
+                swap 3
+                pop
+                swap 3
+                pop
+                // _ *list_a *list_b (i - 1) [acc + a_i * b_i]
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Lists have different lengths".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.random_state(0),
+            self.random_state(1),
+            self.random_state(2),
+            self.random_state(rng.gen_range(3..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.random_state(16)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.random_state(256)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let b_pointer = stack.pop().unwrap();
+        let a_pointer = stack.pop().unwrap();
+
+        let length = safe_list_get_length(a_pointer, memory);
+        assert_eq!(
+            length,
+            safe_list_get_length(b_pointer, memory),
+            "Lists must have the same length"
+        );
+
+        let get_xfe = |list_pointer, i| {
+            let words = safe_list_get(list_pointer, i, memory, DataType::XFE.get_size());
+            XFieldElement::new(words.try_into().unwrap())
+        };
+        let mut acc = XFieldElement::zero();
+        for i in 0..length {
+            acc = acc + get_xfe(a_pointer, i) * get_xfe(b_pointer, i);
+        }
+
+        push_encodable(stack, &acc);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn xfe_inner_product_test() {
+        test_rust_equivalence_multiple_deprecated(&XfeInnerProduct, true);
+    }
+
+    fn prop_xfe_inner_product(length: usize) {
+        let a: Vec<XFieldElement> = (0..length).map(|_| random()).collect_vec();
+        let b: Vec<XFieldElement> = (0..length).map(|_| random()).collect_vec();
+        let expected_result = a
+            .iter()
+            .zip_eq(b.iter())
+            .fold(XFieldElement::zero(), |acc, (&a_i, &b_i)| acc + a_i * b_i);
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &expected_result);
+
+        let mut init_state = XfeInnerProduct.prepare_state(a, b);
+        test_rust_equivalence_given_input_values_deprecated(
+            &XfeInnerProduct,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn empty_lists() {
+        prop_xfe_inner_product(0);
+    }
+
+    #[test]
+    fn single_element_lists() {
+        prop_xfe_inner_product(1);
+    }
+
+    #[test]
+    fn multi_element_lists() {
+        prop_xfe_inner_product(2);
+        prop_xfe_inner_product(7);
+    }
+
+    #[should_panic]
+    #[test]
+    fn mismatched_lengths() {
+        let a = (0..3).map(|_| random()).collect_vec();
+        let b = (0..4).map(|_| random()).collect_vec();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &XfeInnerProduct,
+            XfeInnerProduct.prepare_state(a, b),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn xfe_inner_product_benchmark() {
+        bench_and_write(XfeInnerProduct);
+    }
+}
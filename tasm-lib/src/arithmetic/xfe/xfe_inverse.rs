@@ -0,0 +1,136 @@
+use num::Zero;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::{traits::Inverse, x_field_element::XFieldElement};
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack, pop_encodable, push_encodable,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Compute the multiplicative inverse of an extension field element. Crashes the VM if
+/// the element is zero.
+pub struct XfeInverse;
+
+impl BasicSnippet for XfeInverse {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "value".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "inverse".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_xfe_inverse".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        triton_asm!(
+            // BEFORE: _ value_2 value_1 value_0
+            // AFTER: _ inverse_2 inverse_1 inverse_0
+            { self.entrypoint() }:
+                // crashes if the value is zero
+                xinvert
+                return
+        )
+    }
+}
+
+impl Closure for XfeInverse {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let value = pop_encodable::<XFieldElement>(stack);
+        assert!(!value.is_zero(), "cannot invert zero");
+        push_encodable(stack, &value.inverse());
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let mut value = XFieldElement::new([rng.gen(), rng.gen(), rng.gen()]);
+        while value.is_zero() {
+            value = XFieldElement::new([rng.gen(), rng.gen(), rng.gen()]);
+        }
+
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &value);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::One;
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn xfe_inverse_pbt() {
+        ShadowedClosure::new(XfeInverse).test()
+    }
+
+    fn run_xfe_inverse(value: XFieldElement) -> XFieldElement {
+        let mut init_stack = get_init_tvm_stack();
+        push_encodable(&mut init_stack, &value);
+
+        let vm_output_state = test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(XfeInverse),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            None,
+        );
+
+        let mut final_stack = vm_output_state.final_stack;
+        pop_encodable::<XFieldElement>(&mut final_stack)
+    }
+
+    #[test]
+    fn xfe_inverse_times_value_is_one() {
+        for value in [
+            XFieldElement::one(),
+            XFieldElement::new_const(BFieldElement::new(42)),
+            random(),
+        ] {
+            let inverse = run_xfe_inverse(value);
+            assert_eq!(XFieldElement::one(), value * inverse);
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn xfe_inverse_of_zero() {
+        run_xfe_inverse(XFieldElement::zero());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn xfe_inverse_bench() {
+        ShadowedClosure::new(XfeInverse).bench()
+    }
+}
@@ -0,0 +1,142 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack, pop_encodable, push_encodable,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Multiply two extension field elements.
+pub struct XfeMul;
+
+impl BasicSnippet for XfeMul {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![
+            (DataType::XFE, "lhs".to_string()),
+            (DataType::XFE, "rhs".to_string()),
+        ]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "product".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_xfe_mul".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        triton_asm!(
+            // BEFORE: _ lhs_2 lhs_1 lhs_0 rhs_2 rhs_1 rhs_0
+            // AFTER: _ product_2 product_1 product_0
+            { self.entrypoint() }:
+                xxmul
+                // _ lhs_2 lhs_1 lhs_0 product_2 product_1 product_0
+
+                swap 3
+                pop
+                swap 3
+                pop
+                swap 3
+                pop
+                // _ product_2 product_1 product_0
+
+                return
+        )
+    }
+}
+
+impl Closure for XfeMul {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let rhs = pop_encodable::<XFieldElement>(stack);
+        let lhs = pop_encodable::<XFieldElement>(stack);
+        push_encodable(stack, &(lhs * rhs));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let lhs = XFieldElement::new([rng.gen(), rng.gen(), rng.gen()]);
+        let rhs = XFieldElement::new([rng.gen(), rng.gen(), rng.gen()]);
+
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &lhs);
+        push_encodable(&mut stack, &rhs);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::One;
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn xfe_mul_pbt() {
+        ShadowedClosure::new(XfeMul).test()
+    }
+
+    fn prop_xfe_mul(lhs: XFieldElement, rhs: XFieldElement, expected: XFieldElement) {
+        let mut init_stack = get_init_tvm_stack();
+        push_encodable(&mut init_stack, &lhs);
+        push_encodable(&mut init_stack, &rhs);
+
+        let mut expected_final_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_final_stack, &expected);
+
+        test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(XfeMul),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            Some(&expected_final_stack),
+        );
+    }
+
+    #[test]
+    fn xfe_mul_by_one_unit_test() {
+        let a: XFieldElement = random();
+        prop_xfe_mul(a, XFieldElement::one(), a);
+        prop_xfe_mul(XFieldElement::one(), a, a);
+    }
+
+    #[test]
+    fn xfe_mul_lifted_bfes_unit_test() {
+        let a = XFieldElement::new_const(BFieldElement::new(6));
+        let b = XFieldElement::new_const(BFieldElement::new(7));
+        prop_xfe_mul(a, b, XFieldElement::new_const(BFieldElement::new(42)));
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn xfe_mul_bench() {
+        ShadowedClosure::new(XfeMul).bench()
+    }
+}
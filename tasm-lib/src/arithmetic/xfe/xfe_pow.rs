@@ -0,0 +1,222 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::{traits::ModPowU32, x_field_element::XFieldElement};
+
+use crate::{
+    arithmetic::xfe::xfe_mul::XfeMul,
+    closure::Closure,
+    get_init_tvm_stack, pop_encodable, push_encodable,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Raise an extension field element to a `u32` power using square-and-multiply.
+pub struct XfePow;
+
+impl BasicSnippet for XfePow {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![
+            (DataType::XFE, "base".to_string()),
+            (DataType::U32, "exponent".to_string()),
+        ]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "power".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_xfe_pow".to_string()
+    }
+
+    fn code(
+        &self,
+        library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let xfe_mul = library.import(Box::new(XfeMul));
+
+        triton_asm!(
+            // BEFORE: _ base_2 base_1 base_0 exponent
+            // AFTER: _ power_2 power_1 power_0
+            {entrypoint}:
+                swap 3
+                swap 2
+                swap 1
+                // _ exponent base_2 base_1 base_0
+
+                push 0
+                push 0
+                push 1
+                // _ i [bpow2] [acc]
+
+                call {entrypoint}_loop
+                // _ 0 [bpow2] [acc]
+
+                swap 4
+                pop
+                swap 4
+                pop
+                swap 4
+                pop
+                pop
+                // _ [acc]
+
+                return
+
+            // INVARIANT: _ i [bpow2] [acc]
+            {entrypoint}_loop:
+                dup 6
+                push 0
+                eq
+                skiz
+                    return
+
+                push 2
+                dup 7
+                div
+                // _ i [bpow2] [acc] (i >> 1) (i & 1)
+
+                swap 1
+                swap 8
+                pop
+                // _ (i >> 1) [bpow2] [acc] (i & 1)
+
+                skiz
+                    call {entrypoint}_mul_acc_with_bpow2
+                // _ (i >> 1) [bpow2] [acc]
+
+                dup 5
+                dup 5
+                dup 5
+                dup 2
+                dup 2
+                dup 2
+                call {xfe_mul}
+                // _ (i >> 1) [bpow2] [acc] [bpow2 * bpow2]
+
+                swap 6
+                pop
+                swap 6
+                pop
+                swap 6
+                pop
+                // _ (i >> 1) [bpow2 * bpow2] [acc]
+
+                recurse
+
+            // BEFORE: _ i [bpow2] [acc]
+            // AFTER: _ i [bpow2] [acc * bpow2]
+            {entrypoint}_mul_acc_with_bpow2:
+                dup 5
+                dup 5
+                dup 5
+                call {xfe_mul}
+                return
+        )
+    }
+}
+
+impl Closure for XfePow {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let exponent: u32 = stack.pop().unwrap().try_into().unwrap();
+        let base = pop_encodable::<XFieldElement>(stack);
+        push_encodable(stack, &base.mod_pow_u32(exponent));
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let base = XFieldElement::new([rng.gen(), rng.gen(), rng.gen()]);
+        let exponent: u32 = match bench_case {
+            Some(BenchmarkCase::CommonCase) => 1 << 10,
+            Some(BenchmarkCase::WorstCase) => u32::MAX,
+            Some(BenchmarkCase::Custom(_)) | None => rng.gen(),
+        };
+
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &base);
+        stack.push(BFieldElement::new(exponent as u64));
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use num::{One, Zero};
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn xfe_pow_pbt() {
+        ShadowedClosure::new(XfePow).test()
+    }
+
+    fn prop_xfe_pow(base: XFieldElement, exponent: u32, expected: XFieldElement) {
+        let mut init_stack = get_init_tvm_stack();
+        push_encodable(&mut init_stack, &base);
+        init_stack.push(BFieldElement::new(exponent as u64));
+
+        let mut expected_final_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_final_stack, &expected);
+
+        test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(XfePow),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            Some(&expected_final_stack),
+        );
+    }
+
+    /// Exponentiation by repeated multiplication, as a reference.
+    fn naive_pow(base: XFieldElement, exponent: u32) -> XFieldElement {
+        (0..exponent).fold(XFieldElement::one(), |acc, _| acc * base)
+    }
+
+    #[test]
+    fn xfe_pow_zero_exponent_unit_test() {
+        prop_xfe_pow(random(), 0, XFieldElement::one());
+        prop_xfe_pow(XFieldElement::zero(), 0, XFieldElement::one());
+    }
+
+    #[test]
+    fn xfe_pow_one_exponent_unit_test() {
+        let base: XFieldElement = random();
+        prop_xfe_pow(base, 1, base);
+    }
+
+    #[test]
+    fn xfe_pow_large_exponent_unit_test() {
+        let base: XFieldElement = random();
+        for exponent in [2, 3, 0b1011_0110, 1000] {
+            prop_xfe_pow(base, exponent, naive_pow(base, exponent));
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn xfe_pow_bench() {
+        ShadowedClosure::new(XfePow).bench()
+    }
+}
@@ -0,0 +1,150 @@
+use num::Zero;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+use twenty_first::shared_math::x_field_element::XFieldElement;
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack, pop_encodable, push_encodable,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Project an extension field element onto its constant coefficient. Crashes the VM if
+/// any of the other coefficients is nonzero, _i.e._, if the element does not lie in the
+/// base field. The inverse is [`BfeToXfe`](super::bfe_to_xfe::BfeToXfe).
+pub struct XfeTryToBfe;
+
+impl BasicSnippet for XfeTryToBfe {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::XFE, "value".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::BFE, "projected".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_arithmetic_xfe_try_to_bfe".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        triton_asm!(
+            // BEFORE: _ value_2 value_1 value_0
+            // AFTER: _ value_0
+            { self.entrypoint() }:
+                swap 2
+                // _ value_0 value_1 value_2
+
+                push 0
+                eq
+                assert
+                push 0
+                eq
+                assert
+                // _ value_0
+
+                return
+        )
+    }
+}
+
+impl Closure for XfeTryToBfe {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        let value = pop_encodable::<XFieldElement>(stack);
+        assert!(
+            value.coefficients[1].is_zero() && value.coefficients[2].is_zero(),
+            "cannot project extension field element with nonzero higher coefficients"
+        );
+        stack.push(value.coefficients[0]);
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let value = XFieldElement::new_const(rng.gen());
+
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &value);
+        stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rand::random;
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    #[test]
+    fn xfe_try_to_bfe_pbt() {
+        ShadowedClosure::new(XfeTryToBfe).test()
+    }
+
+    fn run_xfe_try_to_bfe(value: XFieldElement) -> BFieldElement {
+        let mut init_stack = get_init_tvm_stack();
+        push_encodable(&mut init_stack, &value);
+
+        let vm_output_state = test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(XfeTryToBfe),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::default(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            None,
+        );
+
+        *vm_output_state.final_stack.last().unwrap()
+    }
+
+    #[test]
+    fn project_lifted_bfe() {
+        let value: BFieldElement = random();
+        assert_eq!(value, run_xfe_try_to_bfe(XFieldElement::new_const(value)));
+        assert_eq!(
+            BFieldElement::zero(),
+            run_xfe_try_to_bfe(XFieldElement::zero())
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn project_degree_two_xfe() {
+        let one = BFieldElement::new(1);
+        run_xfe_try_to_bfe(XFieldElement::new([one, BFieldElement::zero(), one]));
+    }
+
+    #[should_panic]
+    #[test]
+    fn project_degree_one_xfe() {
+        let one = BFieldElement::new(1);
+        run_xfe_try_to_bfe(XFieldElement::new([one, one, BFieldElement::zero()]));
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn xfe_try_to_bfe_bench() {
+        ShadowedClosure::new(XfeTryToBfe).bench()
+    }
+}
@@ -1,8 +1,9 @@
-use std::cmp::min;
+use std::cmp::{max, min};
+use std::collections::HashMap;
 
-use crate::library::Library;
+use crate::library::{instruction_count, Library};
 use anyhow::Result;
-use triton_vm::instruction::LabelledInstruction;
+use triton_vm::instruction::{Instruction, LabelledInstruction};
 use triton_vm::program::{ProfileLine, Program};
 use triton_vm::{NonDeterminism, PublicInput};
 use twenty_first::shared_math::b_field_element::BFieldElement;
@@ -29,20 +30,244 @@ pub trait CompiledProgram {
         p.run(public_input.clone(), nondeterminism.clone())
     }
 
+    /// Like [`Self::run`], but if the VM crashes, report the state it crashed in.
+    fn run_with_debug(
+        public_input: &PublicInput,
+        nondeterminism: &NonDeterminism<BFieldElement>,
+    ) -> std::result::Result<Vec<BFieldElement>, CrashDiagnostics> {
+        let p = Self::program();
+        match p.debug_terminal_state(public_input.clone(), nondeterminism.clone(), None, None) {
+            Ok(terminal_state) => Ok(terminal_state.public_output),
+            Err((error, crash_state)) => Err(CrashDiagnostics {
+                error: error.to_string(),
+                final_stack: crash_state.op_stack.stack.clone(),
+                cycle_count: crash_state.cycle_count as usize,
+                instruction: crash_state.current_instruction().ok(),
+            }),
+        }
+    }
+
     fn code() -> (Vec<LabelledInstruction>, Library);
 
+    /// The total number of instructions of the program, including all imported
+    /// snippets. Labels are not counted.
+    fn code_len() -> usize {
+        let (program_instructions, library) = Self::code();
+        instruction_count(&program_instructions) + library.assembled_instruction_count()
+    }
+
+    /// How often each instruction occurs in the program, including all imported
+    /// snippets, keyed on the instruction's name, e.g., `swap` or `dup`, disregarding
+    /// its argument.
+    fn instruction_histogram() -> HashMap<String, usize> {
+        let (program_instructions, library) = Self::code();
+        let mut histogram = HashMap::new();
+        for instruction in [program_instructions, library.all_imports()].concat() {
+            if let LabelledInstruction::Instruction(instruction) = instruction {
+                *histogram.entry(instruction.name().to_string()).or_insert(0) += 1;
+            }
+        }
+
+        histogram
+    }
+
     fn crash_conditions() -> Vec<String> {
         vec![]
     }
 }
 
+/// The state of the VM right before the instruction that made it crash.
+#[derive(Debug, Clone)]
+pub struct CrashDiagnostics {
+    pub error: String,
+    pub final_stack: Vec<BFieldElement>,
+    pub cycle_count: usize,
+    pub instruction: Option<Instruction>,
+}
+
 pub fn test_rust_shadow<P: CompiledProgram>(
     public_input: &PublicInput,
     nondeterminism: &NonDeterminism<BFieldElement>,
 ) {
     let rust_output = P::rust_shadow(public_input, nondeterminism).unwrap();
     let tasm_output = P::run(public_input, nondeterminism).unwrap();
-    assert_eq!(rust_output, tasm_output);
+    if rust_output == tasm_output {
+        return;
+    }
+
+    let common_length = min(rust_output.len(), tasm_output.len());
+    let first_divergence = (0..common_length)
+        .find(|&i| rust_output[i] != tasm_output[i])
+        .unwrap_or(common_length);
+    let join_output = |output: &[BFieldElement]| {
+        output
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    let element_or_none = |element: Option<&BFieldElement>| {
+        element
+            .map(|x| x.to_string())
+            .unwrap_or_else(|| "none".to_string())
+    };
+    let diff_str = (0..max(rust_output.len(), tasm_output.len()))
+        .filter(|&i| rust_output.get(i) != tasm_output.get(i))
+        .map(|i| {
+            let rust = element_or_none(rust_output.get(i));
+            let tasm = element_or_none(tasm_output.get(i));
+            format!("({i}: {rust} => {tasm})")
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    panic!(
+        "Rust shadow and TASM output must agree. First divergence at index {first_divergence}.\n\n\
+        Rust: {}\n\nTASM: {}\n\nDifference (index: rust => tasm): {diff_str}\n\n",
+        join_output(&rust_output),
+        join_output(&tasm_output),
+    );
+}
+
+/// Minimum number of calls for a helper to be considered for inlining.
+pub const INLINING_MIN_CALL_COUNT: usize = 100;
+
+/// Maximum average number of cycles spent per call, including `call` and `return`, for
+/// a helper to be considered for inlining.
+pub const INLINING_MAX_CYCLES_PER_CALL: usize = 16;
+
+/// All profile lines with the same label, merged into one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedProfileLine {
+    pub label: String,
+    pub call_stack_depth: usize,
+    pub cycle_count: usize,
+    pub call_count: usize,
+}
+
+/// A helper that is called often but does little work per call. Inlining it saves the
+/// cycles spent on `call` and `return`. This is advice only; no code is transformed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InliningSuggestion {
+    pub label: String,
+    pub call_count: usize,
+    pub cycles_per_call: usize,
+}
+
+/// Merge all profile lines sharing a label. Every profile line corresponds to one call,
+/// so the number of merged lines is the number of times the label was called.
+pub fn aggregate_profile(profile: Vec<ProfileLine>) -> Vec<AggregatedProfileLine> {
+    let mut aggregated: Vec<AggregatedProfileLine> = vec![];
+    for line in profile {
+        if let Some(agg) = aggregated.iter_mut().find(|a| a.label == line.label) {
+            agg.cycle_count += line.cycle_count;
+            agg.call_stack_depth = min(agg.call_stack_depth, line.call_stack_depth);
+            agg.call_count += 1;
+        } else {
+            aggregated.push(AggregatedProfileLine {
+                label: line.label,
+                call_stack_depth: line.call_stack_depth,
+                cycle_count: line.cycle_count,
+                call_count: 1,
+            });
+        }
+    }
+
+    aggregated
+}
+
+/// Flag helpers that are called at least [`INLINING_MIN_CALL_COUNT`] times and spend at
+/// most [`INLINING_MAX_CYCLES_PER_CALL`] cycles per call. Sorted by call count, most
+/// frequently called first.
+pub fn inlining_suggestions(aggregated: &[AggregatedProfileLine]) -> Vec<InliningSuggestion> {
+    let mut suggestions = aggregated
+        .iter()
+        .filter(|line| line.call_count >= INLINING_MIN_CALL_COUNT)
+        .map(|line| InliningSuggestion {
+            label: line.label.clone(),
+            call_count: line.call_count,
+            cycles_per_call: line.cycle_count / line.call_count,
+        })
+        .filter(|suggestion| suggestion.cycles_per_call <= INLINING_MAX_CYCLES_PER_CALL)
+        .collect::<Vec<_>>();
+    suggestions.sort_by(|a, b| b.call_count.cmp(&a.call_count));
+
+    suggestions
+}
+
+/// Render a profile as a call graph, the aggregated profile, and inlining suggestions.
+/// Aggregated lines of snippets that declare a source location are annotated with it.
+pub fn profile_to_string(name: &str, profile: Vec<ProfileLine>, library: &Library) -> String {
+    let mut str = format!("{name}:\n");
+    str = format!("{str}\n# call graph\n");
+    for line in profile.iter() {
+        let indentation = vec!["  "; line.call_stack_depth].join("");
+        let label = &line.label;
+        let cycle_count = line.cycle_count;
+        str = format!("{str}{indentation} {label}: {cycle_count}\n");
+    }
+    str = format!("{str}\n# aggregated\n");
+    let aggregated = aggregate_profile(profile);
+    for line in aggregated.iter() {
+        let indentation = vec!["  "; line.call_stack_depth].join("");
+        let label = &line.label;
+        let cycle_count = line.cycle_count;
+        let location = library
+            .source_location(label)
+            .map(|location| format!(" ({location})"))
+            .unwrap_or_default();
+        str = format!("{str}{indentation} {label}: {cycle_count}{location}\n");
+    }
+    str = format!("{str}\n# inlining suggestions\n");
+    for suggestion in inlining_suggestions(&aggregated) {
+        let label = suggestion.label;
+        let call_count = suggestion.call_count;
+        let cycles_per_call = suggestion.cycles_per_call;
+        str = format!("{str} {label}: {call_count} calls, {cycles_per_call} cycles per call\n");
+    }
+
+    str
+}
+
+/// Render a profile in the folded-stack format understood by `inferno` and
+/// `flamegraph.pl`: one line `root;child;grandchild cycle_count` per profile line, where
+/// the stack is reconstructed from the call stack depths. The profile's cycle counts
+/// include those of the callees, whereas the folded format expects each frame's own
+/// cycles, so the cycle counts of the direct callees are subtracted.
+pub fn profile_to_flamegraph(profile: &[ProfileLine]) -> String {
+    let mut call_stack: Vec<&str> = vec![];
+    let mut str = String::new();
+    for (i, line) in profile.iter().enumerate() {
+        call_stack.truncate(line.call_stack_depth);
+        call_stack.push(&line.label);
+        let stack = call_stack.join(";");
+        let callees_cycle_count = profile[i + 1..]
+            .iter()
+            .take_while(|callee| callee.call_stack_depth > line.call_stack_depth)
+            .filter(|callee| callee.call_stack_depth == line.call_stack_depth + 1)
+            .fold(0, |sum, callee| sum + callee.cycle_count);
+        let cycle_count = line.cycle_count - callees_cycle_count;
+        str = format!("{str}{stack} {cycle_count}\n");
+    }
+
+    str
+}
+
+/// Write the profile in folded-stack format to `profiles/<name>.folded`. The folded files
+/// are local artifacts and are not committed.
+pub fn write_flamegraph_folded(name: &str, profile: &[ProfileLine]) {
+    use std::{
+        fs::{create_dir_all, File},
+        io::Write,
+        path::{Path, PathBuf},
+    };
+
+    let mut path = PathBuf::new();
+    path.push("profiles");
+    create_dir_all(&path).expect("profiles directory should exist");
+
+    path.push(Path::new(name).with_extension("folded"));
+    let mut file = File::create(&path).expect("open file for writing");
+    write!(file, "{}", profile_to_flamegraph(profile)).unwrap();
 }
 
 pub fn bench_program<P: CompiledProgram>(
@@ -85,30 +310,8 @@ pub fn bench_program<P: CompiledProgram>(
         nondeterminism.clone(),
     )
     .unwrap();
-    let mut str = format!("{name}:\n");
-    str = format!("{str}\n# call graph\n");
-    for line in profile.iter() {
-        let indentation = vec!["  "; line.call_stack_depth].join("");
-        let label = &line.label;
-        let cycle_count = line.cycle_count;
-        str = format!("{str}{indentation} {label}: {cycle_count}\n");
-    }
-    str = format!("{str}\n# aggregated\n");
-    let mut aggregated: Vec<ProfileLine> = vec![];
-    for line in profile {
-        if let Some(agg) = aggregated.iter_mut().find(|a| a.label == line.label) {
-            agg.cycle_count += line.cycle_count;
-            agg.call_stack_depth = min(agg.call_stack_depth, line.call_stack_depth);
-        } else {
-            aggregated.push(line);
-        }
-    }
-    for line in aggregated {
-        let indentation = vec!["  "; line.call_stack_depth].join("");
-        let label = line.label;
-        let cycle_count = line.cycle_count;
-        str = format!("{str}{indentation} {label}: {cycle_count}\n");
-    }
+    write_flamegraph_folded(&name, &profile);
+    let str = profile_to_string(&name, profile, &library);
 
     // write profile to standard output in case someone is watching
     println!("{str}");
@@ -125,11 +328,18 @@ pub fn bench_program<P: CompiledProgram>(
 
 #[cfg(test)]
 mod test {
+    use triton_vm::instruction::Instruction;
+    use triton_vm::program::ProfileLine;
     use triton_vm::{triton_asm, BFieldElement, NonDeterminism, PublicInput};
 
-    use crate::{library::Library, snippet_bencher::BenchmarkCase};
+    use crate::{
+        arithmetic::u64::incr_u64::IncrU64, library::Library, snippet_bencher::BenchmarkCase,
+    };
 
-    use super::{bench_program, test_rust_shadow, CompiledProgram};
+    use super::{
+        aggregate_profile, bench_program, inlining_suggestions, profile_to_flamegraph,
+        profile_to_string, test_rust_shadow, CompiledProgram, INLINING_MIN_CALL_COUNT,
+    };
 
     struct FiboTest;
     impl CompiledProgram for FiboTest {
@@ -179,6 +389,46 @@ mod test {
         }
     }
 
+    #[test]
+    fn fibo_code_len_and_instruction_histogram() {
+        // 7 instructions in the main body, 12 in the loop
+        assert_eq!(19, FiboTest::code_len());
+
+        let histogram = FiboTest::instruction_histogram();
+        let expected = [
+            ("push", 4),
+            ("dup", 3),
+            ("add", 2),
+            ("read_io", 1),
+            ("call", 1),
+            ("pop", 1),
+            ("write_io", 1),
+            ("halt", 1),
+            ("eq", 1),
+            ("skiz", 1),
+            ("return", 1),
+            ("swap", 1),
+            ("recurse", 1),
+        ];
+        for (name, count) in expected {
+            assert_eq!(Some(&count), histogram.get(name), "count of {name}");
+        }
+        assert_eq!(expected.len(), histogram.len());
+        assert_eq!(FiboTest::code_len(), histogram.values().sum::<usize>());
+    }
+
+    #[test]
+    fn code_len_includes_imported_snippets() {
+        let (program_instructions, library) = IncrU64Loop::code();
+        let imports_len = library.assembled_instruction_count();
+        assert!(imports_len > 0);
+        assert_eq!(
+            program_instructions.len() - 1 + imports_len,
+            IncrU64Loop::code_len()
+        );
+        assert_eq!(Some(&1), IncrU64Loop::instruction_histogram().get("halt"));
+    }
+
     #[test]
     fn test_fibo_shadow() {
         let public_input = PublicInput::new(vec![BFieldElement::new(501)]);
@@ -186,6 +436,33 @@ mod test {
         test_rust_shadow::<FiboTest>(&public_input, &nondeterminism);
     }
 
+    /// Same program as [`FiboTest`], but the Rust shadow is off by one.
+    struct FiboOffByOne;
+    impl CompiledProgram for FiboOffByOne {
+        fn rust_shadow(
+            public_input: &PublicInput,
+            secret_input: &NonDeterminism<BFieldElement>,
+        ) -> anyhow::Result<Vec<triton_vm::BFieldElement>> {
+            let output = FiboTest::rust_shadow(public_input, secret_input)?;
+            anyhow::Result::Ok(vec![output[0] + BFieldElement::new(1)])
+        }
+
+        fn code() -> (
+            Vec<triton_vm::instruction::LabelledInstruction>,
+            crate::library::Library,
+        ) {
+            FiboTest::code()
+        }
+    }
+
+    #[should_panic(expected = "First divergence at index 0")]
+    #[test]
+    fn wrong_shadow_names_diverging_index() {
+        let public_input = PublicInput::new(vec![BFieldElement::new(10)]);
+        let nondeterminism = NonDeterminism::new(vec![]);
+        test_rust_shadow::<FiboOffByOne>(&public_input, &nondeterminism);
+    }
+
     #[test]
     fn bench_fibo() {
         let public_input = PublicInput::new(vec![BFieldElement::new(501)]);
@@ -197,4 +474,166 @@ mod test {
             &secret_input,
         );
     }
+
+    struct IncrU64Loop;
+    impl CompiledProgram for IncrU64Loop {
+        fn rust_shadow(
+            _public_input: &PublicInput,
+            _secret_input: &NonDeterminism<BFieldElement>,
+        ) -> anyhow::Result<Vec<triton_vm::BFieldElement>> {
+            anyhow::Result::Ok(vec![])
+        }
+
+        fn code() -> (
+            Vec<triton_vm::instruction::LabelledInstruction>,
+            crate::library::Library,
+        ) {
+            let mut library = Library::new();
+            let incr_u64 = library.import(Box::new(IncrU64));
+            let num_iterations = 2 * INLINING_MIN_CALL_COUNT;
+            let code = triton_asm!(
+                push {num_iterations}
+                push 0
+                push 0
+                call incr_u64_loop
+                pop
+                pop
+                pop
+                halt
+
+                // INVARIANT: _ itr value_hi value_lo
+                incr_u64_loop:
+                    dup 2 push 0 eq
+                    skiz return
+
+                    call {incr_u64}
+
+                    swap 2
+                    push -1 add
+                    swap 2
+                    recurse
+            );
+
+            (code, library)
+        }
+    }
+
+    #[test]
+    fn hot_tiny_helper_is_suggested_for_inlining() {
+        test_rust_shadow::<IncrU64Loop>(&PublicInput::new(vec![]), &NonDeterminism::new(vec![]));
+
+        let (program_instructions, library) = IncrU64Loop::code();
+        let all_instructions = [program_instructions, library.all_imports()].concat();
+        let (_output, profile) = triton_vm::program::Program::profile(
+            &all_instructions,
+            PublicInput::new(vec![]),
+            NonDeterminism::new(vec![]),
+        )
+        .unwrap();
+        let suggestions = inlining_suggestions(&aggregate_profile(profile));
+
+        let incr_u64_suggestion = suggestions
+            .iter()
+            .find(|suggestion| suggestion.label == "tasm_arithmetic_u64_incr")
+            .expect("hot tiny helper must be suggested for inlining");
+        assert_eq!(2 * INLINING_MIN_CALL_COUNT, incr_u64_suggestion.call_count);
+
+        // The loop is called only once and must not be suggested.
+        assert!(suggestions
+            .iter()
+            .all(|suggestion| suggestion.label != "incr_u64_loop"));
+    }
+
+    #[test]
+    fn profile_shows_source_location_of_snippet() {
+        let (program_instructions, library) = IncrU64Loop::code();
+        let all_instructions = [program_instructions, library.all_imports()].concat();
+        let (_output, profile) = triton_vm::program::Program::profile(
+            &all_instructions,
+            PublicInput::new(vec![]),
+            NonDeterminism::new(vec![]),
+        )
+        .unwrap();
+        let profile_string = profile_to_string("incr_u64_loop", profile, &library);
+
+        let incr_u64_line = profile_string
+            .split("# aggregated")
+            .nth(1)
+            .unwrap()
+            .lines()
+            .find(|line| line.contains("tasm_arithmetic_u64_incr:"))
+            .unwrap();
+        assert!(incr_u64_line.contains("src/arithmetic/u64/incr_u64.rs:"));
+
+        // The loop is not a snippet and has no source location.
+        assert!(!profile_string.contains("incr_u64_loop: ("));
+    }
+
+    #[test]
+    fn folded_stacks_follow_call_stack_depths() {
+        let profile_line = |label: &str, call_stack_depth, cycle_count| ProfileLine {
+            label: label.to_string(),
+            call_stack_depth,
+            cycle_count,
+        };
+        let profile = vec![
+            profile_line("main", 0, 100),
+            profile_line("a", 1, 40),
+            profile_line("b", 2, 15),
+            profile_line("c", 1, 30),
+            profile_line("b", 2, 15),
+        ];
+
+        let folded = profile_to_flamegraph(&profile);
+        let expected = [
+            "main 30",
+            "main;a 25",
+            "main;a;b 15",
+            "main;c 15",
+            "main;c;b 15",
+        ];
+        assert_eq!(expected.to_vec(), folded.lines().collect::<Vec<_>>());
+    }
+
+    struct AssertFive;
+    impl CompiledProgram for AssertFive {
+        fn rust_shadow(
+            public_input: &PublicInput,
+            _secret_input: &NonDeterminism<BFieldElement>,
+        ) -> anyhow::Result<Vec<triton_vm::BFieldElement>> {
+            anyhow::ensure!(public_input.individual_tokens[0].value() == 5);
+            anyhow::Result::Ok(vec![])
+        }
+
+        fn code() -> (
+            Vec<triton_vm::instruction::LabelledInstruction>,
+            crate::library::Library,
+        ) {
+            let code = triton_asm!(
+                read_io
+                push 5
+                eq
+                assert
+                halt
+            );
+
+            (code, Library::default())
+        }
+    }
+
+    #[test]
+    fn run_with_debug_points_at_failing_assert() {
+        let nondeterminism = NonDeterminism::new(vec![]);
+        let good_input = PublicInput::new(vec![BFieldElement::new(5)]);
+        assert!(AssertFive::run_with_debug(&good_input, &nondeterminism).is_ok());
+
+        let bad_input = PublicInput::new(vec![BFieldElement::new(6)]);
+        let diagnostics = AssertFive::run_with_debug(&bad_input, &nondeterminism).unwrap_err();
+        assert_eq!(3, diagnostics.cycle_count);
+        assert_eq!(Some(Instruction::Assert), diagnostics.instruction);
+        assert_eq!(
+            BFieldElement::new(0),
+            *diagnostics.final_stack.last().unwrap()
+        );
+    }
 }
@@ -0,0 +1,262 @@
+use std::fmt::Display;
+
+use itertools::Itertools;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::ExecutionState;
+
+/// A memory cell whose value differs between two [`ExecutionState`]s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MemoryWrite {
+    pub address: BFieldElement,
+
+    /// `None` if the cell was not initialized before.
+    pub old_value: Option<BFieldElement>,
+    pub new_value: BFieldElement,
+}
+
+/// What changed between two [`ExecutionState`]s, typically the state before and after
+/// running a snippet. See [`ExecutionState::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecutionStateDiff {
+    /// Elements removed from the top of the stack, deepest element first.
+    pub popped: Vec<BFieldElement>,
+
+    /// Elements added to the top of the stack, deepest element first.
+    pub pushed: Vec<BFieldElement>,
+
+    /// Changed or newly initialized memory cells, sorted by address.
+    pub memory_writes: Vec<MemoryWrite>,
+
+    pub consumed_std_in: Vec<BFieldElement>,
+    pub consumed_secret_in: Vec<BFieldElement>,
+}
+
+impl ExecutionStateDiff {
+    /// Net change in stack height.
+    pub fn stack_growth(&self) -> isize {
+        self.pushed.len() as isize - self.popped.len() as isize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.popped.is_empty()
+            && self.pushed.is_empty()
+            && self.memory_writes.is_empty()
+            && self.consumed_std_in.is_empty()
+            && self.consumed_secret_in.is_empty()
+    }
+}
+
+impl ExecutionState {
+    /// Compare this state to a later state `other`. The stacks are compared from the
+    /// bottom up: everything above their longest common prefix counts as popped from
+    /// `self` and pushed in `other`. Input streams are assumed to be consumed from the
+    /// front only, so whatever `other` holds fewer of counts as consumed.
+    pub fn diff(&self, other: &Self) -> ExecutionStateDiff {
+        let common_stack_height = self
+            .stack
+            .iter()
+            .zip(other.stack.iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let memory_writes = other
+            .memory
+            .iter()
+            .filter(|(address, value)| self.memory.get(address) != Some(value))
+            .map(|(address, value)| MemoryWrite {
+                address: *address,
+                old_value: self.memory.get(address).copied(),
+                new_value: *value,
+            })
+            .sorted_by_key(|write| write.address.value())
+            .collect_vec();
+
+        let consumed_prefix = |before: &[BFieldElement], after: &[BFieldElement]| {
+            before[..before.len().saturating_sub(after.len())].to_vec()
+        };
+
+        ExecutionStateDiff {
+            popped: self.stack[common_stack_height..].to_vec(),
+            pushed: other.stack[common_stack_height..].to_vec(),
+            memory_writes,
+            consumed_std_in: consumed_prefix(&self.std_in, &other.std_in),
+            consumed_secret_in: consumed_prefix(
+                &self.nondeterminism.individual_tokens,
+                &other.nondeterminism.individual_tokens,
+            ),
+        }
+    }
+}
+
+impl Display for ExecutionStateDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let render = |elements: &[BFieldElement]| elements.iter().join(", ");
+
+        writeln!(
+            f,
+            "stack: popped [{}], pushed [{}] (growth: {})",
+            render(&self.popped),
+            render(&self.pushed),
+            self.stack_growth()
+        )?;
+
+        writeln!(f, "memory: {} write(s)", self.memory_writes.len())?;
+        for write in self.memory_writes.iter() {
+            let old_value = match write.old_value {
+                Some(value) => value.to_string(),
+                None => "uninitialized".to_string(),
+            };
+            writeln!(
+                f,
+                "  [{}]: {old_value} -> {}",
+                write.address, write.new_value
+            )?;
+        }
+
+        writeln!(f, "std_in: consumed [{}]", render(&self.consumed_std_in))?;
+        write!(
+            f,
+            "secret_in: consumed [{}]",
+            render(&self.consumed_secret_in)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use triton_vm::NonDeterminism;
+
+    use super::*;
+    use crate::get_init_tvm_stack;
+    use crate::io::read_stdin::ReadStdIn;
+    use crate::io::read_stdin_list::ReadStdInList;
+    use crate::rust_shadowing_helper_functions::unsafe_list::unsafe_list_get_length;
+    use crate::snippet::DataType;
+    use crate::test_helpers::test_rust_equivalence_given_execution_state_deprecated;
+
+    /// The state after running a snippet. The VM's output state does not report how much
+    /// input was read, so the input streams are left as they were.
+    fn final_state(
+        init_state: &ExecutionState,
+        final_stack: Vec<BFieldElement>,
+        final_memory: HashMap<BFieldElement, BFieldElement>,
+    ) -> ExecutionState {
+        ExecutionState {
+            stack: final_stack,
+            std_in: init_state.std_in.clone(),
+            nondeterminism: init_state.nondeterminism.clone(),
+            memory: final_memory,
+            words_allocated: init_state.words_allocated,
+        }
+    }
+
+    #[test]
+    fn diff_of_identical_states_is_empty() {
+        let state = ExecutionState::with_stack(get_init_tvm_stack());
+        assert!(state.diff(&state).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_consumed_input_prefixes() {
+        let elements = (0..5).map(BFieldElement::new).collect_vec();
+        let init_state = ExecutionState {
+            stack: get_init_tvm_stack(),
+            std_in: elements.clone(),
+            nondeterminism: NonDeterminism::new(elements.clone()),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        };
+        let final_state = ExecutionState {
+            std_in: elements[2..].to_vec(),
+            nondeterminism: NonDeterminism::new(vec![]),
+            ..init_state.clone()
+        };
+
+        let diff = init_state.diff(&final_state);
+        assert_eq!(elements[..2].to_vec(), diff.consumed_std_in);
+        assert_eq!(elements, diff.consumed_secret_in);
+        assert_eq!(0, diff.stack_growth());
+        assert!(diff.memory_writes.is_empty());
+
+        let rendered = diff.to_string();
+        assert!(rendered.contains("std_in: consumed [0, 1]"));
+        assert!(rendered.contains("secret_in: consumed [0, 1, 2, 3, 4]"));
+    }
+
+    #[test]
+    fn diff_reports_stack_growth() {
+        let std_in = vec![BFieldElement::new(7), BFieldElement::new(8)];
+        let init_state = ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), std_in);
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &ReadStdIn(DataType::U64),
+            init_state.clone(),
+        );
+        let final_state = final_state(
+            &init_state,
+            vm_output_state.final_stack,
+            vm_output_state.final_ram,
+        );
+
+        let diff = init_state.diff(&final_state);
+        assert_eq!(2, diff.stack_growth());
+        assert!(diff.popped.is_empty());
+        assert_eq!(
+            vec![BFieldElement::new(7), BFieldElement::new(8)],
+            diff.pushed
+        );
+        assert!(diff.memory_writes.is_empty());
+    }
+
+    #[test]
+    fn diff_reports_memory_writes() {
+        let count = 2;
+        let std_in = (1..=2 * count as u64).map(BFieldElement::new).collect_vec();
+        let init_state = ExecutionState {
+            stack: [get_init_tvm_stack(), vec![BFieldElement::new(count as u64)]].concat(),
+            std_in,
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        };
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &ReadStdInList(DataType::U64),
+            init_state.clone(),
+        );
+        let final_state = final_state(
+            &init_state,
+            vm_output_state.final_stack,
+            vm_output_state.final_ram,
+        );
+
+        let diff = init_state.diff(&final_state);
+        let list_pointer = *final_state.stack.last().unwrap();
+        assert_eq!(0, diff.stack_growth());
+        assert_eq!(vec![BFieldElement::new(count as u64)], diff.popped);
+        assert_eq!(vec![list_pointer], diff.pushed);
+
+        // The dynamic allocator's state, the list's length, and its elements are written.
+        assert_eq!(1 + 1 + 2 * count, diff.memory_writes.len());
+        assert!(diff
+            .memory_writes
+            .iter()
+            .all(|write| write.old_value.is_none()));
+        let length_write = diff
+            .memory_writes
+            .iter()
+            .find(|write| write.address == list_pointer)
+            .unwrap();
+        assert_eq!(
+            unsafe_list_get_length(list_pointer, &final_state.memory) as u64,
+            length_write.new_value.value()
+        );
+        assert_eq!(count as u64, length_write.new_value.value());
+
+        let rendered = diff.to_string();
+        assert!(rendered.contains("(growth: 0)"));
+        assert!(rendered.contains(&format!("memory: {} write(s)", 2 + 2 * count)));
+        assert!(rendered.contains(&format!("[{list_pointer}]: uninitialized -> {count}")));
+    }
+}
@@ -1,82 +1,121 @@
+use std::str::FromStr;
+
 use crate::{
     arithmetic::{
+        bfe::{bfe_pow::BfePow, sum_list::BfeSumList},
+        i32::assert_i32::AssertI32,
         u128::{
             add_u128::AddU128, safe_mul_u128::SafeMulU128,
             shift_left_static_u128::ShiftLeftStaticU128, shift_left_u128::ShiftLeftU128,
             shift_right_static_u128::ShiftRightStaticU128, shift_right_u128::ShiftRightU128,
             sub_u128::SubU128,
         },
+        u16::assert_u16::AssertU16,
         u32::{
-            isodd::Isodd, isu32::Isu32, leadingzeros::Leadingzeros, or::Or,
+            assert_u32::AssertU32, isodd::Isodd, isu32::Isu32, leadingzeros::Leadingzeros, or::Or,
             overflowingadd::Overflowingadd, safeadd::Safeadd, safemul::Safemul, safepow::Safepow,
             safesub::Safesub, shiftleft::Shiftleft, shiftright::Shiftright,
         },
+        u32s_n::{add_u32s_n::AddU32s, sub_u32s_n::SubU32s},
         u64::{
             add_u64::AddU64,
             and_u64::AndU64,
+            bits::{BitsToU64, U64ToBits},
+            checked_sub_u64::CheckedSubU64,
+            clamp::U64Clamp,
             decr_u64::DecrU64,
             div2_u64::Div2U64,
             div_mod_u64::DivModU64,
             double_pow2_u64::DoublePow2U64,
             eq_u64::EqU64,
+            gcd_u64::GcdU64,
             incr_u64::IncrU64,
             index_of_last_nonzero_bit::IndexOfLastNonZeroBitU64,
             leading_zeros_u64::LeadingZerosU64,
             log_2_floor_u64::Log2FloorU64,
             lt_u64::{LtStandardU64, LtU64},
+            max_u64::MaxU64,
+            min_u64::MinU64,
             mul_two_u64s_to_u128_u64::MulTwoU64sToU128,
+            neg_u64::NegU64,
             or_u64::OrU64,
+            overflowing_add_u64::OverflowingAdd,
             overflowing_sub_u64::OverflowingSub,
             popcount_u64::PopCountU64,
             pow2_u64::Pow2U64,
             safe_mul_u64::SafeMulU64,
+            saturating_add_u64::SaturatingAddU64,
+            saturating_decr_u64::SaturatingDecrU64,
+            saturating_incr_u64::SaturatingIncrU64,
+            saturating_sub_u64::SaturatingSubU64,
             shift_left_u64::ShiftLeftU64,
             shift_right_u64::ShiftRightU64,
             sub_u64::SubU64,
+            validate_u64::ValidateU64,
             wrapping_mul_u64::WrappingMulU64,
             wrapping_sub_u64::WrappingSub,
             xor_u64::XorU64,
         },
+        xfe::{
+            bfe_to_xfe::BfeToXfe, horner::HornerEvaluation, inner_product::XfeInnerProduct,
+            xfe_inverse::XfeInverse, xfe_mul::XfeMul, xfe_pow::XfePow, xfe_try_to_bfe::XfeTryToBfe,
+        },
     },
     hashing::{
-        eq_digest::EqDigest, hash_varlen::HashVarlen,
+        assert_digest::AssertDigestEqDivined, eq_digest::EqDigest, hash_varlen::HashVarlen,
         load_auth_path_from_secret_in_safe_list::LoadAuthPathFromSecretInSafeList,
         load_auth_path_from_secret_in_unsafe_list::LoadAuthPathFromSecretInUnsafeList,
         load_auth_path_from_std_in_safe_list::LoadAuthPathFromStdInSafeList,
         load_auth_path_from_std_in_unsafe_list::LoadAuthPathFromStdInUnsafeList,
-        reverse_digest::ReverseDigest, sample_indices::SampleIndices, swap_digest::SwapDigest,
+        merkle_root::MerkleRoot, reverse_digest::ReverseDigest, sample_indices::SampleIndices,
+        sample_scalars::SampleScalars, sample_unique_indices::SampleUniqueIndices,
+        sponge_absorb::SpongeAbsorb, sponge_squeeze::SpongeSqueeze, swap_digest::SwapDigest,
     },
     io::{
         load_from_input::LoadFromInput, read_secret::ReadSecret, read_stdin::ReadStdIn,
+        read_stdin_list::ReadStdInList, read_validated_u64::ReadValidatedU64FromStdIn,
         write_to_stdout::WriteToStdout,
     },
     list::{
         contiguous_list,
+        conversion::{SafeToUnsafe, UnsafeToSafe},
+        multiset_equals_u64::MultisetEqualsU64,
         range::Range,
         safeimplu32::{
-            get::SafeGet, length::Length as SafeLength, new::SafeNew, pop::SafePop, push::SafePush,
-            set::SafeSet, set_length::SafeSetLength,
+            binary_search::SafeBinarySearch, clone::SafeListClone, equals::SafeListEquals,
+            get::SafeGet, insert::SafeInsert, length::Length as SafeLength, min_max::SafeMinMax,
+            new::SafeNew, pop::SafePop, push::SafePush, remove::SafeRemove, resize::SafeResize,
+            set::SafeSet, set_length::SafeSetLength, sort::SafeListSort, split_off::SafeSplitOff,
         },
+        sort_u64::ListSortU64,
         unsafeimplu32::{
             get::UnsafeGet, length::Length as UnsafeLength, new::UnsafeNew, pop::UnsafePop,
             push::UnsafePush, set::UnsafeSet, set_length::UnsafeSetLength,
         },
         ListType,
     },
-    memory::{dyn_malloc::DynMalloc, memcpy::MemCpy, push_ram_to_stack::PushRamToStack},
+    memory::{
+        dyn_free::DynFree, dyn_malloc::DynMalloc, dyn_malloc_aligned::DynMallocAligned,
+        memcpy::MemCpy, push_ram_to_stack::PushRamToStack,
+    },
     mmr::{
         bag_peaks::BagPeaks, calculate_new_peaks_from_append::CalculateNewPeaksFromAppend,
         calculate_new_peaks_from_leaf_mutation::MmrCalculateNewPeaksFromLeafMutationMtIndices,
-        data_index_to_node_index::DataIndexToNodeIndex,
+        data_index_to_node_index::MmrDataIndexToNodeIndex,
         get_height_from_data_index::GetHeightFromDataIndex,
+        leaf_count_to_num_peaks::MmrLeafCountToNumPeaks,
         leaf_index_to_mt_index::MmrLeafIndexToMtIndexAndPeakIndex, left_child::MmrLeftChild,
         leftmost_ancestor::MmrLeftMostAncestor,
-        load_from_secret_in_then_verify::MmrLoadFromSecretInThenVerify,
-        non_leaf_nodes_left::MmrNonLeafNodesLeftUsingAnd, right_child::MmrRightChild,
-        right_child_and_height::MmrRightChildAndHeight,
+        load_from_secret_in_then_verify::MmrLoadFromSecretInThenVerify, new::MmrNew,
+        node_index_to_leaf_index::MmrNodeIndexToLeafIndex,
+        non_leaf_nodes_left::MmrNonLeafNodesLeftUsingAnd,
+        peaks_consistency::MmrPeaksAndLeafCountConsistency, right_child::MmrRightChild,
+        right_child_and_height::{MmrRightChildAndHeight, MmrRightChildAndHeightBounded},
+        right_child_and_height_iterative::MmrRightChildAndHeightIterative,
         right_lineage_count_and_own_height::MmrRightLineageCountAndHeight,
-        right_lineage_length::MmrRightLineageLength, verify_from_memory::MmrVerifyFromMemory,
-        verify_from_secret_in::MmrVerifyLeafMembershipFromSecretIn,
+        right_lineage_length::MmrRightLineageLength, verify_batch_append::MmrVerifyBatchAppend,
+        verify_from_memory::MmrVerifyFromMemory,
+        verify_from_secret_in::MmrVerifyLeafMembershipFromSecretIn, MmrLoopGuard, MAX_MMR_HEIGHT,
     },
     neptune::mutator_set::{commit::Commit, get_swbf_indices::GetSwbfIndices},
     other_snippets::bfe_add::BfeAdd,
@@ -86,10 +125,21 @@ use crate::{
 };
 
 pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
+    if let Some(snippet) = generic_list_snippet(fn_name) {
+        return snippet;
+    }
+
     match fn_name {
+        // u16
+        "tasm_arithmetic_u16_assert_u16" => Box::new(AssertU16),
+
+        // i32
+        "tasm_arithmetic_i32_assert_i32" => Box::new(AssertI32),
+
         // u32
         "tasm_arithmetic_u32_isodd" => Box::new(Isodd),
         "tasm_arithmetic_u32_isu32" => Box::new(Isu32),
+        "tasm_arithmetic_u32_assert_u32" => Box::new(AssertU32),
         "tasm_arithmetic_u32_safeadd" => Box::new(Safeadd),
         "tasm_arithmetic_u32_safesub" => Box::new(Safesub),
         "tasm_arithmetic_u32_safemul" => Box::new(Safemul),
@@ -109,6 +159,7 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_arithmetic_u64_div2" => Box::new(Div2U64),
         "tasm_arithmetic_u64_div_mod" => Box::new(DivModU64),
         "tasm_arithmetic_u64_eq" => Box::new(EqU64),
+        "tasm_arithmetic_u64_gcd" => Box::new(GcdU64),
         "tasm_arithmetic_u64_incr" => Box::new(IncrU64),
         "tasm_arithmetic_u64_log_2_floor" => Box::new(Log2FloorU64),
         "tasm_arithmetic_u64_lt" => Box::new(LtU64),
@@ -125,7 +176,43 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_arithmetic_u64_shift_left" => Box::new(ShiftLeftU64),
         "tasm_arithmetic_u64_mul_two_u64s_to_u128_u64" => Box::new(MulTwoU64sToU128),
         "tasm_arithmetic_u64_wrapping_sub" => Box::new(WrappingSub),
+        "tasm_arithmetic_u64_neg" => Box::new(NegU64),
+        "tasm_arithmetic_u64_overflowing_add" => Box::new(OverflowingAdd),
         "tasm_arithmetic_u64_overflowing_sub" => Box::new(OverflowingSub),
+        "tasm_arithmetic_u64_saturating_add" => Box::new(SaturatingAddU64),
+        "tasm_arithmetic_u64_saturating_decr" => Box::new(SaturatingDecrU64),
+        "tasm_arithmetic_u64_saturating_incr" => Box::new(SaturatingIncrU64),
+        "tasm_arithmetic_u64_saturating_sub" => Box::new(SaturatingSubU64),
+        "tasm_arithmetic_u64_checked_sub" => Box::new(CheckedSubU64),
+        "tasm_arithmetic_u64_max" => Box::new(MaxU64),
+        "tasm_arithmetic_u64_min" => Box::new(MinU64),
+        "tasm_arithmetic_u64_clamp" => Box::new(U64Clamp),
+        "tasm_arithmetic_u64_validate" => Box::new(ValidateU64),
+        "tasm_arithmetic_u64_to_bits_in_safeimplu32_list" => Box::new(U64ToBits { list_type: ListType::Safe }),
+        "tasm_arithmetic_u64_to_bits_in_unsafeimplu32_list" => Box::new(U64ToBits { list_type: ListType::Unsafe }),
+        "tasm_arithmetic_u64_from_bits_in_safeimplu32_list" => Box::new(BitsToU64 { list_type: ListType::Safe }),
+        "tasm_arithmetic_u64_from_bits_in_unsafeimplu32_list" => Box::new(BitsToU64 { list_type: ListType::Unsafe }),
+
+        // xfe
+        "tasm_arithmetic_xfe_bfe_to_xfe" => Box::new(BfeToXfe),
+        "tasm_arithmetic_xfe_horner_evaluation" => Box::new(HornerEvaluation),
+        "tasm_arithmetic_xfe_inner_product" => Box::new(XfeInnerProduct),
+        "tasm_arithmetic_xfe_inverse" => Box::new(XfeInverse),
+        "tasm_arithmetic_xfe_mul" => Box::new(XfeMul),
+        "tasm_arithmetic_xfe_pow" => Box::new(XfePow),
+        "tasm_arithmetic_xfe_try_to_bfe" => Box::new(XfeTryToBfe),
+
+        // bfe
+        "tasm_arithmetic_bfe_pow" => Box::new(BfePow),
+        "tasm_arithmetic_bfe_sum_list" => Box::new(BfeSumList),
+
+        // u32s_n
+        "tasm_arithmetic_u32s_2_add" => Box::new(AddU32s::<2>),
+        "tasm_arithmetic_u32s_3_add" => Box::new(AddU32s::<3>),
+        "tasm_arithmetic_u32s_4_add" => Box::new(AddU32s::<4>),
+        "tasm_arithmetic_u32s_2_sub" => Box::new(SubU32s::<2>),
+        "tasm_arithmetic_u32s_3_sub" => Box::new(SubU32s::<3>),
+        "tasm_arithmetic_u32s_4_sub" => Box::new(SubU32s::<4>),
 
         // u128
         "tasm_arithmetic_u128_add" => Box::new(AddU128),
@@ -202,6 +289,7 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
 
         // Hashing
         "tasm_hashing_eq_digest" => Box::new(EqDigest),
+        "tasm_hashing_assert_digest_eq_divined" => Box::new(AssertDigestEqDivined),
         "tasm_hashing_load_auth_path_from_secret_in_unsafe_list" => Box::new(LoadAuthPathFromSecretInUnsafeList),
         "tasm_hashing_load_auth_path_from_std_in_unsafe_list" => Box::new(LoadAuthPathFromStdInUnsafeList),
         "tasm_hashing_load_auth_path_from_secret_in_safe_list" => Box::new(LoadAuthPathFromSecretInSafeList),
@@ -210,7 +298,17 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_hashing_hash_varlen" => Box::new(HashVarlen),
         "tasm_hashing_sample_indices_to_safeimplu32_list" => Box::new(SampleIndices{list_type: ListType::Safe}),
         "tasm_hashing_sample_indices_to_unsafeimplu32_list" => Box::new(SampleIndices{list_type: ListType::Unsafe}),
+        "tasm_hashing_sample_unique_indices_to_safeimplu32_list" => Box::new(SampleUniqueIndices{list_type: ListType::Safe}),
+        "tasm_hashing_sample_unique_indices_to_unsafeimplu32_list" => Box::new(SampleUniqueIndices{list_type: ListType::Unsafe}),
+        "tasm_hashing_merkle_root_from_safeimplu32_list" => Box::new(MerkleRoot{list_type: ListType::Safe}),
+        "tasm_hashing_merkle_root_from_unsafeimplu32_list" => Box::new(MerkleRoot{list_type: ListType::Unsafe}),
+        "tasm_hashing_merkle_verify" => Box::new(crate::hashing::merkle_verify::MerkleVerify),
         "tasm_hashing_reverse_digest" => Box::new(ReverseDigest),
+        "tasm_hashing_sample_scalars_to_safeimplu32_list" => Box::new(SampleScalars{list_type: ListType::Safe}),
+        "tasm_hashing_sample_scalars_to_unsafeimplu32_list" => Box::new(SampleScalars{list_type: ListType::Unsafe}),
+        "tasm_hashing_sponge_absorb" => Box::new(SpongeAbsorb),
+        "tasm_hashing_sponge_squeeze_to_safeimplu32_list" => Box::new(SpongeSqueeze{list_type: ListType::Safe}),
+        "tasm_hashing_sponge_squeeze_to_unsafeimplu32_list" => Box::new(SpongeSqueeze{list_type: ListType::Unsafe}),
 
         // io
         "tasm_io_read_secret___bool" => Box::new(ReadSecret(DataType::Bool)),
@@ -222,13 +320,27 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_io_read_secret___digest" => Box::new(ReadSecret(DataType::Digest)),
 
         "tasm_io_read_stdin___bool" => Box::new(ReadStdIn(DataType::Bool)),
+        "tasm_io_read_stdin___u16" => Box::new(ReadStdIn(DataType::U16)),
         "tasm_io_read_stdin___u32" => Box::new(ReadStdIn(DataType::U32)),
+        "tasm_io_read_stdin___i32" => Box::new(ReadStdIn(DataType::I32)),
         "tasm_io_read_stdin___u64" => Box::new(ReadStdIn(DataType::U64)),
         "tasm_io_read_stdin___u128" => Box::new(ReadStdIn(DataType::U128)),
         "tasm_io_read_stdin___bfe" => Box::new(ReadStdIn(DataType::BFE)),
         "tasm_io_read_stdin___xfe" => Box::new(ReadStdIn(DataType::XFE)),
         "tasm_io_read_stdin___digest" => Box::new(ReadStdIn(DataType::Digest)),
 
+        "tasm_io_read_validated_u64_from_stdin" => Box::new(ReadValidatedU64FromStdIn),
+
+        "tasm_io_read_stdin_list___bool" => Box::new(ReadStdInList(DataType::Bool)),
+        "tasm_io_read_stdin_list___u16" => Box::new(ReadStdInList(DataType::U16)),
+        "tasm_io_read_stdin_list___u32" => Box::new(ReadStdInList(DataType::U32)),
+        "tasm_io_read_stdin_list___i32" => Box::new(ReadStdInList(DataType::I32)),
+        "tasm_io_read_stdin_list___u64" => Box::new(ReadStdInList(DataType::U64)),
+        "tasm_io_read_stdin_list___u128" => Box::new(ReadStdInList(DataType::U128)),
+        "tasm_io_read_stdin_list___bfe" => Box::new(ReadStdInList(DataType::BFE)),
+        "tasm_io_read_stdin_list___xfe" => Box::new(ReadStdInList(DataType::XFE)),
+        "tasm_io_read_stdin_list___digest" => Box::new(ReadStdInList(DataType::Digest)),
+
         "tasm_io_load_from_input_stdin" => Box::new(LoadFromInput(InputSource::StdIn)),
         "tasm_io_load_from_input_secin" => Box::new(LoadFromInput(InputSource::SecretIn)),
 
@@ -241,111 +353,20 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_io_write_to_stdout___digest" => Box::new(WriteToStdout(DataType::Digest)),
 
         // safe lists
-        "tasm_list_safeimplu32_get_element___bool" => Box::new(SafeGet(DataType::Bool)),
-        "tasm_list_safeimplu32_get_element___u32" => Box::new(SafeGet(DataType::U32)),
-        "tasm_list_safeimplu32_get_element___u64" => Box::new(SafeGet(DataType::U64)),
-        "tasm_list_safeimplu32_get_element___bfe" => Box::new(SafeGet(DataType::BFE)),
-        "tasm_list_safeimplu32_get_element___xfe" => Box::new(SafeGet(DataType::XFE)),
-        "tasm_list_safeimplu32_get_element___digest" => Box::new(SafeGet(DataType::Digest)),
-
-        "tasm_list_safeimplu32_pop___bool" => Box::new(SafePop(DataType::Bool)),
-        "tasm_list_safeimplu32_pop___u32" => Box::new(SafePop(DataType::U32)),
-        "tasm_list_safeimplu32_pop___u64" => Box::new(SafePop(DataType::U64)),
-        "tasm_list_safeimplu32_pop___bfe" => Box::new(SafePop(DataType::BFE)),
-        "tasm_list_safeimplu32_pop___xfe" => Box::new(SafePop(DataType::XFE)),
-        "tasm_list_safeimplu32_pop___digest" => Box::new(SafePop(DataType::Digest)),
-
-        "tasm_list_safeimplu32_push___bool" => Box::new(SafePush(DataType::Bool)),
-        "tasm_list_safeimplu32_push___u32" => Box::new(SafePush(DataType::U32)),
-        "tasm_list_safeimplu32_push___u64" => Box::new(SafePush(DataType::U64)),
-        "tasm_list_safeimplu32_push___bfe" => Box::new(SafePush(DataType::BFE)),
-        "tasm_list_safeimplu32_push___xfe" => Box::new(SafePush(DataType::XFE)),
-        "tasm_list_safeimplu32_push___digest" => Box::new(SafePush(DataType::Digest)),
-
-        "tasm_list_safeimplu32_set_element___bool" => Box::new(SafeSet(DataType::Bool)),
-        "tasm_list_safeimplu32_set_element___u32" => Box::new(SafeSet(DataType::U32)),
-        "tasm_list_safeimplu32_set_element___u64" => Box::new(SafeSet(DataType::U64)),
-        "tasm_list_safeimplu32_set_element___bfe" => Box::new(SafeSet(DataType::BFE)),
-        "tasm_list_safeimplu32_set_element___xfe" => Box::new(SafeSet(DataType::XFE)),
-        "tasm_list_safeimplu32_set_element___digest" => Box::new(SafeSet(DataType::Digest)),
-
-        "tasm_list_safeimplu32_new___bool" => Box::new(SafeNew(DataType::Bool)),
-        "tasm_list_safeimplu32_new___u32" => Box::new(SafeNew(DataType::U32)),
-        "tasm_list_safeimplu32_new___u64" => Box::new(SafeNew(DataType::U64)),
-        "tasm_list_safeimplu32_new___bfe" => Box::new(SafeNew(DataType::BFE)),
-        "tasm_list_safeimplu32_new___xfe" => Box::new(SafeNew(DataType::XFE)),
-        "tasm_list_safeimplu32_new___digest" => Box::new(SafeNew(DataType::Digest)),
-
-        "tasm_list_safeimplu32_length___bool" => Box::new(SafeLength(DataType::Bool)),
-        "tasm_list_safeimplu32_length___u32" => Box::new(SafeLength(DataType::U32)),
-        "tasm_list_safeimplu32_length___u64" => Box::new(SafeLength(DataType::U64)),
-        "tasm_list_safeimplu32_length___bfe" => Box::new(SafeLength(DataType::BFE)),
-        "tasm_list_safeimplu32_length___xfe" => Box::new(SafeLength(DataType::XFE)),
-        "tasm_list_safeimplu32_length___digest" => Box::new(SafeLength(DataType::Digest)),
-
-        "tasm_list_safeimplu32_set_length___bool" => Box::new(SafeSetLength(DataType::Bool)),
-        "tasm_list_safeimplu32_set_length___u32" => Box::new(SafeSetLength(DataType::U32)),
-        "tasm_list_safeimplu32_set_length___u64" => Box::new(SafeSetLength(DataType::U64)),
-        "tasm_list_safeimplu32_set_length___bfe" => Box::new(SafeSetLength(DataType::BFE)),
-        "tasm_list_safeimplu32_set_length___xfe" => Box::new(SafeSetLength(DataType::XFE)),
-        "tasm_list_safeimplu32_set_length___digest" => Box::new(SafeSetLength(DataType::Digest)),
-
         "tasm_list_safeimplu32_multiset_equality" => Box::new(crate::list::multiset_equality::MultisetEquality(ListType::Safe)),
 
         "tasm_list_safeimplu32_range" => Box::new(Range{list_type: ListType::Safe}),
 
-        // unsafe lists
-        "tasm_list_unsafeimplu32_get_element___bool" => Box::new(UnsafeGet(DataType::Bool)),
-        "tasm_list_unsafeimplu32_get_element___u32" => Box::new(UnsafeGet(DataType::U32)),
-        "tasm_list_unsafeimplu32_get_element___u64" => Box::new(UnsafeGet(DataType::U64)),
-        "tasm_list_unsafeimplu32_get_element___bfe" => Box::new(UnsafeGet(DataType::BFE)),
-        "tasm_list_unsafeimplu32_get_element___xfe" => Box::new(UnsafeGet(DataType::XFE)),
-        "tasm_list_unsafeimplu32_get_element___digest" => Box::new(UnsafeGet(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_pop___bool" => Box::new(UnsafePop(DataType::Bool)),
-        "tasm_list_unsafeimplu32_pop___u32" => Box::new(UnsafePop(DataType::U32)),
-        "tasm_list_unsafeimplu32_pop___u64" => Box::new(UnsafePop(DataType::U64)),
-        "tasm_list_unsafeimplu32_pop___bfe" => Box::new(UnsafePop(DataType::BFE)),
-        "tasm_list_unsafeimplu32_pop___xfe" => Box::new(UnsafePop(DataType::XFE)),
-        "tasm_list_unsafeimplu32_pop___digest" => Box::new(UnsafePop(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_push___bool" => Box::new(UnsafePush(DataType::Bool)),
-        "tasm_list_unsafeimplu32_push___u32" => Box::new(UnsafePush(DataType::U32)),
-        "tasm_list_unsafeimplu32_push___u64" => Box::new(UnsafePush(DataType::U64)),
-        "tasm_list_unsafeimplu32_push___bfe" => Box::new(UnsafePush(DataType::BFE)),
-        "tasm_list_unsafeimplu32_push___xfe" => Box::new(UnsafePush(DataType::XFE)),
-        "tasm_list_unsafeimplu32_push___digest" => Box::new(UnsafePush(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_set_element___bool" => Box::new(UnsafeSet(DataType::Bool)),
-        "tasm_list_unsafeimplu32_set_element___u32" => Box::new(UnsafeSet(DataType::U32)),
-        "tasm_list_unsafeimplu32_set_element___u64" => Box::new(UnsafeSet(DataType::U64)),
-        "tasm_list_unsafeimplu32_set_element___bfe" => Box::new(UnsafeSet(DataType::BFE)),
-        "tasm_list_unsafeimplu32_set_element___xfe" => Box::new(UnsafeSet(DataType::XFE)),
-        "tasm_list_unsafeimplu32_set_element___digest" => Box::new(UnsafeSet(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_new___bool" => Box::new(UnsafeNew(DataType::Bool)),
-        "tasm_list_unsafeimplu32_new___u32" => Box::new(UnsafeNew(DataType::U32)),
-        "tasm_list_unsafeimplu32_new___u64" => Box::new(UnsafeNew(DataType::U64)),
-        "tasm_list_unsafeimplu32_new___bfe" => Box::new(UnsafeNew(DataType::BFE)),
-        "tasm_list_unsafeimplu32_new___xfe" => Box::new(UnsafeNew(DataType::XFE)),
-        "tasm_list_unsafeimplu32_new___digest" => Box::new(UnsafeNew(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_length___bool" => Box::new(UnsafeLength(DataType::Bool)),
-        "tasm_list_unsafeimplu32_length___u32" => Box::new(UnsafeLength(DataType::U32)),
-        "tasm_list_unsafeimplu32_length___u64" => Box::new(UnsafeLength(DataType::U64)),
-        "tasm_list_unsafeimplu32_length___bfe" => Box::new(UnsafeLength(DataType::BFE)),
-        "tasm_list_unsafeimplu32_length___xfe" => Box::new(UnsafeLength(DataType::XFE)),
-        "tasm_list_unsafeimplu32_length___digest" => Box::new(UnsafeLength(DataType::Digest)),
-
-        "tasm_list_unsafeimplu32_set_length___bool" => Box::new(UnsafeSetLength(DataType::Bool)),
-        "tasm_list_unsafeimplu32_set_length___u32" => Box::new(UnsafeSetLength(DataType::U32)),
-        "tasm_list_unsafeimplu32_set_length___u64" => Box::new(UnsafeSetLength(DataType::U64)),
-        "tasm_list_unsafeimplu32_set_length___bfe" => Box::new(UnsafeSetLength(DataType::BFE)),
-        "tasm_list_unsafeimplu32_set_length___xfe" => Box::new(UnsafeSetLength(DataType::XFE)),
-        "tasm_list_unsafeimplu32_set_length___digest" => Box::new(UnsafeSetLength(DataType::Digest)),
+        "tasm_list_safeimplu32_sort_u32" => Box::new(ListSortU64{list_type: ListType::Safe, element_type: DataType::U32}),
+        "tasm_list_safeimplu32_sort_u64" => Box::new(ListSortU64{list_type: ListType::Safe, element_type: DataType::U64}),
+        "tasm_list_safeimplu32_multiset_equals_u64" => Box::new(MultisetEqualsU64(ListType::Safe)),
 
+        // unsafe lists
         "tasm_list_unsafeimplu32_multiset_equality" => Box::new(crate::list::multiset_equality::MultisetEquality(ListType::Unsafe)),
         "tasm_list_unsafeimplu32_range" => Box::new(Range{list_type: ListType::Unsafe}),
+        "tasm_list_unsafeimplu32_sort_u32" => Box::new(ListSortU64{list_type: ListType::Unsafe, element_type: DataType::U32}),
+        "tasm_list_unsafeimplu32_sort_u64" => Box::new(ListSortU64{list_type: ListType::Unsafe, element_type: DataType::U64}),
+        "tasm_list_unsafeimplu32_multiset_equals_u64" => Box::new(MultisetEqualsU64(ListType::Unsafe)),
 
         // Contiguous lists
         "tasm_list_contiguous_list_get_length" => Box::new(contiguous_list::get_length::GetLength),
@@ -353,26 +374,37 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_list_contiguous_list_get_pointer_list_safeimplu32" => Box::new(contiguous_list::get_pointer_list::GetPointerList{output_list_type:ListType::Safe}),
 
         // MMR
-        "tasm_mmr_calculate_new_peaks_from_append_unsafeimplu32" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Unsafe }),
-        "tasm_mmr_calculate_new_peaks_from_append_safeimplu32" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Safe }),
+        "tasm_mmr_calculate_new_peaks_from_append_unsafeimplu32" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Unsafe, loop_bound: None }),
+        "tasm_mmr_calculate_new_peaks_from_append_safeimplu32" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Safe, loop_bound: None }),
+        "tasm_mmr_calculate_new_peaks_from_append_unsafeimplu32_bounded_64" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Unsafe, loop_bound: Some(MAX_MMR_HEIGHT) }),
+        "tasm_mmr_calculate_new_peaks_from_append_safeimplu32_bounded_64" => Box::new(CalculateNewPeaksFromAppend { list_type: ListType::Safe, loop_bound: Some(MAX_MMR_HEIGHT) }),
         "tasm_mmr_calculate_new_peaks_from_leaf_mutation_unsafeimplu32" => {
             Box::new(MmrCalculateNewPeaksFromLeafMutationMtIndices{ list_type: ListType::Unsafe} )
         }
         "tasm_mmr_calculate_new_peaks_from_leaf_mutation_safeimplu32" => {
             Box::new(MmrCalculateNewPeaksFromLeafMutationMtIndices{ list_type: ListType::Safe} )
         }
-        "tasm_mmr_data_index_to_node_index" => Box::new(DataIndexToNodeIndex),
+        "tasm_mmr_data_index_to_node_index" => Box::new(MmrDataIndexToNodeIndex),
         "tasm_mmr_get_height_from_leaf_index" => Box::new(GetHeightFromDataIndex),
+        "tasm_mmr_leaf_count_to_num_peaks" => Box::new(MmrLeafCountToNumPeaks),
         "tasm_mmr_leaf_index_to_mt_index_and_peak_index" => Box::new(MmrLeafIndexToMtIndexAndPeakIndex),
         "tasm_mmr_left_child" => Box::new(MmrLeftChild),
         "tasm_mmr_leftmost_ancestor" => Box::new(MmrLeftMostAncestor),
         "tasm_mmr_verify_load_from_secret_in_unsafeimplu32" => Box::new(MmrLoadFromSecretInThenVerify { list_type: ListType::Unsafe }),
         "tasm_mmr_verify_load_from_secret_in_safeimplu32" => Box::new(MmrLoadFromSecretInThenVerify { list_type: ListType::Safe }),
+        "tasm_mmr_new" => Box::new(MmrNew),
+        "tasm_mmr_node_index_to_leaf_index" => Box::new(MmrNodeIndexToLeafIndex),
         "tasm_mmr_non_leaf_nodes_left" => Box::new(MmrNonLeafNodesLeftUsingAnd),
+        "tasm_mmr_peaks_and_leaf_count_consistency" => Box::new(MmrPeaksAndLeafCountConsistency),
         "tasm_mmr_right_child_and_height" => Box::new(MmrRightChildAndHeight),
+        "tasm_mmr_right_child_and_height_bounded_64" => Box::new(MmrRightChildAndHeightBounded { loop_bound: MAX_MMR_HEIGHT }),
+        "tasm_mmr_loop_guard_64" => Box::new(MmrLoopGuard { max_iterations: MAX_MMR_HEIGHT }),
+        "tasm_mmr_right_child_and_height_iterative" => Box::new(MmrRightChildAndHeightIterative),
         "tasm_mmr_right_child" => Box::new(MmrRightChild),
         "tasm_mmr_right_lineage_count_and_own_height" => Box::new(MmrRightLineageCountAndHeight),
         "tasm_mmr_right_lineage_length" => Box::new(MmrRightLineageLength),
+        "tasm_mmr_verify_batch_append_unsafeimplu32" => Box::new(MmrVerifyBatchAppend { list_type: ListType::Unsafe }),
+        "tasm_mmr_verify_batch_append_safeimplu32" => Box::new(MmrVerifyBatchAppend { list_type: ListType::Safe }),
         "tasm_mmr_verify_from_memory_unsafeimplu32" => Box::new(MmrVerifyFromMemory { list_type: ListType::Unsafe} ),
         "tasm_mmr_verify_from_memory_safeimplu32" => Box::new(MmrVerifyFromMemory { list_type: ListType::Safe} ),
         "tasm_mmr_verify_from_secret_in_unsafeimplu32" => Box::new(MmrVerifyLeafMembershipFromSecretIn { list_type: ListType::Unsafe }),
@@ -391,7 +423,9 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         "tasm_recufier_mt_ap_verify" => Box::new(MerkleVerify),
 
         // memory
+        "tasm_memory_dyn_free" => Box::new(DynFree),
         "tasm_memory_dyn_malloc" => Box::new(DynMalloc),
+        "tasm_memory_dyn_malloc_aligned" => Box::new(DynMallocAligned),
         "tasm_memory_memcpy" => Box::new(MemCpy),
 
         "tasm_memory_push_ram_to_stack___digest" => Box::new(PushRamToStack {
@@ -428,3 +462,106 @@ pub fn name_to_snippet(fn_name: &str) -> Box<dyn BasicSnippet> {
         _ => panic!("Could not find \"{fn_name}\" in the function `exported_snippets`. Did you include it there?"),
     }
 }
+
+/// Resolve the list snippets that are generic over their element type by parsing the
+/// element type from the `___<type>` suffix of the entrypoint, e.g.
+/// `tasm_list_safeimplu32_pop___digest` resolves to `SafePop(DataType::Digest)`.
+fn generic_list_snippet(fn_name: &str) -> Option<Box<dyn BasicSnippet>> {
+    let (prefix, type_label) = fn_name.split_once("___")?;
+    let data_type = DataType::from_str(type_label).ok()?;
+
+    let snippet: Box<dyn BasicSnippet> = match prefix {
+        "tasm_list_safeimplu32_get_element" => Box::new(SafeGet(data_type)),
+        "tasm_list_safeimplu32_pop" => Box::new(SafePop(data_type)),
+        "tasm_list_safeimplu32_push" => Box::new(SafePush(data_type)),
+        "tasm_list_safeimplu32_set_element" => Box::new(SafeSet(data_type)),
+        "tasm_list_safeimplu32_new" => Box::new(SafeNew(data_type)),
+        "tasm_list_safeimplu32_length" => Box::new(SafeLength(data_type)),
+        "tasm_list_safeimplu32_set_length" => Box::new(SafeSetLength(data_type)),
+        "tasm_list_safeimplu32_equals" => Box::new(SafeListEquals(data_type)),
+        "tasm_list_safeimplu32_insert" => Box::new(SafeInsert(data_type)),
+        "tasm_list_safeimplu32_remove" => Box::new(SafeRemove(data_type)),
+        "tasm_list_safeimplu32_split_off" => Box::new(SafeSplitOff(data_type)),
+        "tasm_list_safeimplu32_clone" => Box::new(SafeListClone(data_type)),
+        "tasm_list_safeimplu32_resize" => Box::new(SafeResize(data_type)),
+        "tasm_list_safeimplu32_min_max" if matches!(data_type, DataType::U32 | DataType::U64) => {
+            Box::new(SafeMinMax(data_type))
+        }
+        "tasm_list_safeimplu32_sort" if matches!(data_type, DataType::U32 | DataType::U64) => {
+            Box::new(SafeListSort(data_type))
+        }
+        "tasm_list_safeimplu32_binary_search"
+            if matches!(data_type, DataType::U32 | DataType::U64) =>
+        {
+            Box::new(SafeBinarySearch(data_type))
+        }
+
+        "tasm_list_unsafeimplu32_get_element" => Box::new(UnsafeGet(data_type)),
+        "tasm_list_unsafeimplu32_pop" => Box::new(UnsafePop(data_type)),
+        "tasm_list_unsafeimplu32_push" => Box::new(UnsafePush(data_type)),
+        "tasm_list_unsafeimplu32_set_element" => Box::new(UnsafeSet(data_type)),
+        "tasm_list_unsafeimplu32_new" => Box::new(UnsafeNew(data_type)),
+        "tasm_list_unsafeimplu32_length" => Box::new(UnsafeLength(data_type)),
+        "tasm_list_unsafeimplu32_set_length" => Box::new(UnsafeSetLength(data_type)),
+
+        "tasm_list_safe_to_unsafe" => Box::new(SafeToUnsafe(data_type)),
+        "tasm_list_unsafe_to_safe" => Box::new(UnsafeToSafe(data_type)),
+        _ => return None,
+    };
+
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generic_list_snippets_recover_their_element_type() {
+        let data_types = [
+            DataType::Bool,
+            DataType::U32,
+            DataType::U64,
+            DataType::U128,
+            DataType::BFE,
+            DataType::XFE,
+            DataType::Digest,
+            DataType::Tuple(vec![DataType::U32, DataType::Digest]),
+        ];
+        for data_type in data_types {
+            let snippets: Vec<Box<dyn BasicSnippet>> = vec![
+                Box::new(SafeGet(data_type.clone())),
+                Box::new(SafePop(data_type.clone())),
+                Box::new(SafePush(data_type.clone())),
+                Box::new(SafeSet(data_type.clone())),
+                Box::new(SafeLength(data_type.clone())),
+                Box::new(UnsafeGet(data_type.clone())),
+                Box::new(UnsafePop(data_type.clone())),
+                Box::new(UnsafePush(data_type.clone())),
+                Box::new(UnsafeSet(data_type.clone())),
+                Box::new(UnsafeLength(data_type.clone())),
+            ];
+            for snippet in snippets {
+                let looked_up_snippet = name_to_snippet(&snippet.entrypoint());
+                assert_eq!(snippet.entrypoint(), looked_up_snippet.entrypoint());
+                assert_eq!(snippet.inputs(), looked_up_snippet.inputs());
+                assert_eq!(snippet.outputs(), looked_up_snippet.outputs());
+            }
+        }
+    }
+
+    #[test]
+    fn pop_digest_is_looked_up_as_digest_variant() {
+        let looked_up_snippet = name_to_snippet(&SafePop(DataType::Digest).entrypoint());
+        assert_eq!(
+            SafePop(DataType::Digest).outputs(),
+            looked_up_snippet.outputs()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_element_type_is_not_resolved() {
+        name_to_snippet("tasm_list_safeimplu32_pop___u33");
+    }
+}
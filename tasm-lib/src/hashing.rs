@@ -1,10 +1,17 @@
 pub mod absorb;
+pub mod assert_digest;
 pub mod eq_digest;
 pub mod hash_varlen;
 pub mod load_auth_path_from_secret_in_safe_list;
 pub mod load_auth_path_from_secret_in_unsafe_list;
 pub mod load_auth_path_from_std_in_safe_list;
 pub mod load_auth_path_from_std_in_unsafe_list;
+pub mod merkle_root;
+pub mod merkle_verify;
 pub mod reverse_digest;
 pub mod sample_indices;
+pub mod sample_scalars;
+pub mod sample_unique_indices;
+pub mod sponge_absorb;
+pub mod sponge_squeeze;
 pub mod swap_digest;
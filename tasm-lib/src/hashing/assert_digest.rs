@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, Digest, ExecutionState, DIGEST_LENGTH};
+
+/// Pop a digest and assert that it equals the digest divined from nondeterminism. Exactly
+/// five tokens are divined, in the order of the digest's encoding, i.e., the token for
+/// the word on top of the stack comes first.
+#[derive(Clone, Debug)]
+pub struct AssertDigestEqDivined;
+
+impl AssertDigestEqDivined {
+    fn prepare_state(&self, digest: Digest, divined_digest: Digest) -> ExecutionState {
+        let mut stack = get_init_tvm_stack();
+        push_encodable(&mut stack, &digest);
+
+        ExecutionState {
+            stack,
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(divined_digest.encode()),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
+}
+
+impl DeprecatedSnippet for AssertDigestEqDivined {
+    fn entrypoint_name(&self) -> String {
+        "tasm_hashing_assert_digest_eq_divined".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "digest_4".to_string(),
+            "digest_3".to_string(),
+            "digest_2".to_string(),
+            "digest_1".to_string(),
+            "digest_0".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::Digest]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -(DIGEST_LENGTH as isize)
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let assert_word_eq_divined = "divine\neq\nassert\n".repeat(DIGEST_LENGTH);
+
+        format!(
+            "
+            // BEFORE: _ digest_4 digest_3 digest_2 digest_1 digest_0
+            // AFTER: _
+            {entrypoint}:
+                {assert_word_eq_divined}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "secret input too short".to_string(),
+            "digest does not equal divined digest".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        (0..10)
+            .map(|_| {
+                let digest: Digest = rng.gen();
+                self.prepare_state(digest, digest)
+            })
+            .collect()
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        let digest: Digest = rand::thread_rng().gen();
+        self.prepare_state(digest, digest)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.common_case_input_state()
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let digest = Digest::new([
+            stack.pop().unwrap(),
+            stack.pop().unwrap(),
+            stack.pop().unwrap(),
+            stack.pop().unwrap(),
+            stack.pop().unwrap(),
+        ]);
+
+        assert!(secret_in.len() >= DIGEST_LENGTH, "secret input too short");
+        assert_eq!(
+            digest.values().to_vec(),
+            secret_in[..DIGEST_LENGTH].to_vec(),
+            "digest must equal divined digest"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::One;
+
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn assert_digest_eq_divined_test() {
+        test_rust_equivalence_multiple_deprecated(&AssertDigestEqDivined, true);
+    }
+
+    #[test]
+    fn matching_digest_consumes_exactly_five_tokens() {
+        // Any token read beyond the five supplied ones would crash the VM.
+        let digest: Digest = rand::thread_rng().gen();
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &AssertDigestEqDivined,
+            AssertDigestEqDivined.prepare_state(digest, digest),
+        );
+        assert_eq!(get_init_tvm_stack(), vm_output_state.final_stack);
+    }
+
+    #[should_panic]
+    #[test]
+    fn mismatching_digest_crashes() {
+        let digest: Digest = rand::thread_rng().gen();
+        let mut words = digest.values();
+        words[DIGEST_LENGTH - 1] += BFieldElement::one();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &AssertDigestEqDivined,
+            AssertDigestEqDivined.prepare_state(digest, Digest::new(words)),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn fewer_than_five_tokens_crashes() {
+        let digest: Digest = rand::thread_rng().gen();
+        let mut init_state = AssertDigestEqDivined.prepare_state(digest, digest);
+        init_state.nondeterminism = NonDeterminism::new(digest.values()[..4].to_vec());
+        test_rust_equivalence_given_execution_state_deprecated(&AssertDigestEqDivined, init_state);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn assert_digest_eq_divined_benchmark() {
+        bench_and_write(AssertDigestEqDivined);
+    }
+}
@@ -7,6 +7,8 @@ use crate::library::Library;
 use crate::snippet::{DataType, DeprecatedSnippet};
 use crate::{get_init_tvm_stack, push_encodable, Digest, ExecutionState};
 
+/// Compare two digests for equality. All five word pairs are compared and the results
+/// are combined, so a difference in any single word yields `false`.
 #[derive(Clone, Debug)]
 pub struct EqDigest;
 
@@ -51,7 +53,14 @@ impl DeprecatedSnippet for EqDigest {
         push_encodable(&mut stack, &digest_b);
         push_encodable(&mut stack, &digest_a);
 
-        vec![ExecutionState::with_stack(stack)]
+        let mut equal_stack = get_init_tvm_stack();
+        push_encodable(&mut equal_stack, &digest_a);
+        push_encodable(&mut equal_stack, &digest_a);
+
+        vec![
+            ExecutionState::with_stack(stack),
+            ExecutionState::with_stack(equal_stack),
+        ]
     }
 
     fn stack_diff(&self) -> isize {
@@ -139,14 +148,60 @@ impl DeprecatedSnippet for EqDigest {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+    use num::One;
+
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+    use crate::DIGEST_LENGTH;
 
     use super::*;
 
     #[test]
-    fn swap_digest_test() {
+    fn eq_digest_test() {
         test_rust_equivalence_multiple_deprecated(&EqDigest, true);
     }
+
+    #[test]
+    fn equal_digests() {
+        let digest: Digest = rand::thread_rng().gen();
+        prop_eq_digest(digest, digest, true);
+        prop_eq_digest(Digest::default(), Digest::default(), true);
+    }
+
+    #[test]
+    fn digests_differing_in_one_word() {
+        let digest: Digest = rand::thread_rng().gen();
+        for word_index in 0..DIGEST_LENGTH {
+            let mut words = digest.values();
+            words[word_index] += BFieldElement::one();
+            let other_digest = Digest::new(words);
+
+            prop_eq_digest(digest, other_digest, false);
+            prop_eq_digest(other_digest, digest, false);
+        }
+    }
+
+    fn prop_eq_digest(digest_a: Digest, digest_b: Digest, expected_equal: bool) {
+        let mut init_stack = get_init_tvm_stack();
+        push_encodable(&mut init_stack, &digest_b);
+        push_encodable(&mut init_stack, &digest_a);
+
+        let expected = [
+            get_init_tvm_stack(),
+            vec![BFieldElement::new(expected_equal as u64)],
+        ]
+        .concat();
+        test_rust_equivalence_given_input_values_deprecated(
+            &EqDigest,
+            &init_stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
 }
 
 #[cfg(test)]
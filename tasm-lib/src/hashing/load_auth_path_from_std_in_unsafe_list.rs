@@ -70,6 +70,11 @@ impl DeprecatedSnippet for LoadAuthPathFromStdInUnsafeList {
         "tasm_hashing_load_auth_path_from_std_in_unsafe_list".to_string()
     }
 
+    fn static_allocation_size(&self) -> usize {
+        // An unsafe list of `MAX_MMR_HEIGHT` digests
+        DIGEST_LENGTH * MAX_MMR_HEIGHT + 1
+    }
+
     fn function_code(&self, library: &mut Library) -> String {
         let entrypoint = self.entrypoint_name();
 
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::{is_power_of_two, random_elements};
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::library::Library;
+use crate::list::ListType;
+use crate::rust_shadowing_helper_functions::{safe_list, unsafe_list};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, Digest, ExecutionState, VmHasher, DIGEST_LENGTH};
+
+/// Largest number of leaves the statically allocated scratch region can accommodate.
+pub const MAX_NUM_LEAVES: usize = 1 << 10;
+
+/// MerkleRoot -- compute the root of the Merkle tree whose leaves are the digests of
+/// the given list. The number of leaves must be a power of two. Every layer of inner
+/// nodes is written to a statically allocated scratch region, which is overwritten by
+/// subsequent calls to this function.
+///
+/// inputs:
+///
+///  - *leaf_list : List<Digest>
+///
+/// outputs:
+///
+///  - root : Digest
+#[derive(Clone, Debug)]
+pub struct MerkleRoot {
+    pub list_type: ListType,
+}
+
+impl MerkleRoot {
+    /// Statically allocate the scratch region. Every layer of inner nodes has at most
+    /// half as many nodes as there are leaves.
+    fn allocate_scratch(library: &mut Library) -> usize {
+        library.kmalloc(DIGEST_LENGTH * MAX_NUM_LEAVES / 2)
+    }
+
+    fn init_state(&self, leaves: &[Digest]) -> ExecutionState {
+        // Place the leaf list right after the scratch region, which is the layout the
+        // rust shadow relies on.
+        let mut library = Library::new();
+        Self::allocate_scratch(&mut library);
+        let leaf_list = BFieldElement::new(library.get_next_free_address() as u64);
+        let mut memory = HashMap::default();
+        match self.list_type {
+            ListType::Safe => {
+                safe_list::safe_list_new(leaf_list, MAX_NUM_LEAVES as u32, &mut memory)
+            }
+            ListType::Unsafe => unsafe_list::unsafe_list_new(leaf_list, &mut memory),
+        }
+        for leaf in leaves {
+            let leaf = leaf.values().to_vec();
+            match self.list_type {
+                ListType::Safe => {
+                    safe_list::safe_list_push(leaf_list, leaf, &mut memory, DIGEST_LENGTH)
+                }
+                ListType::Unsafe => {
+                    unsafe_list::unsafe_list_push(leaf_list, leaf, &mut memory, DIGEST_LENGTH)
+                }
+            }
+        }
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(leaf_list);
+
+        ExecutionState {
+            stack,
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory,
+            words_allocated: 0,
+        }
+    }
+}
+
+impl DeprecatedSnippet for MerkleRoot {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_hashing_merkle_root_from_{}_list", self.list_type)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*leaf_list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::Digest))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![
+            "root_4".to_string(),
+            "root_3".to_string(),
+            "root_2".to_string(),
+            "root_1".to_string(),
+            "root_0".to_string(),
+        ]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Digest]
+    }
+
+    fn stack_diff(&self) -> isize {
+        4
+    }
+
+    fn static_allocation_size(&self) -> usize {
+        let mut library = Library::new();
+        Self::allocate_scratch(&mut library);
+        library.kmalloc_total()
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+
+        let scratch = Self::allocate_scratch(library);
+        let max_num_leaves_plus_one = MAX_NUM_LEAVES + 1;
+        let one_half = BFieldElement::MAX / 2 + 1;
+        let pair_size = 2 * DIGEST_LENGTH;
+
+        let safety_offset = match self.list_type {
+            ListType::Safe => 2,
+            ListType::Unsafe => 1,
+        };
+
+        // _ *node -> _ [node]
+        let read_digest = format!(
+            "push {}\n add\n {} read_mem\n swap 1\n pop\n",
+            DIGEST_LENGTH - 1,
+            "read_mem\n swap 1\n push -1\n add\n".repeat(DIGEST_LENGTH - 1)
+        );
+
+        // _ *left -> _ [right] [left]
+        let read_digest_pair = format!(
+            "push {}\n add\n {} read_mem\n swap 1\n pop\n",
+            2 * DIGEST_LENGTH - 1,
+            "read_mem\n swap 1\n push -1\n add\n".repeat(2 * DIGEST_LENGTH - 1)
+        );
+
+        // _ *dst [digest] -> _ *dst
+        let write_digest_to_dst = format!(
+            "dup 6\n swap 1\n write_mem\n {} pop\n",
+            "push 1\n add\n swap 1\n write_mem\n".repeat(DIGEST_LENGTH - 1)
+        );
+
+        format!(
+            "
+            // BEFORE: _ *leaf_list
+            // AFTER: _ root_4 root_3 root_2 root_1 root_0
+            {entrypoint}:
+                dup 0
+                read_mem
+                swap 1
+                pop
+                // _ *leaf_list num_leaves
+
+                // assert num_leaves is a power of two
+                dup 0
+                push 0
+                eq
+                push 0
+                eq
+                assert
+                dup 0
+                push -1
+                add
+                dup 1
+                and
+                push 0
+                eq
+                assert
+
+                // assert the scratch region is large enough
+                push {max_num_leaves_plus_one}
+                dup 1
+                lt
+                assert
+                // _ *leaf_list num_leaves
+
+                swap 1
+                push {safety_offset}
+                add
+                swap 1
+                // _ *leaves num_leaves
+
+                call {entrypoint}_layers
+                // _ *root 1
+
+                pop
+                {read_digest}
+                // _ [root]
+
+                return
+
+            // INVARIANT: _ *nodes num_nodes
+            {entrypoint}_layers:
+                dup 0
+                push 1
+                eq
+                skiz
+                    return
+
+                push {one_half}
+                mul
+                // _ *nodes num_parents
+
+                swap 1
+                push {scratch}
+                dup 2
+                // _ num_parents *nodes *scratch num_parents
+
+                call {entrypoint}_hash_pairs
+                // _ num_parents *nodes' *scratch' 0
+
+                pop
+                pop
+                pop
+                push {scratch}
+                swap 1
+                // _ *scratch num_parents
+
+                recurse
+
+            // INVARIANT: _ *src *dst remaining
+            {entrypoint}_hash_pairs:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                dup 2
+                {read_digest_pair}
+                // _ *src *dst remaining [right] [left]
+
+                hash
+                pop
+                pop
+                pop
+                pop
+                pop
+                // _ *src *dst remaining [parent]
+
+                {write_digest_to_dst}
+                // _ *src *dst remaining
+
+                push -1
+                add
+                swap 1
+                push {DIGEST_LENGTH}
+                add
+                swap 1
+                swap 2
+                push {pair_size}
+                add
+                swap 2
+                // _ (*src + 10) (*dst + 5) (remaining - 1)
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "number of leaves is not a power of two".to_string(),
+            format!("number of leaves exceeds {MAX_NUM_LEAVES}"),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        (0..=5)
+            .map(|log_num_leaves| self.init_state(&random_elements(1 << log_num_leaves)))
+            .collect_vec()
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.init_state(&random_elements(1 << 6))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.init_state(&random_elements(MAX_NUM_LEAVES))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let leaf_list = stack.pop().unwrap();
+        let num_leaves = match self.list_type {
+            ListType::Safe => safe_list::safe_list_get_length(leaf_list, memory),
+            ListType::Unsafe => unsafe_list::unsafe_list_get_length(leaf_list, memory),
+        };
+        assert!(is_power_of_two(num_leaves));
+        assert!(num_leaves <= MAX_NUM_LEAVES);
+
+        let mut nodes = (0..num_leaves)
+            .map(|i| {
+                let words = match self.list_type {
+                    ListType::Safe => safe_list::safe_list_get(leaf_list, i, memory, DIGEST_LENGTH),
+                    ListType::Unsafe => {
+                        unsafe_list::unsafe_list_get(leaf_list, i, memory, DIGEST_LENGTH)
+                    }
+                };
+                Digest::new(words.try_into().unwrap())
+            })
+            .collect_vec();
+
+        // Mirror the TASM code's writes to the scratch region. The TASM code allocates it
+        // on top of whatever was statically allocated before, which the shadow cannot
+        // see. So it relies on the layout of `init_state`, where nothing is allocated
+        // before the scratch region and the leaf list directly follows it.
+        let mut library = Library::new();
+        let scratch = BFieldElement::new(Self::allocate_scratch(&mut library) as u64);
+        assert_eq!(
+            BFieldElement::new(library.get_next_free_address() as u64),
+            leaf_list,
+            "leaf list must directly follow the scratch region"
+        );
+        while nodes.len() > 1 {
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| VmHasher::hash_pair(pair[0], pair[1]))
+                .collect_vec();
+            for (i, word) in nodes.iter().flat_map(|node| node.values()).enumerate() {
+                memory.insert(scratch + BFieldElement::new(i as u64), word);
+            }
+        }
+
+        for word in nodes[0].values().into_iter().rev() {
+            stack.push(word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::util_types::merkle_tree::{CpuParallel, MerkleTree};
+    use twenty_first::util_types::merkle_tree_maker::MerkleTreeMaker;
+
+    use super::*;
+    use crate::test_helpers::{
+        test_crash_conditions, test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn merkle_root_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(&MerkleRoot { list_type }, true);
+        }
+    }
+
+    #[test]
+    fn merkle_root_matches_reference_merkle_tree() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = MerkleRoot { list_type };
+            for num_leaves in [1, 2, 4, 8] {
+                let leaves: Vec<Digest> = random_elements(num_leaves);
+                let tree: MerkleTree<VmHasher> = CpuParallel::from_digests(&leaves);
+                let expected_root = tree.get_root();
+
+                let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+                    &snippet,
+                    snippet.init_state(&leaves),
+                );
+                let final_stack = vm_output_state.final_stack;
+                let root_words = final_stack[final_stack.len() - DIGEST_LENGTH..]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect_vec();
+                assert_eq!(expected_root, Digest::new(root_words.try_into().unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn merkle_root_non_power_of_two_leaf_count() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = MerkleRoot { list_type };
+            let cases = [0, 3, 6]
+                .into_iter()
+                .map(|num_leaves| {
+                    (
+                        snippet.init_state(&random_elements(num_leaves)),
+                        "number of leaves is not a power of two",
+                    )
+                })
+                .collect_vec();
+            test_crash_conditions(&snippet, cases);
+        }
+    }
+
+    #[test]
+    fn merkle_root_too_many_leaves() {
+        let snippet = MerkleRoot {
+            list_type: ListType::Unsafe,
+        };
+        let condition = format!("number of leaves exceeds {MAX_NUM_LEAVES}");
+        test_crash_conditions(
+            &snippet,
+            vec![(
+                snippet.init_state(&random_elements(2 * MAX_NUM_LEAVES)),
+                &condition,
+            )],
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn merkle_root_benchmark() {
+        bench_and_write(MerkleRoot {
+            list_type: ListType::Unsafe,
+        });
+    }
+}
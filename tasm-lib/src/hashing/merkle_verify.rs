@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::random_elements;
+use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+use crate::hashing::swap_digest::SwapDigest;
+use crate::library::Library;
+use crate::rust_shadowing_helper_functions::input::{
+    read_digest_from_secret_in, write_digest_to_secret_in,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, Digest, ExecutionState, VmHasher, DIGEST_LENGTH};
+
+/// MerkleVerify -- verify that a leaf lives in a Merkle tree, given the root, the leaf
+/// index, the tree height, and the leaf. The `tree_height` sibling digests of the
+/// authentication path are read from secret input. Crashes the VM if the path is not
+/// valid.
+///
+/// See also: [crate::recufier::merkle_verify::MerkleVerify], which reads the
+/// authentication path from the nondeterministic digests.
+#[derive(Clone, Debug)]
+pub struct MerkleVerify;
+
+impl MerkleVerify {
+    /// Prepare a state with a valid authentication path for a random leaf. If
+    /// `corrupt_sibling_at_height` is set, the sibling at that height is replaced by a
+    /// random digest.
+    fn prepare_state(
+        leaf_index: u32,
+        tree_height: u32,
+        corrupt_sibling_at_height: Option<usize>,
+    ) -> ExecutionState {
+        let leaf: Digest = random();
+        let mut path: Vec<Digest> = random_elements(tree_height as usize);
+
+        let mut node_digest = leaf;
+        let mut node_index = leaf_index as u64 + (1 << tree_height);
+        for sibling in path.iter() {
+            node_digest = if node_index & 1 == 0 {
+                VmHasher::hash_pair(node_digest, *sibling)
+            } else {
+                VmHasher::hash_pair(*sibling, node_digest)
+            };
+            node_index /= 2;
+        }
+        let root = node_digest;
+
+        if let Some(height) = corrupt_sibling_at_height {
+            path[height] = random();
+        }
+
+        let mut stack = get_init_tvm_stack();
+        for r in root.values().into_iter().rev() {
+            stack.push(r);
+        }
+        stack.push(BFieldElement::new(leaf_index as u64));
+        stack.push(BFieldElement::new(tree_height as u64));
+        for l in leaf.values().into_iter().rev() {
+            stack.push(l);
+        }
+
+        let mut secret_in = vec![];
+        for sibling in path {
+            write_digest_to_secret_in(&mut secret_in, sibling);
+        }
+
+        ExecutionState {
+            stack,
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(secret_in),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
+}
+
+impl DeprecatedSnippet for MerkleVerify {
+    fn entrypoint_name(&self) -> String {
+        "tasm_hashing_merkle_verify".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "root_4".to_string(),
+            "root_3".to_string(),
+            "root_2".to_string(),
+            "root_1".to_string(),
+            "root_0".to_string(),
+            "leaf_index".to_string(),
+            "tree_height".to_string(),
+            "leaf_4".to_string(),
+            "leaf_3".to_string(),
+            "leaf_2".to_string(),
+            "leaf_1".to_string(),
+            "leaf_0".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::Digest,
+            DataType::U32,
+            DataType::U32,
+            DataType::Digest,
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -12
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let swap_digests = library.import(Box::new(SwapDigest));
+        let divine_digest = "divine\n".repeat(DIGEST_LENGTH);
+
+        format!(
+            "
+            // BEFORE: _ [root] leaf_index tree_height [leaf]
+            // AFTER: _
+            {entrypoint}:
+                dup 5
+                push 2
+                pow
+                dup 7
+                add
+                // _ [root] leaf_index tree_height [leaf] node_index
+
+                swap 7
+                pop
+                // _ [root] node_index tree_height [leaf]
+
+                call {entrypoint}_traverse_tree
+                // _ [root] 1 tree_height [root']
+
+                swap 1 swap 2 swap 3 swap 4 swap 5
+                pop
+                swap 1 swap 2 swap 3 swap 4 swap 5
+                pop
+                // _ [root] [root']
+
+                assert_vector
+                pop pop pop pop pop
+                pop pop pop pop pop
+                // _
+
+                return
+
+            // INVARIANT: _ [root] node_index tree_height [node]
+            {entrypoint}_traverse_tree:
+                dup 6
+                push 1
+                eq
+                skiz
+                    return
+
+                {divine_digest}
+                // _ [root] node_index tree_height [node] [sibling]
+
+                dup 11
+                push 2
+                swap 1
+                div
+                // _ [root] node_index tree_height [node] [sibling] (node_index / 2) (node_index % 2)
+
+                push 0
+                eq
+                swap 1
+                swap 13
+                pop
+                // _ [root] (node_index / 2) tree_height [node] [sibling] (node_index is even)
+
+                // if the node is a left child, move it on top
+                skiz
+                    call {swap_digests}
+                // _ [root] (node_index / 2) tree_height [right] [left]
+
+                hash
+                pop pop pop pop pop
+                // _ [root] (node_index / 2) tree_height [parent]
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "authentication path is invalid".to_string(),
+            "not enough sibling digests in secret input".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        (0..10)
+            .map(|tree_height| {
+                let leaf_index = rng.gen_range(0..(1u32 << tree_height));
+                Self::prepare_state(leaf_index, tree_height, None)
+            })
+            .collect_vec()
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state((1 << 5) + 3, 6, None)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state((1 << 19) + 3, 20, None)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let mut leaf_values = [BFieldElement::new(0); DIGEST_LENGTH];
+        for elem in leaf_values.iter_mut() {
+            *elem = stack.pop().unwrap();
+        }
+        let leaf = Digest::new(leaf_values);
+
+        let tree_height: u32 = stack.pop().unwrap().try_into().unwrap();
+        let leaf_index: u32 = stack.pop().unwrap().try_into().unwrap();
+
+        let mut root_values = [BFieldElement::new(0); DIGEST_LENGTH];
+        for elem in root_values.iter_mut() {
+            *elem = stack.pop().unwrap();
+        }
+        let root = Digest::new(root_values);
+
+        let mut secret_in_cursor = 0;
+        let mut node_digest = leaf;
+        let mut node_index = leaf_index as u64 + (1 << tree_height);
+        while node_index != 1 {
+            let sibling = read_digest_from_secret_in(&secret_in, &mut secret_in_cursor);
+            node_digest = if node_index & 1 == 0 {
+                VmHasher::hash_pair(node_digest, sibling)
+            } else {
+                VmHasher::hash_pair(sibling, node_digest)
+            };
+            node_index /= 2;
+        }
+
+        assert_eq!(root, node_digest, "authentication path must be valid");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn merkle_verify_test() {
+        test_rust_equivalence_multiple_deprecated(&MerkleVerify, true);
+    }
+
+    #[test]
+    fn merkle_verify_valid_paths() {
+        // all left-child steps, all right-child steps, and a mix of both
+        for leaf_index in [0b0000, 0b1111, 0b0110, 0b1001] {
+            test_rust_equivalence_given_execution_state_deprecated(
+                &MerkleVerify,
+                MerkleVerify::prepare_state(leaf_index, 4, None),
+            );
+        }
+    }
+
+    #[test]
+    fn merkle_verify_empty_path() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &MerkleVerify,
+            MerkleVerify::prepare_state(0, 0, None),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn merkle_verify_corrupted_sibling_of_left_child() {
+        // Leaf 0b0110 is a left child at height 0.
+        test_rust_equivalence_given_execution_state_deprecated(
+            &MerkleVerify,
+            MerkleVerify::prepare_state(0b0110, 4, Some(0)),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn merkle_verify_corrupted_sibling_of_right_child() {
+        // Node 0b011 above leaf 0b0110 is a right child at height 1.
+        test_rust_equivalence_given_execution_state_deprecated(
+            &MerkleVerify,
+            MerkleVerify::prepare_state(0b0110, 4, Some(1)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn merkle_verify_benchmark() {
+        bench_and_write(MerkleVerify);
+    }
+}
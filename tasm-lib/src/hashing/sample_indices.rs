@@ -381,7 +381,13 @@ impl DeprecatedSnippet for SampleIndices {
 #[cfg(test)]
 mod tests {
 
-    use crate::{list::ListType, test_helpers::test_rust_equivalence_multiple_deprecated};
+    use crate::{
+        list::ListType,
+        test_helpers::{
+            test_rust_equivalence_given_execution_state_deprecated,
+            test_rust_equivalence_multiple_deprecated, verify_sampled_indices,
+        },
+    };
 
     use super::SampleIndices;
 
@@ -400,6 +406,47 @@ mod tests {
             true,
         );
     }
+
+    /// Run the snippet from the initial sponge state and check the sampled indices.
+    fn prop_sampled_indices_invariants(
+        list_type: ListType,
+        number: usize,
+        upper_bound: u32,
+        expect_unique: bool,
+    ) {
+        let snippet = SampleIndices {
+            list_type: list_type.clone(),
+        };
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &snippet,
+            SampleIndices::test_state(number, upper_bound),
+        );
+        let list_pointer = *vm_output_state.final_stack.last().unwrap();
+
+        verify_sampled_indices(
+            list_pointer,
+            &vm_output_state.final_ram,
+            &list_type,
+            upper_bound,
+            expect_unique,
+        );
+    }
+
+    #[test]
+    fn sampled_indices_are_within_bound() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            prop_sampled_indices_invariants(list_type.clone(), 45, 1 << 12, false);
+            prop_sampled_indices_invariants(list_type.clone(), 20, 2, false);
+            prop_sampled_indices_invariants(list_type, 3, 1, false);
+        }
+    }
+
+    #[should_panic(expected = "must be unique")]
+    #[test]
+    fn repeated_indices_are_caught() {
+        // With an upper bound of 1, every sampled index is 0.
+        prop_sampled_indices_invariants(ListType::Safe, 2, 1, true);
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement, NonDeterminism};
+use twenty_first::{
+    shared_math::{tip5::RATE, x_field_element::EXTENSION_DEGREE},
+    util_types::algebraic_hasher::{Domain, SpongeHasher},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    list::ListType,
+    memory::dyn_malloc::DynMalloc,
+    procedure::Procedure,
+    rust_shadowing_helper_functions::{dyn_malloc::dynamic_allocator, safe_list, unsafe_list},
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+    VmHasher, VmHasherState,
+};
+
+/// Sample `num_scalars` extension field elements from the sponge, e.g., to derive
+/// Fiat-Shamir challenges. The sponge is squeezed `ceil(3 * num_scalars / 10)` times
+/// and the squeezed elements are chunked into scalars, consistent with twenty-first's
+/// `sample_scalars`. All squeezed elements are written to the freshly allocated list's
+/// element region, so the allocation may extend beyond the list's last element.
+pub struct SampleScalars {
+    pub list_type: ListType,
+}
+
+impl SampleScalars {
+    fn safety_offset(&self) -> usize {
+        match self.list_type {
+            ListType::Safe => 2,
+            ListType::Unsafe => 1,
+        }
+    }
+}
+
+impl BasicSnippet for SampleScalars {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::U32, "num_scalars".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::List(Box::new(DataType::XFE)), "*list".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        format!("tasm_hashing_sample_scalars_to_{}_list", self.list_type)
+    }
+
+    fn code(
+        &self,
+        library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let main_loop = format!("{entrypoint}_loop");
+        let dyn_malloc = library.import(Box::new(DynMalloc));
+        let safety_offset = self.safety_offset();
+        let rate_minus_one = RATE - 1;
+
+        // _ n ns *list *list -> _ n ns *list (*list + 1), writing the capacity if applicable
+        let write_capacity = match self.list_type {
+            ListType::Safe => "push 1\n add\n dup 3\n write_mem\n".to_string(),
+            ListType::Unsafe => String::default(),
+        };
+
+        // _ *elements remaining d9 ... d0 -> _ *elements remaining
+        let write_squeezed_elements = (0..RATE)
+            .map(|i| {
+                let add_index = if i == 0 {
+                    String::default()
+                } else {
+                    format!("push {i}\n add\n")
+                };
+                format!(
+                    "dup {}\n {add_index} swap 1\n write_mem\n pop\n",
+                    RATE + 1 - i
+                )
+            })
+            .join("");
+
+        triton_asm! {
+            // BEFORE: _ num_scalars
+            // AFTER: _ *list
+            {entrypoint}:
+                dup 0
+                push {EXTENSION_DEGREE}
+                mul
+                push {rate_minus_one}
+                add
+                push {RATE}
+                swap 1
+                div
+                pop
+                // _ num_scalars num_squeezes
+
+                dup 0
+                push {RATE}
+                mul
+                push {safety_offset}
+                add
+                call {dyn_malloc}
+                // _ num_scalars num_squeezes *list
+
+                dup 0
+                dup 3
+                write_mem
+                {write_capacity}
+                push 1
+                add
+                // _ num_scalars num_squeezes *list *elements
+
+                dup 2
+                call {main_loop}
+                // _ num_scalars num_squeezes *list *elements' 0
+
+                pop
+                pop
+                swap 2
+                pop
+                pop
+                // _ *list
+
+                return
+
+            // INVARIANT: _ *elements remaining_squeezes
+            {main_loop}:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                squeeze
+                // _ *elements remaining_squeezes d9 d8 d7 d6 d5 d4 d3 d2 d1 d0
+
+                {write_squeezed_elements}
+                // _ *elements remaining_squeezes
+
+                push -1
+                add
+                swap 1
+                push {RATE}
+                add
+                swap 1
+                // _ (*elements + 10) (remaining_squeezes - 1)
+
+                recurse
+        }
+    }
+}
+
+impl Procedure for SampleScalars {
+    fn rust_shadow(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+        _nondeterminism: &NonDeterminism<BFieldElement>,
+        _public_input: &[BFieldElement],
+        sponge_state: &mut VmHasherState,
+    ) -> Vec<BFieldElement> {
+        let num_scalars = stack.pop().unwrap().value() as usize;
+        let num_squeezes = (num_scalars * EXTENSION_DEGREE + RATE - 1) / RATE;
+
+        let list_pointer = dynamic_allocator(self.safety_offset() + num_squeezes * RATE, memory);
+        match self.list_type {
+            ListType::Safe => {
+                safe_list::safe_list_new(list_pointer, num_scalars as u32, memory);
+                safe_list::safe_list_set_length(list_pointer, num_scalars, memory);
+            }
+            ListType::Unsafe => {
+                unsafe_list::unsafe_list_new(list_pointer, memory);
+                unsafe_list::unsafe_list_set_length(list_pointer, num_scalars as u32, memory);
+            }
+        }
+
+        let elements = list_pointer + BFieldElement::new(self.safety_offset() as u64);
+        let squeezed = (0..num_squeezes)
+            .flat_map(|_| VmHasher::squeeze(sponge_state))
+            .collect_vec();
+        for (i, element) in squeezed.into_iter().enumerate() {
+            memory.insert(elements + BFieldElement::new(i as u64), element);
+        }
+
+        stack.push(list_pointer);
+
+        vec![]
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> (
+        Vec<BFieldElement>,
+        HashMap<BFieldElement, BFieldElement>,
+        NonDeterminism<BFieldElement>,
+        Vec<BFieldElement>,
+        VmHasherState,
+    ) {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let num_scalars = match bench_case {
+            Some(BenchmarkCase::CommonCase) => 10,
+            Some(BenchmarkCase::WorstCase) => 100,
+            Some(BenchmarkCase::Custom(_)) | None => rng.next_u32() % 40,
+        };
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(num_scalars as u64));
+
+        // The VM starts out with the same sponge state as a fresh variable-length sponge.
+        (
+            stack,
+            HashMap::new(),
+            NonDeterminism::new(vec![]),
+            vec![],
+            VmHasherState::new(Domain::VariableLength),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use twenty_first::shared_math::x_field_element::XFieldElement;
+    use twenty_first::util_types::algebraic_hasher::AlgebraicHasher;
+
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::{
+        rust_final_state, tasm_final_state, verify_hasher_state_equivalence,
+        verify_memory_equivalence, verify_stack_equivalence,
+    };
+
+    #[test]
+    fn sample_scalars_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            ShadowedProcedure::new(SampleScalars { list_type }).test();
+        }
+    }
+
+    #[test]
+    fn sample_scalars_matches_reference() {
+        // 7 scalars span three squeezes
+        for num_scalars in [0, 1, 7] {
+            for list_type in [ListType::Safe, ListType::Unsafe] {
+                prop_sample_scalars(list_type, num_scalars);
+            }
+        }
+    }
+
+    fn prop_sample_scalars(list_type: ListType, num_scalars: usize) {
+        let shadowed_procedure = ShadowedProcedure::new(SampleScalars {
+            list_type: list_type.clone(),
+        });
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(num_scalars as u64));
+        let nondeterminism = NonDeterminism::new(vec![]);
+        let memory = HashMap::new();
+        let sponge_state = VmHasherState::new(Domain::VariableLength);
+
+        let mut expected_sponge_state = sponge_state.clone();
+        let expected_scalars = VmHasher::sample_scalars(&mut expected_sponge_state, num_scalars);
+
+        let rust = rust_final_state(
+            &shadowed_procedure,
+            &stack,
+            &[],
+            &nondeterminism,
+            &memory,
+            &sponge_state,
+            0,
+        );
+        let tasm = tasm_final_state(
+            &shadowed_procedure,
+            &stack,
+            &[],
+            &nondeterminism,
+            &memory,
+            &sponge_state,
+            0,
+        );
+
+        verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+        verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+
+        let list_pointer = *tasm.final_stack.last().unwrap();
+        let length = match list_type {
+            ListType::Safe => safe_list::safe_list_get_length(list_pointer, &tasm.final_ram),
+            ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, &tasm.final_ram),
+        };
+        let scalars = (0..length)
+            .map(|i| {
+                let coefficients = match list_type {
+                    ListType::Safe => {
+                        safe_list::safe_list_get(list_pointer, i, &tasm.final_ram, EXTENSION_DEGREE)
+                    }
+                    ListType::Unsafe => unsafe_list::unsafe_list_get(
+                        list_pointer,
+                        i,
+                        &tasm.final_ram,
+                        EXTENSION_DEGREE,
+                    ),
+                };
+                XFieldElement::new(coefficients.try_into().unwrap())
+            })
+            .collect_vec();
+        assert_eq!(expected_scalars, scalars);
+
+        assert_eq!(expected_sponge_state.state, tasm.final_sponge_state.state);
+        verify_hasher_state_equivalence(rust, tasm);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+
+    #[test]
+    fn sample_scalars_benchmark() {
+        ShadowedProcedure::new(SampleScalars {
+            list_type: ListType::Unsafe,
+        })
+        .bench();
+    }
+}
@@ -0,0 +1,563 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::{One, Zero};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::NonDeterminism;
+use twenty_first::{
+    shared_math::{b_field_element::BFieldElement, other::is_power_of_two, tip5::Tip5},
+    util_types::algebraic_hasher::{Domain, SpongeHasher},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    list::{list_new, list_push, ListType},
+    memory::{dyn_free::DynFree, dyn_malloc_aligned::DynMallocAligned},
+    procedure::Procedure,
+    rust_shadowing_helper_functions::dyn_malloc::{dynamic_allocator_aligned, dynamic_free},
+    snippet::{DataType, DeprecatedSnippet},
+    snippet_bencher::BenchmarkCase,
+    ExecutionState, VmHasherState,
+};
+
+/// Like [`SampleIndices`](super::sample_indices::SampleIndices), but every sampled index
+/// is distinct. Pseudorandom elements that map to an index which was already sampled are
+/// rejected, and the sponge is squeezed until `number` distinct indices below
+/// `upper_bound` are found. Already sampled indices are tracked in a seen-set of
+/// `upper_bound` words. The seen-set is allocated with `DynMallocAligned`, which never
+/// reuses freed blocks, so its words are guaranteed to be zero, and it is freed before
+/// returning. Crashes if `number` exceeds `upper_bound`.
+///
+/// The indices are squeezed from the VM's sponge state, which the [`Procedure`]
+/// shadow takes as input. The [`DeprecatedSnippet`] interface only exists to declare
+/// the crash conditions; its shadow starts from the fresh sponge state the VM starts
+/// with in that interface's tests.
+#[derive(Clone, Debug)]
+pub struct SampleUniqueIndices {
+    pub list_type: ListType,
+}
+
+impl SampleUniqueIndices {
+    fn test_state(number: usize, upper_bound: u32) -> ExecutionState {
+        ExecutionState {
+            stack: [
+                get_init_tvm_stack(),
+                vec![
+                    BFieldElement::new(number as u64),
+                    BFieldElement::new(upper_bound as u64),
+                ],
+            ]
+            .concat(),
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory: HashMap::new(),
+            words_allocated: 1,
+        }
+    }
+}
+
+impl DeprecatedSnippet for SampleUniqueIndices {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_hashing_sample_unique_indices_to_{}_list",
+            self.list_type
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["number".to_string(), "upper_bound".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32, DataType::U32]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::U32))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["index_list".to_string()]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut crate::library::Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(list_new(self.list_type.clone(), DataType::U32)));
+        let push_element =
+            library.import(Box::new(list_push(self.list_type.clone(), DataType::U32)));
+        let dyn_malloc_aligned = library.import(Box::new(DynMallocAligned));
+        let dyn_free = library.import(Box::new(DynFree));
+
+        let process_top = format!(
+            "
+                // _ number upper_bound-1 list seen list_index prn^10
+                dup 10 // _ number upper_bound-1 list seen list_index prn^10 list_index
+                dup 15 // _ number upper_bound-1 list seen list_index prn^10 list_index number
+                eq // _ number upper_bound-1 list seen list_index prn^10 list_index==number
+                dup 1 // _ number upper_bound-1 list seen list_index prn^10 list_index==number prn0
+                push -1 eq // _ number upper_bound-1 list seen list_index prn^10 list_index==number prn0==-1
+                add // _ number upper_bound-1 list seen list_index prn^10 list_index==number||prn0==-1
+                push 0 eq // _ number upper_bound-1 list seen list_index prn^10 list_index!=number&&prn0!=-1
+                skiz call {entrypoint}_process_top_function_body
+                // _ number upper_bound-1 list seen list_index prn^10
+        "
+        );
+
+        let rotate_10 = "
+            swap 9
+            swap 8
+            swap 7
+            swap 6
+            swap 5
+            swap 4
+            swap 3
+            swap 2
+            swap 1"
+            .to_string();
+
+        let process_all = vec![format!("{process_top}\n{rotate_10}"); 10].join("\n");
+
+        format!(
+            "
+            // BEFORE: _ number upper_bound
+            // AFTER: _ list
+            {entrypoint}:
+                // assert power of two
+                dup 0 dup 0 // _ number upper_bound upper_bound upper_bound
+                push -1 add and // _ number upper_bound upper_bound&(upper_bound-1)
+                push 0 eq assert // asserts that upper_bound = 2^k for some k
+
+                // assert that enough distinct indices exist
+                dup 1 dup 1 lt // _ number upper_bound (upper_bound < number)
+                push 0 eq assert // asserts that number <= upper_bound
+                push -1 add // _ number upper_bound-1
+
+                // create list and seen-set
+                dup 1 // _ number upper_bound-1 number
+                call {new_list} // _ number upper_bound-1 list
+                dup 1 push 1 add push 1 // _ number upper_bound-1 list upper_bound 1
+                call {dyn_malloc_aligned} // _ number upper_bound-1 list seen
+
+                // prepare and call loop
+                push 0 // _ number upper_bound-1 list seen 0
+                push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0 push 0
+                // _ number upper_bound-1 list seen list_index 0 0 0 0 0 0 0 0 0 0
+
+                squeeze // overwrite top 10 elements with fresh randomness
+                call {entrypoint}_loop // _ number upper_bound-1 list seen number prn9 prn8 prn7 prn6 prn5 prn4 prn3 prn2 prn1 prn0
+
+                // clean up stack
+                pop pop pop pop pop pop pop pop pop pop // _ number upper_bound-1 list seen number
+                pop // _ number upper_bound-1 list seen
+                dup 2 push 1 add // _ number upper_bound-1 list seen upper_bound
+                call {dyn_free} // _ number upper_bound-1 list
+                swap 2 // _ list upper_bound-1 number
+                pop pop // _ list
+
+                return
+
+            // INVARIANT: _ number upper_bound-1 list seen list_index prn9 prn8 prn7 prn6 prn5 prn4 prn3 prn2 prn1 prn0
+            {entrypoint}_loop:
+                // evaluate termination
+                dup 14 // _ number upper_bound-1 list seen list_index prn^10 number
+                dup 11 // _ number upper_bound-1 list seen list_index prn^10 number list_index
+                eq // _ number upper_bound-1 list seen list_index prn^10 number==list_index
+
+                skiz return // continue if unequal
+                // _ number upper_bound-1 list seen list_index prn^10
+
+                {process_all}
+                // _ number upper_bound-1 list seen list_index prn^10
+
+                squeeze // overwrite top 10 elements with fresh randomness
+
+                recurse
+
+            {entrypoint}_process_top_function_body:
+                dup 0 // _ number upper_bound-1 list seen list_index prn^10 prn0
+                split // _ number upper_bound-1 list seen list_index prn^10 hi lo
+                swap 1 pop // _ number upper_bound-1 list seen list_index prn^10 lo
+                dup 14 // _ number upper_bound-1 list seen list_index prn^10 lo upper_bound-1
+                and // _ number upper_bound-1 list seen list_index prn^10 index
+
+                dup 12 dup 1 add // _ number upper_bound-1 list seen list_index prn^10 index (seen+index)
+                read_mem // _ number upper_bound-1 list seen list_index prn^10 index (seen+index) already_seen
+                push 0 eq // _ number upper_bound-1 list seen list_index prn^10 index (seen+index) !already_seen
+                skiz call {entrypoint}_record_index
+                // _ number upper_bound-1 list seen list_index' prn^10 index (seen+index)
+
+                pop pop // _ number upper_bound-1 list seen list_index' prn^10
+                return
+
+            // BEFORE: _ number upper_bound-1 list seen list_index prn^10 index (seen+index)
+            // AFTER: _ number upper_bound-1 list seen list_index+1 prn^10 index (seen+index)
+            {entrypoint}_record_index:
+                push 1 write_mem // _ number upper_bound-1 list seen list_index prn^10 index (seen+index)
+
+                dup 14 dup 2 // _ number upper_bound-1 list seen list_index prn^10 index (seen+index) list index
+                call {push_element} // _ number upper_bound-1 list seen list_index prn^10 index (seen+index)
+
+                swap 12
+                push 1 add
+                swap 12
+                // _ number upper_bound-1 list seen list_index+1 prn^10 index (seen+index)
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "Number exceeds u32::MAX".to_string(),
+            "Upper bound is not a power of two".to_string(),
+            "Number exceeds upper bound".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        vec![
+            Self::test_state(0, 1 << 12),
+            Self::test_state(1, 1 << 12),
+            Self::test_state(10, 1 << 12),
+            Self::test_state(11, 1 << 12),
+            Self::test_state(45, 1 << 12),
+            Self::test_state(4, 1 << 31),
+            Self::test_state(8, 8),
+            Self::test_state(20, 32),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::test_state(45, 1 << 12)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::test_state(160, 1 << 12)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let mut sponge_state = VmHasherState::new(Domain::VariableLength);
+        self.rust_shadow(
+            stack,
+            memory,
+            &NonDeterminism::new(vec![]),
+            &[],
+            &mut sponge_state,
+        );
+    }
+}
+
+impl Procedure for SampleUniqueIndices {
+    fn rust_shadow(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+        _nondeterminism: &NonDeterminism<BFieldElement>,
+        _public_input: &[BFieldElement],
+        sponge_state: &mut VmHasherState,
+    ) -> Vec<BFieldElement> {
+        let upper_bound = stack.pop().unwrap().value() as u32;
+        let number = stack.pop().unwrap().value() as usize;
+
+        assert!(
+            is_power_of_two(upper_bound),
+            "Upper bound {upper_bound} must be a power of two"
+        );
+        assert!(
+            number <= upper_bound as usize,
+            "Cannot sample {number} distinct indices below {upper_bound}"
+        );
+
+        // create list and seen-set
+        stack.push(BFieldElement::new(number as u64));
+        list_new(self.list_type.clone(), DataType::U32).rust_shadowing(
+            stack,
+            vec![],
+            vec![],
+            memory,
+        );
+        let list = stack.pop().unwrap();
+        let seen = dynamic_allocator_aligned(upper_bound as usize, 1, memory);
+
+        // sample indices, rejecting repeats
+        let push_element = list_push(self.list_type.clone(), DataType::U32);
+        let mut num_sampled = 0;
+        let mut squeezed = vec![];
+        while num_sampled < number {
+            if squeezed.is_empty() {
+                squeezed = Tip5::squeeze(sponge_state).into_iter().rev().collect_vec();
+            }
+
+            let element = squeezed.pop().unwrap();
+            if element == BFieldElement::new(BFieldElement::MAX) {
+                continue;
+            }
+
+            let index = BFieldElement::new((element.value() as u32 % upper_bound) as u64);
+            let seen_flag_address = seen + index;
+            let seen_flag = memory
+                .get(&seen_flag_address)
+                .copied()
+                .unwrap_or(BFieldElement::zero());
+            if !seen_flag.is_zero() {
+                continue;
+            }
+
+            memory.insert(seen_flag_address, BFieldElement::one());
+            stack.push(list);
+            stack.push(index);
+            push_element.rust_shadowing(stack, vec![], vec![], memory);
+            num_sampled += 1;
+        }
+
+        dynamic_free(seen, upper_bound as usize, memory);
+        stack.push(list);
+
+        vec![]
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> (
+        Vec<BFieldElement>,
+        HashMap<BFieldElement, BFieldElement>,
+        NonDeterminism<BFieldElement>,
+        Vec<BFieldElement>,
+        VmHasherState,
+    ) {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let (number, upper_bound) = match bench_case {
+            Some(BenchmarkCase::CommonCase) => (45, 1 << 12),
+            Some(BenchmarkCase::WorstCase) => (160, 1 << 12),
+            Some(BenchmarkCase::Custom(_)) | None => {
+                let upper_bound: u32 = 1 << rng.gen_range(0..=12);
+                (
+                    rng.gen_range(0..=upper_bound.min(100)) as usize,
+                    upper_bound,
+                )
+            }
+        };
+
+        // The VM starts out with the same sponge state as a fresh variable-length sponge.
+        (
+            Self::test_state(number, upper_bound).stack,
+            HashMap::new(),
+            NonDeterminism::new(vec![]),
+            vec![],
+            VmHasherState::new(Domain::VariableLength),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::tip5::RATE;
+
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::rust_shadowing_helper_functions::dyn_malloc::dynamic_allocator;
+    use crate::rust_shadowing_helper_functions::safe_list::{safe_list_get, safe_list_get_length};
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::{
+        rust_final_state, tasm_final_state, test_crash_conditions,
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated, verify_hasher_state_equivalence,
+        verify_memory_equivalence, verify_stack_equivalence,
+    };
+    use crate::VmOutputState;
+
+    #[test]
+    fn sample_unique_indices_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(
+                &SampleUniqueIndices {
+                    list_type: list_type.clone(),
+                },
+                true,
+            );
+            ShadowedProcedure::new(SampleUniqueIndices { list_type }).test();
+        }
+    }
+
+    /// Run the snippet from the given sponge state, both in Rust and in the VM, and
+    /// assert that both agree.
+    fn run_from_sponge_state(state: ExecutionState, sponge_state: &VmHasherState) -> VmOutputState {
+        let shadowed_procedure = ShadowedProcedure::new(SampleUniqueIndices {
+            list_type: ListType::Safe,
+        });
+        let rust = rust_final_state(
+            &shadowed_procedure,
+            &state.stack,
+            &[],
+            &state.nondeterminism,
+            &state.memory,
+            sponge_state,
+            state.words_allocated,
+        );
+        let tasm = tasm_final_state(
+            &shadowed_procedure,
+            &state.stack,
+            &[],
+            &state.nondeterminism,
+            &state.memory,
+            sponge_state,
+            state.words_allocated,
+        );
+
+        verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+        verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+        verify_hasher_state_equivalence(rust, tasm.clone());
+
+        tasm
+    }
+
+    fn sampled_indices(vm_output_state: &VmOutputState) -> Vec<u64> {
+        let list = *vm_output_state.final_stack.last().unwrap();
+        let memory = &vm_output_state.final_ram;
+        (0..safe_list_get_length(list, memory))
+            .map(|i| safe_list_get(list, i, memory, 1)[0].value())
+            .collect_vec()
+    }
+
+    fn prop_sampled_indices_are_distinct(number: usize, upper_bound: u32) {
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SampleUniqueIndices {
+                list_type: ListType::Safe,
+            },
+            SampleUniqueIndices::test_state(number, upper_bound),
+        );
+
+        let indices = sampled_indices(&vm_output_state);
+        assert_eq!(number, indices.len());
+        assert!(indices.iter().all(|&index| index < upper_bound as u64));
+        assert!(indices.iter().all_unique());
+    }
+
+    #[test]
+    fn sampled_indices_are_distinct() {
+        prop_sampled_indices_are_distinct(45, 1 << 6);
+        prop_sampled_indices_are_distinct(100, 1 << 12);
+    }
+
+    #[test]
+    fn sampling_every_index_gives_a_permutation() {
+        prop_sampled_indices_are_distinct(16, 16);
+    }
+
+    #[test]
+    fn sample_from_non_default_sponge_state() {
+        let mut sponge_state = VmHasherState::new(Domain::FixedLength);
+        let block: [BFieldElement; RATE] =
+            core::array::from_fn(|i| BFieldElement::new(1 << (i + 20)));
+        Tip5::absorb(&mut sponge_state, &block);
+
+        let number = 20;
+        let upper_bound = 1 << 10;
+
+        // The first `number` distinct indices squeezed from the sponge state.
+        let mut expected_sponge_state = sponge_state.clone();
+        let mut expected_indices = vec![];
+        while expected_indices.len() < number {
+            for element in Tip5::squeeze(&mut expected_sponge_state) {
+                let index = element.value() % upper_bound;
+                if expected_indices.len() < number
+                    && element != BFieldElement::new(BFieldElement::MAX)
+                    && !expected_indices.contains(&index)
+                {
+                    expected_indices.push(index);
+                }
+            }
+        }
+
+        let state = SampleUniqueIndices::test_state(number, upper_bound as u32);
+        let tasm = run_from_sponge_state(state.clone(), &sponge_state);
+        assert_eq!(expected_indices, sampled_indices(&tasm));
+        assert_eq!(expected_sponge_state.state, tasm.final_sponge_state.state);
+
+        let default_sponge_state = VmHasherState::new(Domain::VariableLength);
+        let from_default_sponge_state = run_from_sponge_state(state, &default_sponge_state);
+        assert_ne!(
+            expected_indices,
+            sampled_indices(&from_default_sponge_state)
+        );
+    }
+
+    #[test]
+    fn freed_block_with_stale_data_is_not_used_as_seen_set() {
+        // Free a block of exactly the seen-set's size, with every word set, such that a
+        // free-list allocation would mark every index as already seen.
+        let upper_bound = 16;
+        let mut state = SampleUniqueIndices::test_state(upper_bound, upper_bound as u32);
+        let stale_block = dynamic_allocator(upper_bound, &mut state.memory);
+        for i in 0..upper_bound {
+            state.memory.insert(
+                stale_block + BFieldElement::new(i as u64),
+                BFieldElement::one(),
+            );
+        }
+        dynamic_free(stale_block, upper_bound, &mut state.memory);
+
+        // Initialize the allocator beyond the stale block, as it would be after the free.
+        state.words_allocated = stale_block.value() as usize + upper_bound;
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SampleUniqueIndices {
+                list_type: ListType::Safe,
+            },
+            state,
+        );
+        assert_eq!(upper_bound, sampled_indices(&vm_output_state).len());
+    }
+
+    #[test]
+    fn sample_unique_indices_crash_conditions() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_crash_conditions(
+                &SampleUniqueIndices { list_type },
+                vec![
+                    (
+                        SampleUniqueIndices::test_state(17, 16),
+                        "Number exceeds upper bound",
+                    ),
+                    (
+                        SampleUniqueIndices::test_state(3, 12),
+                        "Upper bound is not a power of two",
+                    ),
+                ],
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn sample_unique_indices_benchmark_safe() {
+        bench_and_write(SampleUniqueIndices {
+            list_type: ListType::Safe,
+        });
+    }
+
+    #[test]
+    fn sample_unique_indices_benchmark_unsafe() {
+        bench_and_write(SampleUniqueIndices {
+            list_type: ListType::Unsafe,
+        });
+    }
+}
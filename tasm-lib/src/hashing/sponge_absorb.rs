@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement, NonDeterminism};
+use twenty_first::{
+    shared_math::tip5::RATE,
+    util_types::algebraic_hasher::{Domain, SpongeHasher},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    procedure::Procedure,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+    VmHasher, VmHasherState,
+};
+
+/// Absorb one rate-sized block of field elements stored in memory into the sponge
+/// state. Unlike `Absorb`, this snippet neither initializes nor pads: it continues
+/// from whatever state the caller's earlier sponge instructions left behind.
+pub struct SpongeAbsorb;
+
+impl BasicSnippet for SpongeAbsorb {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::VoidPointer, "*addr".to_string())]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![]
+    }
+
+    fn entrypoint(&self) -> String {
+        "tasm_hashing_sponge_absorb".to_string()
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let rate_minus_one = RATE - 1;
+
+        // _ (*addr + 9) -> _ element_9 ... element_1 *addr
+        let read_all_but_first_element =
+            "read_mem\n swap 1\n push -1\n add\n".repeat(rate_minus_one);
+        let pop_block = "pop\n".repeat(RATE);
+
+        triton_asm! {
+            // BEFORE: _ *addr
+            // AFTER: _
+            {entrypoint}:
+                push {rate_minus_one}
+                add
+                // _ (*addr+9)
+
+                {read_all_but_first_element}
+                // _ element_9 element_8 element_7 element_6 element_5 element_4 element_3 element_2 element_1 *addr
+
+                read_mem
+                swap 1
+                pop
+                // _ element_9 element_8 element_7 element_6 element_5 element_4 element_3 element_2 element_1 element_0
+
+                absorb
+                {pop_block}
+                // _
+
+                return
+        }
+    }
+}
+
+impl Procedure for SpongeAbsorb {
+    fn rust_shadow(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+        _nondeterminism: &NonDeterminism<BFieldElement>,
+        _public_input: &[BFieldElement],
+        sponge_state: &mut VmHasherState,
+    ) -> Vec<BFieldElement> {
+        let address = stack.pop().unwrap();
+
+        let mut block = [BFieldElement::new(0); RATE];
+        for (i, element) in block.iter_mut().enumerate() {
+            *element = memory
+                .get(&(address + BFieldElement::new(i as u64)))
+                .copied()
+                .unwrap();
+        }
+
+        VmHasher::absorb(sponge_state, &block);
+
+        vec![]
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> (
+        Vec<BFieldElement>,
+        HashMap<BFieldElement, BFieldElement>,
+        NonDeterminism<BFieldElement>,
+        Vec<BFieldElement>,
+        VmHasherState,
+    ) {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+
+        let address = BFieldElement::new(rng.next_u64() % (1 << 20));
+        let mut memory = HashMap::new();
+        for i in 0..RATE {
+            memory.insert(address + BFieldElement::new(i as u64), rng.gen());
+        }
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(address);
+
+        // The VM starts out with the same sponge state as a fresh variable-length sponge.
+        (
+            stack,
+            memory,
+            NonDeterminism::new(vec![]),
+            vec![],
+            VmHasherState::new(Domain::VariableLength),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::random;
+
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::{
+        rust_final_state, tasm_final_state, verify_hasher_state_equivalence,
+        verify_memory_equivalence, verify_stack_equivalence,
+    };
+
+    #[test]
+    fn sponge_absorb_test() {
+        ShadowedProcedure::new(SpongeAbsorb).test();
+    }
+
+    #[test]
+    fn sponge_absorb_matches_vm_sponge_state() {
+        let shadowed_procedure = ShadowedProcedure::new(SpongeAbsorb);
+        let (stack, memory, nondeterminism, stdin, sponge_state) =
+            SpongeAbsorb.pseudorandom_initial_state(random(), None);
+
+        let rust = rust_final_state(
+            &shadowed_procedure,
+            &stack,
+            &stdin,
+            &nondeterminism,
+            &memory,
+            &sponge_state,
+            0,
+        );
+        let tasm = tasm_final_state(
+            &shadowed_procedure,
+            &stack,
+            &stdin,
+            &nondeterminism,
+            &memory,
+            &sponge_state,
+            0,
+        );
+
+        verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+        verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+        verify_hasher_state_equivalence(rust, tasm);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+
+    #[test]
+    fn sponge_absorb_benchmark() {
+        ShadowedProcedure::new(SpongeAbsorb).bench();
+    }
+}
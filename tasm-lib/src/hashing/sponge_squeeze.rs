@@ -0,0 +1,391 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use triton_vm::{triton_asm, BFieldElement, NonDeterminism};
+use twenty_first::{
+    shared_math::tip5::RATE,
+    util_types::algebraic_hasher::{Domain, SpongeHasher},
+};
+
+use crate::{
+    get_init_tvm_stack,
+    list::ListType,
+    memory::dyn_malloc::DynMalloc,
+    procedure::Procedure,
+    rust_shadowing_helper_functions::{dyn_malloc::dynamic_allocator, safe_list, unsafe_list},
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+    VmHasher, VmHasherState,
+};
+
+/// Squeeze one rate-sized block of field elements out of the sponge state and store
+/// it in a freshly allocated list. The first squeezed element is the first element
+/// of the list.
+pub struct SpongeSqueeze {
+    pub list_type: ListType,
+}
+
+impl SpongeSqueeze {
+    fn safety_offset(&self) -> usize {
+        match self.list_type {
+            ListType::Safe => 2,
+            ListType::Unsafe => 1,
+        }
+    }
+}
+
+impl BasicSnippet for SpongeSqueeze {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![(DataType::List(Box::new(DataType::BFE)), "*list".to_string())]
+    }
+
+    fn entrypoint(&self) -> String {
+        format!("tasm_hashing_sponge_squeeze_to_{}_list", self.list_type)
+    }
+
+    fn code(
+        &self,
+        library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        let entrypoint = self.entrypoint();
+        let dyn_malloc = library.import(Box::new(DynMalloc));
+        let list_size = RATE + self.safety_offset();
+
+        // _ *list *list -> _ *list (*list + 1), with the capacity written if applicable
+        let write_capacity = match self.list_type {
+            ListType::Safe => format!("push 1\n add\n push {RATE}\n write_mem\n"),
+            ListType::Unsafe => String::default(),
+        };
+
+        // _ *elements d9 ... d0 -> _ *elements
+        let write_squeezed_elements = (0..RATE)
+            .map(|i| {
+                let add_index = if i == 0 {
+                    String::default()
+                } else {
+                    format!("push {i}\n add\n")
+                };
+                format!("dup {}\n {add_index} swap 1\n write_mem\n pop\n", RATE - i)
+            })
+            .join("");
+
+        triton_asm! {
+            // BEFORE: _
+            // AFTER: _ *list
+            {entrypoint}:
+                push {list_size}
+                call {dyn_malloc}
+                // _ *list
+
+                dup 0
+                push {RATE}
+                write_mem
+                {write_capacity}
+                push 1
+                add
+                // _ *list *elements
+
+                squeeze
+                // _ *list *elements d9 d8 d7 d6 d5 d4 d3 d2 d1 d0
+
+                {write_squeezed_elements}
+                // _ *list *elements
+
+                pop
+                // _ *list
+
+                return
+        }
+    }
+}
+
+impl Procedure for SpongeSqueeze {
+    fn rust_shadow(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+        _nondeterminism: &NonDeterminism<BFieldElement>,
+        _public_input: &[BFieldElement],
+        sponge_state: &mut VmHasherState,
+    ) -> Vec<BFieldElement> {
+        let list_pointer = dynamic_allocator(RATE + self.safety_offset(), memory);
+        match self.list_type {
+            ListType::Safe => safe_list::safe_list_new(list_pointer, RATE as u32, memory),
+            ListType::Unsafe => unsafe_list::unsafe_list_new(list_pointer, memory),
+        }
+
+        let squeezed = VmHasher::squeeze(sponge_state);
+        for element in squeezed {
+            match self.list_type {
+                ListType::Safe => safe_list::safe_list_push(list_pointer, vec![element], memory, 1),
+                ListType::Unsafe => {
+                    unsafe_list::unsafe_list_push(list_pointer, vec![element], memory, 1)
+                }
+            }
+        }
+
+        stack.push(list_pointer);
+
+        vec![]
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        _seed: [u8; 32],
+        _bench_case: Option<BenchmarkCase>,
+    ) -> (
+        Vec<BFieldElement>,
+        HashMap<BFieldElement, BFieldElement>,
+        NonDeterminism<BFieldElement>,
+        Vec<BFieldElement>,
+        VmHasherState,
+    ) {
+        // The VM starts out with the same sponge state as a fresh variable-length sponge.
+        (
+            get_init_tvm_stack(),
+            HashMap::new(),
+            NonDeterminism::new(vec![]),
+            vec![],
+            VmHasherState::new(Domain::VariableLength),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::hashing::sponge_absorb::SpongeAbsorb;
+    use crate::library::Library;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::{
+        rust_final_state, tasm_final_state, verify_hasher_state_equivalence,
+        verify_memory_equivalence, verify_stack_equivalence,
+    };
+
+    /// Absorb a block from memory, then squeeze a block into a new list.
+    struct AbsorbThenSqueeze {
+        list_type: ListType,
+    }
+
+    impl BasicSnippet for AbsorbThenSqueeze {
+        fn inputs(&self) -> Vec<(DataType, String)> {
+            vec![(DataType::VoidPointer, "*addr".to_string())]
+        }
+
+        fn outputs(&self) -> Vec<(DataType, String)> {
+            vec![(DataType::List(Box::new(DataType::BFE)), "*list".to_string())]
+        }
+
+        fn entrypoint(&self) -> String {
+            format!(
+                "tasm_hashing_sponge_absorb_then_squeeze_to_{}_list",
+                self.list_type
+            )
+        }
+
+        fn code(&self, library: &mut Library) -> Vec<triton_vm::instruction::LabelledInstruction> {
+            let entrypoint = self.entrypoint();
+            let absorb = library.import(Box::new(SpongeAbsorb));
+            let squeeze = library.import(Box::new(SpongeSqueeze {
+                list_type: self.list_type.clone(),
+            }));
+
+            triton_asm! {
+                {entrypoint}:
+                    call {absorb}
+                    call {squeeze}
+                    return
+            }
+        }
+    }
+
+    impl Procedure for AbsorbThenSqueeze {
+        fn rust_shadow(
+            &self,
+            stack: &mut Vec<BFieldElement>,
+            memory: &mut HashMap<BFieldElement, BFieldElement>,
+            nondeterminism: &NonDeterminism<BFieldElement>,
+            public_input: &[BFieldElement],
+            sponge_state: &mut VmHasherState,
+        ) -> Vec<BFieldElement> {
+            SpongeAbsorb.rust_shadow(stack, memory, nondeterminism, public_input, sponge_state);
+            SpongeSqueeze {
+                list_type: self.list_type.clone(),
+            }
+            .rust_shadow(stack, memory, nondeterminism, public_input, sponge_state)
+        }
+
+        fn pseudorandom_initial_state(
+            &self,
+            seed: [u8; 32],
+            bench_case: Option<BenchmarkCase>,
+        ) -> (
+            Vec<BFieldElement>,
+            HashMap<BFieldElement, BFieldElement>,
+            NonDeterminism<BFieldElement>,
+            Vec<BFieldElement>,
+            VmHasherState,
+        ) {
+            SpongeAbsorb.pseudorandom_initial_state(seed, bench_case)
+        }
+    }
+
+    fn read_bfe_list(
+        list_type: &ListType,
+        list_pointer: BFieldElement,
+        memory: &HashMap<BFieldElement, BFieldElement>,
+    ) -> Vec<BFieldElement> {
+        let length = match list_type {
+            ListType::Safe => safe_list::safe_list_get_length(list_pointer, memory),
+            ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, memory),
+        };
+        (0..length)
+            .map(|i| match list_type {
+                ListType::Safe => safe_list::safe_list_get(list_pointer, i, memory, 1)[0],
+                ListType::Unsafe => unsafe_list::unsafe_list_get(list_pointer, i, memory, 1)[0],
+            })
+            .collect_vec()
+    }
+
+    #[test]
+    fn sponge_squeeze_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            ShadowedProcedure::new(SpongeSqueeze { list_type }).test();
+        }
+    }
+
+    #[test]
+    fn absorb_then_squeeze_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            ShadowedProcedure::new(AbsorbThenSqueeze { list_type }).test();
+        }
+    }
+
+    #[test]
+    fn absorb_known_block_then_squeeze() {
+        let block_address = BFieldElement::new(1 << 20);
+        let block: [BFieldElement; RATE] =
+            core::array::from_fn(|i| BFieldElement::new(1000 + i as u64));
+        let mut memory = HashMap::new();
+        for (i, element) in block.iter().enumerate() {
+            memory.insert(block_address + BFieldElement::new(i as u64), *element);
+        }
+        let mut stack = get_init_tvm_stack();
+        stack.push(block_address);
+        let sponge_state = VmHasherState::new(Domain::VariableLength);
+
+        let mut expected_sponge_state = sponge_state.clone();
+        VmHasher::absorb(&mut expected_sponge_state, &block);
+        let expected_squeezed = VmHasher::squeeze(&mut expected_sponge_state);
+
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let shadowed_procedure = ShadowedProcedure::new(AbsorbThenSqueeze {
+                list_type: list_type.clone(),
+            });
+            let nondeterminism = NonDeterminism::new(vec![]);
+            let rust = rust_final_state(
+                &shadowed_procedure,
+                &stack,
+                &[],
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+            let tasm = tasm_final_state(
+                &shadowed_procedure,
+                &stack,
+                &[],
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+
+            verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+            verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+
+            let list_pointer = *tasm.final_stack.last().unwrap();
+            assert_eq!(
+                expected_squeezed.to_vec(),
+                read_bfe_list(&list_type, list_pointer, &tasm.final_ram)
+            );
+            assert_eq!(expected_sponge_state.state, tasm.final_sponge_state.state);
+            verify_hasher_state_equivalence(rust, tasm);
+        }
+    }
+
+    #[test]
+    fn squeeze_from_non_default_sponge_state() {
+        let mut sponge_state = VmHasherState::new(Domain::FixedLength);
+        let block: [BFieldElement; RATE] =
+            core::array::from_fn(|i| BFieldElement::new(1 << (i + 20)));
+        VmHasher::absorb(&mut sponge_state, &block);
+
+        let mut expected_sponge_state = sponge_state.clone();
+        let expected_squeezed = VmHasher::squeeze(&mut expected_sponge_state);
+        let mut default_sponge_state = VmHasherState::new(Domain::VariableLength);
+        assert_ne!(
+            expected_squeezed,
+            VmHasher::squeeze(&mut default_sponge_state)
+        );
+
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let shadowed_procedure = ShadowedProcedure::new(SpongeSqueeze {
+                list_type: list_type.clone(),
+            });
+            let stack = get_init_tvm_stack();
+            let nondeterminism = NonDeterminism::new(vec![]);
+            let memory = HashMap::new();
+            let rust = rust_final_state(
+                &shadowed_procedure,
+                &stack,
+                &[],
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+            let tasm = tasm_final_state(
+                &shadowed_procedure,
+                &stack,
+                &[],
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+
+            verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+            verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+
+            let list_pointer = *tasm.final_stack.last().unwrap();
+            assert_eq!(
+                expected_squeezed.to_vec(),
+                read_bfe_list(&list_type, list_pointer, &tasm.final_ram)
+            );
+            assert_eq!(expected_sponge_state.state, tasm.final_sponge_state.state);
+            verify_hasher_state_equivalence(rust, tasm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+
+    #[test]
+    fn sponge_squeeze_benchmark() {
+        ShadowedProcedure::new(SpongeSqueeze {
+            list_type: ListType::Unsafe,
+        })
+        .bench();
+    }
+}
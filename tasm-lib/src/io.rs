@@ -2,4 +2,6 @@ pub mod load_from_input;
 pub mod load_struct_from_input;
 pub mod read_secret;
 pub mod read_stdin;
+pub mod read_stdin_list;
+pub mod read_validated_u64;
 pub mod write_to_stdout;
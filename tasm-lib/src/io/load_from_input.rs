@@ -150,20 +150,13 @@ impl DeprecatedSnippet for LoadFromInput {
             ]
             .concat();
             ret.push(match self.0 {
-                InputSource::StdIn => ExecutionState {
-                    stack: get_init_tvm_stack(),
-                    memory: std::collections::HashMap::new(),
-                    std_in: input,
-                    nondeterminism: NonDeterminism::new(vec![]),
-                    words_allocated: 0,
-                },
-                InputSource::SecretIn => ExecutionState {
-                    stack: get_init_tvm_stack(),
-                    memory: std::collections::HashMap::new(),
-                    std_in: vec![],
-                    nondeterminism: NonDeterminism::new(input),
-                    words_allocated: 0,
-                },
+                InputSource::StdIn => {
+                    ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), input)
+                }
+                InputSource::SecretIn => ExecutionState::with_nondeterminism(
+                    get_init_tvm_stack(),
+                    NonDeterminism::new(input),
+                ),
             });
         }
 
@@ -178,20 +171,13 @@ impl DeprecatedSnippet for LoadFromInput {
         ]
         .concat();
         match self.0 {
-            InputSource::StdIn => ExecutionState {
-                stack: get_init_tvm_stack(),
-                memory: std::collections::HashMap::new(),
-                std_in: input,
-                nondeterminism: NonDeterminism::new(vec![]),
-                words_allocated: 0,
-            },
-            InputSource::SecretIn => ExecutionState {
-                stack: get_init_tvm_stack(),
-                memory: std::collections::HashMap::new(),
-                std_in: vec![],
-                nondeterminism: NonDeterminism::new(input),
-                words_allocated: 0,
-            },
+            InputSource::StdIn => {
+                ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), input)
+            }
+            InputSource::SecretIn => ExecutionState::with_nondeterminism(
+                get_init_tvm_stack(),
+                NonDeterminism::new(input),
+            ),
         }
     }
 
@@ -203,20 +189,13 @@ impl DeprecatedSnippet for LoadFromInput {
         ]
         .concat();
         match self.0 {
-            InputSource::StdIn => ExecutionState {
-                stack: get_init_tvm_stack(),
-                memory: std::collections::HashMap::new(),
-                std_in: input,
-                nondeterminism: NonDeterminism::new(vec![]),
-                words_allocated: 0,
-            },
-            InputSource::SecretIn => ExecutionState {
-                stack: get_init_tvm_stack(),
-                memory: std::collections::HashMap::new(),
-                std_in: vec![],
-                nondeterminism: NonDeterminism::new(input),
-                words_allocated: 0,
-            },
+            InputSource::StdIn => {
+                ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), input)
+            }
+            InputSource::SecretIn => ExecutionState::with_nondeterminism(
+                get_init_tvm_stack(),
+                NonDeterminism::new(input),
+            ),
         }
     }
 
@@ -82,6 +82,10 @@ impl DeprecatedSnippet for ReadSecret {
             twenty_first::shared_math::b_field_element::BFieldElement,
         >,
     ) {
+        assert!(
+            secret_in.len() >= self.0.get_size(),
+            "secret input too short"
+        );
         for elem in secret_in.iter().take(self.0.get_size()) {
             stack.push(*elem)
         }
@@ -104,8 +108,14 @@ impl DeprecatedSnippet for ReadSecret {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::b_field_element::BFieldElement;
+
     use super::*;
-    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
 
     #[test]
     fn new_snippet_test() {
@@ -119,6 +129,47 @@ mod tests {
             test_rust_equivalence_multiple_deprecated(&ReadSecret(DataType::Digest), true);
         }
     }
+
+    fn state_with_secret_in(secret_in: Vec<BFieldElement>) -> ExecutionState {
+        ExecutionState {
+            stack: get_init_tvm_stack(),
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(secret_in),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
+
+    #[test]
+    fn read_secret_consumes_tokens_in_order() {
+        for data_type in [DataType::U32, DataType::U64, DataType::Digest] {
+            let size = data_type.get_size();
+
+            // supply more tokens than needed; only the first `size` ones are read
+            let secret_in = (0..size as u64 + 3)
+                .map(|i| BFieldElement::new(100 + i))
+                .collect_vec();
+            let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+                &ReadSecret(data_type),
+                state_with_secret_in(secret_in.clone()),
+            );
+
+            // The first token read ends up deepest in the stack.
+            let final_stack = vm_output_state.final_stack;
+            let read_element = final_stack[final_stack.len() - size..].to_vec();
+            assert_eq!(secret_in[..size].to_vec(), read_element);
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn read_secret_crashes_on_exhausted_secret_in() {
+        let secret_in = (0..3).map(BFieldElement::new).collect_vec();
+        test_rust_equivalence_given_execution_state_deprecated(
+            &ReadSecret(DataType::Digest),
+            state_with_secret_in(secret_in),
+        );
+    }
 }
 
 #[cfg(test)]
@@ -1,7 +1,4 @@
-use std::collections::HashMap;
-
-use triton_vm::NonDeterminism;
-use twenty_first::shared_math::{b_field_element::BFieldElement, other::random_elements};
+use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use crate::{
     get_init_tvm_stack,
@@ -63,14 +60,11 @@ impl DeprecatedSnippet for ReadStdIn {
     }
 
     fn gen_input_states(&self) -> Vec<crate::ExecutionState> {
-        let std_in: Vec<BFieldElement> = random_elements(self.0.get_size());
-        vec![ExecutionState {
-            stack: get_init_tvm_stack(),
+        let std_in: Vec<BFieldElement> = self.0.random_element();
+        vec![ExecutionState::with_stack_and_std_in(
+            get_init_tvm_stack(),
             std_in,
-            nondeterminism: NonDeterminism::new(vec![]),
-            memory: HashMap::default(),
-            words_allocated: 0,
-        }]
+        )]
     }
 
     fn rust_shadowing(
@@ -89,15 +83,8 @@ impl DeprecatedSnippet for ReadStdIn {
     }
 
     fn common_case_input_state(&self) -> ExecutionState {
-        let mut std_in = vec![];
-        std_in.append(&mut random_elements(self.0.get_size()));
-        ExecutionState {
-            stack: get_init_tvm_stack(),
-            std_in,
-            nondeterminism: NonDeterminism::new(vec![]),
-            memory: HashMap::default(),
-            words_allocated: 0,
-        }
+        let std_in = self.0.random_element();
+        ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), std_in)
     }
 
     fn worst_case_input_state(&self) -> ExecutionState {
@@ -107,7 +94,12 @@ impl DeprecatedSnippet for ReadStdIn {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+    use std::collections::HashMap;
+
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
 
     use super::*;
 
@@ -115,7 +107,9 @@ mod tests {
     fn new_snippet_test() {
         for _ in 0..10 {
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::Bool), true);
+            test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::U16), true);
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::U32), true);
+            test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::I32), true);
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::U64), true);
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::U128), true);
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::BFE), true);
@@ -123,6 +117,36 @@ mod tests {
             test_rust_equivalence_multiple_deprecated(&ReadStdIn(DataType::Digest), true);
         }
     }
+
+    fn prop_read_single_word(data_type: DataType, value: BFieldElement) {
+        let snippet = ReadStdIn(data_type);
+        assert_eq!(1, snippet.stack_diff());
+        assert_eq!(1, snippet.output_field_names().len());
+
+        let init_stack = get_init_tvm_stack();
+        let expected = [init_stack.clone(), vec![value]].concat();
+        test_rust_equivalence_given_input_values_deprecated(
+            &snippet,
+            &init_stack,
+            &[value],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
+
+    #[test]
+    fn read_u16_from_stdin() {
+        prop_read_single_word(DataType::U16, BFieldElement::new(0));
+        prop_read_single_word(DataType::U16, BFieldElement::new(u16::MAX as u64));
+    }
+
+    #[test]
+    fn read_i32_from_stdin() {
+        for value in [0, -1, i32::MIN, i32::MAX] {
+            prop_read_single_word(DataType::I32, DataType::i32_encoding(value));
+        }
+    }
 }
 
 #[cfg(test)]
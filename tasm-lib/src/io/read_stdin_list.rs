@@ -0,0 +1,212 @@
+use std::collections::HashMap;
+
+use rand::{thread_rng, Rng};
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::{
+    get_init_tvm_stack,
+    io::read_stdin::ReadStdIn,
+    library::Library,
+    list::unsafeimplu32::{new::UnsafeNew, push::UnsafePush},
+    snippet::{DataType, DeprecatedSnippet},
+    ExecutionState,
+};
+
+/// Read `count` elements of type `DataType` from standard in into a new unsafe list.
+/// Every element is read as with `ReadStdIn` and then pushed to the list, so the list
+/// holds the elements in the order in which they appear on standard in.
+#[derive(Clone, Debug)]
+pub struct ReadStdInList(pub DataType);
+
+impl ReadStdInList {
+    fn prepare_state(&self, count: usize) -> ExecutionState {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(count as u64));
+        ExecutionState {
+            stack,
+            std_in: self.0.random_elements(count).concat(),
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
+}
+
+impl DeprecatedSnippet for ReadStdInList {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_io_read_stdin_list___{}", self.0.label_friendly_name())
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["count".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(UnsafeNew(self.0.clone())));
+        let read_element = library.import(Box::new(ReadStdIn(self.0.clone())));
+        let push_element = library.import(Box::new(UnsafePush(self.0.clone())));
+
+        format!(
+            "
+            // BEFORE: _ count
+            // AFTER: _ *list
+            {entrypoint}:
+                dup 0
+                call {new_list}
+                // _ count *list
+
+                swap 1
+                call {entrypoint}_loop
+                // _ *list 0
+
+                pop
+                return
+
+            // INVARIANT: _ *list remaining
+            {entrypoint}_loop:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                dup 1
+                call {read_element}
+                // _ *list remaining *list [element]
+
+                call {push_element}
+                // _ *list remaining
+
+                push -1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["std input too short".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0),
+            self.prepare_state(1),
+            self.prepare_state(rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let count = stack.last().unwrap().value() as usize;
+        let element_size = self.0.get_size();
+        assert!(std_in.len() >= count * element_size, "std input too short");
+
+        UnsafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let list_pointer = *stack.last().unwrap();
+
+        for i in 0..count {
+            stack.push(list_pointer);
+            ReadStdIn(self.0.clone()).rust_shadowing(
+                stack,
+                std_in[i * element_size..].to_vec(),
+                secret_in.clone(),
+                memory,
+            );
+            UnsafePush(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::rust_shadowing_helper_functions::unsafe_list::{
+        unsafe_list_get, unsafe_list_get_length,
+    };
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn read_stdin_list_test() {
+        for data_type in [
+            DataType::U16,
+            DataType::U32,
+            DataType::I32,
+            DataType::U64,
+            DataType::XFE,
+            DataType::Digest,
+        ] {
+            test_rust_equivalence_multiple_deprecated(&ReadStdInList(data_type), true);
+        }
+    }
+
+    #[test]
+    fn read_u64s_from_stdin_into_list() {
+        for count in [0, 1, 7] {
+            let snippet = ReadStdInList(DataType::U64);
+            let init_state = snippet.prepare_state(count);
+            let std_in = init_state.std_in.clone();
+            let vm_output_state =
+                test_rust_equivalence_given_execution_state_deprecated(&snippet, init_state);
+
+            let list_pointer = *vm_output_state.final_stack.last().unwrap();
+            let memory = vm_output_state.final_ram;
+            assert_eq!(count, unsafe_list_get_length(list_pointer, &memory));
+
+            // `ReadStdIn` leaves the last-read word on top of the stack, and `UnsafePush`
+            // writes the top of the stack first.
+            for (i, words) in std_in.chunks(2).enumerate() {
+                let expected = words.iter().rev().copied().collect_vec();
+                assert_eq!(expected, unsafe_list_get(list_pointer, i, &memory, 2));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn read_stdin_list_benchmark() {
+        bench_and_write(ReadStdInList(DataType::U64));
+    }
+}
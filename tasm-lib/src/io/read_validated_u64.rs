@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::validate_u64::ValidateU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Move a u64 from standard in to the stack, crashing the VM unless both limbs are valid
+/// u32s. Unlike `ReadStdIn(DataType::U64)`, the result can be trusted by u64 arithmetic
+/// even if standard in is supplied by an adversary.
+#[derive(Clone, Debug)]
+pub struct ReadValidatedU64FromStdIn;
+
+impl ReadValidatedU64FromStdIn {
+    fn prepare_state(value_hi: u64, value_lo: u64) -> ExecutionState {
+        let std_in = vec![BFieldElement::new(value_hi), BFieldElement::new(value_lo)];
+        ExecutionState::with_stack_and_std_in(get_init_tvm_stack(), std_in)
+    }
+}
+
+impl DeprecatedSnippet for ReadValidatedU64FromStdIn {
+    fn entrypoint_name(&self) -> String {
+        "tasm_io_read_validated_u64_from_stdin".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["value_hi".to_string(), "value_lo".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let validate_u64 = library.import(Box::new(ValidateU64));
+
+        format!(
+            "
+            // BEFORE: _
+            // AFTER: _ value_hi value_lo
+            {entrypoint}:
+                read_io
+                read_io
+                call {validate_u64}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "std input too short".to_string(),
+            "stdin u64 limb exceeds u32::MAX".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        vec![
+            Self::prepare_state(0, 0),
+            Self::prepare_state(u32::MAX as u64, u32::MAX as u64),
+            Self::prepare_state(rng.next_u32() as u64, rng.next_u32() as u64),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(1 << 20, 1 << 31)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(u32::MAX as u64, u32::MAX as u64)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        assert!(std_in.len() >= 2, "std input too short");
+        for limb in std_in.iter().take(2) {
+            let _: u32 = limb
+                .value()
+                .try_into()
+                .expect("stdin u64 limb exceeds u32::MAX");
+            stack.push(*limb);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn read_validated_u64_from_stdin_test() {
+        test_rust_equivalence_multiple_deprecated(&ReadValidatedU64FromStdIn, true);
+    }
+
+    #[test]
+    fn canonical_value_is_read() {
+        let value: u64 = (1 << 40) + 17;
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &ReadValidatedU64FromStdIn,
+            ReadValidatedU64FromStdIn::prepare_state(value >> 32, value & u32::MAX as u64),
+        );
+
+        let expected_stack = [
+            get_init_tvm_stack(),
+            vec![BFieldElement::new(1 << 8), BFieldElement::new(17)],
+        ]
+        .concat();
+        assert_eq!(expected_stack, vm_output_state.final_stack);
+    }
+
+    #[should_panic]
+    #[test]
+    fn hi_limb_of_two_pow_32_crashes() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &ReadValidatedU64FromStdIn,
+            ReadValidatedU64FromStdIn::prepare_state(1 << 32, 0),
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn lo_limb_of_two_pow_32_crashes() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &ReadValidatedU64FromStdIn,
+            ReadValidatedU64FromStdIn::prepare_state(0, 1 << 32),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn read_validated_u64_from_stdin_benchmark() {
+        bench_and_write(ReadValidatedU64FromStdIn);
+    }
+}
@@ -13,6 +13,9 @@ use crate::{
     VmHasherState,
 };
 
+/// Move an element of type `DataType` from the stack to standard out. The element's
+/// top-most word is written first; this is the inverse of `ReadStdIn`.
+#[derive(Clone, Debug)]
 pub struct WriteToStdout(pub DataType);
 
 impl BasicSnippet for WriteToStdout {
@@ -88,7 +91,14 @@ impl Procedure for WriteToStdout {
 
 #[cfg(test)]
 mod tests {
-    use crate::{procedure::ShadowedProcedure, snippet::RustShadow};
+    use itertools::Itertools;
+    use rand::random;
+
+    use crate::{
+        procedure::ShadowedProcedure,
+        snippet::RustShadow,
+        test_helpers::{rust_final_state, tasm_final_state, verify_stack_equivalence},
+    };
 
     use super::*;
 
@@ -98,4 +108,44 @@ mod tests {
             ShadowedProcedure::new(WriteToStdout(data_type.clone())).test();
         }
     }
+
+    #[test]
+    fn write_to_stdout_emits_words_from_top_of_stack() {
+        for data_type in [DataType::Bool, DataType::U64, DataType::Digest] {
+            let snippet = WriteToStdout(data_type.clone());
+            let (stack, memory, nondeterminism, stdin, sponge_state) =
+                snippet.pseudorandom_initial_state(random(), None);
+            let shadowed_procedure = ShadowedProcedure::new(snippet);
+
+            let rust = rust_final_state(
+                &shadowed_procedure,
+                &stack,
+                &stdin,
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+            let tasm = tasm_final_state(
+                &shadowed_procedure,
+                &stack,
+                &stdin,
+                &nondeterminism,
+                &memory,
+                &sponge_state,
+                0,
+            );
+
+            let expected_output = stack
+                .iter()
+                .rev()
+                .take(data_type.get_size())
+                .copied()
+                .collect_vec();
+            assert_eq!(expected_output, rust.output);
+            assert_eq!(expected_output, tasm.output);
+            verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+            assert_eq!(stack.len() - data_type.get_size(), tasm.final_stack.len());
+        }
+    }
 }
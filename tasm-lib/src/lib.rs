@@ -12,6 +12,7 @@ use snippet::BasicSnippet;
 use snippet::DeprecatedSnippet;
 use std::collections::HashMap;
 use std::time::SystemTime;
+use triton_vm::aet::AlgebraicExecutionTrace;
 use triton_vm::instruction::LabelledInstruction;
 use triton_vm::program::Program;
 use triton_vm::triton_asm;
@@ -33,6 +34,7 @@ pub mod algorithm;
 pub mod arithmetic;
 pub mod closure;
 pub mod compiled_program;
+pub mod execution_state_diff;
 pub mod exported_snippets;
 pub mod function;
 pub mod hashing;
@@ -109,6 +111,29 @@ impl ExecutionState {
             words_allocated,
         }
     }
+
+    pub fn with_stack_and_std_in(stack: Vec<BFieldElement>, std_in: Vec<BFieldElement>) -> Self {
+        ExecutionState {
+            stack,
+            std_in,
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
+
+    pub fn with_nondeterminism(
+        stack: Vec<BFieldElement>,
+        nondeterminism: NonDeterminism<BFieldElement>,
+    ) -> Self {
+        ExecutionState {
+            stack,
+            std_in: vec![],
+            nondeterminism,
+            memory: HashMap::default(),
+            words_allocated: 0,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -127,6 +152,18 @@ pub struct VmOutputState {
     pub final_stack: Vec<BFieldElement>,
     pub final_ram: HashMap<BFieldElement, BFieldElement>,
     pub final_sponge_state: VmHasherState,
+
+    /// Number of clock cycles the VM spent, excluding the cycles spent on preparing the
+    /// initial state. Always 0 for states produced by a rust shadow.
+    pub clock_cycle_count: usize,
+
+    /// Number of rows in the hash table of the algebraic execution trace. Always 0 for
+    /// states produced by a rust shadow.
+    pub hash_table_height: usize,
+
+    /// Number of rows in the u32 table of the algebraic execution trace. Always 0 for
+    /// states produced by a rust shadow.
+    pub u32_table_height: usize,
 }
 
 pub fn get_init_tvm_stack() -> Vec<BFieldElement> {
@@ -137,6 +174,18 @@ pub fn push_encodable<T: BFieldCodec>(stack: &mut Vec<BFieldElement>, value: &T)
     stack.append(&mut value.encode().into_iter().rev().collect());
 }
 
+/// Pop a value of a statically sized type off the stack, assuming it was put there like
+/// [`push_encodable`] does. Panics if the type is not statically sized or the popped
+/// words are not a valid encoding.
+pub fn pop_encodable<T: BFieldCodec>(stack: &mut Vec<BFieldElement>) -> T {
+    let length = T::static_length().expect("can only pop statically sized types");
+    let encoding = (0..length).map(|_| stack.pop().unwrap()).collect_vec();
+    match T::decode(&encoding) {
+        Ok(value) => *value,
+        Err(_) => panic!("popped words must encode a valid value"),
+    }
+}
+
 #[allow(dead_code)] // used in tests
 pub(crate) fn execute_with_execution_state_deprecated<T: DeprecatedSnippet>(
     snippet: T,
@@ -279,7 +328,9 @@ pub fn execute_bench_deprecated(
 }
 
 /// Execute a Triton-VM program and test correct behavior indicators.
-/// Modify stack and memory. Panic if anything goes wrong.
+/// Modify stack and memory. Panic if anything goes wrong, including, if `max_address` is
+/// set, the program writing to any address above it.
+#[allow(clippy::too_many_arguments)]
 pub fn execute_test(
     code: &[LabelledInstruction],
     stack: &mut Vec<BFieldElement>,
@@ -288,6 +339,34 @@ pub fn execute_test(
     nondeterminism: &mut NonDeterminism<BFieldElement>,
     memory: &mut HashMap<BFieldElement, BFieldElement>,
     initilialize_dynamic_allocator_to: Option<usize>,
+    max_address: Option<BFieldElement>,
+) -> VmOutputState {
+    execute_test_with_sponge_state(
+        code,
+        stack,
+        expected_stack_diff,
+        std_in,
+        nondeterminism,
+        memory,
+        initilialize_dynamic_allocator_to,
+        max_address,
+        None,
+    )
+}
+
+/// Like [`execute_test`], but if `initial_sponge_state` is set, the VM starts with that
+/// sponge state instead of the default one.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_test_with_sponge_state(
+    code: &[LabelledInstruction],
+    stack: &mut Vec<BFieldElement>,
+    expected_stack_diff: isize,
+    std_in: Vec<BFieldElement>,
+    nondeterminism: &mut NonDeterminism<BFieldElement>,
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+    initilialize_dynamic_allocator_to: Option<usize>,
+    max_address: Option<BFieldElement>,
+    initial_sponge_state: Option<&VmHasherState>,
 ) -> VmOutputState {
     let init_stack_height = stack.len();
 
@@ -299,6 +378,7 @@ pub fn execute_test(
             nondeterminism.ram.insert(*key, *value);
         }
     }
+    let initial_ram = nondeterminism.ram.clone();
 
     // produce standalone program that starts off arranging
     // the state as we expect
@@ -310,11 +390,37 @@ pub fn execute_test(
     );
 
     // run VM
-    let maybe_final_state = execute_with_terminal_state(&program, &std_in, nondeterminism);
-    let final_state = maybe_final_state.unwrap();
+    let maybe_final_state = execute_with_terminal_state_and_trace(
+        &program,
+        &std_in,
+        nondeterminism,
+        initial_sponge_state,
+    );
+    let (final_state, aet) = maybe_final_state.unwrap();
+    let clock_cycle_count = final_state.cycle_count as usize
+        - state_preparation_cycle_count(stack, initilialize_dynamic_allocator_to);
 
     *memory = final_state.ram.clone();
 
+    if let Some(max_address) = max_address {
+        // An address counts as written if its value differs from the initial one.
+        let addresses_written_above_cap = final_state
+            .ram
+            .iter()
+            .filter(|(address, value)| initial_ram.get(address) != Some(value))
+            .map(|(address, _)| address.value())
+            .filter(|address| *address > max_address.value())
+            .sorted()
+            .collect_vec();
+        if !addresses_written_above_cap.is_empty() {
+            panic!(
+                "Code must not write to memory above address {max_address}.\n\
+                Written addresses above it: {}",
+                addresses_written_above_cap.iter().join(",")
+            );
+        }
+    }
+
     if !final_state.jump_stack.is_empty() {
         panic!("Jump stack must be unchanged after code execution");
     }
@@ -344,7 +450,12 @@ pub fn execute_test(
     // Notice that this is only done after the successful execution of the program above, so all
     // produced proofs here should be valid.
     // If you run this, make sure `opt-level` is set to 3.
-    if std::env::var("DYING_TO_PROVE").is_ok() {
+    // A proof always starts from the default sponge state, so programs starting from any
+    // other sponge state are not proven.
+    let starts_from_default_sponge_state = initial_sponge_state.map_or(true, |sponge_state| {
+        sponge_state.state == default_vm_sponge_state(&program, &std_in, nondeterminism)
+    });
+    if std::env::var("DYING_TO_PROVE").is_ok() && starts_from_default_sponge_state {
         prove_and_verify(
             &program,
             &std_in,
@@ -360,6 +471,9 @@ pub fn execute_test(
         final_sponge_state: VmHasherState {
             state: final_state.sponge_state,
         },
+        clock_cycle_count,
+        hash_table_height: aet.hash_trace.nrows(),
+        u32_table_height: aet.u32_table_length(),
     }
 }
 
@@ -412,19 +526,89 @@ pub fn execute_with_terminal_state<'a>(
     std_in: &[BFieldElement],
     nondeterminism: &mut NonDeterminism<BFieldElement>,
 ) -> anyhow::Result<VMState<'a>> {
+    execute_with_terminal_state_and_sponge_state(program, std_in, nondeterminism, None)
+}
+
+/// Like [`execute_with_terminal_state`], but if `initial_sponge_state` is set, the VM
+/// starts with that sponge state instead of the default one.
+pub fn execute_with_terminal_state_and_sponge_state<'a>(
+    program: &'a Program,
+    std_in: &[BFieldElement],
+    nondeterminism: &mut NonDeterminism<BFieldElement>,
+    initial_sponge_state: Option<&VmHasherState>,
+) -> anyhow::Result<VMState<'a>> {
+    let public_input = PublicInput::new(std_in.to_vec());
+    let initial_state = initial_sponge_state.map(|sponge_state| {
+        let mut initial_state = VMState::new(program, public_input.clone(), nondeterminism.clone());
+        initial_state.sponge_state = sponge_state.state;
+        initial_state
+    });
+
     // run VM
     program
-        .debug_terminal_state(
-            PublicInput::new(std_in.to_vec()),
-            nondeterminism.clone(),
-            None,
-            None,
-        )
+        .debug_terminal_state(public_input, nondeterminism.clone(), initial_state, None)
         .map_err(|(err, fs)| {
             anyhow!("VM execution failed with error: {err}.\nLast state before crash:\n{fs}")
         })
 }
 
+/// Like [`execute_with_terminal_state_and_sponge_state`], but also record the algebraic
+/// execution trace of the run, from which the heights of the tables can be read.
+pub fn execute_with_terminal_state_and_trace<'a>(
+    program: &'a Program,
+    std_in: &[BFieldElement],
+    nondeterminism: &NonDeterminism<BFieldElement>,
+    initial_sponge_state: Option<&VmHasherState>,
+) -> anyhow::Result<(VMState<'a>, AlgebraicExecutionTrace)> {
+    let mut state = VMState::new(
+        program,
+        PublicInput::new(std_in.to_vec()),
+        nondeterminism.clone(),
+    );
+    if let Some(sponge_state) = initial_sponge_state {
+        state.sponge_state = sponge_state.state;
+    }
+
+    // run VM, recording every state it passes through
+    let mut aet = AlgebraicExecutionTrace::new(program.clone());
+    while !state.halting {
+        aet.record_state(&state)
+            .map_err(|err| anyhow!("Recording the execution trace failed with error: {err}"))?;
+        let co_processor_calls = state.step().map_err(|err| {
+            anyhow!("VM execution failed with error: {err}.\nLast state before crash:\n{state}")
+        })?;
+        for co_processor_call in co_processor_calls {
+            aet.record_co_processor_call(co_processor_call);
+        }
+    }
+
+    Ok((state, aet))
+}
+
+/// The sponge state the VM starts with if no other sponge state is provided.
+fn default_vm_sponge_state(
+    program: &Program,
+    std_in: &[BFieldElement],
+    nondeterminism: &NonDeterminism<BFieldElement>,
+) -> [BFieldElement; tip5::STATE_SIZE] {
+    VMState::new(
+        program,
+        PublicInput::new(std_in.to_vec()),
+        nondeterminism.clone(),
+    )
+    .sponge_state
+}
+
+/// Number of clock cycles spent by the code that `program_with_state_preparation`
+/// prepends to a program. The preparation code is straight-line, so every instruction
+/// takes exactly one cycle.
+pub(crate) fn state_preparation_cycle_count(
+    stack: &[BFieldElement],
+    initilialize_dynamic_allocator_to: Option<usize>,
+) -> usize {
+    state_preparation_code(stack, &HashMap::new(), initilialize_dynamic_allocator_to).len()
+}
+
 /// Produce the code to set the stack and memory into a certain state
 fn state_preparation_code(
     stack: &[BFieldElement],
@@ -507,9 +691,57 @@ fn prove_and_verify(
 
 #[cfg(test)]
 mod tests {
+    use rand::random;
+    use twenty_first::amount::u32s::U32s;
+
     use super::*;
     use crate::dyn_malloc::DYN_MALLOC_ADDRESS;
 
+    #[test]
+    fn push_and_pop_encodable_round_trip() {
+        let mut stack = get_init_tvm_stack();
+
+        let value = U32s::<2>::new([random(), random()]);
+        push_encodable(&mut stack, &value);
+        assert_eq!(NUM_OP_STACK_REGISTERS + 2, stack.len());
+        assert_eq!(value, pop_encodable::<U32s<2>>(&mut stack));
+        assert_eq!(get_init_tvm_stack(), stack);
+
+        let digest: Digest = random();
+        push_encodable(&mut stack, &digest);
+        assert_eq!(NUM_OP_STACK_REGISTERS + DIGEST_LENGTH, stack.len());
+        assert_eq!(digest, pop_encodable::<Digest>(&mut stack));
+        assert_eq!(get_init_tvm_stack(), stack);
+    }
+
+    #[test]
+    fn execution_state_with_stack_and_std_in() {
+        let stack = [get_init_tvm_stack(), vec![BFieldElement::new(14)]].concat();
+        let std_in = vec![BFieldElement::new(1), BFieldElement::new(2)];
+        let state = ExecutionState::with_stack_and_std_in(stack.clone(), std_in.clone());
+
+        assert_eq!(stack, state.stack);
+        assert_eq!(std_in, state.std_in);
+        assert!(state.nondeterminism.individual_tokens.is_empty());
+        assert!(state.memory.is_empty());
+        assert_eq!(0, state.words_allocated);
+    }
+
+    #[test]
+    fn execution_state_with_nondeterminism() {
+        let secret_in = vec![BFieldElement::new(3), BFieldElement::new(4)];
+        let state = ExecutionState::with_nondeterminism(
+            get_init_tvm_stack(),
+            NonDeterminism::new(secret_in.clone()),
+        );
+
+        assert_eq!(get_init_tvm_stack(), state.stack);
+        assert_eq!(secret_in, state.nondeterminism.individual_tokens);
+        assert!(state.std_in.is_empty());
+        assert!(state.memory.is_empty());
+        assert_eq!(0, state.words_allocated);
+    }
+
     #[test]
     fn initialize_dyn_malloc() {
         let mut memory = HashMap::default();
@@ -549,4 +781,32 @@ mod tests {
             .unwrap_or(&BFieldElement::zero())
             .is_zero());
     }
+
+    fn write_to_address(address: u64) -> VmOutputState {
+        execute_test(
+            &triton_asm!(push {address} push 42 write_mem pop halt),
+            &mut get_init_tvm_stack(),
+            0,
+            vec![],
+            &mut NonDeterminism::new(vec![]),
+            &mut HashMap::default(),
+            None,
+            Some(BFieldElement::new(100)),
+        )
+    }
+
+    #[test]
+    fn writes_below_max_address_are_accepted() {
+        let vm_output_state = write_to_address(100);
+        assert_eq!(
+            BFieldElement::new(42),
+            vm_output_state.final_ram[&BFieldElement::new(100)]
+        );
+    }
+
+    #[should_panic(expected = "Code must not write to memory above address 100")]
+    #[test]
+    fn write_above_max_address_is_caught() {
+        write_to_address(101);
+    }
 }
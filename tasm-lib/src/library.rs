@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 use num::One;
-use triton_vm::instruction::LabelledInstruction;
+use triton_vm::instruction::{AnInstruction, LabelledInstruction};
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use crate::snippet::{BasicSnippet, DataType, DeprecatedSnippet};
@@ -18,6 +18,15 @@ pub const STATIC_MEMORY_START_ADDRESS: usize = 1;
 pub struct Library {
     seen_snippets: HashMap<String, Vec<LabelledInstruction>>,
     free_pointer: usize,
+
+    /// Statically allocated regions that were freed, as (pointer, size) pairs.
+    free_list: Vec<(usize, usize)>,
+
+    /// Source locations of those imported snippets that declare one, by entrypoint.
+    source_locations: HashMap<String, &'static str>,
+
+    /// Total number of words requested through [`Self::kmalloc`], including reused ones.
+    kmalloc_total: usize,
 }
 
 impl Default for Library {
@@ -31,6 +40,9 @@ impl Library {
         Self {
             seen_snippets: Default::default(),
             free_pointer: STATIC_MEMORY_START_ADDRESS,
+            free_list: vec![],
+            source_locations: HashMap::new(),
+            kmalloc_total: 0,
         }
     }
 
@@ -47,6 +59,25 @@ impl Library {
         }
     }
 
+    /// Create a library with `words_allocated` words statically allocated and the given
+    /// snippets already imported. The snippets are imported in alphabetical order of
+    /// their entrypoints, so the memory they allocate statically, and hence their code,
+    /// does not depend on the order in which they are listed.
+    pub fn with_preallocated_memory_and_imports(
+        words_allocated: usize,
+        snippets: Vec<Box<dyn BasicSnippet>>,
+    ) -> Self {
+        let mut library = Self::with_preallocated_memory(words_allocated);
+        for snippet in snippets
+            .into_iter()
+            .sorted_by_key(|snippet| snippet.entrypoint())
+        {
+            library.import(snippet);
+        }
+
+        library
+    }
+
     /// Import `T: Snippet` into the library.
     ///
     /// This recursively imports `T`'s dependencies.
@@ -61,6 +92,10 @@ impl Library {
         // The linter's suggestion doesn't work. This suppression is fine imo.
         #[allow(clippy::map_entry)]
         if !self.seen_snippets.contains_key(&dep_entrypoint) {
+            if let Some(location) = snippet.source_location() {
+                self.source_locations
+                    .insert(dep_entrypoint.clone(), location);
+            }
             let dep_body = snippet.code(self);
             self.seen_snippets.insert(dep_entrypoint, dep_body);
         }
@@ -68,6 +103,26 @@ impl Library {
         snippet.entrypoint()
     }
 
+    /// Import a [`DeprecatedSnippet`], e.g. one returned by the list factories in
+    /// [`crate::list`], and return the label under which it can be called.
+    pub fn import_label(&mut self, snippet: Box<dyn DeprecatedSnippet>) -> String {
+        self.import(Box::new(snippet))
+    }
+
+    /// The source location of the imported snippet with the given entrypoint, if that
+    /// snippet declares one.
+    pub fn source_location(&self, entrypoint: &str) -> Option<&'static str> {
+        self.source_locations.get(entrypoint).copied()
+    }
+
+    /// The code of the imported snippet with the given entrypoint. Every snippet's code
+    /// is generated once, on its first import, and reused on every later import.
+    pub fn cached_code(&self, entrypoint: &str) -> Option<&[LabelledInstruction]> {
+        self.seen_snippets
+            .get(entrypoint)
+            .map(|code| code.as_slice())
+    }
+
     pub fn explicit_import(&mut self, name: &str, body: &[LabelledInstruction]) -> String {
         // The linter's suggestion doesn't work. This suppression is fine imo.
         #[allow(clippy::map_entry)]
@@ -102,6 +157,8 @@ impl Library {
         ret
     }
 
+    /// Return the code of all imported snippets. Every snippet is included exactly once,
+    /// no matter how many other snippets imported it.
     #[allow(dead_code)]
     pub fn all_imports(&self) -> Vec<LabelledInstruction> {
         // Collect all imports and return. All snippets are sorted
@@ -109,11 +166,214 @@ impl Library {
         self.all_external_dependencies().concat()
     }
 
+    /// Return the code of those imported snippets that are reachable from the given
+    /// entrypoints through `call` instructions, sorted like in [`Self::all_imports`].
+    /// Snippets that were imported but are never called are left out.
+    pub fn all_imports_reachable_from(&self, entrypoints: &[String]) -> Vec<LabelledInstruction> {
+        // A snippet may call any label of another snippet, not just its entrypoint.
+        let label_owners: HashMap<&String, &String> = self
+            .seen_snippets
+            .iter()
+            .flat_map(|(name, code)| {
+                code.iter()
+                    .filter_map(move |instruction| match instruction {
+                        LabelledInstruction::Label(label) => Some((label, name)),
+                        _ => None,
+                    })
+            })
+            .collect();
+
+        let mut reachable: HashSet<&String> = HashSet::new();
+        let mut to_visit = entrypoints
+            .iter()
+            .filter_map(|entrypoint| label_owners.get(entrypoint).copied())
+            .collect_vec();
+        while let Some(name) = to_visit.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            for label in called_labels(&self.seen_snippets[name]) {
+                if let Some(owner) = label_owners.get(&label) {
+                    to_visit.push(*owner);
+                }
+            }
+        }
+
+        self.seen_snippets
+            .iter()
+            .filter(|(name, _)| reachable.contains(name))
+            .sorted_by_key(|(name, _)| *name)
+            .flat_map(|(_, code)| code.clone())
+            .collect()
+    }
+
+    /// Statically allocate `num_words` words and return the address of the first one.
+    /// Regions returned through [`Self::free`] are reused, first fit, before fresh
+    /// memory is handed out.
     pub fn kmalloc(&mut self, num_words: usize) -> usize {
+        self.kmalloc_total += num_words;
+        if let Some(index) = self
+            .free_list
+            .iter()
+            .position(|&(_, size)| size >= num_words)
+        {
+            let (address, size) = self.free_list[index];
+            if size == num_words {
+                self.free_list.remove(index);
+            } else {
+                self.free_list[index] = (address + num_words, size - num_words);
+            }
+            return address;
+        }
+
         let address = self.free_pointer;
         self.free_pointer += num_words;
         address
     }
+
+    /// The total number of words requested through [`Self::kmalloc`] so far, whether
+    /// or not they were served from freed regions.
+    pub fn kmalloc_total(&self) -> usize {
+        self.kmalloc_total
+    }
+
+    /// Return a statically allocated region to the library, such that later calls to
+    /// [`Self::kmalloc`] can reuse it. This only affects the bookkeeping at compile
+    /// time; no code is emitted. The caller must make sure the region is not used
+    /// after it was freed.
+    pub fn free(&mut self, pointer: usize, num_words: usize) {
+        assert!(
+            pointer >= STATIC_MEMORY_START_ADDRESS && pointer + num_words <= self.free_pointer,
+            "Can only free statically allocated memory"
+        );
+        if num_words > 0 {
+            self.free_list.push((pointer, num_words));
+        }
+    }
+
+    /// Return the number of instructions in all imported snippets. Labels are not
+    /// counted.
+    pub fn assembled_instruction_count(&self) -> usize {
+        instruction_count(&self.all_imports())
+    }
+
+    /// Estimate how many words a piece of code pushes onto the op stack beyond the height
+    /// at which it starts, by simulating each instruction's effect on the stack height.
+    /// Calls to labels defined in `code` are followed; calls to other labels, as well as
+    /// recursive calls, are assumed to leave the stack height unchanged. Instructions
+    /// guarded by `skiz` only count if they grow the stack.
+    pub fn estimate_max_stack_depth(code: &[LabelledInstruction]) -> usize {
+        let label_positions: HashMap<&String, usize> = code
+            .iter()
+            .enumerate()
+            .filter_map(|(position, instruction)| match instruction {
+                LabelledInstruction::Label(label) => Some((label, position)),
+                _ => None,
+            })
+            .collect();
+
+        let (max_depth, _) = stack_depth_from(code, &label_positions, 0, &mut HashSet::default());
+        max_depth.max(0) as usize
+    }
+}
+
+/// Simulate the code starting at `start` until it returns, and return the maximum stack
+/// height relative to the starting height, as well as the relative height when
+/// returning.
+fn stack_depth_from<'a>(
+    code: &'a [LabelledInstruction],
+    label_positions: &HashMap<&'a String, usize>,
+    start: usize,
+    visiting: &mut HashSet<&'a String>,
+) -> (isize, isize) {
+    let mut depth = 0;
+    let mut max_depth = 0;
+    let mut return_depth = None;
+    let mut is_conditional = false;
+    for instruction in code[start..].iter() {
+        let instruction = match instruction {
+            LabelledInstruction::Instruction(instruction) => instruction,
+            _ => continue,
+        };
+
+        let stack_effect = match instruction {
+            AnInstruction::Call(label) => match label_positions.get(label) {
+                Some(&position) if visiting.insert(label) => {
+                    let (callee_max_depth, callee_return_depth) =
+                        stack_depth_from(code, label_positions, position, visiting);
+                    visiting.remove(label);
+                    max_depth = max_depth.max(depth + callee_max_depth);
+                    callee_return_depth
+                }
+                _ => 0,
+            },
+            AnInstruction::Return | AnInstruction::Recurse | AnInstruction::Halt => {
+                if matches!(instruction, AnInstruction::Return) {
+                    return_depth.get_or_insert(depth);
+                }
+                if !is_conditional {
+                    break;
+                }
+                0
+            }
+            _ => stack_size_influence(instruction),
+        };
+
+        depth += if is_conditional {
+            stack_effect.max(0)
+        } else {
+            stack_effect
+        };
+        max_depth = max_depth.max(depth);
+        is_conditional = matches!(instruction, AnInstruction::Skiz);
+    }
+
+    (max_depth, return_depth.unwrap_or(depth))
+}
+
+/// The net number of words an instruction pushes onto the op stack. Control flow is
+/// handled by the caller.
+fn stack_size_influence(instruction: &AnInstruction<String>) -> isize {
+    match instruction {
+        AnInstruction::Push(_)
+        | AnInstruction::Divine
+        | AnInstruction::Dup(_)
+        | AnInstruction::ReadMem
+        | AnInstruction::Split
+        | AnInstruction::ReadIo => 1,
+        AnInstruction::Pop
+        | AnInstruction::Skiz
+        | AnInstruction::Assert
+        | AnInstruction::WriteMem
+        | AnInstruction::Add
+        | AnInstruction::Mul
+        | AnInstruction::Eq
+        | AnInstruction::Lt
+        | AnInstruction::And
+        | AnInstruction::Xor
+        | AnInstruction::Pow
+        | AnInstruction::XbMul
+        | AnInstruction::WriteIo => -1,
+        AnInstruction::XxAdd | AnInstruction::XxMul => -3,
+        _ => 0,
+    }
+}
+
+/// Count the instructions in a piece of code, ignoring labels.
+pub fn instruction_count(code: &[LabelledInstruction]) -> usize {
+    code.iter()
+        .filter(|instruction| matches!(instruction, LabelledInstruction::Instruction(_)))
+        .count()
+}
+
+/// Return the labels targeted by the `call` instructions in a piece of code.
+pub fn called_labels(code: &[LabelledInstruction]) -> Vec<String> {
+    code.iter()
+        .filter_map(|instruction| match instruction {
+            LabelledInstruction::Instruction(AnInstruction::Call(label)) => Some(label.to_owned()),
+            _ => None,
+        })
+        .collect()
 }
 
 #[derive(Debug)]
@@ -325,18 +585,58 @@ impl DeprecatedSnippet for DummyTestSnippetC {
 
 #[cfg(test)]
 mod tests {
+    use std::cell::Cell;
     use std::collections::HashMap;
+    use std::rc::Rc;
     use triton_vm::program::Program;
     use triton_vm::triton_asm;
 
+    use crate::arithmetic::u64::div_mod_u64::DivModU64;
+    use crate::arithmetic::u64::incr_u64::IncrU64;
+    use crate::arithmetic::u64::shift_right_u64::ShiftRightU64;
     use crate::get_init_tvm_stack;
-    use crate::list::ListType;
+    use crate::hashing::load_auth_path_from_std_in_unsafe_list::LoadAuthPathFromStdInUnsafeList;
+    use crate::hashing::merkle_root::MerkleRoot;
+    use crate::list::{list_push, ListType};
     use crate::memory::memcpy::MemCpy;
     use crate::mmr::calculate_new_peaks_from_leaf_mutation::MmrCalculateNewPeaksFromLeafMutationMtIndices;
     use crate::test_helpers::test_rust_equivalence_given_input_values_deprecated;
 
     use super::*;
 
+    #[test]
+    fn pre_imports_do_not_depend_on_insertion_order() {
+        // Both `DivModU64` and `LoadAuthPathFromStdInUnsafeList` allocate memory
+        // statically, so their code depends on which one is imported first.
+        let library_a = Library::with_preallocated_memory_and_imports(
+            3,
+            vec![
+                Box::new(DivModU64),
+                Box::new(LoadAuthPathFromStdInUnsafeList),
+                Box::new(IncrU64),
+            ],
+        );
+        let library_b = Library::with_preallocated_memory_and_imports(
+            3,
+            vec![
+                Box::new(IncrU64),
+                Box::new(LoadAuthPathFromStdInUnsafeList),
+                Box::new(DivModU64),
+            ],
+        );
+
+        assert_eq!(library_a.all_imports(), library_b.all_imports());
+        assert_eq!(
+            library_a.all_imports().iter().join("\n"),
+            library_b.all_imports().iter().join("\n")
+        );
+        assert_eq!(
+            library_a.get_next_free_address(),
+            library_b.get_next_free_address()
+        );
+        assert!(library_a.get_next_free_address() > 3 + STATIC_MEMORY_START_ADDRESS);
+    }
+
     #[test]
     fn library_includes() {
         let empty_stack = get_init_tvm_stack();
@@ -368,6 +668,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn import_label_returns_entrypoint() {
+        let mut lib = Library::new();
+        assert_eq!(
+            DummyTestSnippetB.entrypoint_name(),
+            lib.import_label(Box::new(DummyTestSnippetB))
+        );
+
+        let push = list_push(ListType::Unsafe, DataType::Digest);
+        let expected_label = push.entrypoint_name();
+        assert_eq!(expected_label, lib.import_label(push));
+        assert!(lib.get_all_snippet_names().contains(&expected_label));
+    }
+
     #[test]
     fn get_all_snippet_names_test_a() {
         let mut lib = Library::new();
@@ -401,6 +715,72 @@ mod tests {
         let _ret = lib.all_imports();
     }
 
+    /// A snippet that counts how often its code is generated.
+    struct CountingSnippet(Rc<Cell<usize>>);
+
+    impl BasicSnippet for CountingSnippet {
+        fn inputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn outputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn entrypoint(&self) -> String {
+            "tasm_counting_dummy_test_value".to_string()
+        }
+
+        fn code(&self, _library: &mut Library) -> Vec<LabelledInstruction> {
+            self.0.set(self.0.get() + 1);
+            triton_asm!({self.entrypoint()}: return)
+        }
+    }
+
+    #[test]
+    fn code_is_generated_once_per_library() {
+        let code_generation_count = Rc::new(Cell::new(0));
+        let mut lib = Library::new();
+        let label = lib.import(Box::new(CountingSnippet(code_generation_count.clone())));
+        lib.import(Box::new(CountingSnippet(code_generation_count.clone())));
+        assert_eq!(1, code_generation_count.get());
+
+        let cached_code = lib.cached_code(&label).unwrap();
+        assert_eq!(triton_asm!({label}: return), cached_code);
+        assert!(lib.cached_code("tasm_not_imported").is_none());
+
+        // A fresh library generates the code anew.
+        let counting_snippet = CountingSnippet(code_generation_count.clone());
+        Library::new().import(Box::new(counting_snippet));
+        assert_eq!(2, code_generation_count.get());
+    }
+
+    #[test]
+    fn snippet_imported_through_two_parents_is_emitted_once() {
+        // C is imported by both A and B, and A also imports B.
+        let mut lib = Library::new();
+        let a = lib.import(Box::new(DummyTestSnippetA));
+        let b = lib.import(Box::new(DummyTestSnippetB));
+        let c_label = DummyTestSnippetC.entrypoint_name();
+
+        let imports = lib.all_imports();
+        let c_label_count = imports
+            .iter()
+            .filter(|instruction| {
+                matches!(instruction, LabelledInstruction::Label(label) if *label == c_label)
+            })
+            .count();
+        assert_eq!(1, c_label_count);
+
+        let mut src = triton_asm!(
+            call {a}
+            call {b}
+            halt
+        );
+        src.append(&mut lib.all_imports());
+        let _program = Program::new(&src);
+    }
+
     #[test]
     fn program_is_deterministic() {
         // Ensure that a generated program is deterministic, by checking that the imports
@@ -442,6 +822,125 @@ mod tests {
         }
     }
 
+    #[test]
+    fn assembled_instruction_count_test() {
+        assert_eq!(0, Library::new().assembled_instruction_count());
+
+        // C consists of 2 instructions. B and A consist of 3 instructions each, and pull
+        // in C and B, respectively.
+        for (snippet, expected_count) in [
+            (Box::new(DummyTestSnippetC) as Box<dyn BasicSnippet>, 2),
+            (Box::new(DummyTestSnippetB), 5),
+            (Box::new(DummyTestSnippetA), 8),
+        ] {
+            let mut lib = Library::new();
+            lib.import(snippet);
+            assert_eq!(expected_count, lib.assembled_instruction_count());
+        }
+    }
+
+    struct ImportsUnusedHelper;
+
+    impl BasicSnippet for ImportsUnusedHelper {
+        fn inputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn outputs(&self) -> Vec<(DataType, String)> {
+            vec![(DataType::BFE, "1".to_string())]
+        }
+
+        fn entrypoint(&self) -> String {
+            "tasm_imports_unused_helper_dummy_test_value".to_string()
+        }
+
+        fn code(&self, library: &mut Library) -> Vec<LabelledInstruction> {
+            let entrypoint = self.entrypoint();
+            let _unused_helper = library.import(Box::new(DummyTestSnippetB));
+            let c = library.import(Box::new(DummyTestSnippetC));
+
+            triton_asm!(
+                {entrypoint}:
+                    call {c}
+                    return
+            )
+        }
+    }
+
+    fn has_label(code: &[LabelledInstruction], label: &str) -> bool {
+        code.iter()
+            .any(|instruction| matches!(instruction, LabelledInstruction::Label(l) if l == label))
+    }
+
+    #[test]
+    fn uncalled_import_is_not_reachable() {
+        let mut lib = Library::new();
+        let entrypoint = lib.import(Box::new(ImportsUnusedHelper));
+        let helper_label = DummyTestSnippetB.entrypoint_name();
+        let c_label = DummyTestSnippetC.entrypoint_name();
+
+        let all_imports = lib.all_imports();
+        assert!(has_label(&all_imports, &helper_label));
+
+        let reachable_imports = lib.all_imports_reachable_from(&[entrypoint.clone()]);
+        assert!(has_label(&reachable_imports, &entrypoint));
+        assert!(has_label(&reachable_imports, &c_label));
+        assert!(!has_label(&reachable_imports, &helper_label));
+        assert!(reachable_imports.len() < all_imports.len());
+
+        assert!(lib.all_imports_reachable_from(&[]).is_empty());
+    }
+
+    #[test]
+    fn transitively_called_imports_are_reachable() {
+        let mut lib = Library::new();
+        let a = lib.import(Box::new(DummyTestSnippetA));
+        assert_eq!(lib.all_imports(), lib.all_imports_reachable_from(&[a]));
+
+        let b = DummyTestSnippetB.entrypoint_name();
+        let reachable_from_b = lib.all_imports_reachable_from(&[b.clone()]);
+        assert!(!has_label(
+            &reachable_from_b,
+            &DummyTestSnippetA.entrypoint_name()
+        ));
+        assert!(has_label(&reachable_from_b, &b));
+        assert!(has_label(
+            &reachable_from_b,
+            &DummyTestSnippetC.entrypoint_name()
+        ));
+    }
+
+    #[test]
+    fn stack_depth_of_shift_right_u64_is_small() {
+        let code = ShiftRightU64.function_code_as_instructions(&mut Library::new());
+        let estimate = Library::estimate_max_stack_depth(&code);
+        assert!(
+            (1..16).contains(&estimate),
+            "estimated maximum stack depth: {estimate}"
+        );
+    }
+
+    #[test]
+    fn deep_snippet_is_flagged() {
+        // The caller and the callee each push 10 words, which exceeds the 16 op stack
+        // registers once the callee runs.
+        let push_ten = "push 0\n".repeat(10);
+        let pop_ten = "pop\n".repeat(10);
+        let code = triton_asm!(
+            deep_snippet:
+                {push_ten}
+                call deep_snippet_helper
+                {pop_ten}
+                return
+            deep_snippet_helper:
+                {push_ten}
+                {pop_ten}
+                return
+        );
+
+        assert_eq!(20, Library::estimate_max_stack_depth(&code));
+    }
+
     #[test]
     fn kmalloc_test() {
         let mut lib = Library::new();
@@ -463,4 +962,59 @@ mod tests {
         assert_eq!(9, third_free_address);
         assert_eq!(1009, lib.get_next_free_address());
     }
+
+    #[test]
+    fn kmalloc_reuses_freed_memory() {
+        let mut lib = Library::new();
+        let first = lib.kmalloc(10);
+        let second = lib.kmalloc(5);
+        assert_eq!(16, lib.get_next_free_address());
+
+        // a freed region of the same size is reused
+        lib.free(first, 10);
+        assert_eq!(first, lib.kmalloc(10));
+        assert_eq!(16, lib.get_next_free_address());
+
+        // a larger freed region is split
+        lib.free(first, 10);
+        assert_eq!(first, lib.kmalloc(4));
+        assert_eq!(first + 4, lib.kmalloc(6));
+        assert_eq!(16, lib.get_next_free_address());
+
+        // a too small freed region is not used
+        lib.free(second, 5);
+        assert_eq!(16, lib.kmalloc(6));
+        assert_eq!(22, lib.get_next_free_address());
+        assert_eq!(second, lib.kmalloc(5));
+    }
+
+    #[should_panic]
+    #[test]
+    fn free_unallocated_memory() {
+        let mut lib = Library::new();
+        lib.kmalloc(3);
+        lib.free(2, 3);
+    }
+
+    #[test]
+    fn static_allocation_size_matches_kmalloc_total() {
+        let snippets: Vec<Box<dyn BasicSnippet>> = vec![
+            Box::new(DivModU64),
+            Box::new(LoadAuthPathFromStdInUnsafeList),
+            Box::new(MerkleRoot {
+                list_type: ListType::Unsafe,
+            }),
+            Box::new(IncrU64),
+        ];
+        for snippet in snippets {
+            let expected = snippet.static_allocation_size();
+            let mut library = Library::new();
+            library.import(snippet);
+            assert_eq!(expected, library.kmalloc_total());
+            assert_eq!(
+                STATIC_MEMORY_START_ADDRESS + expected,
+                library.get_next_free_address()
+            );
+        }
+    }
 }
@@ -6,18 +6,30 @@ use triton_vm::{
 };
 
 use crate::{
-    library::Library, prove_and_verify, snippet::BasicSnippet, state_preparation_code,
-    ExecutionResult,
+    library::{called_labels, Library},
+    prove_and_verify,
+    snippet::BasicSnippet,
+    state_preparation_code, ExecutionResult,
 };
 
 pub fn link_for_isolated_run<T: BasicSnippet>(
     snippet: Rc<RefCell<T>>,
     words_statically_allocated: usize,
+) -> Vec<LabelledInstruction> {
+    link_snippet(&*snippet.borrow(), words_statically_allocated)
+}
+
+/// Link the snippet and the snippets it calls into a program that calls the snippet's
+/// entrypoint and then halts. The dynamic allocator is not initialized; see
+/// [`BasicSnippet::assemble_with_preallocated`] for a self-contained program.
+pub fn link_snippet<T: BasicSnippet + ?Sized>(
+    snippet: &T,
+    words_statically_allocated: usize,
 ) -> Vec<LabelledInstruction> {
     let mut snippet_state = Library::with_preallocated_memory(words_statically_allocated);
-    let entrypoint = snippet.borrow().entrypoint();
-    let function_body = snippet.borrow().code(&mut snippet_state);
-    let library_code = snippet_state.all_imports();
+    let entrypoint = snippet.entrypoint();
+    let function_body = snippet.code(&mut snippet_state);
+    let library_code = snippet_state.all_imports_reachable_from(&called_labels(&function_body));
 
     // The TASM code is always run through a function call, so the 1st instruction
     // is a call to the function in question.
@@ -1,10 +1,24 @@
 use std::fmt::Display;
 
+use crate::snippet::{DataType, DeprecatedSnippet};
+
+use self::safeimplu32::{
+    get::SafeGet, length::Length as SafeLength, new::SafeNew, pop::SafePop, push::SafePush,
+    set::SafeSet,
+};
+use self::unsafeimplu32::{
+    get::UnsafeGet, length::Length as UnsafeLength, new::UnsafeNew, pop::UnsafePop,
+    push::UnsafePush, set::UnsafeSet,
+};
+
 pub mod contiguous_list;
+pub mod conversion;
 pub mod higher_order;
 pub mod multiset_equality;
+pub mod multiset_equals_u64;
 pub mod range;
 pub mod safeimplu32;
+pub mod sort_u64;
 pub mod unsafeimplu32;
 
 #[derive(Clone, Debug)]
@@ -21,3 +35,73 @@ impl Display for ListType {
         }
     }
 }
+
+// The following functions return the safe or the unsafe implementation of a list
+// operation, such that snippets can be written once for both list types.
+
+pub fn list_new(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafeNew(element_type)),
+        ListType::Unsafe => Box::new(UnsafeNew(element_type)),
+    }
+}
+
+pub fn list_push(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafePush(element_type)),
+        ListType::Unsafe => Box::new(UnsafePush(element_type)),
+    }
+}
+
+pub fn list_pop(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafePop(element_type)),
+        ListType::Unsafe => Box::new(UnsafePop(element_type)),
+    }
+}
+
+pub fn list_get(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafeGet(element_type)),
+        ListType::Unsafe => Box::new(UnsafeGet(element_type)),
+    }
+}
+
+pub fn list_set(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafeSet(element_type)),
+        ListType::Unsafe => Box::new(UnsafeSet(element_type)),
+    }
+}
+
+pub fn list_length(list_type: ListType, element_type: DataType) -> Box<dyn DeprecatedSnippet> {
+    match list_type {
+        ListType::Safe => Box::new(SafeLength(element_type)),
+        ListType::Unsafe => Box::new(UnsafeLength(element_type)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+
+    #[test]
+    fn list_operations_of_both_list_types() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let prefix = format!("tasm_list_{list_type}_");
+            for factory in [
+                list_new,
+                list_push,
+                list_pop,
+                list_get,
+                list_set,
+                list_length,
+            ] {
+                let snippet = factory(list_type.clone(), DataType::U64);
+                assert!(snippet.entrypoint_name().starts_with(&prefix));
+                test_rust_equivalence_multiple_deprecated(&snippet, true);
+            }
+        }
+    }
+}
@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::new::SafeNew;
+use crate::list::unsafeimplu32::new::UnsafeNew;
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get_length, safe_list_set_length,
+};
+use crate::rust_shadowing_helper_functions::unsafe_list::{
+    unsafe_insert_random_list, unsafe_list_get_length, unsafe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Offset of the first element relative to the list pointer, for safe lists:
+/// `[length, capacity, elements...]`.
+const SAFE_LIST_HEADER_SIZE: usize = 2;
+
+/// Offset of the first element relative to the list pointer, for unsafe lists:
+/// `[length, elements...]`.
+const UNSAFE_LIST_HEADER_SIZE: usize = 1;
+
+/// Code for a loop that copies the elements of one list to another list, where the
+/// elements start `src_offset` and `dest_offset` words after the respective list
+/// pointers.
+fn copy_elements_loop(label: &str, src_offset: usize, dest_offset: usize) -> String {
+    format!(
+        "
+        // INVARIANT: _ *src *dest num_words i
+        {label}:
+            dup 1
+            dup 1
+            eq
+            skiz
+                return
+
+            dup 3
+            dup 1
+            add
+            push {src_offset}
+            add
+            read_mem
+            swap 1
+            pop
+            // _ *src *dest num_words i word
+
+            dup 3
+            dup 2
+            add
+            push {dest_offset}
+            add
+            swap 1
+            write_mem
+            pop
+            // _ *src *dest num_words i
+
+            push 1
+            add
+            recurse
+        "
+    )
+}
+
+/// Copy the words of the elements of the list at `src_pointer` to the list at
+/// `dest_pointer`.
+fn copy_elements(
+    src_pointer: BFieldElement,
+    src_offset: usize,
+    dest_pointer: BFieldElement,
+    dest_offset: usize,
+    num_words: usize,
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+) {
+    for i in 0..num_words {
+        let word = memory[&(src_pointer + BFieldElement::new((src_offset + i) as u64))];
+        memory.insert(
+            dest_pointer + BFieldElement::new((dest_offset + i) as u64),
+            word,
+        );
+    }
+}
+
+fn mul_with_size(element_size: usize) -> String {
+    // If size is 1, do nothing to save two clock cycles.
+    if element_size != 1 {
+        format!("push {element_size}\n mul\n")
+    } else {
+        String::default()
+    }
+}
+
+/// Copy a safe list into a newly allocated unsafe list. The original list is left
+/// untouched.
+#[derive(Clone, Debug)]
+pub struct SafeToUnsafe(pub DataType);
+
+impl SafeToUnsafe {
+    fn prepare_state(&self, capacity: u32, list_length: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut memory = HashMap::default();
+        safe_insert_random_list(&self.0, list_pointer, capacity, list_length, &mut memory);
+
+        let stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeToUnsafe {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safe_to_unsafe___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*safe_list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*unsafe_list".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(UnsafeNew(self.0.clone())));
+        let mul_with_size = mul_with_size(self.0.get_size());
+        let copy_loop = copy_elements_loop(
+            &format!("{entrypoint}_loop"),
+            SAFE_LIST_HEADER_SIZE,
+            UNSAFE_LIST_HEADER_SIZE,
+        );
+
+        format!(
+            "
+            // BEFORE: _ *safe_list
+            // AFTER: _ *unsafe_list
+            {entrypoint}:
+                dup 0
+                read_mem
+                swap 1
+                pop
+                // _ *safe_list length
+
+                dup 0
+                call {new_list}
+                // _ *safe_list length *unsafe_list
+
+                dup 1
+                write_mem
+                // _ *safe_list length *unsafe_list
+
+                swap 1
+                {mul_with_size}
+                push 0
+                // _ *safe_list *unsafe_list num_words 0
+
+                call {entrypoint}_loop
+                // _ *safe_list *unsafe_list num_words num_words
+
+                pop
+                pop
+                swap 1
+                pop
+                // _ *unsafe_list
+
+                return
+
+            {copy_loop}
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0, 0),
+            self.prepare_state(10, 0),
+            self.prepare_state(10, 1),
+            self.prepare_state(20, rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(20, 10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let safe_list_pointer = stack.pop().unwrap();
+        let length = safe_list_get_length(safe_list_pointer, memory);
+
+        stack.push(BFieldElement::new(length as u64));
+        UnsafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let unsafe_list_pointer = *stack.last().unwrap();
+
+        unsafe_list_set_length(unsafe_list_pointer, length as u32, memory);
+        copy_elements(
+            safe_list_pointer,
+            SAFE_LIST_HEADER_SIZE,
+            unsafe_list_pointer,
+            UNSAFE_LIST_HEADER_SIZE,
+            length * self.0.get_size(),
+            memory,
+        );
+    }
+}
+
+/// Copy an unsafe list into a newly allocated safe list with the given capacity. The
+/// original list is left untouched. Crashes if the capacity is smaller than the length
+/// of the list.
+#[derive(Clone, Debug)]
+pub struct UnsafeToSafe(pub DataType);
+
+impl UnsafeToSafe {
+    fn prepare_state(&self, capacity: u32, list_length: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut memory = HashMap::default();
+        unsafe_insert_random_list(&self.0, list_pointer, list_length, &mut memory);
+
+        let stack = [
+            get_init_tvm_stack(),
+            vec![list_pointer, BFieldElement::new(capacity as u64)],
+        ]
+        .concat();
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for UnsafeToSafe {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_unsafe_to_safe___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*unsafe_list".to_string(), "capacity".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone())), DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*safe_list".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(SafeNew(self.0.clone())));
+        let mul_with_size = mul_with_size(self.0.get_size());
+        let copy_loop = copy_elements_loop(
+            &format!("{entrypoint}_loop"),
+            UNSAFE_LIST_HEADER_SIZE,
+            SAFE_LIST_HEADER_SIZE,
+        );
+
+        format!(
+            "
+            // BEFORE: _ *unsafe_list capacity
+            // AFTER: _ *safe_list
+            {entrypoint}:
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *unsafe_list capacity length
+
+                // assert length <= capacity
+                dup 0
+                dup 2
+                lt
+                push 0
+                eq
+                assert
+                // _ *unsafe_list capacity length
+
+                swap 1
+                call {new_list}
+                // _ *unsafe_list length *safe_list
+
+                dup 1
+                write_mem
+                // _ *unsafe_list length *safe_list
+
+                swap 1
+                {mul_with_size}
+                push 0
+                // _ *unsafe_list *safe_list num_words 0
+
+                call {entrypoint}_loop
+                // _ *unsafe_list *safe_list num_words num_words
+
+                pop
+                pop
+                swap 1
+                pop
+                // _ *safe_list
+
+                return
+
+            {copy_loop}
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Capacity is smaller than the length of the list".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0, 0),
+            self.prepare_state(10, 0),
+            self.prepare_state(1, 1),
+            self.prepare_state(10, 1),
+            self.prepare_state(20, rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(20, 10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let capacity = stack.pop().unwrap();
+        let unsafe_list_pointer = stack.pop().unwrap();
+        let length = unsafe_list_get_length(unsafe_list_pointer, memory);
+        assert!(
+            length as u64 <= capacity.value(),
+            "Capacity must not be smaller than the length of the list"
+        );
+
+        stack.push(capacity);
+        SafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let safe_list_pointer = *stack.last().unwrap();
+
+        safe_list_set_length(safe_list_pointer, length, memory);
+        copy_elements(
+            unsafe_list_pointer,
+            UNSAFE_LIST_HEADER_SIZE,
+            safe_list_pointer,
+            SAFE_LIST_HEADER_SIZE,
+            length * self.0.get_size(),
+            memory,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::One;
+
+    use crate::list::ListType;
+    use crate::rust_shadowing_helper_functions::{list_to_vec, vec_to_list};
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn safe_to_unsafe_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeToUnsafe(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeToUnsafe(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeToUnsafe(DataType::Digest), true);
+    }
+
+    #[test]
+    fn unsafe_to_safe_test() {
+        test_rust_equivalence_multiple_deprecated(&UnsafeToSafe(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&UnsafeToSafe(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&UnsafeToSafe(DataType::Digest), true);
+    }
+
+    #[test]
+    fn u64_list_round_trip() {
+        let elements: Vec<[BFieldElement; 2]> = (0..7)
+            .map(|_| [random::<u32>().into(), random::<u32>().into()])
+            .collect();
+        let safe_list_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        vec_to_list(
+            safe_list_pointer,
+            10,
+            &elements,
+            &mut memory,
+            &ListType::Safe,
+        );
+        let stack = [get_init_tvm_stack(), vec![safe_list_pointer]].concat();
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SafeToUnsafe(DataType::U64),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+        let unsafe_list_pointer = *vm_output_state.final_stack.last().unwrap();
+        let memory = vm_output_state.final_ram;
+        assert_eq!(
+            elements,
+            list_to_vec::<2>(unsafe_list_pointer, &memory, &ListType::Unsafe)
+        );
+
+        let capacity = BFieldElement::new(12);
+        let stack = [get_init_tvm_stack(), vec![unsafe_list_pointer, capacity]].concat();
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &UnsafeToSafe(DataType::U64),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+        let new_safe_list_pointer = *vm_output_state.final_stack.last().unwrap();
+        let memory = vm_output_state.final_ram;
+        assert_eq!(
+            elements,
+            list_to_vec::<2>(new_safe_list_pointer, &memory, &ListType::Safe)
+        );
+        assert_eq!(
+            capacity,
+            memory[&(new_safe_list_pointer + BFieldElement::one())]
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn unsafe_to_safe_capacity_below_length() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &UnsafeToSafe(DataType::U64),
+            UnsafeToSafe(DataType::U64).prepare_state(4, 5),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_to_unsafe_benchmark() {
+        bench_and_write(SafeToUnsafe(DataType::Digest));
+    }
+
+    #[test]
+    fn unsafe_to_safe_benchmark() {
+        bench_and_write(UnsafeToSafe(DataType::Digest));
+    }
+}
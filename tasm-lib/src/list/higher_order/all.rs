@@ -289,7 +289,7 @@ impl Function for All {
                 let execution_state = self.generate_input_state(list_pointer, list_length, false);
                 (execution_state.stack, execution_state.memory)
             }
-            None => {
+            Some(BenchmarkCase::Custom(_)) | None => {
                 let mut rng: StdRng = SeedableRng::from_seed(seed);
                 let list_pointer = BFieldElement::new(rng.next_u64() % (1 << 20));
                 let list_length = 1 << (rng.next_u32() as usize % 4);
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use rand::{thread_rng, Rng};
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use super::safeimplu32::equals::SafeListEquals;
+use super::sort_u64::{insert_u64_list, random_u64s, read_u64_list, ListSortU64};
+use super::ListType;
+use crate::library::Library;
+use crate::memory::dyn_malloc::DynMalloc;
+use crate::memory::memcpy::MemCpy;
+use crate::rust_shadowing_helper_functions::dyn_malloc::dynamic_allocator;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Determine whether two lists of u64s contain the same elements with the same
+/// multiplicities, i.e., whether they are equal up to permutation. Both lists are
+/// copied to freshly allocated safe lists whose capacity equals their length, the
+/// copies are sorted, and the sorted copies are compared with `SafeListEquals`. The
+/// input lists are left untouched.
+#[derive(Clone, Debug)]
+pub struct MultisetEqualsU64(pub ListType);
+
+impl MultisetEqualsU64 {
+    fn safety_offset(&self) -> usize {
+        match self.0 {
+            ListType::Safe => 2,
+            ListType::Unsafe => 1,
+        }
+    }
+
+    fn init_state(&self, list_a: &[u64], list_b: &[u64]) -> ExecutionState {
+        let mut rng = thread_rng();
+        let pointer_a = BFieldElement::new(rng.gen_range(1u64 << 20..1u64 << 30));
+        let footprint_a = (self.safety_offset() + 2 * list_a.len()) as u64;
+        let pointer_b = pointer_a + BFieldElement::new(footprint_a + rng.gen_range(0..100));
+
+        let mut memory = HashMap::default();
+        insert_u64_list(&self.0, pointer_a, list_a, &mut memory);
+        insert_u64_list(&self.0, pointer_b, list_b, &mut memory);
+
+        ExecutionState {
+            stack: [get_init_tvm_stack(), vec![pointer_a, pointer_b]].concat(),
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory,
+            words_allocated: 1,
+        }
+    }
+
+    fn random_permuted_lists_state(&self, length: usize) -> ExecutionState {
+        let list_a = random_u64s(length);
+        let mut list_b = list_a.clone();
+        list_b.reverse();
+        list_b.rotate_left(length / 3);
+        self.init_state(&list_a, &list_b)
+    }
+}
+
+impl DeprecatedSnippet for MultisetEqualsU64 {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_list_{}_multiset_equals_u64", self.0)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list_a".to_string(), "*list_b".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(DataType::U64)),
+            DataType::List(Box::new(DataType::U64)),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["multisets_are_equal".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let dyn_malloc = library.import(Box::new(DynMalloc));
+        let memcpy = library.import(Box::new(MemCpy));
+        let sort = library.import(Box::new(ListSortU64 {
+            list_type: ListType::Safe,
+            element_type: DataType::U64,
+        }));
+        let list_equals = library.import(Box::new(SafeListEquals(DataType::U64)));
+        let safety_offset = self.safety_offset();
+
+        format!(
+            "
+            // BEFORE: _ *list_a *list_b
+            // AFTER: _ multisets_are_equal
+            {entrypoint}:
+                dup 1
+                call {entrypoint}_sorted_copy
+                // _ *list_a *list_b *sorted_a
+
+                dup 1
+                call {entrypoint}_sorted_copy
+                // _ *list_a *list_b *sorted_a *sorted_b
+
+                call {list_equals}
+                // _ *list_a *list_b multisets_are_equal
+
+                swap 2
+                pop
+                pop
+                // _ multisets_are_equal
+
+                return
+
+            // BEFORE: _ *list
+            // AFTER: _ *sorted_copy
+            {entrypoint}_sorted_copy:
+                dup 0
+                read_mem
+                swap 1
+                pop
+                // _ *list len
+
+                dup 0
+                push 2
+                mul
+                push 2
+                add
+                call {dyn_malloc}
+                // _ *list len *copy
+
+                dup 1
+                write_mem
+                push 1
+                add
+                dup 1
+                write_mem
+                push 1
+                add
+                // _ *list len *copy_elements
+
+                dup 2
+                push {safety_offset}
+                add
+                dup 1
+                dup 3
+                push 2
+                mul
+                // _ *list len *copy_elements *list_elements *copy_elements num_words
+
+                call {memcpy}
+                // _ *list len *copy_elements
+
+                push -2
+                add
+                // _ *list len *copy
+
+                dup 0
+                call {sort}
+                // _ *list len *copy
+
+                swap 2
+                pop
+                pop
+                // _ *copy
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let length = rng.gen_range(1..20);
+        let list = random_u64s(length);
+        let mut list_with_other_multiplicities = list.clone();
+        list_with_other_multiplicities[0] = list[length - 1];
+
+        vec![
+            self.init_state(&[], &[]),
+            self.random_permuted_lists_state(length),
+            self.init_state(&list, &list_with_other_multiplicities),
+            self.init_state(&list, &list[1..]),
+            self.init_state(&list[1..], &list),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.random_permuted_lists_state(1 << 4)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.random_permuted_lists_state(1 << 6)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let pointer_b = stack.pop().unwrap();
+        let pointer_a = stack.pop().unwrap();
+
+        // Mirror the TASM code's allocation and sorting of copies.
+        for pointer in [pointer_a, pointer_b] {
+            let values = read_u64_list(&self.0, pointer, memory);
+            let copy = dynamic_allocator(2 + 2 * values.len(), memory);
+            insert_u64_list(&ListType::Safe, copy, &values, memory);
+            ListSortU64 {
+                list_type: ListType::Safe,
+                element_type: DataType::U64,
+            }
+            .rust_shadowing(&mut vec![copy], vec![], vec![], memory);
+            stack.push(copy);
+        }
+
+        SafeListEquals(DataType::U64).rust_shadowing(stack, vec![], vec![], memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn multiset_equals_u64_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(&MultisetEqualsU64(list_type), true);
+        }
+    }
+
+    #[test]
+    fn reordered_lists_are_equal() {
+        prop_multiset_equals(&[], &[], true);
+        prop_multiset_equals(&[1, 2, 3], &[3, 1, 2], true);
+        prop_multiset_equals(&[5, 5, 1 << 40, 7], &[7, 5, 1 << 40, 5], true);
+        prop_multiset_equals(
+            &[u64::MAX, 0, u32::MAX as u64],
+            &[0, u32::MAX as u64, u64::MAX],
+            true,
+        );
+    }
+
+    #[test]
+    fn differing_multiplicities_are_detected() {
+        prop_multiset_equals(&[1, 1, 2], &[1, 2, 2], false);
+        prop_multiset_equals(&[1 << 32, 1], &[1, 1], false);
+    }
+
+    #[test]
+    fn differing_lengths_are_detected() {
+        prop_multiset_equals(&[1, 2, 3], &[1, 2], false);
+        prop_multiset_equals(&[], &[0], false);
+    }
+
+    fn prop_multiset_equals(list_a: &[u64], list_b: &[u64], expected: bool) {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = MultisetEqualsU64(list_type);
+            let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+                &snippet,
+                snippet.init_state(list_a, list_b),
+            );
+            assert_eq!(
+                BFieldElement::new(expected as u64),
+                *vm_output_state.final_stack.last().unwrap()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn multiset_equals_u64_benchmark() {
+        bench_and_write(MultisetEqualsU64(ListType::Unsafe));
+    }
+}
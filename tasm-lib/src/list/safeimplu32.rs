@@ -1,10 +1,23 @@
 // TODO: Replace this when we have dynamical allocation
 pub const SAFE_LIST_ELEMENT_CAPACITY: u32 = 1000;
 
+pub mod binary_search;
+pub mod clone;
+pub mod equals;
+pub mod filter;
+pub mod fold;
+pub mod for_each;
 pub mod get;
+pub mod insert;
 pub mod length;
+pub mod map;
+pub mod min_max;
 pub mod new;
 pub mod pop;
 pub mod push;
+pub mod remove;
+pub mod resize;
 pub mod set;
 pub mod set_length;
+pub mod sort;
+pub mod split_off;
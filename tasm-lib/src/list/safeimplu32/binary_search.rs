@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::eq_u64::EqU64;
+use crate::arithmetic::u64::lt_u64::LtStandardU64;
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, length::Length as SafeLength};
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_new, safe_list_push,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Search a safe list of `U32`s or `U64`s, sorted in ascending order, for a target
+/// value. Returns whether the target was found, and the index of its first occurrence
+/// if so. Otherwise, the returned index is where the target would have to be inserted
+/// to keep the list sorted. Like `slice::partition_point`, this takes a logarithmic
+/// number of steps. The result is meaningless if the list is not sorted.
+#[derive(Clone, Debug)]
+pub struct SafeBinarySearch(pub DataType);
+
+impl SafeBinarySearch {
+    fn prepare_state(&self, list_length: usize, target_is_element: bool) -> ExecutionState {
+        let mut rng = thread_rng();
+        let mut elements = self.0.random_elements(list_length);
+        elements.sort_by_key(|element| self.element_value(element));
+        let target = match target_is_element && !elements.is_empty() {
+            true => elements[rng.gen_range(0..elements.len())].clone(),
+            false => self.0.random_element(),
+        };
+
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut memory = HashMap::default();
+        safe_list_new(list_pointer, list_length as u32, &mut memory);
+        for element in elements {
+            safe_list_push(list_pointer, element, &mut memory, self.0.get_size());
+        }
+
+        // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.extend(target.into_iter().rev());
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    /// Code consuming `_ [rhs] [lhs]` and leaving `_ (lhs < rhs)`.
+    fn lt_code(&self, library: &mut Library) -> String {
+        match self.0 {
+            DataType::U32 => "lt".to_string(),
+            DataType::U64 => format!("call {}", library.import(Box::new(LtStandardU64))),
+            _ => panic!("Unsupported element type for binary search: {}", self.0),
+        }
+    }
+
+    /// Code consuming `_ [rhs] [lhs]` and leaving `_ (lhs == rhs)`.
+    fn eq_code(&self, library: &mut Library) -> String {
+        match self.0 {
+            DataType::U32 => "eq".to_string(),
+            DataType::U64 => format!("call {}", library.import(Box::new(EqU64))),
+            _ => panic!("Unsupported element type for binary search: {}", self.0),
+        }
+    }
+
+    /// The numeric value of a list element, as read from memory.
+    fn element_value(&self, element: &[BFieldElement]) -> u64 {
+        match self.0 {
+            DataType::U32 => element[0].value(),
+            DataType::U64 => (element[1].value() << 32) + element[0].value(),
+            _ => panic!("Unsupported element type for binary search: {}", self.0),
+        }
+    }
+}
+
+impl DeprecatedSnippet for SafeBinarySearch {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_binary_search___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        let element_size = self.0.get_size();
+        let mut ret = vec!["*list".to_string()];
+        for i in 0..element_size {
+            ret.push(format!("target_{}", element_size - 1 - i));
+        }
+
+        ret
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone())), self.0.clone()]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["found".to_string(), "index".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool, DataType::U32]
+    }
+
+    fn stack_diff(&self) -> isize {
+        1 - self.0.get_size() as isize
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let lt = self.lt_code(library);
+        let eq = self.eq_code(library);
+        let get_length = library.import(Box::new(SafeLength(self.0.clone())));
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+
+        // Stack during the loop: _ *list [target] lo hi mid [target]
+        let dup_target_below_lo_hi_mid =
+            vec![format!("dup {}", element_size + 2); element_size].join("\n");
+        let list_pointer_depth_in_loop = 2 * element_size + 3;
+        let mid_depth_in_loop = element_size + 1;
+
+        // Stack in the final comparison: _ *list [target] index [target]
+        let dup_target_below_index = vec![format!("dup {element_size}"); element_size].join("\n");
+        let list_pointer_depth_in_comparison = 2 * element_size + 1;
+        let index_depth_in_comparison = element_size + 1;
+
+        let list_pointer_depth_at_start = element_size;
+        let list_pointer_depth_after_loop = element_size + 1;
+        let found_flag_swap_depth = element_size + 2;
+        let pop_target = vec!["pop"; element_size].join("\n");
+
+        format!(
+            "
+            // BEFORE: _ *list [target]
+            // AFTER: _ found index
+            {entrypoint}:
+                dup {list_pointer_depth_at_start}
+                call {get_length}
+                push 0
+                swap 1
+                // _ *list [target] 0 len
+
+                call {entrypoint}_loop
+                // _ *list [target] index index
+
+                pop
+                dup {list_pointer_depth_after_loop}
+                call {get_length}
+                dup 1
+                lt
+                // _ *list [target] index (index < len)
+
+                push 0
+                swap 1
+                skiz
+                    call {entrypoint}_compare
+                // _ *list [target] index found
+
+                swap {found_flag_swap_depth}
+                pop
+                swap {element_size}
+                {pop_target}
+                // _ found index
+
+                return
+
+            // Find the first index whose element is not smaller than the target.
+            // INVARIANT: _ *list [target] lo hi
+            {entrypoint}_loop:
+                dup 0
+                dup 2
+                lt
+                push 0
+                eq
+                skiz
+                    return
+                // _ *list [target] lo hi, where lo < hi
+
+                dup 0
+                dup 2
+                push -1
+                mul
+                add
+                push 2
+                swap 1
+                div
+                pop
+                dup 2
+                add
+                // _ *list [target] lo hi mid, where mid = lo + (hi - lo) / 2
+
+                {dup_target_below_lo_hi_mid}
+                dup {list_pointer_depth_in_loop}
+                dup {mid_depth_in_loop}
+                call {get_element}
+                {lt}
+                // _ *list [target] lo hi mid (element_mid < target)
+
+                dup 2
+                dup 2
+                push -1
+                mul
+                add
+                dup 1
+                mul
+                dup 2
+                add
+                // _ *list [target] lo hi mid (element_mid < target) hi', where hi' = mid + flag·(hi - mid)
+
+                dup 2
+                push 1
+                add
+                dup 5
+                push -1
+                mul
+                add
+                dup 2
+                mul
+                dup 5
+                add
+                // _ *list [target] lo hi mid (element_mid < target) hi' lo', where lo' = lo + flag·(mid + 1 - lo)
+
+                swap 5
+                pop
+                swap 3
+                pop
+                pop
+                pop
+                // _ *list [target] lo' hi'
+
+                recurse
+
+            // BEFORE: _ *list [target] index 0
+            // AFTER: _ *list [target] index (element_index == target)
+            {entrypoint}_compare:
+                pop
+                {dup_target_below_index}
+                dup {list_pointer_depth_in_comparison}
+                dup {index_depth_in_comparison}
+                call {get_element}
+                {eq}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0, false),
+            self.prepare_state(1, true),
+            self.prepare_state(1, false),
+            self.prepare_state(rng.gen_range(2..20), true),
+            self.prepare_state(rng.gen_range(2..20), false),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 5, true)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 10, true)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.0.get_size();
+        let target = (0..element_size)
+            .map(|_| stack.pop().unwrap())
+            .collect_vec();
+        let target_value = self.element_value(&target);
+        let list_pointer = stack.pop().unwrap();
+
+        let values = (0..safe_list_get_length(list_pointer, memory))
+            .map(|i| self.element_value(&safe_list_get(list_pointer, i, memory, element_size)))
+            .collect_vec();
+        let index = values.partition_point(|&value| value < target_value);
+        let found = values.get(index) == Some(&target_value);
+
+        stack.push(BFieldElement::new(found as u64));
+        stack.push(BFieldElement::new(index as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::push_encodable;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_binary_search_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeBinarySearch(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeBinarySearch(DataType::U64), true);
+    }
+
+    fn prop_binary_search<T: BFieldCodec + Ord>(
+        data_type: DataType,
+        sorted_values: Vec<T>,
+        target: T,
+        expected_found: bool,
+        expected_index: usize,
+    ) {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            list_pointer,
+            sorted_values.len() as u32,
+            sorted_values,
+            &mut memory,
+        );
+        let mut init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        push_encodable(&mut init_stack, &target);
+
+        let mut expected_stack = get_init_tvm_stack();
+        expected_stack.push(BFieldElement::new(expected_found as u64));
+        expected_stack.push(BFieldElement::new(expected_index as u64));
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeBinarySearch(data_type),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn find_first_last_and_middle_element() {
+        let values = vec![2u32, 3, 5, 7, 11, 13, u32::MAX];
+        for (index, value) in values.iter().enumerate() {
+            assert_eq!(Ok(index), values.binary_search(value));
+            prop_binary_search(DataType::U32, values.clone(), *value, true, index);
+        }
+
+        let values = vec![0u64, 1 << 32, (1 << 32) + 1, 5 << 40, u64::MAX];
+        for (index, value) in values.iter().enumerate() {
+            prop_binary_search(DataType::U64, values.clone(), *value, true, index);
+        }
+    }
+
+    #[test]
+    fn missing_element_gives_insertion_index() {
+        let values = vec![2u32, 3, 5, 7, 11, 13];
+        for target in [0u32, 1, 4, 6, 12, 14, u32::MAX] {
+            let insertion_index = values.binary_search(&target).unwrap_err();
+            prop_binary_search(
+                DataType::U32,
+                values.clone(),
+                target,
+                false,
+                insertion_index,
+            );
+        }
+
+        let values = vec![1u64 << 32, 3 << 32, u64::MAX - 1];
+        for (target, insertion_index) in
+            [(0u64, 0), (2 << 32, 1), ((3 << 32) + 1, 2), (u64::MAX, 3)]
+        {
+            prop_binary_search(
+                DataType::U64,
+                values.clone(),
+                target,
+                false,
+                insertion_index,
+            );
+        }
+    }
+
+    #[test]
+    fn duplicates_give_first_occurrence() {
+        prop_binary_search(DataType::U32, vec![1u32, 4, 4, 4, 4, 9], 4, true, 1);
+        prop_binary_search(DataType::U64, vec![7u64, 7, 7], 7, true, 0);
+    }
+
+    #[test]
+    fn search_empty_list() {
+        prop_binary_search(DataType::U32, Vec::<u32>::new(), 42, false, 0);
+        prop_binary_search(DataType::U64, Vec::<u64>::new(), 42, false, 0);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_binary_search_benchmark() {
+        bench_and_write(SafeBinarySearch(DataType::U64));
+    }
+}
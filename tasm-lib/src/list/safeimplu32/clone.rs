@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use num::One;
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::new::SafeNew;
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get_length, safe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Deep-copy a safe list into a newly allocated safe list with the same capacity. The
+/// two lists are independent afterwards.
+#[derive(Clone, Debug)]
+pub struct SafeListClone(pub DataType);
+
+impl SafeListClone {
+    fn prepare_state(&self, capacity: u32, list_length: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(&self.0, list_pointer, capacity, list_length, &mut memory);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeListClone {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_clone___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*src".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*dest".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let new_list = library.import(Box::new(SafeNew(self.0.clone())));
+
+        // Code to multiply with size. If size is 1, do nothing to save two clock cycles.
+        let mul_with_size = if element_size != 1 {
+            format!("push {element_size}\n mul\n")
+        } else {
+            String::default()
+        };
+
+        format!(
+            "
+            // BEFORE: _ *src
+            // AFTER: _ *dest
+            {entrypoint}:
+                dup 0
+                push 1
+                add
+                read_mem
+                swap 1
+                pop
+                // _ *src capacity
+
+                call {new_list}
+                // _ *src *dest
+
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *src *dest length
+
+                dup 1
+                dup 1
+                write_mem
+                pop
+                // _ *src *dest length
+
+                {mul_with_size}
+                push 0
+                // _ *src *dest num_words 0
+
+                call {entrypoint}_loop
+                // _ *src *dest num_words num_words
+
+                pop
+                pop
+                swap 1
+                pop
+                // _ *dest
+
+                return
+
+            // INVARIANT: _ *src *dest num_words i
+            {entrypoint}_loop:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 3
+                dup 1
+                add
+                push 2
+                add
+                read_mem
+                swap 1
+                pop
+                // _ *src *dest num_words i word
+
+                dup 3
+                dup 2
+                add
+                push 2
+                add
+                swap 1
+                write_mem
+                pop
+                // _ *src *dest num_words i
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0, 0),
+            self.prepare_state(10, 0),
+            self.prepare_state(10, 1),
+            self.prepare_state(20, rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(20, 10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let src_pointer = stack.pop().unwrap();
+        let capacity = memory[&(src_pointer + BFieldElement::one())];
+        let length = safe_list_get_length(src_pointer, memory);
+
+        stack.push(capacity);
+        SafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let dest_pointer = *stack.last().unwrap();
+
+        safe_list_set_length(dest_pointer, length, memory);
+        for i in 0..length * self.0.get_size() {
+            let offset = BFieldElement::new(2 + i as u64);
+            let word = memory[&(src_pointer + offset)];
+            memory.insert(dest_pointer + offset, word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::list::safeimplu32::{push::SafePush, set::SafeSet};
+    use crate::rust_shadowing_helper_functions::safe_list::{safe_list_get, safe_list_insert};
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_list_clone_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeListClone(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeListClone(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeListClone(DataType::XFE), true);
+        test_rust_equivalence_multiple_deprecated(&SafeListClone(DataType::Digest), true);
+    }
+
+    #[test]
+    fn mutating_clone_leaves_original_unchanged() {
+        let values = vec![1u32, 2, 3, 4];
+        let src_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        safe_list_insert(src_pointer, 10, values.clone(), &mut memory);
+        let stack = [get_init_tvm_stack(), vec![src_pointer]].concat();
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SafeListClone(DataType::U32),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+        let dest_pointer = *vm_output_state.final_stack.last().unwrap();
+        let mut memory = vm_output_state.final_ram;
+        let read_list = |pointer, memory: &HashMap<BFieldElement, BFieldElement>| {
+            (0..safe_list_get_length(pointer, memory))
+                .map(|i| safe_list_get(pointer, i, memory, 1))
+                .collect_vec()
+        };
+        let encoded_values = values.iter().map(|value| value.encode()).collect_vec();
+        assert_eq!(encoded_values, read_list(dest_pointer, &memory));
+        assert_eq!(
+            memory[&(src_pointer + BFieldElement::one())],
+            memory[&(dest_pointer + BFieldElement::one())],
+            "clone must have the same capacity as the original"
+        );
+
+        // overwrite the first element of the clone and push to it
+        let mut stack = [
+            get_init_tvm_stack(),
+            vec![BFieldElement::new(42), dest_pointer, BFieldElement::new(0)],
+        ]
+        .concat();
+        SafeSet(DataType::U32)
+            .link_and_run_tasm_for_test(&mut stack, vec![], vec![], &mut memory, None)
+            .unwrap();
+        let mut stack = [
+            get_init_tvm_stack(),
+            vec![dest_pointer, BFieldElement::new(43)],
+        ]
+        .concat();
+        SafePush(DataType::U32)
+            .link_and_run_tasm_for_test(&mut stack, vec![], vec![], &mut memory, None)
+            .unwrap();
+
+        assert_eq!(encoded_values, read_list(src_pointer, &memory));
+        assert_eq!(
+            [vec![42u32], values[1..].to_vec(), vec![43]]
+                .concat()
+                .iter()
+                .map(|value| value.encode())
+                .collect_vec(),
+            read_list(dest_pointer, &memory)
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_list_clone_benchmark() {
+        bench_and_write(SafeListClone(DataType::Digest));
+    }
+}
@@ -0,0 +1,357 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::{One, Zero};
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_new, safe_list_push,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Compare two safe lists for element-wise equality. Lengths are compared first, and
+/// only if they match are the element regions compared, word by word. Comparison
+/// stops at the first mismatching word.
+#[derive(Clone, Debug)]
+pub struct SafeListEquals(pub DataType);
+
+impl SafeListEquals {
+    /// Return an execution state with two lists in memory. The list `b` is a copy of
+    /// the first `length_b` elements of list `a`, padded with random elements if
+    /// `length_b` exceeds `length_a`. If `differ_in_last_element` is set, the last
+    /// word of the last element of `b` is changed.
+    fn prepare_state(
+        &self,
+        length_a: usize,
+        length_b: usize,
+        differ_in_last_element: bool,
+    ) -> ExecutionState {
+        let element_size = self.0.get_size();
+        let capacity = std::cmp::max(length_a, length_b) + 2;
+        let mut memory = HashMap::default();
+
+        let elements_a = self.0.random_elements(length_a);
+        let mut elements_b = elements_a.iter().take(length_b).cloned().collect_vec();
+        let num_padding_elements = length_b - elements_b.len();
+        elements_b.append(&mut self.0.random_elements(num_padding_elements));
+        if differ_in_last_element {
+            let last_word = &mut elements_b.last_mut().unwrap()[element_size - 1];
+            *last_word = if last_word.is_zero() {
+                BFieldElement::one()
+            } else {
+                BFieldElement::zero()
+            };
+        }
+
+        let list_a = BFieldElement::one();
+        let list_b = list_a + BFieldElement::new((2 + capacity * element_size) as u64);
+        for (list_pointer, elements) in [(list_a, elements_a), (list_b, elements_b)] {
+            safe_list_new(list_pointer, capacity as u32, &mut memory);
+            for element in elements {
+                safe_list_push(list_pointer, element, &mut memory, element_size);
+            }
+        }
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_a);
+        stack.push(list_b);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeListEquals {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_equals___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list_a".to_string(), "*list_b".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(self.0.clone())),
+            DataType::List(Box::new(self.0.clone())),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["lists_are_equal".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+
+        // Code to multiply with size. If size is 1, do nothing to save two clock cycles.
+        let mul_with_size = if element_size != 1 {
+            format!("push {element_size}\n mul\n")
+        } else {
+            String::default()
+        };
+
+        format!(
+            "
+            // BEFORE: _ *list_a *list_b
+            // AFTER: _ lists_are_equal
+            {entrypoint}:
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *list_a *list_b len_a
+
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *list_a *list_b len_a len_b
+
+                dup 1
+                eq
+                // _ *list_a *list_b len_a (len_a == len_b)
+
+                dup 0
+                skiz
+                    call {entrypoint}_compare_elements
+                // _ *list_a *list_b len_a lists_are_equal
+
+                swap 3
+                pop
+                pop
+                pop
+                // _ lists_are_equal
+
+                return
+
+            // BEFORE: _ *list_a *list_b len 1
+            // AFTER: _ *list_a *list_b len elements_are_equal
+            {entrypoint}_compare_elements:
+                pop
+                dup 0
+                {mul_with_size}
+                // _ *list_a *list_b len num_words
+
+                dup 3
+                push 2
+                add
+                // _ *list_a *list_b len num_words *a_elem
+
+                dup 3
+                push 2
+                add
+                // _ *list_a *list_b len num_words *a_elem *b_elem
+
+                swap 2
+                // _ *list_a *list_b len *b_elem *a_elem num_words
+
+                call {entrypoint}_loop
+                // _ *list_a *list_b len *b_elem' *a_elem' remaining_words
+
+                swap 2
+                pop
+                pop
+                // _ *list_a *list_b len remaining_words
+
+                push 0
+                eq
+                // _ *list_a *list_b len (remaining_words == 0)
+
+                return
+
+            // Returns early with a nonzero number of remaining words on the first mismatch.
+            // INVARIANT: _ *b_elem *a_elem remaining_words
+            {entrypoint}_loop:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+                // _ *b_elem *a_elem remaining_words
+
+                dup 2
+                read_mem
+                swap 1
+                pop
+                // _ *b_elem *a_elem remaining_words b_word
+
+                dup 2
+                read_mem
+                swap 1
+                pop
+                // _ *b_elem *a_elem remaining_words b_word a_word
+
+                eq
+                push 0
+                eq
+                skiz
+                    return
+                // _ *b_elem *a_elem remaining_words
+
+                push -1
+                add
+                swap 1
+                push 1
+                add
+                swap 1
+                swap 2
+                push 1
+                add
+                swap 2
+                // _ (*b_elem + 1) (*a_elem + 1) (remaining_words - 1)
+
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let length = rng.gen_range(1..20);
+        vec![
+            self.prepare_state(0, 0, false),
+            self.prepare_state(length, length, false),
+            self.prepare_state(length, length, true),
+            self.prepare_state(length, length + 1, false),
+            self.prepare_state(length + 1, length, false),
+            self.prepare_state(0, length, false),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 5, 1 << 5, false)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 6, 1 << 6, false)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let list_b = stack.pop().unwrap();
+        let list_a = stack.pop().unwrap();
+        let element_size = self.0.get_size();
+
+        let length_a = safe_list_get_length(list_a, memory);
+        let length_b = safe_list_get_length(list_b, memory);
+        let lists_are_equal = length_a == length_b
+            && (0..length_a).all(|i| {
+                safe_list_get(list_a, i, memory, element_size)
+                    == safe_list_get(list_b, i, memory, element_size)
+            });
+
+        stack.push(BFieldElement::new(lists_are_equal as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_list_equals_test() {
+        for data_type in [
+            DataType::Bool,
+            DataType::U32,
+            DataType::U64,
+            DataType::BFE,
+            DataType::XFE,
+            DataType::Digest,
+        ] {
+            test_rust_equivalence_multiple_deprecated(&SafeListEquals(data_type), true);
+        }
+    }
+
+    #[test]
+    fn equal_lists_are_equal() {
+        for length in [0, 1, 7] {
+            prop_equals(DataType::Digest, length, length, false, true);
+        }
+    }
+
+    #[test]
+    fn differing_last_element_is_detected() {
+        for data_type in [DataType::Bool, DataType::U64, DataType::Digest] {
+            prop_equals(data_type.clone(), 1, 1, true, false);
+            prop_equals(data_type, 9, 9, true, false);
+        }
+    }
+
+    #[test]
+    fn differing_lengths_are_detected() {
+        prop_equals(DataType::U64, 4, 5, false, false);
+        prop_equals(DataType::U64, 5, 4, false, false);
+        prop_equals(DataType::U64, 0, 4, false, false);
+    }
+
+    #[test]
+    fn differing_lengths_does_not_read_elements() {
+        // Remove all element words of the longer list from memory. The rust shadowing
+        // would panic if it tried to read any of them.
+        let snippet = SafeListEquals(DataType::XFE);
+        let mut execution_state = snippet.prepare_state(3, 4, false);
+        let list_b = execution_state.stack[execution_state.stack.len() - 1];
+        execution_state
+            .memory
+            .retain(|k, _| k.value() < list_b.value() + 2);
+        let vm_output_state =
+            test_rust_equivalence_given_execution_state_deprecated(&snippet, execution_state);
+        assert!(vm_output_state.final_stack.last().unwrap().is_zero());
+    }
+
+    fn prop_equals(
+        data_type: DataType,
+        length_a: usize,
+        length_b: usize,
+        differ_in_last_element: bool,
+        expected: bool,
+    ) {
+        let snippet = SafeListEquals(data_type);
+        let execution_state = snippet.prepare_state(length_a, length_b, differ_in_last_element);
+        let vm_output_state =
+            test_rust_equivalence_given_execution_state_deprecated(&snippet, execution_state);
+        assert_eq!(
+            BFieldElement::new(expected as u64),
+            *vm_output_state.final_stack.last().unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_list_equals_benchmark() {
+        bench_and_write(SafeListEquals(DataType::Digest));
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashMap;
+
+use num::Zero;
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{
+    get::SafeGet, length::Length as SafeLength, new::SafeNew, push::SafePush,
+};
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length, safe_list_push,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Copy those elements of a safe list for which the inner predicate returns true into a
+/// new safe list, preserving their order. The new list's capacity is the length of the
+/// source list. The inner snippet must map one element to a `Bool`.
+pub struct SafeFilter<S: DeprecatedSnippet + Clone + 'static>(pub S);
+
+impl<S: DeprecatedSnippet + Clone + 'static> SafeFilter<S> {
+    fn element_type(&self) -> DataType {
+        let input_types = self.0.input_types();
+        assert_eq!(1, input_types.len(), "predicate must take one element");
+        assert_eq!(
+            vec![DataType::Bool],
+            self.0.output_types(),
+            "predicate must return a bool"
+        );
+
+        input_types[0].clone()
+    }
+
+    fn prepare_state(&self, list_length: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(
+            &self.element_type(),
+            list_pointer,
+            list_length as u32 + 10,
+            list_length,
+            &mut memory,
+        );
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl<S: DeprecatedSnippet + Clone + 'static> DeprecatedSnippet for SafeFilter<S> {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_filter___{}",
+            self.0.entrypoint_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*src".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.element_type()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*dest".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.element_type()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_type = self.element_type();
+
+        let get_length = library.import(Box::new(SafeLength(element_type.clone())));
+        let new_list = library.import(Box::new(SafeNew(element_type.clone())));
+        let get_element = library.import(Box::new(SafeGet(element_type.clone())));
+        let push_element = library.import(Box::new(SafePush(element_type)));
+        let predicate = library.import(Box::new(self.0.clone()));
+
+        format!(
+            "
+            // BEFORE: _ *src
+            // AFTER: _ *dest
+            {entrypoint}:
+                dup 0
+                call {get_length}
+                // _ *src length
+
+                dup 0
+                call {new_list}
+                // _ *src length *dest
+
+                swap 1
+                push 0
+                // _ *src *dest length 0
+
+                call {entrypoint}_loop
+                // _ *src *dest length length
+
+                pop
+                pop
+                swap 1
+                pop
+                // _ *dest
+
+                return
+
+            // INVARIANT: _ *src *dest length index
+            {entrypoint}_loop:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 3
+                dup 1
+                call {get_element}
+                // _ *src *dest length index [element]
+
+                call {predicate}
+                // _ *src *dest length index keep
+
+                skiz
+                    call {entrypoint}_keep
+                // _ *src *dest length index
+
+                push 1
+                add
+                recurse
+
+            // BEFORE: _ *src *dest length index
+            // AFTER: _ *src *dest length index
+            {entrypoint}_keep:
+                dup 2
+                dup 4
+                dup 2
+                call {get_element}
+                // _ *src *dest length index *dest [element]
+
+                call {push_element}
+                // _ *src *dest length index
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        self.0.crash_conditions()
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0),
+            self.prepare_state(1),
+            self.prepare_state(rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_type = self.element_type();
+        let element_size = element_type.get_size();
+        let src_pointer = stack.pop().unwrap();
+        let length = safe_list_get_length(src_pointer, memory);
+
+        stack.push(BFieldElement::new(length as u64));
+        SafeNew(element_type).rust_shadowing(stack, vec![], vec![], memory);
+        let dest_pointer = *stack.last().unwrap();
+
+        for i in 0..length {
+            // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+            let element = safe_list_get(src_pointer, i, memory, element_size);
+            stack.extend(element.iter().rev());
+
+            self.0
+                .rust_shadowing(stack, std_in.clone(), secret_in.clone(), memory);
+
+            let keep = stack.pop().unwrap();
+            if !keep.is_zero() {
+                safe_list_push(dest_pointer, element, memory, element_size);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::arithmetic::u32::isodd::Isodd;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_filter_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeFilter(Isodd), false);
+    }
+
+    fn prop_filter_odd(values: Vec<u32>) {
+        let src_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            src_pointer,
+            values.len() as u32,
+            values.clone(),
+            &mut memory,
+        );
+        let stack = [get_init_tvm_stack(), vec![src_pointer]].concat();
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SafeFilter(Isodd),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+
+        let mut expected = values.clone();
+        expected.retain(|value| value % 2 == 1);
+
+        let dest_pointer = *vm_output_state.final_stack.last().unwrap();
+        let memory = vm_output_state.final_ram;
+        let filtered = (0..safe_list_get_length(dest_pointer, &memory))
+            .map(|i| safe_list_get(dest_pointer, i, &memory, 1))
+            .collect_vec();
+        assert_eq!(
+            expected.iter().map(|value| value.encode()).collect_vec(),
+            filtered
+        );
+
+        // capacity equals the length of the source list
+        assert_eq!(
+            BFieldElement::new(values.len() as u64),
+            memory[&(dest_pointer + BFieldElement::new(1))]
+        );
+    }
+
+    #[test]
+    fn filter_odd_u32s() {
+        prop_filter_odd(vec![1, 2, 3, 4, 5, 6, u32::MAX]);
+    }
+
+    #[test]
+    fn filter_odd_u32s_none_pass() {
+        prop_filter_odd(vec![0, 2, 14, 1 << 31]);
+    }
+
+    #[test]
+    fn filter_odd_u32s_all_pass() {
+        prop_filter_odd(vec![1, 3, 15, u32::MAX]);
+    }
+
+    #[test]
+    fn filter_odd_u32s_empty_list() {
+        prop_filter_odd(vec![]);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::arithmetic::u32::isodd::Isodd;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_filter_benchmark() {
+        bench_and_write(SafeFilter(Isodd));
+    }
+}
@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::Zero;
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, length::Length as SafeLength};
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_new, safe_list_push,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Reduce a safe list from left to right with the inner snippet, starting from the
+/// accumulator on top of the stack. The inner snippet combines an accumulator and an
+/// element into a new accumulator: `_ [acc] [element]` → `_ [acc]`.
+pub struct SafeFold<S: DeprecatedSnippet + Clone + 'static>(pub S);
+
+impl<S: DeprecatedSnippet + Clone + 'static> SafeFold<S> {
+    fn accumulator_type(&self) -> DataType {
+        let output_types = self.0.output_types();
+        assert_eq!(
+            1,
+            output_types.len(),
+            "combiner must return one accumulator"
+        );
+        assert_eq!(
+            output_types[0],
+            self.0.input_types()[0],
+            "combiner must take the accumulator first"
+        );
+
+        output_types[0].clone()
+    }
+
+    fn element_type(&self) -> DataType {
+        let input_types = self.0.input_types();
+        assert_eq!(2, input_types.len(), "combiner must take two arguments");
+
+        input_types[1].clone()
+    }
+
+    /// The list holds random elements with small words, such that typical combiners like
+    /// additions do not overflow. The initial accumulator is all zeros.
+    fn prepare_state(&self, list_length: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let element_size = self.element_type().get_size();
+
+        let mut memory = HashMap::default();
+        safe_list_new(list_pointer, list_length as u32, &mut memory);
+        for element in self.element_type().random_elements(list_length) {
+            let small_element = element
+                .iter()
+                .map(|word| BFieldElement::new(word.value() % (1 << 16)))
+                .collect_vec();
+            safe_list_push(list_pointer, small_element, &mut memory, element_size);
+        }
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.extend(vec![
+            BFieldElement::zero();
+            self.accumulator_type().get_size()
+        ]);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl<S: DeprecatedSnippet + Clone + 'static> DeprecatedSnippet for SafeFold<S> {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_list_safeimplu32_fold___{}", self.0.entrypoint_name())
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        let accumulator_size = self.accumulator_type().get_size();
+        let mut ret = vec!["*list".to_string()];
+        for i in 0..accumulator_size {
+            ret.push(format!("init_acc_{}", accumulator_size - 1 - i));
+        }
+
+        ret
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(self.element_type())),
+            self.accumulator_type(),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        let accumulator_size = self.accumulator_type().get_size();
+        (0..accumulator_size)
+            .map(|i| format!("acc_{}", accumulator_size - 1 - i))
+            .collect()
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![self.accumulator_type()]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_type = self.element_type();
+        let accumulator_size = self.accumulator_type().get_size();
+
+        let get_length = library.import(Box::new(SafeLength(element_type.clone())));
+        let get_element = library.import(Box::new(SafeGet(element_type)));
+        let combiner = library.import(Box::new(self.0.clone()));
+
+        // Move the top word below the accumulator, or the word right below the
+        // accumulator to the top.
+        let bury_top_word = (1..=accumulator_size)
+            .rev()
+            .map(|i| format!("swap {i}\n"))
+            .join("");
+        let dig_up_word = (1..=accumulator_size)
+            .map(|i| format!("swap {i}\n"))
+            .join("");
+
+        let list_pointer_depth = accumulator_size + 2;
+        let length_depth = accumulator_size + 1;
+
+        format!(
+            "
+            // BEFORE: _ *list [init_acc]
+            // AFTER: _ [acc]
+            {entrypoint}:
+                dup {accumulator_size}
+                call {get_length}
+                {bury_top_word}
+                push 0
+                {bury_top_word}
+                // _ *list length 0 [init_acc]
+
+                call {entrypoint}_loop
+                // _ *list length length [acc]
+
+                {dig_up_word}
+                pop
+                {dig_up_word}
+                pop
+                {dig_up_word}
+                pop
+                // _ [acc]
+
+                return
+
+            // INVARIANT: _ *list length index [acc]
+            {entrypoint}_loop:
+                dup {length_depth}
+                dup {length_depth}
+                eq
+                skiz
+                    return
+
+                dup {list_pointer_depth}
+                dup {length_depth}
+                call {get_element}
+                // _ *list length index [acc] [element]
+
+                call {combiner}
+                // _ *list length index [acc']
+
+                swap {accumulator_size}
+                push 1
+                add
+                swap {accumulator_size}
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        self.0.crash_conditions()
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0),
+            self.prepare_state(1),
+            self.prepare_state(rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.element_type().get_size();
+        let accumulator_size = self.accumulator_type().get_size();
+
+        let accumulator = stack.split_off(stack.len() - accumulator_size);
+        let list_pointer = stack.pop().unwrap();
+        stack.extend(accumulator);
+
+        for i in 0..safe_list_get_length(list_pointer, memory) {
+            // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+            let element = safe_list_get(list_pointer, i, memory, element_size);
+            stack.extend(element.into_iter().rev());
+
+            self.0
+                .rust_shadowing(stack, std_in.clone(), secret_in.clone(), memory);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arithmetic::u64::add_u64::AddU64;
+    use crate::push_encodable;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_fold_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeFold(AddU64), false);
+    }
+
+    fn prop_sum_u64s(values: Vec<u64>, init_acc: u64) {
+        let list_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            list_pointer,
+            values.len() as u32,
+            values.clone(),
+            &mut memory,
+        );
+
+        let mut init_stack = get_init_tvm_stack();
+        init_stack.push(list_pointer);
+        push_encodable(&mut init_stack, &init_acc);
+
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(
+            &mut expected_stack,
+            &(init_acc + values.iter().sum::<u64>()),
+        );
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeFold(AddU64),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn sum_u64_list() {
+        prop_sum_u64s(vec![1, 2, 3, 1 << 40, (1 << 32) - 1], 0);
+        prop_sum_u64s(vec![14, u32::MAX as u64], 1 << 33);
+    }
+
+    #[test]
+    fn fold_empty_list_returns_initial_accumulator() {
+        prop_sum_u64s(vec![], 0);
+        prop_sum_u64s(vec![], 0x1234_5678_9abc);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::arithmetic::u64::add_u64::AddU64;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_fold_benchmark() {
+        bench_and_write(SafeFold(AddU64));
+    }
+}
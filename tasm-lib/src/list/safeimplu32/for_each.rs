@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement, NonDeterminism};
+use twenty_first::util_types::algebraic_hasher::Domain;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, length::Length as SafeLength};
+use crate::procedure::Procedure;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_list_get, safe_list_get_length, safe_list_new, safe_list_push,
+};
+use crate::snippet::{BasicSnippet, DataType};
+use crate::snippet_bencher::BenchmarkCase;
+use crate::{get_init_tvm_stack, VmHasherState};
+
+/// Run the inner snippet on every element of a safe list, from first to last, for its
+/// side effects only, e.g., writing each element to standard out. The inner snippet
+/// must consume one element of the list's element type and leave nothing:
+/// `_ [element]` → `_`. It is a [`Procedure`] rather than a `DeprecatedSnippet`
+/// because only procedures can report what their shadows write to standard out.
+pub struct SafeForEach<P: Procedure + Clone + 'static>(pub DataType, pub P);
+
+impl<P: Procedure + Clone + 'static> SafeForEach<P> {
+    fn assert_inner_signature(&self) {
+        let input_types = self
+            .1
+            .inputs()
+            .into_iter()
+            .map(|(data_type, _)| data_type)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![self.0.clone()],
+            input_types,
+            "inner snippet must take one element of the list's element type"
+        );
+        assert!(
+            self.1.outputs().is_empty(),
+            "inner snippet must not leave anything on the stack"
+        );
+    }
+}
+
+impl<P: Procedure + Clone + 'static> BasicSnippet for SafeForEach<P> {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        vec![(
+            DataType::List(Box::new(self.0.clone())),
+            "*list".to_string(),
+        )]
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        vec![]
+    }
+
+    fn entrypoint(&self) -> String {
+        format!("tasm_list_safeimplu32_for_each___{}", self.1.entrypoint())
+    }
+
+    fn code(&self, library: &mut Library) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        self.assert_inner_signature();
+
+        let entrypoint = self.entrypoint();
+        let main_loop = format!("{entrypoint}_loop");
+        let get_length = library.import(Box::new(SafeLength(self.0.clone())));
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+        let inner = library.import(Box::new(self.1.clone()));
+
+        triton_asm!(
+            // BEFORE: _ *list
+            // AFTER: _
+            {entrypoint}:
+                dup 0
+                call {get_length}
+                push 0
+                // _ *list length 0
+
+                call {main_loop}
+                // _ *list length length
+
+                pop
+                pop
+                pop
+                return
+
+            // INVARIANT: _ *list length index
+            {main_loop}:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 2
+                dup 1
+                call {get_element}
+                // _ *list length index [element]
+
+                call {inner}
+                // _ *list length index
+
+                push 1
+                add
+                recurse
+        )
+    }
+}
+
+impl<P: Procedure + Clone + 'static> Procedure for SafeForEach<P> {
+    fn rust_shadow(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+        nondeterminism: &NonDeterminism<BFieldElement>,
+        public_input: &[BFieldElement],
+        sponge_state: &mut VmHasherState,
+    ) -> Vec<BFieldElement> {
+        let element_size = self.0.get_size();
+        let list_pointer = stack.pop().unwrap();
+
+        let mut output = vec![];
+        for i in 0..safe_list_get_length(list_pointer, memory) {
+            // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+            let element = safe_list_get(list_pointer, i, memory, element_size);
+            stack.extend(element.into_iter().rev());
+
+            output.extend(self.1.rust_shadow(
+                stack,
+                memory,
+                nondeterminism,
+                public_input,
+                sponge_state,
+            ));
+        }
+
+        output
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> (
+        Vec<BFieldElement>,
+        HashMap<BFieldElement, BFieldElement>,
+        NonDeterminism<BFieldElement>,
+        Vec<BFieldElement>,
+        VmHasherState,
+    ) {
+        let mut rng: StdRng = SeedableRng::from_seed(seed);
+        let list_length = match bench_case {
+            Some(BenchmarkCase::CommonCase) => 10,
+            Some(BenchmarkCase::WorstCase) => 100,
+            Some(BenchmarkCase::Custom(_)) | None => rng.gen_range(0..20),
+        };
+
+        let list_pointer = BFieldElement::new(rng.gen_range(1..(1u64 << 32)));
+        let mut memory = HashMap::default();
+        safe_list_new(list_pointer, list_length as u32, &mut memory);
+        for element in self.0.seeded_random_elements(list_length, &mut rng) {
+            safe_list_push(list_pointer, element, &mut memory, self.0.get_size());
+        }
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        (
+            stack,
+            memory,
+            NonDeterminism::new(vec![]),
+            vec![],
+            VmHasherState::new(Domain::VariableLength),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::write_to_stdout::WriteToStdout;
+    use crate::procedure::ShadowedProcedure;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+
+    use super::*;
+
+    #[test]
+    fn safe_for_each_test() {
+        for data_type in [DataType::U32, DataType::U64, DataType::Digest] {
+            ShadowedProcedure::new(SafeForEach(data_type.clone(), WriteToStdout(data_type))).test();
+        }
+    }
+
+    fn prop_write_u32s_to_stdout(values: Vec<u32>) {
+        let list_pointer = BFieldElement::new(1 << 20);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            list_pointer,
+            values.len() as u32,
+            values.clone(),
+            &mut memory,
+        );
+
+        let init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        let vm_output_state = test_rust_equivalence_given_complete_state(
+            &ShadowedProcedure::new(SafeForEach(DataType::U32, WriteToStdout(DataType::U32))),
+            &init_stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &memory,
+            &VmHasherState::new(Domain::VariableLength),
+            0,
+            Some(&get_init_tvm_stack()),
+        );
+
+        let expected_output = values
+            .into_iter()
+            .map(|value| BFieldElement::new(value as u64))
+            .collect::<Vec<_>>();
+        assert_eq!(expected_output, vm_output_state.output);
+    }
+
+    #[test]
+    fn writes_all_elements_in_order() {
+        prop_write_u32s_to_stdout(vec![7, 3, 1 << 31, 0, u32::MAX]);
+        prop_write_u32s_to_stdout(vec![42]);
+    }
+
+    #[test]
+    fn empty_list_writes_nothing() {
+        prop_write_u32s_to_stdout(vec![]);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::io::write_to_stdout::WriteToStdout;
+    use crate::procedure::ShadowedProcedure;
+    use crate::snippet::RustShadow;
+
+    #[test]
+    fn safe_for_each_benchmark() {
+        ShadowedProcedure::new(SafeForEach(DataType::U32, WriteToStdout(DataType::U32))).bench();
+    }
+}
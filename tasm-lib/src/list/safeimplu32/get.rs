@@ -218,7 +218,9 @@ mod tests {
     fn new_snippet_test() {
         for _ in 0..10 {
             test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::Bool), true);
+            test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::U16), true);
             test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::U32), true);
+            test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::I32), true);
             test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::U64), true);
             test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::BFE), true);
             test_rust_equivalence_multiple_deprecated(&SafeGet(DataType::XFE), true);
@@ -0,0 +1,328 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::One;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, set::SafeSet};
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length, safe_list_set,
+    safe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Insert an element at an arbitrary position of a safe list, shifting all later
+/// elements up by one. Like `Vec::insert`.
+#[derive(Clone, Debug)]
+pub struct SafeInsert(pub DataType);
+
+impl SafeInsert {
+    fn prepare_state(&self, capacity: u32, list_length: usize, index: usize) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.push(BFieldElement::new(index as u64));
+        stack.extend(self.0.random_element().into_iter().rev());
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(&self.0, list_pointer, capacity, list_length, &mut memory);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeInsert {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_insert___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        let element_size = self.0.get_size();
+
+        // _ *list index elem{{N - 1}}, elem{{N - 2}}, ..., elem{{0}}
+        let mut ret = vec!["*list".to_string(), "index".to_string()];
+        for i in 0..element_size {
+            ret.push(format!("element_{}", element_size - 1 - i));
+        }
+
+        ret
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(self.0.clone())),
+            DataType::U32,
+            self.0.clone(),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -(self.0.get_size() as isize) - 2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+        let set_element = library.import(Box::new(SafeSet(self.0.clone())));
+
+        let index_depth = element_size + 2;
+        let list_pointer_depth_in_loop = element_size + 2;
+        let list_pointer_depth_below_element_copy = 2 * element_size + 2;
+        let list_pointer_depth_above_element = element_size + 1;
+
+        format!(
+            "
+            // BEFORE: _ *list index elem{{N - 1}}, elem{{N - 2}}, ..., elem{{0}}
+            // AFTER: _
+            {entrypoint}:
+                dup {list_pointer_depth_above_element}
+                read_mem
+                // _ *list index [element] *list length
+
+                // Verify that index <= length
+                dup {index_depth}
+                dup 1
+                lt
+                push 0
+                eq
+                assert
+                // _ *list index [element] *list length
+
+                // Verify that length < capacity
+                swap 1
+                push 1
+                add
+                read_mem
+                // _ *list index [element] length (*list + 1) capacity
+
+                dup 2
+                lt
+                assert
+                // _ *list index [element] length (*list + 1)
+
+                push -1
+                add
+                dup 1
+                push 1
+                add
+                write_mem
+                pop
+                // _ *list index [element] length
+
+                call {entrypoint}_shift
+                // _ *list index [element] index
+
+                pop
+                dup {list_pointer_depth_above_element}
+                dup {list_pointer_depth_above_element}
+                call {set_element}
+                // _ *list index
+
+                pop
+                pop
+                return
+
+            // INVARIANT: _ *list index [element] i
+            {entrypoint}_shift:
+                dup 0
+                dup {index_depth}
+                eq
+                skiz
+                    return
+
+                dup {list_pointer_depth_in_loop}
+                dup 1
+                push -1
+                add
+                call {get_element}
+                // _ *list index [element] i [list[i - 1]]
+
+                dup {list_pointer_depth_below_element_copy}
+                dup {list_pointer_depth_above_element}
+                call {set_element}
+                // _ *list index [element] i
+
+                push -1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "index exceeds list length".to_string(),
+            "New length exceeds capacity".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let list_length = rng.gen_range(1..20);
+        vec![
+            self.prepare_state(10, 0, 0),
+            self.prepare_state(10, 1, 0),
+            self.prepare_state(10, 1, 1),
+            self.prepare_state(20, list_length, 0),
+            self.prepare_state(20, list_length, rng.gen_range(0..=list_length)),
+            self.prepare_state(20, list_length, list_length),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100, 10, 5)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 100, 0)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.0.get_size();
+        let mut element: Vec<BFieldElement> = vec![];
+        for _ in 0..element_size {
+            element.push(stack.pop().unwrap());
+        }
+        let index: u32 = stack.pop().unwrap().try_into().unwrap();
+        let index = index as usize;
+        let list_pointer = stack.pop().unwrap();
+
+        let length = safe_list_get_length(list_pointer, memory);
+        assert!(index <= length, "index exceeds list length");
+        let capacity = memory[&(list_pointer + BFieldElement::one())].value() as usize;
+        assert!(length < capacity, "New length exceeds capacity");
+
+        let mut elements = (0..length)
+            .map(|i| safe_list_get(list_pointer, i, memory, element_size))
+            .collect_vec();
+        elements.insert(index, element);
+
+        safe_list_set_length(list_pointer, length + 1, memory);
+        for (i, element) in elements.into_iter().enumerate() {
+            safe_list_set(list_pointer, i, element, memory, element_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::push_encodable;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_insert_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeInsert(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeInsert(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeInsert(DataType::XFE), true);
+        test_rust_equivalence_multiple_deprecated(&SafeInsert(DataType::Digest), true);
+    }
+
+    fn prop_insert<T: BFieldCodec + Clone>(
+        data_type: DataType,
+        capacity: u32,
+        values: Vec<T>,
+        index: usize,
+        new_value: T,
+    ) {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, capacity, values.clone(), &mut memory);
+
+        let mut init_stack = get_init_tvm_stack();
+        init_stack.push(list_pointer);
+        init_stack.push(BFieldElement::new(index as u64));
+        push_encodable(&mut init_stack, &new_value);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeInsert(data_type.clone()),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&get_init_tvm_stack()),
+        );
+
+        let mut expected_values = values;
+        expected_values.insert(index, new_value);
+        assert_eq!(
+            expected_values.len(),
+            safe_list_get_length(list_pointer, &memory)
+        );
+        for (i, expected) in expected_values.into_iter().enumerate() {
+            assert_eq!(
+                expected.encode(),
+                safe_list_get(list_pointer, i, &memory, data_type.get_size())
+            );
+        }
+    }
+
+    #[test]
+    fn insert_at_front() {
+        prop_insert(DataType::U32, 10, vec![1u32, 2, 3], 0, 42);
+        prop_insert(DataType::U64, 10, vec![1u64 << 40, 2, 3], 0, u64::MAX);
+    }
+
+    #[test]
+    fn insert_in_middle() {
+        prop_insert(DataType::U32, 10, vec![1u32, 2, 3, 4], 2, 42);
+        prop_insert(DataType::U64, 10, vec![1u64 << 40, 2, 3], 1, (7 << 32) + 1);
+    }
+
+    #[test]
+    fn insert_at_end_is_push() {
+        prop_insert(DataType::U32, 10, vec![1u32, 2, 3], 3, 42);
+        prop_insert(DataType::U64, 4, vec![1u64 << 40, 2, 3], 3, 1 << 33);
+        prop_insert(DataType::U32, 1, Vec::<u32>::new(), 0, 42);
+    }
+
+    #[should_panic]
+    #[test]
+    fn insert_into_full_list() {
+        prop_insert(DataType::U32, 3, vec![1u32, 2, 3], 1, 42);
+    }
+
+    #[should_panic]
+    #[test]
+    fn insert_past_length() {
+        prop_insert(DataType::U32, 10, vec![1u32, 2, 3], 4, 42);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_insert_benchmark() {
+        bench_and_write(SafeInsert(DataType::Digest));
+    }
+}
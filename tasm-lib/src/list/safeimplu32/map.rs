@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, length::Length as SafeLength, set::SafeSet};
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length, safe_list_set,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Apply the inner snippet to every element of a safe list, in place. The inner snippet
+/// must map one element to one element of the same type.
+pub struct SafeMap<S: DeprecatedSnippet + Clone + 'static>(pub S);
+
+impl<S: DeprecatedSnippet + Clone + 'static> SafeMap<S> {
+    fn element_type(&self) -> DataType {
+        let input_types = self.0.input_types();
+        let output_types = self.0.output_types();
+        assert_eq!(1, input_types.len(), "inner snippet must take one element");
+        assert_eq!(
+            input_types, output_types,
+            "inner snippet must map elements to elements of the same type"
+        );
+
+        input_types[0].clone()
+    }
+
+    fn prepare_state(&self, list_length: usize) -> ExecutionState {
+        let list_pointer: BFieldElement = random();
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(
+            &self.element_type(),
+            list_pointer,
+            list_length as u32 + 10,
+            list_length,
+            &mut memory,
+        );
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl<S: DeprecatedSnippet + Clone + 'static> DeprecatedSnippet for SafeMap<S> {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_list_safeimplu32_map___{}", self.0.entrypoint_name())
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.element_type()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.element_type()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_type = self.element_type();
+        let element_size = element_type.get_size();
+
+        let get_length = library.import(Box::new(SafeLength(element_type.clone())));
+        let get_element = library.import(Box::new(SafeGet(element_type.clone())));
+        let set_element = library.import(Box::new(SafeSet(element_type)));
+        let inner = library.import(Box::new(self.0.clone()));
+
+        let list_pointer_depth = element_size + 2;
+        let index_depth = element_size + 1;
+
+        format!(
+            "
+            // BEFORE: _ *list
+            // AFTER: _ *list
+            {entrypoint}:
+                dup 0
+                call {get_length}
+                push 0
+                // _ *list length 0
+
+                call {entrypoint}_loop
+                // _ *list length length
+
+                pop
+                pop
+                return
+
+            // INVARIANT: _ *list length index
+            {entrypoint}_loop:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 2
+                dup 1
+                call {get_element}
+                // _ *list length index [element]
+
+                call {inner}
+                // _ *list length index [mapped_element]
+
+                dup {list_pointer_depth}
+                dup {index_depth}
+                call {set_element}
+                // _ *list length index
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        self.0.crash_conditions()
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0),
+            self.prepare_state(1),
+            self.prepare_state(rng.gen_range(2..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.element_type().get_size();
+        let list_pointer = *stack.last().unwrap();
+        let length = safe_list_get_length(list_pointer, memory);
+
+        for i in 0..length {
+            // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+            let element = safe_list_get(list_pointer, i, memory, element_size);
+            stack.extend(element.into_iter().rev());
+
+            self.0
+                .rust_shadowing(stack, std_in.clone(), secret_in.clone(), memory);
+
+            let mapped_element = (0..element_size).map(|_| stack.pop().unwrap()).collect();
+            safe_list_set(list_pointer, i, mapped_element, memory, element_size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::arithmetic::u64::incr_u64::IncrU64;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_map_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeMap(IncrU64), false);
+    }
+
+    #[test]
+    fn incrementing_u64_list_in_place() {
+        let list_pointer = BFieldElement::new(48);
+        let values: Vec<u64> = vec![0, 14, (1 << 32) - 1, u64::MAX - 1];
+
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, 10, values.clone(), &mut memory);
+
+        let init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeMap(IncrU64),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&init_stack),
+        );
+
+        assert_eq!(values.len(), safe_list_get_length(list_pointer, &memory));
+        let mapped_values = (0..values.len())
+            .map(|i| safe_list_get(list_pointer, i, &memory, 2))
+            .collect_vec();
+        let expected_values = values.iter().map(|v| (v + 1).encode()).collect_vec();
+        assert_eq!(expected_values, mapped_values);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::arithmetic::u64::incr_u64::IncrU64;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_map_benchmark() {
+        bench_and_write(SafeMap(IncrU64));
+    }
+}
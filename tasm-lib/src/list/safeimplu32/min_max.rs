@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::lt_u64::LtStandardU64;
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, length::Length as SafeLength};
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Find the smallest and the largest element of a nonempty safe list of `U32`s or
+/// `U64`s in a single pass.
+#[derive(Clone, Debug)]
+pub struct SafeMinMax(pub DataType);
+
+impl SafeMinMax {
+    fn prepare_state(&self, list_length: usize) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(
+            &self.0,
+            list_pointer,
+            list_length as u32,
+            list_length,
+            &mut memory,
+        );
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    /// Code consuming `_ [rhs] [lhs]` and leaving `_ (lhs < rhs)`.
+    fn lt_code(&self, library: &mut Library) -> String {
+        match self.0 {
+            DataType::U32 => "lt".to_string(),
+            DataType::U64 => format!("call {}", library.import(Box::new(LtStandardU64))),
+            _ => panic!("Unsupported element type for min/max: {:?}", self.0),
+        }
+    }
+
+    /// The numeric value of a list element, as read from memory.
+    fn element_value(&self, element: &[BFieldElement]) -> u64 {
+        match self.0 {
+            DataType::U32 => element[0].value(),
+            DataType::U64 => (element[1].value() << 32) + element[0].value(),
+            _ => panic!("Unsupported element type for min/max: {:?}", self.0),
+        }
+    }
+}
+
+impl DeprecatedSnippet for SafeMinMax {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_min_max___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        let element_size = self.0.get_size();
+        let mut ret = vec![];
+        for name in ["min", "max"] {
+            for i in 0..element_size {
+                ret.push(format!("{name}_{}", element_size - 1 - i));
+            }
+        }
+
+        ret
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![self.0.clone(), self.0.clone()]
+    }
+
+    fn stack_diff(&self) -> isize {
+        2 * self.0.get_size() as isize - 1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let lt = self.lt_code(library);
+        let get_length = library.import(Box::new(SafeLength(self.0.clone())));
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+
+        // Stack during the loop: _ *list length index [min] [max] [element]
+        let dup_element = vec![format!("dup {}", element_size - 1); element_size].join("\n");
+        let dup_max_below_element =
+            vec![format!("dup {}", 3 * element_size - 1); element_size].join("\n");
+        let dup_min_below_element_copy =
+            vec![format!("dup {}", 3 * element_size - 1); element_size].join("\n");
+        let dup_element_below_min_copy =
+            vec![format!("dup {}", 2 * element_size - 1); element_size].join("\n");
+        let overwrite = |offset: usize| {
+            (0..element_size)
+                .map(|i| format!("dup {i}\nswap {}\npop", offset + i + 1))
+                .join("\n")
+        };
+        let overwrite_max = overwrite(element_size);
+        let overwrite_min = overwrite(2 * element_size);
+        let pop_element = vec!["pop"; element_size].join("\n");
+
+        let first_list_pointer_depth = element_size + 2;
+        let extremes_size = 2 * element_size;
+        let list_pointer_depth = extremes_size + 2;
+        let length_depth = extremes_size + 1;
+        let dig_up_word = (1..=extremes_size).map(|i| format!("swap {i}")).join("\n");
+
+        format!(
+            "
+            // BEFORE: _ *list
+            // AFTER: _ [min] [max]
+            {entrypoint}:
+                dup 0
+                call {get_length}
+                // _ *list length
+
+                dup 0
+                push 0
+                eq
+                push 0
+                eq
+                assert
+                // _ *list length
+
+                push 1
+                dup 2
+                push 0
+                call {get_element}
+                dup {first_list_pointer_depth}
+                push 0
+                call {get_element}
+                // _ *list length 1 [min] [max]
+
+                call {entrypoint}_loop
+                // _ *list length length [min] [max]
+
+                {dig_up_word}
+                pop
+                {dig_up_word}
+                pop
+                {dig_up_word}
+                pop
+                // _ [min] [max]
+
+                return
+
+            // INVARIANT: _ *list length index [min] [max]
+            {entrypoint}_loop:
+                dup {length_depth}
+                dup {length_depth}
+                eq
+                skiz
+                    return
+
+                dup {list_pointer_depth}
+                dup {length_depth}
+                call {get_element}
+                // _ *list length index [min] [max] [element]
+
+                {dup_element}
+                {dup_max_below_element}
+                {lt}
+                // _ *list length index [min] [max] [element] (max < element)
+
+                skiz
+                    call {entrypoint}_overwrite_max
+                // _ *list length index [min] [max'] [element]
+
+                {dup_min_below_element_copy}
+                {dup_element_below_min_copy}
+                {lt}
+                // _ *list length index [min] [max'] [element] (element < min)
+
+                skiz
+                    call {entrypoint}_overwrite_min
+                // _ *list length index [min'] [max'] [element]
+
+                {pop_element}
+                swap {extremes_size}
+                push 1
+                add
+                swap {extremes_size}
+                recurse
+
+            // BEFORE: _ [min] [max] [element]
+            // AFTER: _ [min] [element] [element]
+            {entrypoint}_overwrite_max:
+                {overwrite_max}
+                return
+
+            // BEFORE: _ [min] [max] [element]
+            // AFTER: _ [element] [max] [element]
+            {entrypoint}_overwrite_min:
+                {overwrite_min}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["list is empty".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(1),
+            self.prepare_state(2),
+            self.prepare_state(rng.gen_range(3..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.0.get_size();
+        let list_pointer = stack.pop().unwrap();
+        let length = safe_list_get_length(list_pointer, memory);
+        assert!(length > 0, "list is empty");
+
+        let elements = (0..length)
+            .map(|i| safe_list_get(list_pointer, i, memory, element_size))
+            .collect_vec();
+        let min = elements
+            .iter()
+            .min_by_key(|element| self.element_value(element))
+            .unwrap();
+        let max = elements
+            .iter()
+            .max_by_key(|element| self.element_value(element))
+            .unwrap();
+
+        // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+        stack.extend(min.iter().rev());
+        stack.extend(max.iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::push_encodable;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_min_max_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeMinMax(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeMinMax(DataType::U64), true);
+    }
+
+    fn prop_min_max<T: BFieldCodec + Ord + Copy>(data_type: DataType, values: Vec<T>) {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(
+            list_pointer,
+            values.len() as u32,
+            values.clone(),
+            &mut memory,
+        );
+        let init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, values.iter().min().unwrap());
+        push_encodable(&mut expected_stack, values.iter().max().unwrap());
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeMinMax(data_type),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&expected_stack),
+        );
+    }
+
+    #[test]
+    fn min_max_u32_list() {
+        prop_min_max(DataType::U32, vec![14u32, 3, u32::MAX, 0, 1 << 20, 3]);
+        prop_min_max(DataType::U32, vec![0u32, u32::MAX]);
+        prop_min_max(DataType::U32, vec![u32::MAX, 0u32]);
+    }
+
+    #[test]
+    fn min_max_u64_list() {
+        prop_min_max(
+            DataType::U64,
+            vec![1u64 << 32, (1 << 32) - 1, u64::MAX, 5, 1 << 40],
+        );
+        prop_min_max(
+            DataType::U64,
+            vec![(3u64 << 32) + 1, (3 << 32) + 2, 3 << 32],
+        );
+    }
+
+    #[test]
+    fn single_element_is_min_and_max() {
+        prop_min_max(DataType::U32, vec![42u32]);
+        prop_min_max(DataType::U64, vec![(42u64 << 32) + 7]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn min_max_of_empty_list() {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, 10, Vec::<u64>::new(), &mut memory);
+        let stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+
+        test_rust_equivalence_given_execution_state_deprecated(
+            &SafeMinMax(DataType::U64),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_min_max_benchmark() {
+        bench_and_write(SafeMinMax(DataType::U64));
+    }
+}
@@ -35,7 +35,7 @@ impl DeprecatedSnippet for SafePop {
     }
 
     fn crash_conditions(&self) -> Vec<String> {
-        vec!["stack underflow".to_string()]
+        vec!["stack underflow".to_string(), "list is empty".to_string()]
     }
 
     fn gen_input_states(&self) -> Vec<ExecutionState> {
@@ -179,7 +179,7 @@ mod tests {
     use crate::get_init_tvm_stack;
     use crate::rust_shadowing_helper_functions::safe_list::safe_list_push;
     use crate::test_helpers::{
-        test_rust_equivalence_given_input_values_deprecated,
+        test_crash_conditions, test_rust_equivalence_given_input_values_deprecated,
         test_rust_equivalence_multiple_deprecated,
     };
 
@@ -216,6 +216,16 @@ mod tests {
         prop_pop(DataType::XFE, list_address, 0, 107);
     }
 
+    #[test]
+    fn pop_on_empty_list_crashes_vm() {
+        for data_type in [DataType::BFE, DataType::U64, DataType::Digest] {
+            test_crash_conditions(
+                &SafePop(data_type.clone()),
+                vec![(prepare_state(&data_type, 0), "list is empty")],
+            );
+        }
+    }
+
     #[test]
     fn list_u32_n_is_n_pop() {
         prop_pop(DataType::Digest, BFieldElement::new(1), 1, 1);
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use num::One;
 use rand::{thread_rng, Rng};
-use twenty_first::shared_math::{b_field_element::BFieldElement, other::random_elements};
+use twenty_first::shared_math::b_field_element::BFieldElement;
 
 use crate::{
     get_init_tvm_stack,
@@ -206,7 +206,7 @@ fn prepare_execution_state(
         BFieldElement::new(thread_rng().gen_range(0..u32::MAX as u64));
     let mut stack = get_init_tvm_stack();
     stack.push(list_pointer);
-    let mut push_value: Vec<BFieldElement> = random_elements(data_type.get_size());
+    let mut push_value: Vec<BFieldElement> = data_type.random_element();
     while let Some(element) = push_value.pop() {
         stack.push(element);
     }
@@ -231,7 +231,9 @@ mod tests {
     #[test]
     fn new_snippet_test() {
         test_rust_equivalence_multiple_deprecated(&SafePush(DataType::Bool), true);
+        test_rust_equivalence_multiple_deprecated(&SafePush(DataType::U16), true);
         test_rust_equivalence_multiple_deprecated(&SafePush(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafePush(DataType::I32), true);
         test_rust_equivalence_multiple_deprecated(&SafePush(DataType::U64), true);
         test_rust_equivalence_multiple_deprecated(&SafePush(DataType::BFE), true);
         test_rust_equivalence_multiple_deprecated(&SafePush(DataType::XFE), true);
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, set::SafeSet};
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length, safe_list_set,
+    safe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Remove the element at an arbitrary position of a safe list and return it, shifting
+/// all later elements down by one. Like `Vec::remove`.
+#[derive(Clone, Debug)]
+pub struct SafeRemove(pub DataType);
+
+impl SafeRemove {
+    fn prepare_state(&self, capacity: u32, list_length: usize, index: usize) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.push(BFieldElement::new(index as u64));
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(&self.0, list_pointer, capacity, list_length, &mut memory);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeRemove {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_remove___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string(), "index".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone())), DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        let element_size = self.0.get_size();
+        (0..element_size)
+            .map(|i| format!("removed_element_{}", element_size - 1 - i))
+            .collect()
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![self.0.clone()]
+    }
+
+    fn stack_diff(&self) -> isize {
+        self.0.get_size() as isize - 2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+        let set_element = library.import(Box::new(SafeSet(self.0.clone())));
+
+        let list_pointer_depth_above_removed = element_size + 1;
+        let list_pointer_depth_above_length = element_size + 2;
+        let list_pointer_depth_in_loop = element_size + 3;
+        let list_pointer_depth_below_element = 2 * element_size + 3;
+        let i_depth_below_list_pointer = element_size + 1;
+
+        // Remove the word right below the removed element
+        let dig_up_word = (1..=element_size).map(|i| format!("swap {i}")).join("\n");
+
+        format!(
+            "
+            // BEFORE: _ *list index
+            // AFTER: _ removed_elem{{N - 1}}, removed_elem{{N - 2}}, ..., removed_elem{{0}}
+            {entrypoint}:
+                dup 1
+                dup 1
+                call {get_element}
+                // _ *list index [removed]
+
+                dup {list_pointer_depth_above_removed}
+                read_mem
+                swap 1
+                pop
+                // _ *list index [removed] length
+
+                dup {list_pointer_depth_above_removed}
+                push 1
+                add
+                // _ *list index [removed] length (index + 1)
+
+                call {entrypoint}_shift
+                // _ *list index [removed] length length
+
+                pop
+                push -1
+                add
+                dup {list_pointer_depth_above_length}
+                swap 1
+                write_mem
+                pop
+                // _ *list index [removed]
+
+                {dig_up_word}
+                pop
+                {dig_up_word}
+                pop
+                // _ [removed]
+
+                return
+
+            // INVARIANT: _ *list index [removed] length i
+            {entrypoint}_shift:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup {list_pointer_depth_in_loop}
+                dup 1
+                call {get_element}
+                // _ *list index [removed] length i [list[i]]
+
+                dup {list_pointer_depth_below_element}
+                dup {i_depth_below_list_pointer}
+                push -1
+                add
+                call {set_element}
+                // _ *list index [removed] length i
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["index out of bounds".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let list_length = rng.gen_range(2..20);
+        vec![
+            self.prepare_state(10, 1, 0),
+            self.prepare_state(20, list_length, 0),
+            self.prepare_state(20, list_length, rng.gen_range(0..list_length)),
+            self.prepare_state(20, list_length, list_length - 1),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100, 10, 5)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 100, 0)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.0.get_size();
+        let index: u32 = stack.pop().unwrap().try_into().unwrap();
+        let index = index as usize;
+        let list_pointer = stack.pop().unwrap();
+
+        let length = safe_list_get_length(list_pointer, memory);
+        assert!(index < length, "index out of bounds");
+
+        let mut elements = (0..length)
+            .map(|i| safe_list_get(list_pointer, i, memory, element_size))
+            .collect_vec();
+        let removed = elements.remove(index);
+
+        for (i, element) in elements.into_iter().enumerate() {
+            safe_list_set(list_pointer, i, element, memory, element_size);
+        }
+        safe_list_set_length(list_pointer, length - 1, memory);
+
+        // elements are placed on stack as: `elem[N - 1] elem[N - 2] .. elem[0]`
+        stack.extend(removed.into_iter().rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::push_encodable;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_remove_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeRemove(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeRemove(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeRemove(DataType::XFE), true);
+        test_rust_equivalence_multiple_deprecated(&SafeRemove(DataType::Digest), true);
+    }
+
+    fn prop_remove<T: BFieldCodec + Clone>(data_type: DataType, values: Vec<T>, index: usize) {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, 20, values.clone(), &mut memory);
+
+        let init_stack = [
+            get_init_tvm_stack(),
+            vec![list_pointer, BFieldElement::new(index as u64)],
+        ]
+        .concat();
+
+        let mut expected_values = values;
+        let removed = expected_values.remove(index);
+        let mut expected_stack = get_init_tvm_stack();
+        push_encodable(&mut expected_stack, &removed);
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeRemove(data_type.clone()),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&expected_stack),
+        );
+
+        assert_eq!(
+            expected_values.len(),
+            safe_list_get_length(list_pointer, &memory)
+        );
+        for (i, expected) in expected_values.into_iter().enumerate() {
+            assert_eq!(
+                expected.encode(),
+                safe_list_get(list_pointer, i, &memory, data_type.get_size())
+            );
+        }
+    }
+
+    #[test]
+    fn remove_first_element() {
+        prop_remove(DataType::U32, vec![1u32, 2, 3], 0);
+        prop_remove(DataType::U64, vec![1u64 << 40, 2, 3], 0);
+        prop_remove(DataType::U32, vec![42u32], 0);
+    }
+
+    #[test]
+    fn remove_middle_element() {
+        prop_remove(DataType::U32, vec![1u32, 2, 3, 4], 2);
+        prop_remove(DataType::U64, vec![1u64 << 40, (7 << 32) + 1, 3], 1);
+    }
+
+    #[test]
+    fn remove_last_element() {
+        prop_remove(DataType::U32, vec![1u32, 2, 3], 2);
+        prop_remove(DataType::U64, vec![1u64 << 40, 2, u64::MAX], 2);
+    }
+
+    #[should_panic]
+    #[test]
+    fn remove_out_of_range() {
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, 20, vec![1u32, 2, 3], &mut memory);
+        let stack = [
+            get_init_tvm_stack(),
+            vec![list_pointer, BFieldElement::new(3)],
+        ]
+        .concat();
+
+        test_rust_equivalence_given_execution_state_deprecated(
+            &SafeRemove(DataType::U32),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_remove_benchmark() {
+        bench_and_write(SafeRemove(DataType::Digest));
+    }
+}
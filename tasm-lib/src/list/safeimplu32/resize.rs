@@ -0,0 +1,301 @@
+use std::collections::HashMap;
+
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::new::SafeNew;
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get_length, safe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Copy a safe list into a newly allocated safe list of the given capacity, which must
+/// be at least the list's length. The memory of the original list is not freed.
+#[derive(Clone, Debug)]
+pub struct SafeResize(pub DataType);
+
+impl SafeResize {
+    fn prepare_state(
+        &self,
+        capacity: u32,
+        list_length: usize,
+        new_capacity: u32,
+    ) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.push(BFieldElement::new(new_capacity as u64));
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(&self.0, list_pointer, capacity, list_length, &mut memory);
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeResize {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_resize___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*src".to_string(), "new_capacity".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone())), DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*dest".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let element_size = self.0.get_size();
+        let new_list = library.import(Box::new(SafeNew(self.0.clone())));
+
+        // Code to multiply with size. If size is 1, do nothing to save two clock cycles.
+        let mul_with_size = if element_size != 1 {
+            format!("push {element_size}\n mul\n")
+        } else {
+            String::default()
+        };
+
+        format!(
+            "
+            // BEFORE: _ *src new_capacity
+            // AFTER: _ *dest
+            {entrypoint}:
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *src new_capacity length
+
+                dup 1
+                lt
+                push 0
+                eq
+                assert
+                // _ *src new_capacity
+
+                call {new_list}
+                // _ *src *dest
+
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *src *dest length
+
+                dup 1
+                dup 1
+                write_mem
+                pop
+                // _ *src *dest length
+
+                {mul_with_size}
+                push 0
+                // _ *src *dest num_words 0
+
+                call {entrypoint}_loop
+                // _ *src *dest num_words num_words
+
+                pop
+                pop
+                swap 1
+                pop
+                // _ *dest
+
+                return
+
+            // INVARIANT: _ *src *dest num_words i
+            {entrypoint}_loop:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 3
+                dup 1
+                add
+                push 2
+                add
+                read_mem
+                swap 1
+                pop
+                // _ *src *dest num_words i word
+
+                dup 3
+                dup 2
+                add
+                push 2
+                add
+                swap 1
+                write_mem
+                pop
+                // _ *src *dest num_words i
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["New capacity is smaller than the list's length".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let list_length = rng.gen_range(2..20);
+        vec![
+            self.prepare_state(0, 0, 0),
+            self.prepare_state(10, 0, 20),
+            self.prepare_state(10, 1, 1),
+            self.prepare_state(20, list_length, 40),
+            self.prepare_state(20, list_length, list_length as u32),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(20, 10, 40)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(200, 200, 400)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let new_capacity = stack.pop().unwrap();
+        let src_pointer = stack.pop().unwrap();
+        let length = safe_list_get_length(src_pointer, memory);
+        assert!(
+            length as u64 <= new_capacity.value(),
+            "New capacity must be at least the list's length"
+        );
+
+        stack.push(new_capacity);
+        SafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let dest_pointer = *stack.last().unwrap();
+
+        safe_list_set_length(dest_pointer, length, memory);
+        for i in 0..length * self.0.get_size() {
+            let offset = BFieldElement::new(2 + i as u64);
+            let word = memory[&(src_pointer + offset)];
+            memory.insert(dest_pointer + offset, word);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use num::One;
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::list::safeimplu32::push::SafePush;
+    use crate::rust_shadowing_helper_functions::safe_list::{safe_list_get, safe_list_insert};
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_resize_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeResize(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeResize(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeResize(DataType::XFE), true);
+        test_rust_equivalence_multiple_deprecated(&SafeResize(DataType::Digest), true);
+    }
+
+    #[test]
+    fn growing_partially_full_list() {
+        let values = vec![1u64, 2, 3, 1 << 40];
+        let src_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        safe_list_insert(src_pointer, 5, values.clone(), &mut memory);
+        let new_capacity = 12;
+        let stack = [
+            get_init_tvm_stack(),
+            vec![src_pointer, BFieldElement::new(new_capacity)],
+        ]
+        .concat();
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SafeResize(DataType::U64),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+        let dest_pointer = *vm_output_state.final_stack.last().unwrap();
+        let mut memory = vm_output_state.final_ram;
+        assert_eq!(
+            BFieldElement::new(new_capacity),
+            memory[&(dest_pointer + BFieldElement::one())]
+        );
+
+        // the resized list has room beyond the original capacity
+        for value in 4..new_capacity {
+            let mut stack = [get_init_tvm_stack(), vec![dest_pointer]].concat();
+            stack.extend(value.encode().into_iter().rev());
+            SafePush(DataType::U64)
+                .link_and_run_tasm_for_test(&mut stack, vec![], vec![], &mut memory, None)
+                .unwrap();
+        }
+
+        let expected_values = [values, (4..new_capacity).collect_vec()].concat();
+        let read_values = (0..safe_list_get_length(dest_pointer, &memory))
+            .map(|i| safe_list_get(dest_pointer, i, &memory, 2))
+            .collect_vec();
+        assert_eq!(
+            expected_values
+                .iter()
+                .map(|value| value.encode())
+                .collect_vec(),
+            read_values
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn new_capacity_smaller_than_length() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &SafeResize(DataType::U64),
+            SafeResize(DataType::U64).prepare_state(10, 5, 4),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_resize_benchmark() {
+        bench_and_write(SafeResize(DataType::Digest));
+    }
+}
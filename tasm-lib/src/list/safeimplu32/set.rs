@@ -1,7 +1,6 @@
 use rand::{random, thread_rng, Rng};
 use std::collections::HashMap;
 use twenty_first::shared_math::b_field_element::BFieldElement;
-use twenty_first::shared_math::other::random_elements;
 
 use crate::library::Library;
 use crate::rust_shadowing_helper_functions::safe_list::{safe_insert_random_list, safe_list_set};
@@ -169,7 +168,7 @@ impl DeprecatedSnippet for SafeSet {
 fn prepare_state(data_type: &DataType, capacity: u32, list_length: usize) -> ExecutionState {
     let index: usize = thread_rng().gen_range(0..list_length);
     let mut stack = get_init_tvm_stack();
-    let mut push_value: Vec<BFieldElement> = random_elements(data_type.get_size());
+    let mut push_value: Vec<BFieldElement> = data_type.random_element();
     while let Some(element) = push_value.pop() {
         stack.push(element);
     }
@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::sort_u64::ListSortU64;
+use crate::list::ListType;
+use crate::rust_shadowing_helper_functions::safe_list::safe_insert_random_list;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Sort a safe list of `U32`s or `U64`s in place, in ascending order, using
+/// [`ListSortU64`]. Other element types have no built-in order and are rejected when
+/// generating the code.
+#[derive(Clone, Debug)]
+pub struct SafeListSort(pub DataType);
+
+impl SafeListSort {
+    fn list_sort(&self) -> ListSortU64 {
+        ListSortU64 {
+            list_type: ListType::Safe,
+            element_type: self.0.clone(),
+        }
+    }
+
+    fn prepare_state(&self, list_length: usize) -> ExecutionState {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(
+            &self.0,
+            list_pointer,
+            list_length as u32,
+            list_length,
+            &mut memory,
+        );
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeListSort {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_sort___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let sort = library.import(Box::new(self.list_sort()));
+
+        format!(
+            "
+            // BEFORE: _ *list
+            // AFTER: _
+            {entrypoint}:
+                call {sort}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        vec![
+            self.prepare_state(0),
+            self.prepare_state(1),
+            self.prepare_state(2),
+            self.prepare_state(rng.gen_range(3..20)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 4)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(1 << 6)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        self.list_sort()
+            .rust_shadowing(stack, vec![], vec![], memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_list_sort_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeListSort(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeListSort(DataType::U64), true);
+    }
+
+    fn prop_sort<T: BFieldCodec + Ord + Clone>(data_type: DataType, values: Vec<T>) {
+        let list_pointer = BFieldElement::new(48);
+        let capacity = values.len() as u32;
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, capacity, values.clone(), &mut memory);
+        let init_stack = [get_init_tvm_stack(), vec![list_pointer]].concat();
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &SafeListSort(data_type),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&get_init_tvm_stack()),
+        );
+
+        let mut sorted_values = values;
+        sorted_values.sort();
+        let mut expected_memory = HashMap::default();
+        safe_list_insert(list_pointer, capacity, sorted_values, &mut expected_memory);
+        for (address, value) in expected_memory {
+            assert_eq!(Some(&value), memory.get(&address), "address {address}");
+        }
+    }
+
+    #[test]
+    fn already_sorted_list() {
+        prop_sort(DataType::U32, vec![0u32, 1, 2, 3, 1 << 20, u32::MAX]);
+        prop_sort(DataType::U64, vec![0u64, 1 << 32, (1 << 32) + 1, u64::MAX]);
+    }
+
+    #[test]
+    fn reverse_sorted_list() {
+        prop_sort(DataType::U32, vec![u32::MAX, 1 << 20, 3, 2, 1, 0]);
+        prop_sort(DataType::U64, vec![u64::MAX, (1 << 32) + 1, 1 << 32, 0]);
+    }
+
+    #[test]
+    fn list_with_duplicates() {
+        prop_sort(DataType::U32, vec![5u32, 3, 5, 0, 3, 5, u32::MAX, 0]);
+        prop_sort(
+            DataType::U64,
+            vec![7u64 << 32, 7, 7 << 32, (7 << 32) + 7, 7, u64::MAX],
+        );
+    }
+
+    #[test]
+    fn empty_and_single_element_lists() {
+        prop_sort(DataType::U32, Vec::<u32>::new());
+        prop_sort(DataType::U64, Vec::<u64>::new());
+        prop_sort(DataType::U32, vec![42u32]);
+        prop_sort(DataType::U64, vec![(42u64 << 32) + 7]);
+    }
+
+    #[should_panic(expected = "Unsupported element type for sorting")]
+    #[test]
+    fn digests_cannot_be_sorted() {
+        SafeListSort(DataType::Digest).function_code(&mut Library::new());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_list_sort_benchmark() {
+        bench_and_write(SafeListSort(DataType::U64));
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::list::safeimplu32::{get::SafeGet, new::SafeNew, push::SafePush};
+use crate::rust_shadowing_helper_functions::dyn_malloc::random_pointer_above_dynamic_allocations;
+use crate::rust_shadowing_helper_functions::safe_list::{
+    safe_insert_random_list, safe_list_get, safe_list_get_length, safe_list_push,
+    safe_list_set_length,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Split a safe list into two at the given index, like `Vec::split_off`. Elements
+/// `[index, length)` are moved into a new safe list, whose capacity is the number of
+/// moved elements, and the original list is truncated to `index` elements.
+#[derive(Clone, Debug)]
+pub struct SafeSplitOff(pub DataType);
+
+impl SafeSplitOff {
+    fn prepare_state(&self, list_length: usize, index: usize) -> ExecutionState {
+        let list_pointer = random_pointer_above_dynamic_allocations();
+        let mut stack = get_init_tvm_stack();
+        stack.push(list_pointer);
+        stack.push(BFieldElement::new(index as u64));
+
+        let mut memory = HashMap::default();
+        safe_insert_random_list(
+            &self.0,
+            list_pointer,
+            list_length as u32 + 10,
+            list_length,
+            &mut memory,
+        );
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for SafeSplitOff {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_safeimplu32_split_off___{}",
+            self.0.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string(), "index".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.0.clone())), DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string(), "*tail".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(self.0.clone())),
+            DataType::List(Box::new(self.0.clone())),
+        ]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(SafeNew(self.0.clone())));
+        let get_element = library.import(Box::new(SafeGet(self.0.clone())));
+        let push_element = library.import(Box::new(SafePush(self.0.clone())));
+
+        format!(
+            "
+            // BEFORE: _ *list index
+            // AFTER: _ *list *tail
+            {entrypoint}:
+                dup 1
+                read_mem
+                swap 1
+                pop
+                // _ *list index length
+
+                // Verify that index <= length
+                dup 1
+                dup 1
+                lt
+                push 0
+                eq
+                assert
+                // _ *list index length
+
+                dup 0
+                dup 2
+                push -1
+                mul
+                add
+                call {new_list}
+                // _ *list index length *tail
+
+                dup 2
+                call {entrypoint}_loop
+                // _ *list index length *tail length
+
+                pop
+                swap 1
+                pop
+                swap 1
+                // _ *list *tail index
+
+                dup 2
+                swap 1
+                write_mem
+                pop
+                // _ *list *tail
+
+                return
+
+            // INVARIANT: _ *list index length *tail i
+            {entrypoint}_loop:
+                dup 2
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 1
+                dup 5
+                dup 2
+                call {get_element}
+                // _ *list index length *tail i *tail [element]
+
+                call {push_element}
+                // _ *list index length *tail i
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["index exceeds list length".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let list_length = rng.gen_range(1..20);
+        vec![
+            self.prepare_state(0, 0),
+            self.prepare_state(list_length, 0),
+            self.prepare_state(list_length, rng.gen_range(0..=list_length)),
+            self.prepare_state(list_length, list_length),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(20, 10)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.prepare_state(100, 0)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let element_size = self.0.get_size();
+        let index: u32 = stack.pop().unwrap().try_into().unwrap();
+        let index = index as usize;
+        let list_pointer = stack.pop().unwrap();
+
+        let length = safe_list_get_length(list_pointer, memory);
+        assert!(index <= length, "index exceeds list length");
+
+        stack.push(list_pointer);
+        stack.push(BFieldElement::new((length - index) as u64));
+        SafeNew(self.0.clone()).rust_shadowing(stack, vec![], vec![], memory);
+        let tail_pointer = *stack.last().unwrap();
+
+        for i in index..length {
+            let element = safe_list_get(list_pointer, i, memory, element_size);
+            safe_list_push(tail_pointer, element, memory, element_size);
+        }
+        safe_list_set_length(list_pointer, index, memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::shared_math::bfield_codec::BFieldCodec;
+
+    use super::*;
+    use crate::rust_shadowing_helper_functions::safe_list::safe_list_insert;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn safe_split_off_test() {
+        test_rust_equivalence_multiple_deprecated(&SafeSplitOff(DataType::U32), true);
+        test_rust_equivalence_multiple_deprecated(&SafeSplitOff(DataType::U64), true);
+        test_rust_equivalence_multiple_deprecated(&SafeSplitOff(DataType::Digest), true);
+    }
+
+    fn prop_split_off<T: BFieldCodec + Clone>(data_type: DataType, values: Vec<T>, index: usize) {
+        let element_size = data_type.get_size();
+        let list_pointer = BFieldElement::new(1 << 32);
+        let mut memory = HashMap::default();
+        safe_list_insert(list_pointer, 20, values.clone(), &mut memory);
+        let stack = [
+            get_init_tvm_stack(),
+            vec![list_pointer, BFieldElement::new(index as u64)],
+        ]
+        .concat();
+
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &SafeSplitOff(data_type),
+            ExecutionState::with_stack_and_memory(stack, memory, 0),
+        );
+
+        let mut final_stack = vm_output_state.final_stack;
+        let tail_pointer = final_stack.pop().unwrap();
+        assert_eq!(list_pointer, final_stack.pop().unwrap());
+
+        let memory = vm_output_state.final_ram;
+        let read_list = |pointer| {
+            (0..safe_list_get_length(pointer, &memory))
+                .map(|i| safe_list_get(pointer, i, &memory, element_size))
+                .collect_vec()
+        };
+
+        let mut expected_head = values;
+        let expected_tail = expected_head.split_off(index);
+        let encode_all = |values: Vec<T>| values.iter().map(|value| value.encode()).collect_vec();
+        assert_eq!(encode_all(expected_head), read_list(list_pointer));
+        assert_eq!(encode_all(expected_tail), read_list(tail_pointer));
+    }
+
+    #[test]
+    fn split_off_at_zero_empties_original() {
+        prop_split_off(DataType::U32, vec![1u32, 2, 3], 0);
+        prop_split_off(DataType::U64, vec![1u64 << 40, 2, 3], 0);
+    }
+
+    #[test]
+    fn split_off_at_length_gives_empty_tail() {
+        prop_split_off(DataType::U32, vec![1u32, 2, 3], 3);
+        prop_split_off(DataType::U32, Vec::<u32>::new(), 0);
+    }
+
+    #[test]
+    fn split_off_in_middle() {
+        prop_split_off(DataType::U32, vec![1u32, 2, 3, 4, 5], 2);
+        prop_split_off(
+            DataType::U64,
+            vec![1u64 << 40, (7 << 32) + 1, 3, u64::MAX],
+            1,
+        );
+    }
+
+    #[should_panic]
+    #[test]
+    fn split_off_past_length() {
+        prop_split_off(DataType::U32, vec![1u32, 2, 3], 4);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn safe_split_off_benchmark() {
+        bench_and_write(SafeSplitOff(DataType::Digest));
+    }
+}
@@ -0,0 +1,459 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use rand::{random, thread_rng, Rng};
+use triton_vm::NonDeterminism;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use super::ListType;
+use crate::arithmetic::u64::lt_u64::LtStandardU64;
+use crate::library::Library;
+use crate::rust_shadowing_helper_functions::{safe_list, unsafe_list};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Sort a (safe or unsafe) list of `U32`s or `U64`s in place, in ascending order. Uses
+/// insertion sort, so the number of clock cycles is quadratic in the length of the list.
+/// Other element types have no built-in order and are rejected when generating the code.
+#[derive(Clone, Debug)]
+pub struct ListSortU64 {
+    pub list_type: ListType,
+    pub element_type: DataType,
+}
+
+impl ListSortU64 {
+    fn random_list_state(&self, length: usize) -> ExecutionState {
+        let list_pointer = BFieldElement::new(thread_rng().gen_range(1u64 << 20..1u64 << 30));
+        let mut memory = HashMap::default();
+        match self.list_type {
+            ListType::Safe => safe_list::safe_insert_random_list(
+                &self.element_type,
+                list_pointer,
+                length as u32,
+                length,
+                &mut memory,
+            ),
+            ListType::Unsafe => unsafe_list::unsafe_insert_random_list(
+                &self.element_type,
+                list_pointer,
+                length,
+                &mut memory,
+            ),
+        }
+
+        ExecutionState {
+            stack: [get_init_tvm_stack(), vec![list_pointer]].concat(),
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(vec![]),
+            memory,
+            words_allocated: 1,
+        }
+    }
+
+    /// Code for reading the element at `*element + offset` onto the stack, given that
+    /// `*element` is at stack depth `pointer_depth`. The word at the lowest address ends
+    /// up on top of the stack.
+    fn read_element(&self, offset: isize, pointer_depth: usize) -> String {
+        (0..self.element_type.get_size())
+            .rev()
+            .enumerate()
+            .map(|(num_words_read, word)| {
+                let depth = pointer_depth + num_words_read;
+                match offset + word as isize {
+                    0 => format!("dup {depth}\nread_mem\nswap 1\npop\n"),
+                    address_offset => {
+                        format!("dup {depth}\npush {address_offset}\nadd\nread_mem\nswap 1\npop\n")
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+pub(crate) fn random_u64s(length: usize) -> Vec<u64> {
+    (0..length).map(|_| random()).collect_vec()
+}
+
+/// Write a list of u64s to memory. The low limb of every element lives at the lower
+/// address, such that it ends up on top of the stack when the element is read.
+pub(crate) fn insert_u64_list(
+    list_type: &ListType,
+    list_pointer: BFieldElement,
+    values: &[u64],
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+) {
+    match list_type {
+        ListType::Safe => safe_list::safe_list_new(list_pointer, values.len() as u32, memory),
+        ListType::Unsafe => unsafe_list::unsafe_list_new(list_pointer, memory),
+    }
+    for value in values {
+        let element = vec![
+            BFieldElement::new(value & u32::MAX as u64),
+            BFieldElement::new(value >> 32),
+        ];
+        match list_type {
+            ListType::Safe => safe_list::safe_list_push(list_pointer, element, memory, 2),
+            ListType::Unsafe => unsafe_list::unsafe_list_push(list_pointer, element, memory, 2),
+        }
+    }
+}
+
+/// Read a list of u64s from memory.
+pub(crate) fn read_u64_list(
+    list_type: &ListType,
+    list_pointer: BFieldElement,
+    memory: &HashMap<BFieldElement, BFieldElement>,
+) -> Vec<u64> {
+    let length = match list_type {
+        ListType::Safe => safe_list::safe_list_get_length(list_pointer, memory),
+        ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, memory),
+    };
+    (0..length)
+        .map(|i| {
+            let element = match list_type {
+                ListType::Safe => safe_list::safe_list_get(list_pointer, i, memory, 2),
+                ListType::Unsafe => unsafe_list::unsafe_list_get(list_pointer, i, memory, 2),
+            };
+            (element[1].value() << 32) | element[0].value()
+        })
+        .collect_vec()
+}
+
+impl DeprecatedSnippet for ListSortU64 {
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_list_{}_sort_{}",
+            self.list_type,
+            self.element_type.label_friendly_name()
+        )
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*list".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(self.element_type.clone()))]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let compare = match self.element_type {
+            DataType::U32 => "lt".to_string(),
+            DataType::U64 => {
+                let lt_u64 = library.import(Box::new(LtStandardU64));
+                format!("call {lt_u64}")
+            }
+            _ => panic!(
+                "Unsupported element type for sorting: {}",
+                self.element_type
+            ),
+        };
+
+        let safety_offset = match self.list_type {
+            ListType::Safe => 2,
+            ListType::Unsafe => 1,
+        };
+        let element_size = self.element_type.get_size();
+        let read_predecessor = self.read_element(-(element_size as isize), 0);
+        let read_element = self.read_element(0, element_size);
+        let swap_words =
+            vec![format!("call {entrypoint}_swap_word"); element_size].join("\npush 1\nadd\n");
+
+        format!(
+            "
+            // BEFORE: _ *list
+            // AFTER: _
+            {entrypoint}:
+                dup 0
+                read_mem
+                swap 1
+                pop
+                // _ *list len
+
+                swap 1
+                push {safety_offset}
+                add
+                swap 1
+                // _ *elements len
+
+                push 1
+                call {entrypoint}_outer
+                // _ *elements len i
+
+                pop
+                pop
+                pop
+                return
+
+            // INVARIANT: _ *elements len i
+            {entrypoint}_outer:
+                dup 1
+                dup 1
+                lt
+                push 0
+                eq
+                skiz
+                    return
+                // _ *elements len i
+
+                dup 0
+                call {entrypoint}_inner
+                pop
+                // _ *elements len i
+
+                push 1
+                add
+                recurse
+
+            // Move element `j` towards the front until its predecessor is not larger.
+            // INVARIANT: _ *elements len i j
+            {entrypoint}_inner:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                dup 3
+                dup 1
+                push {element_size}
+                mul
+                add
+                // _ *elements len i j *element_j
+
+                {read_predecessor}
+                {read_element}
+                // _ *elements len i j *element_j [element_{{j-1}}] [element_j]
+
+                {compare}
+                swap 1
+                pop
+                // _ *elements len i j (element_j < element_{{j-1}})
+
+                push 0
+                eq
+                skiz
+                    return
+
+                call {entrypoint}_swap_with_predecessor
+                push -1
+                add
+                recurse
+
+            // BEFORE: _ *elements len i j
+            // AFTER: _ *elements len i j
+            {entrypoint}_swap_with_predecessor:
+                dup 3
+                dup 1
+                push -1
+                add
+                push {element_size}
+                mul
+                add
+                // _ *elements len i j *element_{{j-1}}
+
+                {swap_words}
+                pop
+                return
+
+            // Swap the words at addresses `*word` and `*word + {element_size}`.
+            // BEFORE: _ *word
+            // AFTER: _ *word
+            {entrypoint}_swap_word:
+                read_mem
+                dup 1
+                push {element_size}
+                add
+                read_mem
+                // _ *word x (*word + {element_size}) y
+
+                swap 1
+                swap 2
+                swap 1
+                swap 2
+                swap 1
+                // _ *word y (*word + {element_size}) x
+
+                write_mem
+                pop
+                write_mem
+                // _ *word
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        [0, 1, 2, 3, 10, 17]
+            .into_iter()
+            .map(|length| self.random_list_state(length))
+            .collect_vec()
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.random_list_state(1 << 4)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.random_list_state(1 << 6)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let list_pointer = stack.pop().unwrap();
+        let element_size = self.element_type.get_size();
+        let length = match self.list_type {
+            ListType::Safe => safe_list::safe_list_get_length(list_pointer, memory),
+            ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, memory),
+        };
+
+        let mut elements = (0..length)
+            .map(|i| match self.list_type {
+                ListType::Safe => safe_list::safe_list_get(list_pointer, i, memory, element_size),
+                ListType::Unsafe => {
+                    unsafe_list::unsafe_list_get(list_pointer, i, memory, element_size)
+                }
+            })
+            .collect_vec();
+
+        // The word at the lowest address is the least significant one.
+        elements.sort_by_key(|element| element.iter().rev().map(|word| word.value()).collect_vec());
+
+        for (i, element) in elements.into_iter().enumerate() {
+            match self.list_type {
+                ListType::Safe => {
+                    safe_list::safe_list_set(list_pointer, i, element, memory, element_size)
+                }
+                ListType::Unsafe => {
+                    unsafe_list::unsafe_list_set(list_pointer, i, element, memory, element_size)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn list_sort_u64_test() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            for element_type in [DataType::U32, DataType::U64] {
+                test_rust_equivalence_multiple_deprecated(
+                    &ListSortU64 {
+                        list_type: list_type.clone(),
+                        element_type,
+                    },
+                    true,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn list_sort_u64_sorts_ascending() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = ListSortU64 {
+                list_type: list_type.clone(),
+                element_type: DataType::U64,
+            };
+            let execution_state = snippet.random_list_state(12);
+            let list_pointer = *execution_state.stack.last().unwrap();
+            let mut expected = read_u64_list(&list_type, list_pointer, &execution_state.memory);
+            expected.sort();
+
+            let vm_output_state =
+                test_rust_equivalence_given_execution_state_deprecated(&snippet, execution_state);
+            let sorted = read_u64_list(&list_type, list_pointer, &vm_output_state.final_ram);
+            assert_eq!(expected, sorted);
+        }
+    }
+
+    #[test]
+    fn list_sort_u32_sorts_ascending() {
+        let values = vec![5u32, u32::MAX, 0, 3, 5, 1 << 20, 0];
+        let mut expected = values.clone();
+        expected.sort();
+
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = ListSortU64 {
+                list_type: list_type.clone(),
+                element_type: DataType::U32,
+            };
+            let list_pointer = BFieldElement::new(1 << 20);
+            let mut memory = HashMap::default();
+            match list_type {
+                ListType::Safe => safe_list::safe_list_insert(
+                    list_pointer,
+                    values.len() as u32,
+                    values.clone(),
+                    &mut memory,
+                ),
+                ListType::Unsafe => {
+                    unsafe_list::unsafe_list_insert(list_pointer, values.clone(), &mut memory)
+                }
+            }
+            let execution_state = ExecutionState::with_stack_and_memory(
+                [get_init_tvm_stack(), vec![list_pointer]].concat(),
+                memory,
+                1,
+            );
+
+            let vm_output_state =
+                test_rust_equivalence_given_execution_state_deprecated(&snippet, execution_state);
+            let safety_offset = match list_type {
+                ListType::Safe => 2,
+                ListType::Unsafe => 1,
+            };
+            let sorted = (0..values.len())
+                .map(|i| {
+                    let address = list_pointer + BFieldElement::new((safety_offset + i) as u64);
+                    vm_output_state.final_ram[&address].value() as u32
+                })
+                .collect_vec();
+            assert_eq!(expected, sorted);
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn list_sort_u64_benchmark() {
+        bench_and_write(ListSortU64 {
+            list_type: ListType::Unsafe,
+            element_type: DataType::U64,
+        });
+    }
+}
@@ -11,6 +11,10 @@ use crate::rust_shadowing_helper_functions::unsafe_list::{
 use crate::snippet::{DataType, DeprecatedSnippet};
 use crate::{get_init_tvm_stack, ExecutionState};
 
+/// Like its safe counterpart, but without a bounds check. The element with index `index`
+/// lives at `*list + 1 + index * element_size`, as unsafe lists store only their length
+/// in front of the elements. An out-of-range index reads whatever memory lies beyond
+/// the list.
 #[derive(Clone, Debug)]
 pub struct UnsafeGet(pub DataType);
 
@@ -39,6 +43,8 @@ impl DeprecatedSnippet for UnsafeGet {
     }
 
     fn crash_conditions(&self) -> Vec<String> {
+        // An out-of-range index does not crash the VM but silently reads memory outside
+        // of the list.
         vec![]
     }
 
@@ -207,6 +213,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn get_out_of_bounds_does_not_crash() {
+        // The word after the last element is read without complaint.
+        let list_pointer = BFieldElement::new(48);
+        let mut memory = HashMap::default();
+        memory.insert(list_pointer, BFieldElement::new(1));
+        memory.insert(list_pointer + BFieldElement::new(1), BFieldElement::new(14));
+        memory.insert(list_pointer + BFieldElement::new(2), BFieldElement::new(15));
+
+        let mut init_stack = get_init_tvm_stack();
+        init_stack.push(list_pointer);
+        init_stack.push(BFieldElement::new(1));
+        let mut expected_end_stack = get_init_tvm_stack();
+        expected_end_stack.push(BFieldElement::new(15));
+
+        test_rust_equivalence_given_input_values_deprecated(
+            &UnsafeGet(DataType::BFE),
+            &init_stack,
+            &[],
+            &mut memory,
+            0,
+            Some(&expected_end_stack),
+        );
+    }
+
     fn prop_get(data_type: DataType, list_pointer: BFieldElement, index: u32, list_length: u32) {
         let mut init_stack = get_init_tvm_stack();
         init_stack.push(list_pointer);
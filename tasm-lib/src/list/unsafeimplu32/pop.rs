@@ -173,6 +173,7 @@ mod tests {
     use twenty_first::shared_math::b_field_element::BFieldElement;
 
     use crate::get_init_tvm_stack;
+    use crate::list::unsafeimplu32::push::UnsafePush;
     use crate::test_helpers::{
         test_rust_equivalence_given_input_values_deprecated,
         test_rust_equivalence_multiple_deprecated,
@@ -230,6 +231,51 @@ mod tests {
         // prop_pop::<16>(list_address, 20);
     }
 
+    #[test]
+    fn pushed_elements_are_popped_in_lifo_order() {
+        let list_address = BFieldElement::new(48);
+        for data_type in [
+            DataType::Bool,
+            DataType::U32,
+            DataType::U64,
+            DataType::BFE,
+            DataType::XFE,
+            DataType::Digest,
+        ] {
+            let mut memory = HashMap::default();
+            memory.insert(list_address, BFieldElement::zero());
+            let elements = data_type.random_elements(5);
+
+            for (i, element) in elements.iter().enumerate() {
+                let init_stack =
+                    [get_init_tvm_stack(), vec![list_address], element.clone()].concat();
+                test_rust_equivalence_given_input_values_deprecated(
+                    &UnsafePush(data_type.clone()),
+                    &init_stack,
+                    &[],
+                    &mut memory,
+                    0,
+                    Some(&get_init_tvm_stack()),
+                );
+                assert_eq!(BFieldElement::new(i as u64 + 1), memory[&list_address]);
+            }
+
+            for (i, element) in elements.iter().enumerate().rev() {
+                let init_stack = [get_init_tvm_stack(), vec![list_address]].concat();
+                let expected_end_stack = [get_init_tvm_stack(), element.clone()].concat();
+                test_rust_equivalence_given_input_values_deprecated(
+                    &UnsafePop(data_type.clone()),
+                    &init_stack,
+                    &[],
+                    &mut memory,
+                    0,
+                    Some(&expected_end_stack),
+                );
+                assert_eq!(BFieldElement::new(i as u64), memory[&list_address]);
+            }
+        }
+    }
+
     fn prop_pop<const N: usize>(
         data_type: DataType,
         list_address: BFieldElement,
@@ -3,7 +3,6 @@ use std::collections::HashMap;
 use num::One;
 use rand::{random, thread_rng, Rng};
 use twenty_first::shared_math::b_field_element::BFieldElement;
-use twenty_first::shared_math::other::random_elements;
 
 use crate::library::Library;
 use crate::rust_shadowing_helper_functions::unsafe_list::untyped_unsafe_insert_random_list;
@@ -170,7 +169,7 @@ fn prepare_state(data_type: &DataType) -> ExecutionState {
     let init_length: usize = thread_rng().gen_range(0..100);
     let mut stack = get_init_tvm_stack();
     stack.push(list_pointer);
-    let mut push_value: Vec<BFieldElement> = random_elements(data_type.get_size());
+    let mut push_value: Vec<BFieldElement> = data_type.random_element();
     while let Some(element) = push_value.pop() {
         stack.push(element);
     }
@@ -2,7 +2,6 @@ use std::collections::HashMap;
 
 use rand::{random, thread_rng, Rng};
 use twenty_first::shared_math::b_field_element::BFieldElement;
-use twenty_first::shared_math::other::random_elements;
 
 use crate::library::Library;
 use crate::rust_shadowing_helper_functions::unsafe_list::{
@@ -11,6 +10,10 @@ use crate::rust_shadowing_helper_functions::unsafe_list::{
 use crate::snippet::{DataType, DeprecatedSnippet};
 use crate::{get_init_tvm_stack, ExecutionState};
 
+/// Like its safe counterpart, but without a bounds check. The element with index `index`
+/// lives at `*list + 1 + index * element_size`, as unsafe lists store only their length
+/// in front of the elements. An out-of-range index overwrites whatever memory lies beyond
+/// the list.
 #[derive(Clone, Debug)]
 pub struct UnsafeSet(pub DataType);
 
@@ -42,6 +45,8 @@ impl DeprecatedSnippet for UnsafeSet {
     }
 
     fn crash_conditions(&self) -> Vec<String> {
+        // An out-of-range index does not crash the VM but silently corrupts memory outside
+        // of the list.
         vec![]
     }
 
@@ -140,7 +145,7 @@ fn prepare_state(data_type: &DataType) -> ExecutionState {
     let list_length: usize = thread_rng().gen_range(1..100);
     let index: usize = thread_rng().gen_range(0..list_length);
     let mut stack = get_init_tvm_stack();
-    let mut push_value: Vec<BFieldElement> = random_elements(data_type.get_size());
+    let mut push_value: Vec<BFieldElement> = data_type.random_element();
     while let Some(element) = push_value.pop() {
         stack.push(element);
     }
@@ -1,3 +1,5 @@
+pub mod dyn_free;
 pub mod dyn_malloc;
+pub mod dyn_malloc_aligned;
 pub mod memcpy;
 pub mod push_ram_to_stack;
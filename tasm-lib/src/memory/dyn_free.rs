@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::{
+    get_init_tvm_stack,
+    library::Library,
+    memory::dyn_malloc::DYN_MALLOC_FREE_LIST_ADDRESS,
+    rust_shadowing_helper_functions::dyn_malloc::dynamic_free,
+    snippet::{DataType, DeprecatedSnippet},
+    ExecutionState,
+};
+
+/// Return a block of `size` words, previously obtained from `DynMalloc`, to the
+/// dynamic allocator. The block is pushed onto a free list whose nodes live inside the
+/// freed blocks themselves, and a later `DynMalloc` of exactly `size` words reuses it.
+/// Blocks of fewer than two words cannot hold a list node and are not reused.
+#[derive(Clone, Debug)]
+pub struct DynFree;
+
+impl DynFree {
+    fn init_state(pointer: u64, size: u64, free_list_head: Option<u64>) -> ExecutionState {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(pointer));
+        stack.push(BFieldElement::new(size));
+
+        let mut memory = HashMap::new();
+        if let Some(head) = free_list_head {
+            memory.insert(
+                BFieldElement::new(DYN_MALLOC_FREE_LIST_ADDRESS),
+                BFieldElement::new(head),
+            );
+        }
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for DynFree {
+    fn entrypoint_name(&self) -> String {
+        "tasm_memory_dyn_free".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["*addr".to_string(), "size".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::VoidPointer, DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -2
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        format!(
+            "
+            // BEFORE: _ *addr size
+            // AFTER: _
+            {entrypoint}:
+                push 2
+                dup 1
+                lt                         // _ *addr size (size < 2)
+                push 0
+                eq
+                skiz
+                    call {entrypoint}_push_block
+
+                pop
+                pop
+                return
+
+            // BEFORE: _ *addr size
+            // AFTER: _ *addr size
+            {entrypoint}_push_block:
+                dup 1
+                push 1
+                add
+                dup 1
+                write_mem
+                pop                        // _ *addr size
+
+                push {DYN_MALLOC_FREE_LIST_ADDRESS}
+                read_mem
+                swap 1
+                pop                        // _ *addr size *old_head
+
+                dup 2
+                swap 1
+                write_mem
+                pop                        // _ *addr size
+
+                push {DYN_MALLOC_FREE_LIST_ADDRESS}
+                dup 2
+                write_mem
+                pop                        // _ *addr size
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let pointer = rng.gen_range(1..1u64 << 30);
+        let other_block = rng.gen_range(1..1u64 << 30);
+
+        vec![
+            Self::init_state(pointer, 0, None),
+            Self::init_state(pointer, 1, None),
+            Self::init_state(pointer, 2, None),
+            Self::init_state(pointer, rng.gen_range(2..1000), None),
+            Self::init_state(pointer, rng.gen_range(2..1000), Some(other_block)),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::init_state(1 << 20, 10, Some(1 << 10))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::init_state(1 << 20, 1 << 10, Some(1 << 10))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let size = stack.pop().unwrap();
+        let pointer = stack.pop().unwrap();
+        dynamic_free(pointer, size.value() as usize, memory);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::dyn_malloc::DynMalloc;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn dyn_free_test() {
+        test_rust_equivalence_multiple_deprecated(&DynFree, true);
+    }
+
+    /// Run `DynMalloc` on the given memory and return the allocated pointer.
+    fn malloc(size: u64, memory: &mut HashMap<BFieldElement, BFieldElement>) -> BFieldElement {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(size));
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &DynMalloc,
+            ExecutionState::with_stack_and_memory(stack, memory.clone(), 0),
+        );
+        *memory = vm_output_state.final_ram;
+        *vm_output_state.final_stack.last().unwrap()
+    }
+
+    /// Run `DynFree` on the given memory.
+    fn free(pointer: BFieldElement, size: u64, memory: &mut HashMap<BFieldElement, BFieldElement>) {
+        let mut stack = get_init_tvm_stack();
+        stack.push(pointer);
+        stack.push(BFieldElement::new(size));
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &DynFree,
+            ExecutionState::with_stack_and_memory(stack, memory.clone(), 0),
+        );
+        *memory = vm_output_state.final_ram;
+    }
+
+    #[test]
+    fn freed_block_of_same_size_is_reused() {
+        let mut memory = HashMap::new();
+        let first = malloc(10, &mut memory);
+        let _second = malloc(4, &mut memory);
+        free(first, 10, &mut memory);
+
+        assert_eq!(first, malloc(10, &mut memory));
+
+        // the block was taken off the free list
+        let third = malloc(10, &mut memory);
+        assert_ne!(first, third);
+    }
+
+    #[test]
+    fn size_mismatch_bumps_allocator() {
+        let mut memory = HashMap::new();
+        let first = malloc(10, &mut memory);
+        let second = malloc(4, &mut memory);
+        free(first, 10, &mut memory);
+
+        let third = malloc(9, &mut memory);
+        assert_eq!(second + BFieldElement::new(4), third);
+
+        // the freed block is still available
+        assert_eq!(first, malloc(10, &mut memory));
+    }
+
+    #[test]
+    fn free_list_is_searched_past_its_head() {
+        let mut memory = HashMap::new();
+        let first = malloc(10, &mut memory);
+        let second = malloc(5, &mut memory);
+        free(first, 10, &mut memory);
+        free(second, 5, &mut memory);
+
+        assert_eq!(first, malloc(10, &mut memory));
+        assert_eq!(second, malloc(5, &mut memory));
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn dyn_free_benchmark() {
+        bench_and_write(DynFree);
+    }
+}
@@ -1,15 +1,20 @@
 use std::collections::HashMap;
 
-use num::{One, Zero};
 use rand::Rng;
 use triton_vm::{instruction::LabelledInstruction, triton_instr};
 use twenty_first::shared_math::b_field_element::{BFieldElement, BFIELD_ZERO};
 
 pub const DYN_MALLOC_ADDRESS: u32 = 0;
 
+/// Address of the head of the list of blocks returned by `DynFree`. It is the largest
+/// address, far beyond the 2^32 words that the static and dynamic allocators can hand
+/// out, and beyond where tests place their data. A value of 0 denotes the empty list.
+pub const DYN_MALLOC_FREE_LIST_ADDRESS: u64 = BFieldElement::MAX;
+
 use crate::{
     get_init_tvm_stack,
     library::Library,
+    rust_shadowing_helper_functions::dyn_malloc::dynamic_allocator,
     snippet::{DataType, DeprecatedSnippet},
     ExecutionState,
 };
@@ -61,45 +66,149 @@ impl DeprecatedSnippet for DynMalloc {
         let entrypoint = self.entrypoint_name();
         format!(
             "
-            // Return a pointer to a free address and allocate `size` words for this pointer
+            // Return a pointer to a free address and allocate `size` words for this pointer.
+            // A freed block of exactly `size` words is reused, if one exists. Allocating zero
+            // words returns the current free pointer without modifying memory.
 
             // Before: _ size
             // After: _ *next_addr
             {entrypoint}:
-                push {DYN_MALLOC_ADDRESS}  // _ size *free_pointer
-                read_mem                   // _ size *free_pointer *next_addr'
-
-                // add 1 iff `next_addr` was 0, i.e. uninitialized.
-                dup 0                      // _ size *free_pointer *next_addr' *next_addr'
-                push 0                     // _ size *free_pointer *next_addr' *next_addr' 0
-                eq                         // _ size *free_pointer *next_addr' (*next_addr' == 0)
-                add                        // _ size *free_pointer *next_addr
+                push 0
+                push {DYN_MALLOC_FREE_LIST_ADDRESS}
+                read_mem
+                swap 1
+                pop                        // _ size 0 *head
 
-                dup 0                      // _ size *free_pointer *next_addr *next_addr
-                dup 3                      // _ size *free_pointer *next_addr *next_addr size
+                // Only walk the free list if something was ever freed.
+                skiz
+                    call {entrypoint}_take_free_block
+                // _ size *block, where `*block` is 0 if no block of size `size` was freed
 
-                // Ensure that `size` does not exceed 2^32
-                split
-                swap 1
+                dup 0
                 push 0
                 eq
-                assert
+                skiz
+                    call {entrypoint}_allocate_fresh
+                // _ size *next_addr
 
-                add                        // _ size *free_pointer *next_addr *(next_addr + size)
+                swap 1
+                pop
+                return
 
-                // Ensure that no more than 2^32 words are allocated, because I don't want a wrap-around
-                // in the address space
-                split
+            // BEFORE: _ size 0
+            // AFTER: _ size *block, where `*block` is 0 if no block of size `size` exists
+            {entrypoint}_take_free_block:
+                pop
+                push {DYN_MALLOC_FREE_LIST_ADDRESS}
+                call {entrypoint}_find_free_block
+                // _ size *link *block
+
+                dup 0
+                skiz
+                    call {entrypoint}_unlink
+                swap 1
+                pop
+                return
+
+            // Walk the free list. Every freed block stores the pointer to the next block in
+            // its first word and its size in its second word.
+            // INVARIANT: _ size *link
+            // AFTER: _ size *link *block, where `*block` is 0 if no block of size `size` exists
+            {entrypoint}_find_free_block:
+                dup 0
+                read_mem
                 swap 1
+                pop                        // _ size *link *block
+
+                dup 0
                 push 0
                 eq
-                assert
+                skiz
+                    return
+
+                dup 0
+                push 1
+                add
+                read_mem
+                swap 1
+                pop                        // _ size *link *block block_size
 
-                swap 1                     // _ size *free_pointer *(next_addr + size) *next_addr
-                swap 3                     // _ *next_addr *free_pointer *(next_addr + size) size
-                pop                        // _ *next_addr *free_pointer *(next_addr + size)
+                dup 3
+                eq
+                skiz
+                    return
+
+                swap 1
+                pop                        // _ size *block
+                recurse
+
+            // BEFORE: _ size *link *block
+            // AFTER: _ size *link *block
+            {entrypoint}_unlink:
+                dup 0
+                read_mem
+                swap 1
+                pop                        // _ size *link *block *next_block
+
+                dup 2
+                swap 1
                 write_mem
-                pop                        // _ next_addr
+                pop                        // _ size *link *block
+                return
+
+            // BEFORE: _ size 0
+            // AFTER: _ size *next_addr
+            {entrypoint}_allocate_fresh:
+                pop                        // _ size
+                dup 0                      // _ size size
+                push {DYN_MALLOC_ADDRESS}  // _ size size *free_pointer
+                read_mem                   // _ size size *free_pointer *next_addr'
+
+                // add 1 iff `next_addr` was 0, i.e. uninitialized.
+                dup 0                      // _ size size *free_pointer *next_addr' *next_addr'
+                push 0                     // _ size size *free_pointer *next_addr' *next_addr' 0
+                eq                         // _ size size *free_pointer *next_addr' (*next_addr' == 0)
+                add                        // _ size size *free_pointer *next_addr
+
+                dup 0                      // _ size size *free_pointer *next_addr *next_addr
+                dup 3                      // _ size size *free_pointer *next_addr *next_addr size
+
+                // Ensure that `size` does not exceed 2^32
+                split                      // _ size size *free_pointer *next_addr *next_addr size_hi size_lo
+                swap 1                     // _ size size *free_pointer *next_addr *next_addr size_lo size_hi
+                push 0                     // _ size size *free_pointer *next_addr *next_addr size_lo size_hi 0
+                eq                         // _ size size *free_pointer *next_addr *next_addr size_lo (size_hi == 0)
+                assert                     // _ size size *free_pointer *next_addr *next_addr size
+
+                add                        // _ size size *free_pointer *next_addr *(next_addr + size)
+
+                // Ensure that no more than 2^32 words are allocated, because I don't want a wrap-around
+                // in the address space
+                split                      // _ size size *free_pointer *next_addr end_hi end_lo
+                swap 1                     // _ size size *free_pointer *next_addr end_lo end_hi
+                push 0                     // _ size size *free_pointer *next_addr end_lo end_hi 0
+                eq                         // _ size size *free_pointer *next_addr end_lo (end_hi == 0)
+                assert                     // _ size size *free_pointer *next_addr *(next_addr + size)
+
+                swap 1                     // _ size size *free_pointer *(next_addr + size) *next_addr
+                swap 3                     // _ size *next_addr *free_pointer *(next_addr + size) size
+
+                // A zero-size allocation leaves the allocator untouched.
+                skiz
+                    call {entrypoint}_write_free_pointer
+                // _ size *next_addr *free_pointer *(next_addr + size)
+
+                pop                        // _ size *next_addr *free_pointer
+                pop                        // _ size *next_addr
+                return
+
+            // BEFORE: _ *free_pointer *(next_addr + size)
+            // AFTER: _ *free_pointer *(next_addr + size)
+            {entrypoint}_write_free_pointer:
+                dup 1                      // _ *free_pointer *(next_addr + size) *free_pointer
+                dup 1                      // _ *free_pointer *(next_addr + size) *free_pointer *(next_addr + size)
+                write_mem                  // _ *free_pointer *(next_addr + size) *free_pointer
+                pop                        // _ *free_pointer *(next_addr + size)
                 return
             "
         )
@@ -136,26 +245,13 @@ impl DeprecatedSnippet for DynMalloc {
         _secret_in: Vec<BFieldElement>,
         memory: &mut HashMap<BFieldElement, BFieldElement>,
     ) {
-        let allocator_addr = BFIELD_ZERO;
-        let used_memory = memory
-            .entry(allocator_addr)
-            .and_modify(|e| {
-                *e = if e.is_zero() {
-                    BFieldElement::one()
-                } else {
-                    *e
-                }
-            })
-            .or_insert_with(BFieldElement::one);
-
         let size = stack.pop().unwrap();
         assert!(size.value() < (1u64 << 32));
 
-        let next_addr = *used_memory;
-
+        let next_addr = dynamic_allocator(size.value() as usize, memory);
         stack.push(next_addr);
-        *used_memory += size;
 
+        let used_memory = memory.get(&BFIELD_ZERO).copied().unwrap_or(BFIELD_ZERO);
         assert!(used_memory.value() < (1u64 << 32));
     }
 
@@ -174,7 +270,14 @@ impl DeprecatedSnippet for DynMalloc {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+    use num::{One, Zero};
+
+    use crate::rust_shadowing_helper_functions::dyn_malloc::rust_dyn_malloc_initialize;
+    use crate::test_helpers::{
+        assert_tasm_snapshot, test_crash_conditions,
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
 
     use super::*;
 
@@ -189,6 +292,11 @@ mod tests {
         test_rust_equivalence_multiple_deprecated(&DynMalloc, true);
     }
 
+    #[test]
+    fn dyn_malloc_matches_snapshot() {
+        assert_tasm_snapshot(&DynMalloc, "snapshots/tasm_memory_dyn_malloc.tasm");
+    }
+
     #[test]
     fn unit_test() {
         let mut init_stack = get_init_tvm_stack();
@@ -202,6 +310,101 @@ mod tests {
         DynMalloc.link_and_run_tasm_from_state_for_test(&mut non_empty_memory_state);
         assert_eq!(100, non_empty_memory_state.stack.pop().unwrap().value());
     }
+
+    #[test]
+    fn zero_size_allocation_leaves_allocator_unchanged() {
+        let allocator_addr = BFieldElement::new(DYN_MALLOC_ADDRESS as u64);
+        let stack = [get_init_tvm_stack(), vec![BFieldElement::zero()]].concat();
+
+        for (words_allocated, expected_pointer) in [(0, 1), (100, 100)] {
+            // TASM, checked against the Rust shadowing
+            let init_state =
+                ExecutionState::with_stack_and_memory(stack.clone(), HashMap::default(), 0);
+            let first_run = test_rust_equivalence_given_execution_state_deprecated(
+                &DynMalloc,
+                ExecutionState {
+                    words_allocated,
+                    ..init_state.clone()
+                },
+            );
+            let second_run = test_rust_equivalence_given_execution_state_deprecated(
+                &DynMalloc,
+                ExecutionState {
+                    memory: first_run.final_ram.clone(),
+                    ..init_state
+                },
+            );
+            let first_pointer = *first_run.final_stack.last().unwrap();
+            let second_pointer = *second_run.final_stack.last().unwrap();
+            assert_eq!(expected_pointer, first_pointer.value());
+            assert_eq!(first_pointer, second_pointer);
+            assert_eq!(
+                first_run.final_ram.get(&allocator_addr),
+                second_run.final_ram.get(&allocator_addr)
+            );
+
+            // Rust shadowing
+            let mut memory = HashMap::default();
+            if words_allocated > 0 {
+                rust_dyn_malloc_initialize(&mut memory, words_allocated);
+            }
+            let memory_before = memory.clone();
+            let mut rust_stack = get_init_tvm_stack();
+            for _ in 0..2 {
+                rust_stack.push(BFieldElement::zero());
+                DynMalloc.rust_shadowing(&mut rust_stack, vec![], vec![], &mut memory);
+                assert_eq!(expected_pointer, rust_stack.pop().unwrap().value());
+            }
+            assert_eq!(memory_before, memory);
+        }
+    }
+
+    #[test]
+    fn dyn_malloc_crash_conditions() {
+        let mut too_big_stack = get_init_tvm_stack();
+        too_big_stack.push(BFieldElement::new(1 << 32));
+
+        let mut overflowing_stack = get_init_tvm_stack();
+        overflowing_stack.push(BFieldElement::new(10));
+        let mut almost_full_memory = HashMap::default();
+        almost_full_memory.insert(
+            BFieldElement::new(DYN_MALLOC_ADDRESS as u64),
+            BFieldElement::new((1 << 32) - 5),
+        );
+
+        test_crash_conditions(
+            &DynMalloc,
+            vec![
+                (
+                    ExecutionState::with_stack(too_big_stack),
+                    "Caller attempts to allocate more than 2^32 words",
+                ),
+                (
+                    ExecutionState::with_stack_and_memory(overflowing_stack, almost_full_memory, 0),
+                    "More than 2^32 words allocated to memory",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn execution_cost_is_reported_and_stable() {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(10));
+        let init_state = ExecutionState::with_stack_and_memory(stack, HashMap::default(), 100);
+
+        let first_run =
+            test_rust_equivalence_given_execution_state_deprecated(&DynMalloc, init_state.clone());
+        let second_run =
+            test_rust_equivalence_given_execution_state_deprecated(&DynMalloc, init_state);
+        assert!(first_run.clock_cycle_count > 0);
+        assert!(first_run.hash_table_height > 0);
+        assert!(first_run.u32_table_height > 0);
+
+        assert_eq!(first_run.clock_cycle_count, second_run.clock_cycle_count);
+        assert_eq!(first_run.hash_table_height, second_run.hash_table_height);
+        assert_eq!(first_run.u32_table_height, second_run.u32_table_height);
+    }
 }
 
 #[cfg(test)]
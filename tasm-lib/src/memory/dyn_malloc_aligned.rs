@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::{
+    get_init_tvm_stack,
+    library::Library,
+    memory::dyn_malloc::DYN_MALLOC_ADDRESS,
+    rust_shadowing_helper_functions::dyn_malloc::dynamic_allocator_aligned,
+    snippet::{DataType, DeprecatedSnippet},
+    ExecutionState,
+};
+
+/// Allocate `size` words starting at an address that is a multiple of `alignment`,
+/// which must be a power of two. The free pointer of `DynMalloc` is rounded up to the
+/// requested alignment before it is bumped; the words skipped by the rounding are lost.
+/// Blocks on `DynFree`'s free list are not reused, since they need not be aligned.
+/// Allocating zero words returns the rounded-up free pointer without modifying memory.
+#[derive(Clone, Debug)]
+pub struct DynMallocAligned;
+
+impl DynMallocAligned {
+    fn init_state(size: u64, alignment: u64, free_pointer: Option<u64>) -> ExecutionState {
+        let mut stack = get_init_tvm_stack();
+        stack.push(BFieldElement::new(size));
+        stack.push(BFieldElement::new(alignment));
+
+        let mut memory = HashMap::new();
+        if let Some(free_pointer) = free_pointer {
+            memory.insert(
+                BFieldElement::new(DYN_MALLOC_ADDRESS as u64),
+                BFieldElement::new(free_pointer),
+            );
+        }
+
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+}
+
+impl DeprecatedSnippet for DynMallocAligned {
+    fn entrypoint_name(&self) -> String {
+        "tasm_memory_dyn_malloc_aligned".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["size".to_string(), "alignment".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32, DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["*addr".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U32]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        format!(
+            "
+            // BEFORE: _ size alignment
+            // AFTER: _ *addr
+            {entrypoint}:
+                // Ensure that `alignment` is a power of two
+                dup 0
+                push 0
+                eq
+                push 0
+                eq
+                assert
+                dup 0
+                push -1
+                add
+                dup 1
+                and
+                push 0
+                eq
+                assert                     // _ size alignment
+
+                push {DYN_MALLOC_ADDRESS}
+                read_mem                   // _ size alignment *free_pointer *next_addr'
+
+                // add 1 iff `next_addr` was 0, i.e. uninitialized.
+                dup 0
+                push 0
+                eq
+                add                        // _ size alignment *free_pointer *next_addr
+
+                dup 2
+                push -1
+                add                        // _ size alignment *free_pointer *next_addr mask
+                dup 1
+                dup 1
+                and                        // _ size alignment *free_pointer *next_addr mask remainder
+                push -1
+                mul
+                dup 4
+                add                        // _ size alignment *free_pointer *next_addr mask (alignment - remainder)
+                and
+                add                        // _ size alignment *free_pointer *aligned_addr
+
+                dup 0
+                dup 4
+
+                // Ensure that `size` does not exceed 2^32
+                split
+                swap 1
+                push 0
+                eq
+                assert
+
+                add                        // _ size alignment *free_pointer *aligned_addr *(aligned_addr + size)
+
+                // Ensure that no more than 2^32 words are allocated
+                split
+                swap 1
+                push 0
+                eq
+                assert
+
+                swap 1
+                swap 4                     // _ *aligned_addr alignment *free_pointer *(aligned_addr + size) size
+
+                // A zero-size allocation leaves the allocator untouched.
+                skiz
+                    call {entrypoint}_write_free_pointer
+                pop
+                pop
+                pop                        // _ *aligned_addr
+                return
+
+            // BEFORE: _ *free_pointer *(aligned_addr + size)
+            // AFTER: _ *free_pointer *(aligned_addr + size)
+            {entrypoint}_write_free_pointer:
+                dup 1
+                dup 1
+                write_mem
+                pop
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "Alignment is not a power of two".to_owned(),
+            "Caller attempts to allocate more than 2^32 words".to_owned(),
+            "More than 2^32 words allocated to memory".to_owned(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+
+        let mut states = vec![];
+        for log_alignment in [0, 1, 3, 6] {
+            let alignment = 1 << log_alignment;
+            let size = rng.gen_range(0..10_000);
+            states.push(Self::init_state(size, alignment, None));
+            states.push(Self::init_state(
+                size,
+                alignment,
+                Some(rng.gen_range(1..10_000)),
+            ));
+        }
+
+        states
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::init_state(10, 8, Some(1000))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::init_state(1 << 30, 1 << 20, Some(1000))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let alignment = stack.pop().unwrap().value() as usize;
+        let size = stack.pop().unwrap().value() as usize;
+
+        let next_addr = dynamic_allocator_aligned(size, alignment, memory);
+        stack.push(next_addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::dyn_malloc::DynMalloc;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn dyn_malloc_aligned_test() {
+        test_rust_equivalence_multiple_deprecated(&DynMallocAligned, true);
+    }
+
+    #[test]
+    fn alignment_one_is_plain_malloc() {
+        for free_pointer in [None, Some(13)] {
+            let aligned = test_rust_equivalence_given_execution_state_deprecated(
+                &DynMallocAligned,
+                DynMallocAligned::init_state(10, 1, free_pointer),
+            );
+
+            let mut stack = get_init_tvm_stack();
+            stack.push(BFieldElement::new(10));
+            let mut memory = HashMap::new();
+            if let Some(free_pointer) = free_pointer {
+                memory.insert(
+                    BFieldElement::new(DYN_MALLOC_ADDRESS as u64),
+                    BFieldElement::new(free_pointer),
+                );
+            }
+            let plain = test_rust_equivalence_given_execution_state_deprecated(
+                &DynMalloc,
+                ExecutionState::with_stack_and_memory(stack, memory, 0),
+            );
+
+            assert_eq!(
+                plain.final_stack.last().unwrap(),
+                aligned.final_stack.last().unwrap()
+            );
+            assert_eq!(plain.final_ram, aligned.final_ram);
+        }
+    }
+
+    #[test]
+    fn alignment_eight_from_unaligned_start() {
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &DynMallocAligned,
+            DynMallocAligned::init_state(10, 8, Some(13)),
+        );
+
+        assert_eq!(16, vm_output_state.final_stack.last().unwrap().value());
+        assert_eq!(
+            26,
+            vm_output_state.final_ram[&BFieldElement::new(DYN_MALLOC_ADDRESS as u64)].value()
+        );
+    }
+
+    #[test]
+    fn aligned_start_is_not_bumped() {
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &DynMallocAligned,
+            DynMallocAligned::init_state(10, 8, Some(24)),
+        );
+
+        assert_eq!(24, vm_output_state.final_stack.last().unwrap().value());
+    }
+
+    #[test]
+    fn zero_size_allocation_leaves_allocator_unchanged() {
+        for (free_pointer, expected_pointer) in [(None, 8), (Some(13), 16), (Some(24), 24)] {
+            let init_state = DynMallocAligned::init_state(0, 8, free_pointer);
+            let init_memory = init_state.memory.clone();
+            let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+                &DynMallocAligned,
+                init_state,
+            );
+
+            assert_eq!(
+                expected_pointer,
+                vm_output_state.final_stack.last().unwrap().value()
+            );
+            assert_eq!(init_memory, vm_output_state.final_ram);
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn alignment_not_power_of_two() {
+        test_rust_equivalence_given_execution_state_deprecated(
+            &DynMallocAligned,
+            DynMallocAligned::init_state(10, 6, Some(13)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn dyn_malloc_aligned_benchmark() {
+        bench_and_write(DynMallocAligned);
+    }
+}
@@ -1,18 +1,231 @@
+use std::collections::HashMap;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
 pub mod bag_peaks;
 pub mod calculate_new_peaks_from_append;
 pub mod calculate_new_peaks_from_leaf_mutation;
 pub mod data_index_to_node_index;
 pub mod get_height_from_data_index;
+pub mod leaf_count_to_num_peaks;
 pub mod leaf_index_to_mt_index;
 pub mod left_child;
 pub mod leftmost_ancestor;
 pub mod load_from_secret_in_then_verify;
+pub mod new;
+pub mod node_index_to_leaf_index;
 pub mod non_leaf_nodes_left;
+pub mod peaks_consistency;
 pub mod right_child;
 pub mod right_child_and_height;
+pub mod right_child_and_height_iterative;
 pub mod right_lineage_count_and_own_height;
 pub mod right_lineage_length;
+pub mod verify_batch_append;
 pub mod verify_from_memory;
 pub mod verify_from_secret_in;
 
 pub const MAX_MMR_HEIGHT: usize = 64;
+
+/// Count one iteration of a loop and crash the VM if the loop has run for more than
+/// `max_iterations` iterations. Loops in MMR snippets terminate after at most
+/// [`MAX_MMR_HEIGHT`] iterations on well-formed input, so guarding them bounds the
+/// number of cycles spent on malformed input.
+#[derive(Clone, Debug)]
+pub struct MmrLoopGuard {
+    pub max_iterations: usize,
+}
+
+impl MmrLoopGuard {
+    fn prepare_state(iteration_count: usize) -> ExecutionState {
+        let stack = [
+            get_init_tvm_stack(),
+            vec![BFieldElement::new(iteration_count as u64)],
+        ]
+        .concat();
+        ExecutionState::with_stack(stack)
+    }
+}
+
+impl DeprecatedSnippet for MmrLoopGuard {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_mmr_loop_guard_{}", self.max_iterations)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["iteration_count".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U32]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["iteration_count + 1".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U32]
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn function_code(&self, _library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let max_iterations_plus_one = self.max_iterations + 1;
+        format!(
+            "
+            // BEFORE: _ iteration_count
+            // AFTER: _ (iteration_count + 1)
+            {entrypoint}:
+                push 1
+                add
+                push {max_iterations_plus_one}
+                dup 1
+                lt
+                assert
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["loop exceeded its maximum number of iterations".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        vec![
+            Self::prepare_state(0),
+            Self::prepare_state(self.max_iterations / 2),
+            Self::prepare_state(self.max_iterations - 1),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(self.max_iterations / 2)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        Self::prepare_state(self.max_iterations - 1)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let iteration_count = stack.pop().unwrap().value() as usize + 1;
+        assert!(
+            iteration_count <= self.max_iterations,
+            "loop exceeded its maximum number of iterations"
+        );
+        stack.push(BFieldElement::new(iteration_count as u64));
+    }
+}
+
+// The following functions return the code for guarding a loop with an `MmrLoopGuard`,
+// or no code at all if the loop is unbounded. The iteration counter lives on the stack,
+// right below the loop's state.
+
+/// Insert an iteration counter of 0 below the top `elements_above_counter` elements.
+pub fn loop_guard_init(loop_bound: Option<usize>, elements_above_counter: usize) -> String {
+    if loop_bound.is_none() {
+        return String::default();
+    }
+
+    let move_counter_down = (1..=elements_above_counter)
+        .rev()
+        .map(|depth| format!("swap {depth}\n"))
+        .collect::<String>();
+    format!("push 0\n{move_counter_down}")
+}
+
+/// Count one iteration, crashing if the loop bound is exceeded. The counter must be at
+/// stack depth `counter_depth`.
+pub fn loop_guard_tick(
+    library: &mut Library,
+    loop_bound: Option<usize>,
+    counter_depth: usize,
+) -> String {
+    let max_iterations = match loop_bound {
+        Some(max_iterations) => max_iterations,
+        None => return String::default(),
+    };
+
+    let guard = library.import(Box::new(MmrLoopGuard { max_iterations }));
+    format!("swap {counter_depth}\ncall {guard}\nswap {counter_depth}\n")
+}
+
+/// Remove the iteration counter from below the top `elements_above_counter` elements.
+pub fn loop_guard_cleanup(loop_bound: Option<usize>, elements_above_counter: usize) -> String {
+    if loop_bound.is_none() {
+        return String::default();
+    }
+
+    let restore_order = (1..elements_above_counter)
+        .map(|depth| format!("swap {depth}\n"))
+        .collect::<String>();
+    format!("swap {elements_above_counter}\npop\n{restore_order}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::{test_crash_conditions, test_rust_equivalence_multiple_deprecated};
+
+    #[test]
+    fn mmr_loop_guard_test() {
+        for max_iterations in [1, 10, MAX_MMR_HEIGHT] {
+            test_rust_equivalence_multiple_deprecated(&MmrLoopGuard { max_iterations }, true);
+        }
+    }
+
+    #[test]
+    fn mmr_loop_guard_crashes_after_max_iterations() {
+        test_crash_conditions(
+            &MmrLoopGuard {
+                max_iterations: MAX_MMR_HEIGHT,
+            },
+            vec![
+                (
+                    MmrLoopGuard::prepare_state(MAX_MMR_HEIGHT),
+                    "loop exceeded its maximum number of iterations",
+                ),
+                (
+                    MmrLoopGuard::prepare_state(2 * MAX_MMR_HEIGHT),
+                    "loop exceeded its maximum number of iterations",
+                ),
+            ],
+        );
+    }
+
+    #[test]
+    fn unbounded_loops_get_no_guard() {
+        let mut library = Library::new();
+        assert!(loop_guard_init(None, 4).is_empty());
+        assert!(loop_guard_tick(&mut library, None, 4).is_empty());
+        assert!(loop_guard_cleanup(None, 2).is_empty());
+        assert!(library.get_all_snippet_names().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn mmr_loop_guard_benchmark() {
+        bench_and_write(MmrLoopGuard {
+            max_iterations: MAX_MMR_HEIGHT,
+        });
+    }
+}
@@ -13,11 +13,17 @@ use crate::{
     Digest, ExecutionState, VmHasher, DIGEST_LENGTH,
 };
 
+/// Bag the peaks of an MMR, stored in an unsafe list, into a single digest. Follows
+/// twenty-first's convention: no peaks bag to the hash of the empty sequence, a single
+/// peak is its own bag, and otherwise peaks are folded right-to-left with `hash_pair`.
 pub struct BagPeaks;
 
 impl BagPeaks {
     fn input_state(num_peaks: usize) -> ExecutionState {
-        let peaks: Vec<Digest> = random_elements(num_peaks);
+        Self::input_state_from_peaks(random_elements(num_peaks))
+    }
+
+    fn input_state_from_peaks(peaks: Vec<Digest>) -> ExecutionState {
         let address: BFieldElement = random();
         let mut stack = get_init_tvm_stack();
         stack.push(address);
@@ -249,14 +255,59 @@ impl DeprecatedSnippet for BagPeaks {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_helpers::test_rust_equivalence_multiple_deprecated;
+    use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+    use twenty_first::util_types::mmr::mmr_trait::Mmr;
 
-    use super::BagPeaks;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
 
     #[test]
     fn new_prop_test() {
         test_rust_equivalence_multiple_deprecated(&BagPeaks, true);
     }
+
+    #[test]
+    fn bag_no_peaks() {
+        prop_bag_peaks(vec![], bag_peaks::<VmHasher>(&[]));
+    }
+
+    #[test]
+    fn bag_one_peak() {
+        let peak: Digest = random();
+        prop_bag_peaks(vec![peak], peak);
+    }
+
+    #[test]
+    fn bag_two_peaks() {
+        let peaks: Vec<Digest> = random_elements(2);
+        let expected = bag_peaks::<VmHasher>(&peaks);
+        prop_bag_peaks(peaks, expected);
+    }
+
+    #[test]
+    fn bag_peaks_of_accumulator() {
+        // 1337 leaves yield an accumulator with six peaks
+        let mmra = MmrAccumulator::<VmHasher>::new(random_elements(1337));
+        prop_bag_peaks(mmra.get_peaks(), mmra.bag_peaks());
+    }
+
+    fn prop_bag_peaks(peaks: Vec<Digest>, expected: Digest) {
+        let vm_output_state = test_rust_equivalence_given_execution_state_deprecated(
+            &BagPeaks,
+            BagPeaks::input_state_from_peaks(peaks),
+        );
+        let final_stack = vm_output_state.final_stack;
+        let bag = final_stack[final_stack.len() - DIGEST_LENGTH..]
+            .iter()
+            .rev()
+            .copied()
+            .collect_vec();
+        assert_eq!(expected, Digest::new(bag.try_into().unwrap()));
+    }
 }
 
 #[cfg(test)]
@@ -8,7 +8,7 @@ use twenty_first::util_types::mmr;
 use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
 use twenty_first::util_types::mmr::mmr_trait::Mmr;
 
-use super::MAX_MMR_HEIGHT;
+use super::{loop_guard_cleanup, loop_guard_init, loop_guard_tick, MAX_MMR_HEIGHT};
 use crate::arithmetic::u64::incr_u64::IncrU64;
 use crate::arithmetic::u64::index_of_last_nonzero_bit::IndexOfLastNonZeroBitU64;
 use crate::library::Library;
@@ -27,9 +27,12 @@ use crate::{
     DIGEST_LENGTH,
 };
 
+/// If `loop_bound` is set, the VM crashes if more than that many peaks are merged, see
+/// `MmrLoopGuard`.
 #[derive(Clone, Debug)]
 pub struct CalculateNewPeaksFromAppend {
     pub list_type: ListType,
+    pub loop_bound: Option<usize>,
 }
 
 impl CalculateNewPeaksFromAppend {
@@ -133,7 +136,10 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
     }
 
     fn crash_conditions(&self) -> Vec<String> {
-        vec!["Snippet arguments are not a valid MMR accumulator".to_string()]
+        vec![
+            "Snippet arguments are not a valid MMR accumulator".to_string(),
+            "loop exceeded its maximum number of iterations".to_string(),
+        ]
     }
 
     fn gen_input_states(&self) -> Vec<ExecutionState> {
@@ -157,10 +163,16 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
     }
 
     fn entrypoint_name(&self) -> String {
-        format!(
-            "tasm_mmr_calculate_new_peaks_from_append_{}",
-            self.list_type
-        )
+        match self.loop_bound {
+            Some(loop_bound) => format!(
+                "tasm_mmr_calculate_new_peaks_from_append_{}_bounded_{loop_bound}",
+                self.list_type
+            ),
+            None => format!(
+                "tasm_mmr_calculate_new_peaks_from_append_{}",
+                self.list_type
+            ),
+        }
     }
 
     fn function_code(&self, library: &mut Library) -> String {
@@ -183,6 +195,9 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
         };
         let u64incr = library.import(Box::new(IncrU64));
         let right_lineage_count = library.import(Box::new(IndexOfLastNonZeroBitU64));
+        let init_loop_guard = loop_guard_init(self.loop_bound, 5);
+        let count_iteration = loop_guard_tick(library, self.loop_bound, 5);
+        let cleanup_loop_guard = loop_guard_cleanup(self.loop_bound, 2);
 
         // Allocate memory for the returned auth path for the newly inserted element
         // Warning: This auth path is only allocated *once* even though the code is called multiple times.
@@ -213,11 +228,13 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
                     call {u64incr}
                     call {right_lineage_count}
 
+                    {init_loop_guard}
                     call {entrypoint}_while
                     // stack: _ old_leaf_count_hi old_leaf_count_lo *auth_path *peaks (rll = 0)
 
                     pop
                     swap 3 pop swap 1 pop
+                    {cleanup_loop_guard}
                     // stack: _ *peaks *auth_path
 
                     return
@@ -229,6 +246,7 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
                     eq
                     skiz
                         return
+                    {count_iteration}
                     // Stack: _ old_leaf_count_hi old_leaf_count_lo *auth_path *peaks rll
 
                     swap 2 swap 1
@@ -347,6 +365,12 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
         let new_node_index = mmr::shared_advanced::leaf_index_to_node_index(old_leaf_count);
         let (mut right_lineage_count, _height) =
             mmr::shared_advanced::right_lineage_length_and_own_height(new_node_index);
+        if let Some(loop_bound) = self.loop_bound {
+            assert!(
+                right_lineage_count as usize <= loop_bound,
+                "loop exceeded its maximum number of iterations"
+            );
+        }
         while right_lineage_count != 0 {
             let new_hash = Digest::new(
                 list_pop(peaks_pointer, memory, DIGEST_LENGTH)
@@ -397,6 +421,7 @@ impl DeprecatedSnippet for CalculateNewPeaksFromAppend {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use num::Zero;
     use twenty_first::shared_math::b_field_element::BFieldElement;
     use twenty_first::shared_math::other::random_elements;
@@ -405,8 +430,9 @@ mod tests {
     use twenty_first::util_types::mmr::mmr_membership_proof::MmrMembershipProof;
     use twenty_first::util_types::mmr::mmr_trait::Mmr;
 
+    use crate::rust_shadowing_helper_functions::{list_to_vec, vec_to_list};
     use crate::test_helpers::{
-        test_rust_equivalence_given_input_values_deprecated,
+        test_crash_conditions, test_rust_equivalence_given_input_values_deprecated,
         test_rust_equivalence_multiple_deprecated,
     };
     use crate::{get_init_tvm_stack, VmHasher};
@@ -420,6 +446,7 @@ mod tests {
         test_rust_equivalence_multiple_deprecated(
             &CalculateNewPeaksFromAppend {
                 list_type: ListType::Unsafe,
+                loop_bound: None,
             },
             true,
         );
@@ -430,11 +457,47 @@ mod tests {
         test_rust_equivalence_multiple_deprecated(
             &CalculateNewPeaksFromAppend {
                 list_type: ListType::Safe,
+                loop_bound: None,
             },
             true,
         );
     }
 
+    #[test]
+    fn calculate_new_peaks_from_append_with_loop_bound() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            test_rust_equivalence_multiple_deprecated(
+                &CalculateNewPeaksFromAppend {
+                    list_type,
+                    loop_bound: Some(MAX_MMR_HEIGHT),
+                },
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn loop_guard_fires_when_merging_more_peaks_than_loop_bound() {
+        // An MMR with fewer than 2^32 leaves never merges more than 32 peaks on append.
+        // Appending to an MMR with 2^40 - 1 leaves merges 40 peaks, exceeding that bound.
+        let num_merges = 40;
+        let peaks: Vec<Digest> = random_elements(num_merges);
+        let mmra = MmrAccumulator::init(peaks, (1 << num_merges) - 1);
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            let snippet = CalculateNewPeaksFromAppend {
+                list_type,
+                loop_bound: Some(32),
+            };
+            test_crash_conditions(
+                &snippet,
+                vec![(
+                    snippet.prepare_state_with_mmra(mmra.clone(), random()),
+                    "loop exceeded its maximum number of iterations",
+                )],
+            );
+        }
+    }
+
     #[test]
     fn mmr_sanity_check_new_and_init() {
         for mmr_size in [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 14, 100, 1000] {
@@ -615,34 +678,18 @@ mod tests {
         // Initialize memory
         let mut memory: HashMap<BFieldElement, BFieldElement> = HashMap::default();
 
-        match list_type {
-            ListType::Safe => rust_shadowing_helper_functions::safe_list::safe_list_new(
-                peaks_pointer,
-                MAX_MMR_HEIGHT as u32,
-                &mut memory,
-            ),
-            ListType::Unsafe => rust_shadowing_helper_functions::unsafe_list::unsafe_list_new(
-                peaks_pointer,
-                &mut memory,
-            ),
-        }
-
-        let list_push = match list_type {
-            ListType::Safe => rust_shadowing_helper_functions::safe_list::safe_list_push,
-            ListType::Unsafe => rust_shadowing_helper_functions::unsafe_list::unsafe_list_push,
-        };
-        let list_get = match list_type {
-            ListType::Safe => rust_shadowing_helper_functions::safe_list::safe_list_get,
-            ListType::Unsafe => rust_shadowing_helper_functions::unsafe_list::unsafe_list_get,
-        };
-        for peak in start_mmr.get_peaks() {
-            list_push(
-                peaks_pointer,
-                peak.values().to_vec(),
-                &mut memory,
-                DIGEST_LENGTH,
-            );
-        }
+        let start_peaks = start_mmr
+            .get_peaks()
+            .iter()
+            .map(|peak| peak.values())
+            .collect_vec();
+        vec_to_list(
+            peaks_pointer,
+            MAX_MMR_HEIGHT as u32,
+            &start_peaks,
+            &mut memory,
+            &list_type,
+        );
 
         let words_allocated = match list_type {
             ListType::Safe => 1 + MAX_MMR_HEIGHT * DIGEST_LENGTH + 2,
@@ -654,7 +701,10 @@ mod tests {
         expected_final_stack.push(auth_paths_pointer);
 
         test_rust_equivalence_given_input_values_deprecated(
-            &CalculateNewPeaksFromAppend { list_type },
+            &CalculateNewPeaksFromAppend {
+                list_type,
+                loop_bound: None,
+            },
             &init_stack,
             &[],
             &mut memory,
@@ -663,16 +713,10 @@ mod tests {
         );
 
         // Find produced MMR
-        let peaks_count = memory[&peaks_pointer].value();
-        let mut produced_peaks = vec![];
-        for i in 0..peaks_count {
-            let peak = Digest::new(
-                list_get(peaks_pointer, i as usize, &memory, DIGEST_LENGTH)
-                    .try_into()
-                    .unwrap(),
-            );
-            produced_peaks.push(peak);
-        }
+        let produced_peaks = list_to_vec::<DIGEST_LENGTH>(peaks_pointer, &memory, &list_type)
+            .into_iter()
+            .map(Digest::new)
+            .collect_vec();
 
         let produced_mmr: Mmra = MmrAccumulator::init(produced_peaks, start_mmr.count_leaves() + 1);
 
@@ -680,15 +724,11 @@ mod tests {
         assert_eq!(expected_mmr, produced_mmr);
 
         // Verify that produced auth paths are valid
-        let auth_path_element_count = memory[&auth_paths_pointer].value();
-        let mut produced_auth_path = vec![];
-        for i in 0..auth_path_element_count {
-            produced_auth_path.push(Digest::new(
-                list_get(auth_paths_pointer, i as usize, &memory, DIGEST_LENGTH)
-                    .try_into()
-                    .unwrap(),
-            ));
-        }
+        let produced_auth_path =
+            list_to_vec::<DIGEST_LENGTH>(auth_paths_pointer, &memory, &list_type)
+                .into_iter()
+                .map(Digest::new)
+                .collect_vec();
 
         let produced_mp = MmrMembershipProof::<VmHasher> {
             leaf_index: start_mmr.count_leaves(),
@@ -716,6 +756,7 @@ mod benches {
     fn calculate_new_peaks_from_append_unsafe_lists_benchmark() {
         bench_and_write(CalculateNewPeaksFromAppend {
             list_type: ListType::Unsafe,
+            loop_bound: None,
         });
     }
 
@@ -723,6 +764,7 @@ mod benches {
     fn calculate_new_peaks_from_append_safe_lists_benchmark() {
         bench_and_write(CalculateNewPeaksFromAppend {
             list_type: ListType::Safe,
+            loop_bound: None,
         });
     }
 }
@@ -3,18 +3,21 @@ use std::collections::HashMap;
 use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::util_types::mmr;
 
-use crate::arithmetic::u64::add_u64::AddU64;
 use crate::arithmetic::u64::incr_u64::IncrU64;
+use crate::arithmetic::u64::popcount_u64::PopCountU64;
+use crate::arithmetic::u64::safe_mul_u64::SafeMulU64;
+use crate::arithmetic::u64::sub_u64::SubU64;
 use crate::library::Library;
 use crate::snippet::{DataType, DeprecatedSnippet};
 use crate::{get_init_tvm_stack, ExecutionState};
 
-use super::non_leaf_nodes_left::MmrNonLeafNodesLeftUsingAnd;
-
+/// Convert the index of a leaf into the index of the corresponding node in the MMR,
+/// where node indices start at 1. The node index is `2*leaf_index - popcount(leaf_index)
+/// + 1`.
 #[derive(Clone, Debug)]
-pub struct DataIndexToNodeIndex;
+pub struct MmrDataIndexToNodeIndex;
 
-impl DeprecatedSnippet for DataIndexToNodeIndex {
+impl DeprecatedSnippet for MmrDataIndexToNodeIndex {
     fn input_field_names(&self) -> Vec<String> {
         vec!["leaf_index_hi".to_string(), "leaf_index_lo".to_string()]
     }
@@ -55,25 +58,40 @@ impl DeprecatedSnippet for DataIndexToNodeIndex {
 
     fn function_code(&self, library: &mut Library) -> String {
         let entrypoint = self.entrypoint_name();
-        let non_leaf_nodes_left = library.import(Box::new(MmrNonLeafNodesLeftUsingAnd));
+        let popcount_u64 = library.import(Box::new(PopCountU64));
+        let safe_mul_u64 = library.import(Box::new(SafeMulU64));
+        let sub_u64 = library.import(Box::new(SubU64));
         let incr_u64 = library.import(Box::new(IncrU64));
-        let add_u64 = library.import(Box::new(AddU64));
-        format!("
-                // BEFORE: _ leaf_index_hi leaf_index_lo
-                // AFTER: _ node_index_hi node_index_lo
-                {entrypoint}:
-                    dup 1 dup 1
-                    // stack: _ leaf_index_hi leaf_index_lo leaf_index_hi leaf_index_lo
-
-                    call {non_leaf_nodes_left}
-                    // stack: _ leaf_index_hi leaf_index_lo non_leaf_nodes_left_hi non_leaf_nodes_left_lo
-
-                    call {add_u64}
-                    call {incr_u64}
-                    // stack: _ node_index_hi node_index_lo
-
-                    return
-                    ")
+        format!(
+            "
+            // BEFORE: _ leaf_index_hi leaf_index_lo
+            // AFTER: _ node_index_hi node_index_lo
+            {entrypoint}:
+                dup 1
+                dup 1
+                call {popcount_u64}
+                // _ leaf_index_hi leaf_index_lo popcount
+
+                push 0
+                swap 3
+                swap 1
+                swap 2
+                // _ 0 popcount leaf_index_hi leaf_index_lo
+
+                push 0
+                push 2
+                call {safe_mul_u64}
+                // _ 0 popcount (2 * leaf_index)_hi (2 * leaf_index)_lo
+
+                call {sub_u64}
+                // _ (2 * leaf_index - popcount)_hi (2 * leaf_index - popcount)_lo
+
+                call {incr_u64}
+                // _ node_index_hi node_index_lo
+
+                return
+            "
+        )
     }
 
     fn rust_shadowing(
@@ -128,7 +146,7 @@ mod tests {
 
     #[test]
     fn data_index_to_node_index_test() {
-        test_rust_equivalence_multiple_deprecated(&DataIndexToNodeIndex, true);
+        test_rust_equivalence_multiple_deprecated(&MmrDataIndexToNodeIndex, true);
     }
 
     #[test]
@@ -249,6 +267,26 @@ mod tests {
         prop_data_index_to_node_index((1u64 << 33) + 1, None);
     }
 
+    #[test]
+    fn data_index_to_node_index_against_reference() {
+        let small_indices = 0..16;
+        let popcount_boundaries = [5, 16, 31, 32, 33, 62]
+            .into_iter()
+            .flat_map(|k| [(1u64 << k) - 1, 1u64 << k, (1u64 << k) + 1]);
+        let large_indices = [u32::MAX as u64 - 1, (1u64 << 63) - 2, (1u64 << 63) - 1];
+
+        for leaf_index in small_indices
+            .chain(popcount_boundaries)
+            .chain(large_indices)
+        {
+            let node_index = mmr::shared_advanced::leaf_index_to_node_index(leaf_index);
+            let mut expected = get_init_tvm_stack();
+            expected.push(BFieldElement::new(node_index >> 32));
+            expected.push(BFieldElement::new(node_index & u32::MAX as u64));
+            prop_data_index_to_node_index(leaf_index, Some(&expected));
+        }
+    }
+
     #[test]
     fn data_index_to_node_index_pbt() {
         let mut rng = thread_rng();
@@ -268,8 +306,8 @@ mod tests {
             init_stack.push(elem);
         }
 
-        test_rust_equivalence_given_input_values_deprecated::<DataIndexToNodeIndex>(
-            &DataIndexToNodeIndex,
+        test_rust_equivalence_given_input_values_deprecated::<MmrDataIndexToNodeIndex>(
+            &MmrDataIndexToNodeIndex,
             &init_stack,
             &[],
             &mut HashMap::default(),
@@ -286,6 +324,6 @@ mod benches {
 
     #[test]
     fn data_index_to_node_index_benchmark() {
-        bench_and_write(DataIndexToNodeIndex);
+        bench_and_write(MmrDataIndexToNodeIndex);
     }
 }
@@ -0,0 +1,159 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::popcount_u64::PopCountU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, ExecutionState};
+
+/// The number of peaks of an MMR with the given number of leafs, i.e. the number of
+/// set bits in the leaf count.
+#[derive(Clone, Debug)]
+pub struct MmrLeafCountToNumPeaks;
+
+impl DeprecatedSnippet for MmrLeafCountToNumPeaks {
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_leaf_count_to_num_peaks".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["leaf_count_hi".to_string(), "leaf_count_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["num_peaks".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::U32]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -1
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let popcount_u64 = library.import(Box::new(PopCountU64));
+
+        format!(
+            "
+            // BEFORE: _ leaf_count_hi leaf_count_lo
+            // AFTER: _ num_peaks
+            {entrypoint}:
+                call {popcount_u64}
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Inputs are not u32s".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = rand::thread_rng();
+        let mut ret = vec![prepare_state(0), prepare_state(u64::MAX)];
+        for _ in 0..10 {
+            ret.push(prepare_state(rng.next_u64()));
+        }
+
+        ret
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state((1 << 32) - 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(u64::MAX)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let leaf_count_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let leaf_count_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let leaf_count = ((leaf_count_hi as u64) << 32) + leaf_count_lo as u64;
+
+        stack.push(BFieldElement::new(leaf_count.count_ones() as u64));
+    }
+}
+
+fn prepare_state(leaf_count: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    push_encodable(&mut stack, &leaf_count);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn leaf_count_to_num_peaks_test() {
+        test_rust_equivalence_multiple_deprecated(&MmrLeafCountToNumPeaks, true);
+    }
+
+    #[test]
+    fn empty_mmr_has_no_peaks() {
+        prop_num_peaks(0, 0);
+    }
+
+    #[test]
+    fn power_of_two_leaf_count_has_one_peak() {
+        prop_num_peaks(1, 1);
+        prop_num_peaks(1 << 20, 1);
+        prop_num_peaks(1 << 32, 1);
+        prop_num_peaks(1 << 63, 1);
+    }
+
+    #[test]
+    fn dense_leaf_count_has_many_peaks() {
+        prop_num_peaks(0b1011, 3);
+        prop_num_peaks(u32::MAX as u64, 32);
+        prop_num_peaks(u64::MAX, 64);
+    }
+
+    fn prop_num_peaks(leaf_count: u64, expected_num_peaks: u32) {
+        let expected = [
+            get_init_tvm_stack(),
+            vec![BFieldElement::new(expected_num_peaks as u64)],
+        ]
+        .concat();
+        test_rust_equivalence_given_input_values_deprecated(
+            &MmrLeafCountToNumPeaks,
+            &prepare_state(leaf_count).stack,
+            &[],
+            &mut HashMap::default(),
+            0,
+            Some(&expected),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn leaf_count_to_num_peaks_benchmark() {
+        bench_and_write(MmrLeafCountToNumPeaks);
+    }
+}
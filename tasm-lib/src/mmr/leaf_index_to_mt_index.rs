@@ -354,6 +354,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn leaf_index_to_mt_index_agrees_with_reference_on_small_mmrs() {
+        // Covers every leaf of every MMR up to 33 leaves, so in particular every leaf that
+        // is a peak on its own.
+        for leaf_count in 1..=33u64 {
+            for leaf_index in 0..leaf_count {
+                let (mt_index, peak_index) =
+                    mmr::shared_basic::leaf_index_to_mt_index_and_peak_index(
+                        leaf_index, leaf_count,
+                    );
+                prop_leaf_index_to_mt_index_and_peak_index(
+                    leaf_index, leaf_count, mt_index, peak_index,
+                );
+            }
+        }
+    }
+
     fn prop_leaf_index_to_mt_index_and_peak_index(
         leaf_index: u64,
         leaf_count: u64,
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use super::MAX_MMR_HEIGHT;
+use crate::library::Library;
+use crate::list::unsafeimplu32::new::UnsafeNew;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+/// Create the accumulator of an empty MMR: an empty unsafe list of peaks, with room for
+/// `MAX_MMR_HEIGHT` of them, and a leaf count of zero.
+#[derive(Clone, Debug)]
+pub struct MmrNew;
+
+impl DeprecatedSnippet for MmrNew {
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_new".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec![
+            "*peaks".to_string(),
+            "leaf_count_hi".to_string(),
+            "leaf_count_lo".to_string(),
+        ]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::Digest)), DataType::U64]
+    }
+
+    fn stack_diff(&self) -> isize {
+        3
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let new_list = library.import(Box::new(UnsafeNew(DataType::Digest)));
+
+        format!(
+            "
+            // BEFORE: _
+            // AFTER: _ *peaks leaf_count_hi leaf_count_lo
+            {entrypoint}:
+                push {MAX_MMR_HEIGHT}
+                call {new_list}
+                // _ *peaks
+
+                push 0
+                push 0
+                // _ *peaks 0 0
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        vec![ExecutionState::with_stack(get_init_tvm_stack())]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        ExecutionState::with_stack(get_init_tvm_stack())
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        ExecutionState::with_stack(get_init_tvm_stack())
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        stack.push(BFieldElement::new(MAX_MMR_HEIGHT as u64));
+        UnsafeNew(DataType::Digest).rust_shadowing(stack, std_in, secret_in, memory);
+
+        stack.push(BFieldElement::new(0));
+        stack.push(BFieldElement::new(0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+    use twenty_first::util_types::mmr::mmr_trait::Mmr;
+
+    use super::*;
+    use crate::list::ListType;
+    use crate::mmr::calculate_new_peaks_from_append::CalculateNewPeaksFromAppend;
+    use crate::rust_shadowing_helper_functions::list_to_vec;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+    use crate::{Digest, VmHasher, DIGEST_LENGTH};
+
+    #[test]
+    fn mmr_new_test() {
+        test_rust_equivalence_multiple_deprecated(&MmrNew, true);
+    }
+
+    #[test]
+    fn new_then_append_one_leaf() {
+        let mut memory = HashMap::default();
+        let vm_output_state = test_rust_equivalence_given_input_values_deprecated(
+            &MmrNew,
+            &get_init_tvm_stack(),
+            &[],
+            &mut memory,
+            0,
+            None,
+        );
+
+        // _ *peaks leaf_count_hi leaf_count_lo
+        let mut final_stack = vm_output_state.final_stack;
+        let leaf_count_lo = final_stack.pop().unwrap();
+        let leaf_count_hi = final_stack.pop().unwrap();
+        let peaks_pointer = final_stack.pop().unwrap();
+        assert_eq!(0, leaf_count_hi.value());
+        assert_eq!(0, leaf_count_lo.value());
+
+        let empty_mmr: MmrAccumulator<VmHasher> = MmrAccumulator::new(vec![]);
+        let produced_peaks =
+            list_to_vec::<DIGEST_LENGTH>(peaks_pointer, &memory, &ListType::Unsafe)
+                .into_iter()
+                .map(Digest::new)
+                .collect_vec();
+        assert_eq!(
+            empty_mmr,
+            MmrAccumulator::init(produced_peaks, leaf_count_lo.value())
+        );
+
+        // Append one leaf to the new accumulator
+        let new_leaf: Digest = rand::random();
+        let mut init_stack = get_init_tvm_stack();
+        init_stack.push(leaf_count_hi);
+        init_stack.push(leaf_count_lo);
+        init_stack.push(peaks_pointer);
+        for value in new_leaf.values().iter().rev() {
+            init_stack.push(*value);
+        }
+
+        // The auth path of the new leaf is allocated right after the peaks list
+        let words_allocated = 1 + MAX_MMR_HEIGHT * DIGEST_LENGTH + 1;
+        test_rust_equivalence_given_input_values_deprecated(
+            &CalculateNewPeaksFromAppend {
+                list_type: ListType::Unsafe,
+                loop_bound: None,
+            },
+            &init_stack,
+            &[],
+            &mut memory,
+            words_allocated,
+            None,
+        );
+
+        let produced_peaks =
+            list_to_vec::<DIGEST_LENGTH>(peaks_pointer, &memory, &ListType::Unsafe)
+                .into_iter()
+                .map(Digest::new)
+                .collect_vec();
+        let produced_mmr: MmrAccumulator<VmHasher> = MmrAccumulator::init(produced_peaks, 1);
+
+        let mut expected_mmr: MmrAccumulator<VmHasher> = MmrAccumulator::new(vec![]);
+        expected_mmr.append(new_leaf);
+        assert_eq!(expected_mmr, produced_mmr);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn mmr_new_benchmark() {
+        bench_and_write(MmrNew);
+    }
+}
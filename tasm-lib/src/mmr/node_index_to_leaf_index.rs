@@ -0,0 +1,309 @@
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::util_types::mmr;
+
+use crate::arithmetic::u64::add_u64::AddU64;
+use crate::arithmetic::u64::decr_u64::DecrU64;
+use crate::arithmetic::u64::log_2_floor_u64::Log2FloorU64;
+use crate::arithmetic::u64::lt_u64::LtStandardU64;
+use crate::arithmetic::u64::pow2_u64::Pow2U64;
+use crate::arithmetic::u64::sub_u64::SubU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+use super::right_child_and_height::MmrRightChildAndHeight;
+
+/// Convert the index of a leaf node in the MMR into the index of that leaf, i.e., the
+/// inverse of `MmrDataIndexToNodeIndex`. Crashes if the node is not a leaf.
+///
+/// The nodes preceding leaf `l` form one perfect binary tree of height `h` for every bit
+/// `h` set in `l`. Since a perfect tree of height `h` has `2^(h+1) - 1` nodes, the leaf
+/// index is found by greedily subtracting the sizes of perfect trees, largest first,
+/// from `node_index - 1`.
+#[derive(Clone, Debug)]
+pub struct MmrNodeIndexToLeafIndex;
+
+impl DeprecatedSnippet for MmrNodeIndexToLeafIndex {
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["node_index_hi".to_string(), "node_index_lo".to_string()]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["leaf_index_hi".to_string(), "leaf_index_lo".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<crate::snippet::DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_types(&self) -> Vec<crate::snippet::DataType> {
+        vec![DataType::U64]
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["node index does not belong to a leaf".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut ret: Vec<ExecutionState> = vec![];
+        for _ in 0..20 {
+            let leaf_index = thread_rng().gen_range(0..u64::MAX / 4);
+            ret.push(prepare_state(
+                mmr::shared_advanced::leaf_index_to_node_index(leaf_index),
+            ));
+        }
+
+        ret
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_node_index_to_leaf_index".to_string()
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let right_child_and_height = library.import(Box::new(MmrRightChildAndHeight));
+        let log_2_floor_u64 = library.import(Box::new(Log2FloorU64));
+        let pow2_u64 = library.import(Box::new(Pow2U64));
+        let decr_u64 = library.import(Box::new(DecrU64));
+        let lt_u64 = library.import(Box::new(LtStandardU64));
+        let sub_u64 = library.import(Box::new(SubU64));
+        let add_u64 = library.import(Box::new(AddU64));
+
+        format!(
+            "
+            // BEFORE: _ node_index_hi node_index_lo
+            // AFTER: _ leaf_index_hi leaf_index_lo
+            {entrypoint}:
+                dup 1
+                dup 1
+                call {right_child_and_height}
+                // _ node_index_hi node_index_lo is_right_child height
+
+                push 0
+                eq
+                assert
+                pop
+                // _ node_index_hi node_index_lo
+
+                dup 1
+                dup 1
+                call {decr_u64}
+                push 0
+                push 0
+                // _ node_index_hi node_index_lo nodes_left_hi nodes_left_lo 0 0
+
+                dup 5
+                dup 5
+                call {log_2_floor_u64}
+                push 1
+                add
+                // _ node_index_hi node_index_lo nodes_left_hi nodes_left_lo 0 0 (height + 1)
+
+                call {entrypoint}_loop
+                // _ node_index_hi node_index_lo 0 0 leaf_index_hi leaf_index_lo 0
+
+                pop
+                swap 2
+                pop
+                swap 2
+                pop
+                swap 2
+                pop
+                swap 2
+                pop
+                // _ leaf_index_hi leaf_index_lo
+
+                return
+
+            // INVARIANT: _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo k
+            {entrypoint}_loop:
+                dup 0
+                push 0
+                eq
+                skiz
+                    return
+
+                dup 0
+                call {pow2_u64}
+                call {decr_u64}
+                // _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo k tree_size_hi tree_size_lo
+
+                dup 1
+                dup 1
+                dup 8
+                dup 8
+                // _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo k tree_size_hi tree_size_lo tree_size_hi tree_size_lo nodes_left_hi nodes_left_lo
+
+                call {lt_u64}
+                push 0
+                eq
+                // _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo k tree_size_hi tree_size_lo (nodes_left >= tree_size)
+
+                skiz
+                    call {entrypoint}_skip_tree
+
+                pop
+                pop
+                push -1
+                add
+                // _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo (k - 1)
+
+                recurse
+
+            // BEFORE: _ nodes_left_hi nodes_left_lo leaf_index_hi leaf_index_lo k tree_size_hi tree_size_lo
+            // AFTER: _ (nodes_left - tree_size)_hi (nodes_left - tree_size)_lo (leaf_index + 2^(k-1))_hi (leaf_index + 2^(k-1))_lo k tree_size_hi tree_size_lo
+            {entrypoint}_skip_tree:
+                dup 1
+                dup 1
+                dup 8
+                dup 8
+                call {sub_u64}
+                swap 7
+                pop
+                swap 7
+                pop
+                // _ (nodes_left - tree_size)_hi (nodes_left - tree_size)_lo leaf_index_hi leaf_index_lo k tree_size_hi tree_size_lo
+
+                dup 2
+                push -1
+                add
+                call {pow2_u64}
+                dup 6
+                dup 6
+                call {add_u64}
+                swap 5
+                pop
+                swap 5
+                pop
+                // _ (nodes_left - tree_size)_hi (nodes_left - tree_size)_lo (leaf_index + 2^(k-1))_hi (leaf_index + 2^(k-1))_lo k tree_size_hi tree_size_lo
+
+                return
+            "
+        )
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        _memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let node_index_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let node_index_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let node_index: u64 = (node_index_hi as u64) * (1u64 << 32) + node_index_lo as u64;
+
+        let (_, height) = mmr::shared_advanced::right_lineage_length_and_own_height(node_index);
+        assert_eq!(0, height, "node index must belong to a leaf");
+
+        let mut nodes_left = node_index - 1;
+        let mut leaf_index = 0u64;
+        for tree_height in (0..64 - node_index.leading_zeros()).rev() {
+            let tree_size = (1u64 << (tree_height + 1)) - 1;
+            if nodes_left >= tree_size {
+                nodes_left -= tree_size;
+                leaf_index += 1 << tree_height;
+            }
+        }
+        assert_eq!(
+            node_index,
+            mmr::shared_advanced::leaf_index_to_node_index(leaf_index)
+        );
+
+        stack.push(BFieldElement::new(leaf_index >> 32));
+        stack.push(BFieldElement::new(leaf_index & u32::MAX as u64));
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(mmr::shared_advanced::leaf_index_to_node_index(
+            (1 << 32) - 1,
+        ))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(mmr::shared_advanced::leaf_index_to_node_index(
+            (1 << 62) - 1,
+        ))
+    }
+}
+
+fn prepare_state(node_index: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    let node_index_hi = BFieldElement::new(node_index >> 32);
+    let node_index_lo = BFieldElement::new(node_index & u32::MAX as u64);
+    stack.push(node_index_hi);
+    stack.push(node_index_lo);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn node_index_to_leaf_index_test() {
+        test_rust_equivalence_multiple_deprecated(&MmrNodeIndexToLeafIndex, true);
+    }
+
+    #[test]
+    fn node_index_to_leaf_index_round_trip() {
+        let small_leaf_indices = 0..33;
+        let large_leaf_indices = [
+            u32::MAX as u64,
+            1u64 << 32,
+            (1u64 << 32) + 1,
+            (1u64 << 61) + 400,
+            (1u64 << 62) - 1,
+        ];
+
+        for leaf_index in small_leaf_indices.chain(large_leaf_indices) {
+            let node_index = mmr::shared_advanced::leaf_index_to_node_index(leaf_index);
+            let mut expected = get_init_tvm_stack();
+            expected.push(BFieldElement::new(leaf_index >> 32));
+            expected.push(BFieldElement::new(leaf_index & u32::MAX as u64));
+
+            test_rust_equivalence_given_input_values_deprecated(
+                &MmrNodeIndexToLeafIndex,
+                &prepare_state(node_index).stack,
+                &[],
+                &mut HashMap::default(),
+                0,
+                Some(&expected),
+            );
+        }
+    }
+
+    #[should_panic]
+    #[test]
+    fn internal_node_index_fails() {
+        // Node 3 is the parent of the leaves with node indices 1 and 2.
+        test_rust_equivalence_given_execution_state_deprecated(
+            &MmrNodeIndexToLeafIndex,
+            prepare_state(3),
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn node_index_to_leaf_index_benchmark() {
+        bench_and_write(MmrNodeIndexToLeafIndex);
+    }
+}
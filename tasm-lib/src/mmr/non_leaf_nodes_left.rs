@@ -62,13 +62,13 @@ impl DeprecatedSnippet for MmrNonLeafNodesLeftUsingAnd {
 
     fn function_code(&self, library: &mut Library) -> String {
         let entrypoint = self.entrypoint_name();
-        let log_2_floor_u64 = library.import(Box::new(Log2FloorU64));
-        let pow2_u64 = library.import(Box::new(Pow2U64));
-        let and_u64 = library.import(Box::new(AndU64));
-        let eq_u64 = library.import(Box::new(EqU64));
-        let decr_u64 = library.import(Box::new(DecrU64));
-        let incr_u64 = library.import(Box::new(IncrU64));
-        let add_u64 = library.import(Box::new(AddU64));
+        let log_2_floor_u64 = library.import_label(Box::new(Log2FloorU64));
+        let pow2_u64 = library.import_label(Box::new(Pow2U64));
+        let and_u64 = library.import_label(Box::new(AndU64));
+        let eq_u64 = library.import_label(Box::new(EqU64));
+        let decr_u64 = library.import_label(Box::new(DecrU64));
+        let incr_u64 = library.import_label(Box::new(IncrU64));
+        let add_u64 = library.import_label(Box::new(AddU64));
 
         format!(
             "
@@ -201,7 +201,7 @@ mod tests {
     use crate::get_init_tvm_stack;
 
     use crate::test_helpers::{
-        test_rust_equivalence_given_input_values_deprecated,
+        assert_program_size_at_most, test_rust_equivalence_given_input_values_deprecated,
         test_rust_equivalence_multiple_deprecated,
     };
 
@@ -212,6 +212,12 @@ mod tests {
         test_rust_equivalence_multiple_deprecated(&MmrNonLeafNodesLeftUsingAnd, true);
     }
 
+    #[test]
+    fn non_leaf_nodes_left_program_size() {
+        // Imports seven u64 helpers, so allow for a generous budget.
+        assert_program_size_at_most(&MmrNonLeafNodesLeftUsingAnd, 1_000);
+    }
+
     #[test]
     fn non_leaf_nodes_left_using_and_test() {
         let mut expected = get_init_tvm_stack();
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::random_elements;
+
+use crate::arithmetic::u64::popcount_u64::PopCountU64;
+use crate::library::Library;
+use crate::list::unsafeimplu32::length::Length;
+use crate::rust_shadowing_helper_functions::unsafe_list::{
+    unsafe_list_get_length, unsafe_list_insert,
+};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, push_encodable, Digest, ExecutionState};
+
+/// Check whether the peaks of an MMR, stored in an unsafe list, are consistent with the
+/// leaf count, i.e., whether there is exactly one peak per set bit of the leaf count.
+/// The height of the i-th peak is the position of the i-th most significant set bit, so
+/// when the counts agree, the implied peak heights are strictly decreasing by
+/// construction and need no separate check.
+#[derive(Clone, Debug)]
+pub struct MmrPeaksAndLeafCountConsistency;
+
+impl MmrPeaksAndLeafCountConsistency {
+    fn prepare_state(&self, num_peaks: usize, leaf_count: u64) -> ExecutionState {
+        let peaks_pointer = BFieldElement::new(random::<u32>() as u64 + 1);
+        let peaks: Vec<Digest> = random_elements(num_peaks);
+        let mut memory = HashMap::default();
+        unsafe_list_insert(peaks_pointer, peaks, &mut memory);
+
+        let mut stack = get_init_tvm_stack();
+        stack.push(peaks_pointer);
+        push_encodable(&mut stack, &leaf_count);
+        ExecutionState::with_stack_and_memory(stack, memory, 0)
+    }
+
+    fn consistent_state(&self, leaf_count: u64) -> ExecutionState {
+        self.prepare_state(leaf_count.count_ones() as usize, leaf_count)
+    }
+}
+
+impl DeprecatedSnippet for MmrPeaksAndLeafCountConsistency {
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_peaks_and_leaf_count_consistency".to_string()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "*peaks".to_string(),
+            "leaf_count_hi".to_string(),
+            "leaf_count_lo".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::List(Box::new(DataType::Digest)), DataType::U64]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["num_peaks == popcount(leaf_count)".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -2
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let popcount_u64 = library.import(Box::new(PopCountU64));
+        let get_length = library.import(Box::new(Length(DataType::Digest)));
+
+        format!(
+            "
+            // BEFORE: _ *peaks leaf_count_hi leaf_count_lo
+            // AFTER: _ (num_peaks == popcount(leaf_count))
+            {entrypoint}:
+                call {popcount_u64}
+                // _ *peaks popcount(leaf_count)
+
+                swap 1
+                call {get_length}
+                // _ popcount(leaf_count) num_peaks
+
+                eq
+                // _ (num_peaks == popcount(leaf_count))
+
+                return
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Leaf count is not a u64".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let leaf_count = rng.gen::<u64>();
+        vec![
+            self.consistent_state(0),
+            self.consistent_state(1),
+            self.consistent_state(0b1011),
+            self.consistent_state(leaf_count),
+            self.prepare_state(1, 0),
+            self.prepare_state(leaf_count.count_ones() as usize + 1, leaf_count),
+        ]
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        self.consistent_state((1 << 32) - 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        self.consistent_state(u64::MAX)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let leaf_count_lo: u32 = stack.pop().unwrap().try_into().unwrap();
+        let leaf_count_hi: u32 = stack.pop().unwrap().try_into().unwrap();
+        let leaf_count = ((leaf_count_hi as u64) << 32) + leaf_count_lo as u64;
+        let peaks_pointer = stack.pop().unwrap();
+
+        let num_peaks = unsafe_list_get_length(peaks_pointer, memory);
+        let consistent = num_peaks == leaf_count.count_ones() as usize;
+        stack.push(BFieldElement::new(consistent as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::{One, Zero};
+
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_input_values_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn peaks_and_leaf_count_consistency_test() {
+        test_rust_equivalence_multiple_deprecated(&MmrPeaksAndLeafCountConsistency, true);
+    }
+
+    fn prop_consistency(num_peaks: usize, leaf_count: u64, expected_consistent: bool) {
+        let expected = [
+            get_init_tvm_stack(),
+            vec![if expected_consistent {
+                BFieldElement::one()
+            } else {
+                BFieldElement::zero()
+            }],
+        ]
+        .concat();
+
+        let mut init_state = MmrPeaksAndLeafCountConsistency.prepare_state(num_peaks, leaf_count);
+        test_rust_equivalence_given_input_values_deprecated(
+            &MmrPeaksAndLeafCountConsistency,
+            &init_state.stack,
+            &[],
+            &mut init_state.memory,
+            0,
+            Some(&expected),
+        );
+    }
+
+    #[test]
+    fn consistent_accumulator() {
+        prop_consistency(0, 0, true);
+        prop_consistency(1, 1, true);
+        prop_consistency(3, 0b1011, true);
+        prop_consistency(1, 1 << 40, true);
+        prop_consistency(64, u64::MAX, true);
+    }
+
+    #[test]
+    fn inconsistent_peak_count() {
+        prop_consistency(1, 0, false);
+        prop_consistency(0, 1, false);
+        prop_consistency(2, 0b1011, false);
+        prop_consistency(4, 0b1011, false);
+        prop_consistency(2, 1 << 40, false);
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn peaks_and_leaf_count_consistency_benchmark() {
+        bench_and_write(MmrPeaksAndLeafCountConsistency);
+    }
+}
@@ -14,63 +14,50 @@ use crate::{get_init_tvm_stack, ExecutionState};
 use super::left_child::MmrLeftChild;
 use super::leftmost_ancestor::MmrLeftMostAncestor;
 use super::right_child::MmrRightChild;
+use super::{loop_guard_cleanup, loop_guard_init, loop_guard_tick};
 
-// You probably don't want to use this but a right lineage count function instead
+// You probably don't want to use this but a right lineage count function instead, see
+// `MmrRightLineageLength`.
 #[derive(Clone, Debug)]
 pub struct MmrRightChildAndHeight;
 
-impl DeprecatedSnippet for MmrRightChildAndHeight {
-    fn input_field_names(&self) -> Vec<String> {
-        vec!["node_index_hi".to_string(), "node_index_lo".to_string()]
-    }
-
-    fn output_field_names(&self) -> Vec<String> {
-        vec!["is_right_child".to_string(), "height".to_string()]
-    }
-
-    fn input_types(&self) -> Vec<crate::snippet::DataType> {
-        vec![DataType::U64]
-    }
-
-    fn output_types(&self) -> Vec<crate::snippet::DataType> {
-        vec![DataType::Bool, DataType::U32]
-    }
-
-    fn crash_conditions(&self) -> Vec<String> {
-        vec!["Node index exceeds 2^63?".to_string()]
-    }
-
-    fn gen_input_states(&self) -> Vec<crate::ExecutionState> {
-        let mut ret: Vec<ExecutionState> = vec![];
-        for _ in 0..10 {
-            let node_index = thread_rng().gen_range(0..u64::MAX / 2);
-            ret.push(prepare_state(node_index));
-        }
-
-        ret
-    }
-
-    fn stack_diff(&self) -> isize {
-        0
-    }
+/// Same as [`MmrRightChildAndHeight`], but the VM crashes if the climb down from the
+/// leftmost ancestor takes more than `loop_bound` iterations, see `MmrLoopGuard`.
+#[derive(Clone, Debug)]
+pub struct MmrRightChildAndHeightBounded {
+    pub loop_bound: usize,
+}
 
-    fn entrypoint_name(&self) -> String {
-        "tasm_mmr_right_child_and_height".to_string()
+impl MmrRightChildAndHeight {
+    /// Number of iterations of the loop climbing down from the leftmost ancestor to the
+    /// node, which equals the difference between their heights. Node index 0 does not
+    /// exist and makes the VM crash before the loop is entered.
+    fn loop_iterations(node_index: u64) -> usize {
+        let log_2_floor = match node_index.checked_ilog2() {
+            Some(log_2_floor) => log_2_floor,
+            None => return 0,
+        };
+        let (_, height) = mmr::shared_advanced::right_lineage_length_and_own_height(node_index);
+        log_2_floor as usize - height as usize
     }
 
-    fn function_code(&self, library: &mut Library) -> String {
-        let entrypoint = self.entrypoint_name();
+    /// The code of [`MmrRightChildAndHeight`], with a loop guard if `loop_bound` is set.
+    fn code(entrypoint: &str, library: &mut Library, loop_bound: Option<usize>) -> String {
         let eq_u64 = library.import(Box::new(EqU64));
         let lt_u64 = library.import(Box::new(LtU64));
         let left_child = library.import(Box::new(MmrLeftChild));
         let right_child = library.import(Box::new(MmrRightChild));
         let leftmost_ancestor = library.import(Box::new(MmrLeftMostAncestor));
+        let init_loop_guard = loop_guard_init(loop_bound, 2);
+        let count_iteration = loop_guard_tick(library, loop_bound, 6);
+        let cleanup_loop_guard = loop_guard_cleanup(loop_bound, 2);
 
         format!(
             "
             // Before: _ ni_hi ni_lo
             // After: _ is_right_child height
             {entrypoint}:
+                {init_loop_guard}
                 // Get leftmost ancestor and its height on top of stack
                 push 0 // is `is_r` onto stack
                 dup 2
@@ -89,6 +76,7 @@ impl DeprecatedSnippet for MmrRightChildAndHeight {
                 pop
                 swap 2
                 pop
+                {cleanup_loop_guard}
 
                 // Stack: _ is_r height
                 return
@@ -103,6 +91,7 @@ impl DeprecatedSnippet for MmrRightChildAndHeight {
                 call {eq_u64}
                 // Stack: _ ni_hi ni_lo is_r height c_hi c_lo (c == ni)
                 skiz return
+                {count_iteration}
 
                 // Stack: ni_hi ni_lo is_r height c_hi c_lo
                 dup 1
@@ -184,6 +173,50 @@ impl DeprecatedSnippet for MmrRightChildAndHeight {
             "
         )
     }
+}
+
+impl DeprecatedSnippet for MmrRightChildAndHeight {
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["node_index_hi".to_string(), "node_index_lo".to_string()]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["is_right_child".to_string(), "height".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<crate::snippet::DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_types(&self) -> Vec<crate::snippet::DataType> {
+        vec![DataType::Bool, DataType::U32]
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Node index exceeds 2^63?".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<crate::ExecutionState> {
+        let mut ret: Vec<ExecutionState> = vec![];
+        for _ in 0..10 {
+            let node_index = thread_rng().gen_range(0..u64::MAX / 2);
+            ret.push(prepare_state(node_index));
+        }
+
+        ret
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_right_child_and_height".to_string()
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        Self::code(&self.entrypoint_name(), library, None)
+    }
 
     fn rust_shadowing(
         &self,
@@ -217,6 +250,90 @@ impl DeprecatedSnippet for MmrRightChildAndHeight {
     }
 }
 
+impl MmrRightChildAndHeightBounded {
+    /// Smallest node index that is at least `iterations` levels below its leftmost ancestor.
+    fn node_index_with_depth(iterations: usize) -> u64 {
+        1 << iterations.min(62)
+    }
+}
+
+impl DeprecatedSnippet for MmrRightChildAndHeightBounded {
+    fn input_field_names(&self) -> Vec<String> {
+        MmrRightChildAndHeight.input_field_names()
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        MmrRightChildAndHeight.output_field_names()
+    }
+
+    fn input_types(&self) -> Vec<crate::snippet::DataType> {
+        MmrRightChildAndHeight.input_types()
+    }
+
+    fn output_types(&self) -> Vec<crate::snippet::DataType> {
+        MmrRightChildAndHeight.output_types()
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec![
+            "Node index exceeds 2^63?".to_string(),
+            "loop exceeded its maximum number of iterations".to_string(),
+        ]
+    }
+
+    fn gen_input_states(&self) -> Vec<crate::ExecutionState> {
+        let max_node_index = Self::node_index_with_depth(self.loop_bound + 1);
+        let mut ret: Vec<ExecutionState> = vec![];
+        for _ in 0..10 {
+            let node_index = thread_rng().gen_range(1..max_node_index);
+            ret.push(prepare_state(node_index));
+        }
+
+        ret
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn entrypoint_name(&self) -> String {
+        format!(
+            "tasm_mmr_right_child_and_height_bounded_{}",
+            self.loop_bound
+        )
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        MmrRightChildAndHeight::code(&self.entrypoint_name(), library, Some(self.loop_bound))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let node_index_lo: u64 = stack[stack.len() - 1].value();
+        let node_index_hi: u64 = stack[stack.len() - 2].value();
+        let node_index = (node_index_hi << 32) + node_index_lo;
+        assert!(
+            MmrRightChildAndHeight::loop_iterations(node_index) <= self.loop_bound,
+            "loop exceeded its maximum number of iterations"
+        );
+
+        MmrRightChildAndHeight.rust_shadowing(stack, std_in, secret_in, memory);
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state(Self::node_index_with_depth(self.loop_bound / 2) + 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state(Self::node_index_with_depth(self.loop_bound))
+    }
+}
+
 fn prepare_state(node_index: u64) -> ExecutionState {
     let mut stack = get_init_tvm_stack();
     let node_index_hi = BFieldElement::new(node_index >> 32);
@@ -234,7 +351,9 @@ mod tests {
 
     use crate::get_init_tvm_stack;
 
+    use crate::mmr::MAX_MMR_HEIGHT;
     use crate::test_helpers::{
+        test_crash_conditions, test_rust_equivalence_given_execution_state_deprecated,
         test_rust_equivalence_given_input_values_deprecated,
         test_rust_equivalence_multiple_deprecated,
     };
@@ -246,6 +365,66 @@ mod tests {
         test_rust_equivalence_multiple_deprecated(&MmrRightChildAndHeight, true);
     }
 
+    #[test]
+    fn right_child_and_height_bounded_test() {
+        for loop_bound in [1, 32, MAX_MMR_HEIGHT] {
+            test_rust_equivalence_multiple_deprecated(
+                &MmrRightChildAndHeightBounded { loop_bound },
+                true,
+            );
+        }
+    }
+
+    #[test]
+    fn well_formed_node_indices_stay_below_max_mmr_height() {
+        for node_index in [1, 2, 3, 14, 1 << 32, (1 << 62) + 1, u64::MAX / 2] {
+            assert!(MmrRightChildAndHeight::loop_iterations(node_index) <= MAX_MMR_HEIGHT);
+            let init_state = prepare_state(node_index);
+            let unbounded = test_rust_equivalence_given_execution_state_deprecated(
+                &MmrRightChildAndHeight,
+                init_state.clone(),
+            );
+            let bounded = test_rust_equivalence_given_execution_state_deprecated(
+                &MmrRightChildAndHeightBounded {
+                    loop_bound: MAX_MMR_HEIGHT,
+                },
+                init_state,
+            );
+            assert_eq!(unbounded.final_stack, bounded.final_stack);
+        }
+    }
+
+    #[test]
+    fn loop_iterations_of_nonexistent_node_index_zero_is_zero() {
+        assert_eq!(0, MmrRightChildAndHeight::loop_iterations(0));
+    }
+
+    #[test]
+    fn loop_bound_equal_to_iteration_count_is_accepted() {
+        let node_index = 1 << 32;
+        let loop_bound = MmrRightChildAndHeight::loop_iterations(node_index);
+        assert_eq!(32, loop_bound);
+        test_rust_equivalence_given_execution_state_deprecated(
+            &MmrRightChildAndHeightBounded { loop_bound },
+            prepare_state(node_index),
+        );
+    }
+
+    #[test]
+    fn loop_guard_fires_on_node_index_deeper_than_loop_bound() {
+        // An MMR with at most 2^32 leaves never needs more than 32 iterations. A leaf index
+        // 62 levels below its leftmost ancestor makes the climb down exceed that bound.
+        let node_index = 1 << 62;
+        assert_eq!(62, MmrRightChildAndHeight::loop_iterations(node_index));
+        test_crash_conditions(
+            &MmrRightChildAndHeightBounded { loop_bound: 32 },
+            vec![(
+                prepare_state(node_index),
+                "loop exceeded its maximum number of iterations",
+            )],
+        );
+    }
+
     #[test]
     fn right_child_and_height_node_index_equal_leftmost_ancestor() {
         // All should return (false, height) as leftmost ancestors are always left-children.
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use rand::{thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::arithmetic::u64::decr_u64::DecrU64;
+use crate::arithmetic::u64::eq_u64::EqU64;
+use crate::arithmetic::u64::lt_u64::LtStandardU64;
+use crate::arithmetic::u64::pow2_u64::Pow2U64;
+use crate::arithmetic::u64::sub_u64::SubU64;
+use crate::library::Library;
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, ExecutionState};
+
+use super::leftmost_ancestor::MmrLeftMostAncestor;
+use super::right_child_and_height::MmrRightChildAndHeight;
+
+/// Same as [`MmrRightChildAndHeight`], but with a flat loop: the children of the
+/// candidate node are computed inline, and the loop descends exactly one level per
+/// iteration, so it runs at most `height` times. The only branch is a single `skiz`
+/// choosing between the left and the right child.
+#[derive(Clone, Debug)]
+pub struct MmrRightChildAndHeightIterative;
+
+impl DeprecatedSnippet for MmrRightChildAndHeightIterative {
+    fn input_field_names(&self) -> Vec<String> {
+        vec!["node_index_hi".to_string(), "node_index_lo".to_string()]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["is_right_child".to_string(), "height".to_string()]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![DataType::U64]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool, DataType::U32]
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Node index exceeds 2^63?".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut ret: Vec<ExecutionState> = vec![];
+        for _ in 0..10 {
+            let node_index = thread_rng().gen_range(1..u64::MAX / 2);
+            ret.push(prepare_state(node_index));
+        }
+
+        ret
+    }
+
+    fn stack_diff(&self) -> isize {
+        0
+    }
+
+    fn entrypoint_name(&self) -> String {
+        "tasm_mmr_right_child_and_height_iterative".to_string()
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let leftmost_ancestor = library.import(Box::new(MmrLeftMostAncestor));
+        let eq_u64 = library.import(Box::new(EqU64));
+        let pow2_u64 = library.import(Box::new(Pow2U64));
+        let sub_u64 = library.import(Box::new(SubU64));
+        let lt_u64 = library.import(Box::new(LtStandardU64));
+        let decr_u64 = library.import(Box::new(DecrU64));
+
+        format!(
+            "
+            // BEFORE: _ ni_hi ni_lo
+            // AFTER: _ is_right_child height
+            {entrypoint}:
+                push 0
+                dup 2
+                dup 2
+                call {leftmost_ancestor}
+                // _ ni_hi ni_lo is_r c_hi c_lo height
+
+                swap 2
+                swap 1
+                // _ ni_hi ni_lo is_r height c_hi c_lo
+
+                call {entrypoint}_loop
+                // _ ni_hi ni_lo is_r height ni_hi ni_lo
+
+                pop
+                pop
+                swap 2
+                pop
+                swap 2
+                pop
+                // _ is_r height
+
+                return
+
+            // INVARIANT: _ ni_hi ni_lo is_r height c_hi c_lo
+            {entrypoint}_loop:
+                dup 5
+                dup 5
+                dup 3
+                dup 3
+                call {eq_u64}
+                skiz
+                    return
+                // _ ni_hi ni_lo is_r height c_hi c_lo
+
+                dup 2
+                call {pow2_u64}
+                dup 3
+                dup 3
+                call {sub_u64}
+                // _ ni_hi ni_lo is_r height c_hi c_lo lc_hi lc_lo
+
+                dup 7
+                dup 7
+                dup 3
+                dup 3
+                call {lt_u64}
+                // _ ni_hi ni_lo is_r height c_hi c_lo lc_hi lc_lo (lc < ni)
+
+                dup 0
+                swap 7
+                pop
+                // _ ni_hi ni_lo (lc < ni) height c_hi c_lo lc_hi lc_lo (lc < ni)
+
+                skiz
+                    call {entrypoint}_right_child
+                // _ ni_hi ni_lo is_r height c_hi c_lo child_hi child_lo
+
+                swap 2
+                pop
+                swap 2
+                pop
+                // _ ni_hi ni_lo is_r height child_hi child_lo
+
+                swap 2
+                push -1
+                add
+                swap 2
+                // _ ni_hi ni_lo is_r (height - 1) child_hi child_lo
+
+                recurse
+
+            // BEFORE: _ c_hi c_lo lc_hi lc_lo
+            // AFTER: _ c_hi c_lo rc_hi rc_lo
+            {entrypoint}_right_child:
+                pop
+                pop
+                dup 1
+                dup 1
+                call {decr_u64}
+                return
+            "
+        )
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        MmrRightChildAndHeight.rust_shadowing(stack, std_in, secret_in, memory);
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        prepare_state((1 << 32) + 1)
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        prepare_state((1 << 62) + 1)
+    }
+}
+
+fn prepare_state(node_index: u64) -> ExecutionState {
+    let mut stack = get_init_tvm_stack();
+    let node_index_hi = BFieldElement::new(node_index >> 32);
+    let node_index_lo = BFieldElement::new(node_index & u32::MAX as u64);
+    stack.push(node_index_hi);
+    stack.push(node_index_lo);
+    ExecutionState::with_stack(stack)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    use super::*;
+
+    #[test]
+    fn right_child_and_height_iterative_test() {
+        test_rust_equivalence_multiple_deprecated(&MmrRightChildAndHeightIterative, true);
+    }
+
+    #[test]
+    fn agrees_with_recursive_version() {
+        let small_node_indices = 1..70;
+        let large_node_indices = [
+            (1u64 << 32) - 1,
+            1u64 << 32,
+            (1u64 << 32) + 1,
+            (1u64 << 62) - 2,
+            (1u64 << 62) - 1,
+            (1u64 << 62) + 1,
+            (1u64 << 63) - 2,
+        ];
+
+        for node_index in small_node_indices.chain(large_node_indices) {
+            let recursive = test_rust_equivalence_given_execution_state_deprecated(
+                &MmrRightChildAndHeight,
+                prepare_state(node_index),
+            );
+            let iterative = test_rust_equivalence_given_execution_state_deprecated(
+                &MmrRightChildAndHeightIterative,
+                prepare_state(node_index),
+            );
+            assert_eq!(
+                recursive.final_stack, iterative.final_stack,
+                "Both versions must agree on node index {node_index}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn right_child_and_height_iterative_benchmark() {
+        bench_and_write(MmrRightChildAndHeightIterative);
+    }
+}
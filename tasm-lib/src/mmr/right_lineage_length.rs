@@ -1,14 +1,21 @@
 use rand::{thread_rng, Rng};
 use twenty_first::{shared_math::b_field_element::BFieldElement, util_types::mmr};
 
+use crate::arithmetic::u64::add_u64::AddU64;
 use crate::arithmetic::u64::decr_u64::DecrU64;
+use crate::arithmetic::u64::div2_u64::Div2U64;
 use crate::arithmetic::u64::incr_u64::IncrU64;
-use crate::arithmetic::u64::log_2_floor_u64::Log2FloorU64;
-use crate::arithmetic::u64::pow2_u64::Pow2U64;
-use crate::arithmetic::u64::sub_u64::SubU64;
+use crate::arithmetic::u64::popcount_u64::PopCountU64;
+use crate::arithmetic::u64::xor_u64::XorU64;
 use crate::snippet::{DataType, DeprecatedSnippet};
 use crate::{get_init_tvm_stack, ExecutionState};
 
+/// Count how many times in a row a node is the right child of its parent, going
+/// upwards from the node. Let `m = node_index - 1` and let `L` be the index of the last
+/// leaf added before or at the node, i.e. the largest `L` with `2L - popcount(L) <= m`.
+/// The node has height `h = m - (2L - popcount(L))` and its right lineage has length
+/// `trailing_ones(L) - h`. Since `L` lies in `[m/2, m/2 + 31]`, it is found by five
+/// unrolled bisection steps, so the code has no loop and a constant cycle count.
 #[derive(Clone, Debug)]
 pub struct MmrRightLineageLength;
 
@@ -53,93 +60,88 @@ impl DeprecatedSnippet for MmrRightLineageLength {
 
     fn function_code(&self, library: &mut crate::library::Library) -> String {
         let entrypoint = self.entrypoint_name();
-        let log_2_floor_u64 = library.import(Box::new(Log2FloorU64));
-        let pow2_u64 = library.import(Box::new(Pow2U64));
-        let sub_u64 = library.import(Box::new(SubU64));
-        let decr = library.import(Box::new(DecrU64));
-        let incr = library.import(Box::new(IncrU64));
+        let add_u64 = library.import(Box::new(AddU64));
+        let decr_u64 = library.import(Box::new(DecrU64));
+        let div2_u64 = library.import(Box::new(Div2U64));
+        let incr_u64 = library.import(Box::new(IncrU64));
+        let popcount_u64 = library.import(Box::new(PopCountU64));
+        let xor_u64 = library.import(Box::new(XorU64));
+
+        // Find the largest `delta` in [0, 31] with `2 * delta <= popcount(m/2 + delta) + (m % 2)`,
+        // which is `L - m/2`, one bit at a time.
+        let bisection_steps = [16, 8, 4, 2, 1]
+            .into_iter()
+            .map(|bit| {
+                format!(
+                    "
+                // _ parity a_hi a_lo delta
+                dup 2 dup 2
+                push 0 dup 3 push {bit} add
+                call {add_u64}
+                call {popcount_u64}
+                // _ parity a_hi a_lo delta popcount(a + delta + {bit})
+
+                dup 4 add push 1 add
+                dup 1 push {bit} add push 2 mul
+                // _ parity a_hi a_lo delta (popcount(a + delta + {bit}) + parity + 1) (2 * (delta + {bit}))
+
+                lt
+                push {bit} mul add
+                // _ parity a_hi a_lo delta'
+                "
+                )
+            })
+            .collect::<String>();
+
         format!(
             "// Before: _ ni_hi ni_lo
             // After: _ right_lineage_count
             {entrypoint}:
-                dup 1 dup 1 call {log_2_floor_u64}
-                // _ ni_hi ni_lo log_2_floor
+                push {two_pow_31} dup 2 lt assert
+                // _ ni_hi ni_lo
 
-                push 1
-                add
-                // _ ni_hi ni_lo log_2_floor_plus_one
+                call {decr_u64}
+                // _ m_hi m_lo
 
-                // rename: log_2_floor_plus_one -> bit_width
-                // _ ni_hi ni_lo bit_width
+                dup 0 push 1 and
+                swap 2 swap 1
+                // _ parity m_hi m_lo
 
-                dup 2 dup 2
-                // _ ni_hi ni_lo bit_width ni_hi ni_lo
+                call {div2_u64}
+                // _ parity a_hi a_lo
 
-                dup 2
-                call {pow2_u64}
-                // _ ni_hi ni_lo bit_width ni_hi ni_lo npo2_hi npo2_lo
+                push 0
+                // _ parity a_hi a_lo 0
 
-                call {sub_u64}
-                // _ ni_hi ni_lo bit_width dist_hi dist_lo
+                {bisection_steps}
+                // _ parity a_hi a_lo delta
 
-                dup 1 push 0 eq push 0 eq
-                // _ ni_hi ni_lo bit_width dist_hi dist_lo (dist_hi != 0)
+                dup 0 push 2 mul
+                dup 4 push -1 mul add push -1 add
+                // _ parity a_hi a_lo delta (2 * delta - parity - 1)
 
-                dup 1 dup 4 lt
-                // _ ni_hi ni_lo bit_width dist_hi dist_lo (dist_hi != 0) (bit_width < dist_lo)
+                swap 4 pop
+                // _ (2 * delta - parity - 1) a_hi a_lo delta
 
-                add
-                // _ ni_hi ni_lo bit_width dist_hi dist_lo (dist_hi != 0 || bit_width < dist_lo)
+                push 0 swap 1
+                call {add_u64}
+                // _ (2 * delta - parity - 1) l_hi l_lo
 
-                push 1 swap 1
-                skiz call {entrypoint}_then
-                skiz call {entrypoint}_else
+                dup 1 dup 1 call {popcount_u64}
+                push -1 mul dup 3 add swap 3 pop
+                // _ (2 * delta - parity - 1 - popcount(l)) l_hi l_lo
 
-                // _ ni_hi ni_lo bit_width dist_hi ret
+                dup 1 dup 1 call {incr_u64}
+                call {xor_u64}
+                call {popcount_u64}
+                // _ (2 * delta - parity - 1 - popcount(l)) popcount(l ^ (l + 1))
 
-                swap 4 pop pop pop pop
-                // _ ret
+                add
+                // _ right_lineage_count
 
                 return
-
-                {entrypoint}_then:
-                    // _ ni_hi ni_lo bit_width dist_hi dist_lo 1
-
-                    pop
-                    // _ ni_hi ni_lo bit_width dist_hi dist_lo
-
-                    swap 1 swap 2
-                    // _ ni_hi ni_lo dist_hi dist_lo bit_width
-
-                    call {decr}
-                    // _ ni_hi ni_lo dist_hi dist_lo (bit_width - 1)
-
-                    call {pow2_u64}
-                    // _ ni_hi ni_lo dist_hi dist_lo (npo2 >> 1)_hi (npo2 >> 1)_lo
-
-                    dup 5 dup 5 call {sub_u64}
-                    // _ ni_hi ni_lo dist_hi dist_lo (node_index - npo2 >> 1)_hi (node_index - npo2 >> 1)_lo
-
-                    call {incr}
-                    // _ ni_hi ni_lo dist_hi dist_lo (node_index - npo2 >> 1 + 1)_hi (node_index - npo2 >> 1 + 1)_lo
-
-                    call {entrypoint}
-                    // _ ni_hi ni_lo dist_hi dist_lo ret
-
-                    push 0
-                    return
-                {entrypoint}_else:
-                    // _ ni_hi ni_lo bit_width dist_hi dist_lo
-
-                    push -1 add
-                    // _ ni_hi ni_lo bit_width dist_hi (dist_lo - 1)
-
-                    // rename: dist_lo - 1 -> ret
-                    // _ ni_hi ni_lo bit_width dist_hi ret
-
-                    return
-
-            "
+            ",
+            two_pow_31 = 1u64 << 31,
         )
     }
 
@@ -256,6 +258,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn right_lineage_length_agrees_with_right_child_and_height() {
+        // The node indices used in the `MmrRightChildAndHeight` tests. A node is a right
+        // child iff its right lineage is nonempty.
+        let node_indices_and_is_right_child = [
+            (1, false),
+            (2, true),
+            (3, false),
+            (4, false),
+            (5, true),
+            (6, true),
+            (7, false),
+            (8, false),
+            (14, true),
+            (15, false),
+            (16, false),
+            (17, true),
+            (18, false),
+            (31, false),
+            (63, false),
+            ((u64::MAX >> 1) - 1, true),
+            (u64::MAX >> 1, false),
+        ];
+
+        for (node_index, is_right_child) in node_indices_and_is_right_child {
+            let expected_count =
+                mmr::shared_advanced::right_lineage_length_from_node_index(node_index);
+            assert_eq!(is_right_child, expected_count != 0);
+            prop_right_lineage_count(node_index, expected_count);
+        }
+    }
+
     fn prop_right_lineage_count(node_index: u64, expected_count: u32) {
         println!("node_index = {node_index}");
         let mut init_stack = get_init_tvm_stack();
@@ -284,10 +318,30 @@ mod tests {
 #[cfg(test)]
 mod benches {
     use super::*;
+    use crate::mmr::right_child_and_height::MmrRightChildAndHeight;
     use crate::snippet_bencher::bench_and_write;
 
     #[test]
     fn right_lineage_length_benchmark() {
         bench_and_write(MmrRightLineageLength);
     }
+
+    #[test]
+    fn right_lineage_length_is_cheaper_than_right_child_and_height() {
+        for node_index in [(1 << 32) + 1, (1 << 62) + 1] {
+            let lineage_cycles = MmrRightLineageLength
+                .link_and_run_tasm_from_state_for_bench(&mut prepare_state(node_index))
+                .unwrap()
+                .cycle_count;
+            let climb_cycles = MmrRightChildAndHeight
+                .link_and_run_tasm_from_state_for_bench(&mut prepare_state(node_index))
+                .unwrap()
+                .cycle_count;
+            assert!(
+                lineage_cycles < climb_cycles,
+                "node index {node_index}: right lineage length: {lineage_cycles} cycles, \
+                right child and height: {climb_cycles} cycles"
+            );
+        }
+    }
 }
@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use num::One;
+use rand::{random, thread_rng, Rng};
+use twenty_first::shared_math::b_field_element::BFieldElement;
+use twenty_first::shared_math::other::random_elements;
+use twenty_first::util_types::mmr::mmr_accumulator::MmrAccumulator;
+use twenty_first::util_types::mmr::mmr_trait::Mmr;
+
+use super::calculate_new_peaks_from_append::CalculateNewPeaksFromAppend;
+use super::MAX_MMR_HEIGHT;
+use crate::arithmetic::u64::incr_u64::IncrU64;
+use crate::hashing::eq_digest::EqDigest;
+use crate::library::Library;
+use crate::list::safeimplu32::get::SafeGet;
+use crate::list::safeimplu32::length::Length as SafeLength;
+use crate::list::unsafeimplu32::get::UnsafeGet;
+use crate::list::unsafeimplu32::length::Length as UnsafeLength;
+use crate::list::ListType;
+use crate::rust_shadowing_helper_functions::{list_to_vec, vec_to_list};
+use crate::snippet::{DataType, DeprecatedSnippet};
+use crate::{get_init_tvm_stack, Digest, ExecutionState, VmHasher, DIGEST_LENGTH};
+
+/// Verify that appending every leaf of the list `*new_leaves`, in order, to the MMR
+/// accumulator given by `*old_peaks` and `old_leaf_count` results in exactly the peaks
+/// in the list `*claimed_new_peaks`. The leaves are appended to the list `*old_peaks`
+/// in place, so after the call it holds the actual new peaks, whether or not they match
+/// the claimed ones. All three lists must be of the same list type. Every append goes
+/// through [`CalculateNewPeaksFromAppend`], so each new leaf also allocates an
+/// authentication path list in dynamically allocated memory.
+#[derive(Clone, Debug)]
+pub struct MmrVerifyBatchAppend {
+    pub list_type: ListType,
+}
+
+impl MmrVerifyBatchAppend {
+    fn prepare_state(
+        &self,
+        start_mmr: &MmrAccumulator<VmHasher>,
+        new_leaves: &[Digest],
+        claimed_new_peaks: &[Digest],
+    ) -> ExecutionState {
+        // We assume that the lists can safely be stored in memory on these addresses
+        let old_peaks_pointer = BFieldElement::one();
+        let new_leaves_pointer = BFieldElement::new(1 << 20);
+        let claimed_new_peaks_pointer = BFieldElement::new(1 << 21);
+
+        let mut memory = HashMap::default();
+        let to_list_elements =
+            |digests: &[Digest]| digests.iter().map(|digest| digest.values()).collect_vec();
+        vec_to_list(
+            old_peaks_pointer,
+            MAX_MMR_HEIGHT as u32,
+            &to_list_elements(&start_mmr.get_peaks()),
+            &mut memory,
+            &self.list_type,
+        );
+        vec_to_list(
+            new_leaves_pointer,
+            new_leaves.len() as u32,
+            &to_list_elements(new_leaves),
+            &mut memory,
+            &self.list_type,
+        );
+        vec_to_list(
+            claimed_new_peaks_pointer,
+            MAX_MMR_HEIGHT as u32,
+            &to_list_elements(claimed_new_peaks),
+            &mut memory,
+            &self.list_type,
+        );
+
+        let old_leaf_count = start_mmr.count_leaves();
+        let mut stack = get_init_tvm_stack();
+        stack.push(old_peaks_pointer);
+        stack.push(BFieldElement::new(old_leaf_count >> 32));
+        stack.push(BFieldElement::new(old_leaf_count & u32::MAX as u64));
+        stack.push(new_leaves_pointer);
+        stack.push(claimed_new_peaks_pointer);
+
+        // The authentication paths produced by the appends are allocated right after the
+        // old peaks list
+        let words_allocated = match self.list_type {
+            ListType::Safe => 1 + MAX_MMR_HEIGHT * DIGEST_LENGTH + 2,
+            ListType::Unsafe => 1 + MAX_MMR_HEIGHT * DIGEST_LENGTH + 1,
+        };
+
+        ExecutionState::with_stack_and_memory(stack, memory, words_allocated)
+    }
+
+    /// A state where the claimed new peaks are the correct ones.
+    fn prepare_valid_state(
+        &self,
+        start_mmr: MmrAccumulator<VmHasher>,
+        new_leaves: &[Digest],
+    ) -> ExecutionState {
+        let mut end_mmr = start_mmr.clone();
+        for leaf in new_leaves {
+            end_mmr.append(*leaf);
+        }
+
+        self.prepare_state(&start_mmr, new_leaves, &end_mmr.get_peaks())
+    }
+}
+
+impl DeprecatedSnippet for MmrVerifyBatchAppend {
+    fn entrypoint_name(&self) -> String {
+        format!("tasm_mmr_verify_batch_append_{}", self.list_type)
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        vec![
+            "*old_peaks".to_string(),
+            "old_leaf_count_hi".to_string(),
+            "old_leaf_count_lo".to_string(),
+            "*new_leaves".to_string(),
+            "*claimed_new_peaks".to_string(),
+        ]
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        vec![
+            DataType::List(Box::new(DataType::Digest)),
+            DataType::U64,
+            DataType::List(Box::new(DataType::Digest)),
+            DataType::List(Box::new(DataType::Digest)),
+        ]
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        vec!["validation_result".to_string()]
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        vec![DataType::Bool]
+    }
+
+    fn stack_diff(&self) -> isize {
+        -4
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        let entrypoint = self.entrypoint_name();
+        let (get, length) = match self.list_type {
+            ListType::Safe => (
+                library.import(Box::new(SafeGet(DataType::Digest))),
+                library.import(Box::new(SafeLength(DataType::Digest))),
+            ),
+            ListType::Unsafe => (
+                library.import(Box::new(UnsafeGet(DataType::Digest))),
+                library.import(Box::new(UnsafeLength(DataType::Digest))),
+            ),
+        };
+        let append = library.import(Box::new(CalculateNewPeaksFromAppend {
+            list_type: self.list_type.clone(),
+            loop_bound: None,
+        }));
+        let u64incr = library.import(Box::new(IncrU64));
+        let eq_digest = library.import(Box::new(EqDigest));
+
+        format!(
+            "
+            // BEFORE: _ *old_peaks old_leaf_count_hi old_leaf_count_lo *new_leaves *claimed_new_peaks
+            // AFTER: _ validation_result
+            {entrypoint}:
+                swap 4
+                swap 3
+                swap 2
+                swap 1
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves
+
+                dup 0
+                call {length}
+                push 0
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves 0
+
+                call {entrypoint}_append_all
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves num_leaves
+
+                pop
+                pop
+                pop
+                pop
+                pop
+                // _ *claimed_new_peaks *peaks
+
+                dup 1
+                call {length}
+                dup 1
+                call {length}
+                // _ *claimed_new_peaks *peaks claimed_length length
+
+                dup 1
+                eq
+                swap 1
+                push 0
+                // _ *claimed_new_peaks *peaks (claimed_length == length) claimed_length 0
+
+                dup 2
+                skiz
+                    call {entrypoint}_compare_peaks
+                // _ *claimed_new_peaks *peaks validation_result claimed_length i
+
+                pop
+                pop
+                swap 2
+                pop
+                pop
+                // _ validation_result
+
+                return
+
+            // INVARIANT: _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves i
+            {entrypoint}_append_all:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 4
+                dup 4
+                dup 7
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves i leaf_count_hi leaf_count_lo *peaks
+
+                dup 5
+                dup 4
+                call {get}
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves i leaf_count_hi leaf_count_lo *peaks [digest (new_leaf)]
+
+                call {append}
+                pop
+                pop
+                // _ *claimed_new_peaks *peaks leaf_count_hi leaf_count_lo *new_leaves num_leaves i
+
+                dup 4
+                dup 4
+                call {u64incr}
+                swap 5
+                pop
+                swap 5
+                pop
+                // _ *claimed_new_peaks *peaks (leaf_count + 1)_hi (leaf_count + 1)_lo *new_leaves num_leaves i
+
+                push 1
+                add
+                recurse
+
+            // INVARIANT: _ *claimed_new_peaks *peaks validation_result length i
+            {entrypoint}_compare_peaks:
+                dup 1
+                dup 1
+                eq
+                skiz
+                    return
+
+                dup 4
+                dup 1
+                call {get}
+                // _ *claimed_new_peaks *peaks validation_result length i [digest (claimed_peak)]
+
+                dup 8
+                dup 6
+                call {get}
+                // _ *claimed_new_peaks *peaks validation_result length i [digest (claimed_peak)] [digest (peak)]
+
+                call {eq_digest}
+                dup 3
+                mul
+                swap 3
+                pop
+                // _ *claimed_new_peaks *peaks validation_result' length i
+
+                push 1
+                add
+                recurse
+            "
+        )
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        vec!["Snippet arguments are not a valid MMR accumulator".to_string()]
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        let mut rng = thread_rng();
+        let mut states = vec![];
+        for (old_leaf_count, num_new_leaves) in [(0, 0), (0, 1), (3, 5), (7, 1), (1000, 17)] {
+            let start_mmr = MmrAccumulator::new(random_elements(old_leaf_count));
+            let new_leaves: Vec<Digest> = random_elements(num_new_leaves);
+            states.push(self.prepare_valid_state(start_mmr, &new_leaves));
+        }
+
+        let start_mmr = MmrAccumulator::new(random_elements(rng.gen_range(0..100)));
+        let new_leaves: Vec<Digest> = random_elements(rng.gen_range(1..20));
+        let mut wrong_peaks = start_mmr.get_peaks();
+        wrong_peaks.push(random());
+        states.push(self.prepare_state(&start_mmr, &new_leaves, &wrong_peaks));
+
+        states
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        let start_mmr = MmrAccumulator::init(random_elements(31), (1 << 31) - 1);
+        self.prepare_valid_state(start_mmr, &random_elements(10))
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        let start_mmr = MmrAccumulator::init(random_elements(62), (1 << 62) - 1);
+        self.prepare_valid_state(start_mmr, &random_elements(100))
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        _std_in: Vec<BFieldElement>,
+        _secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        let claimed_new_peaks_pointer = stack.pop().unwrap();
+        let new_leaves_pointer = stack.pop().unwrap();
+        let old_leaf_count_lo = stack.pop().unwrap().value();
+        let old_leaf_count_hi = stack.pop().unwrap().value();
+        let peaks_pointer = stack.pop().unwrap();
+        let old_leaf_count = (old_leaf_count_hi << 32) | old_leaf_count_lo;
+
+        let read_digests = |list_pointer, memory: &HashMap<BFieldElement, BFieldElement>| {
+            list_to_vec::<DIGEST_LENGTH>(list_pointer, memory, &self.list_type)
+                .into_iter()
+                .map(Digest::new)
+                .collect_vec()
+        };
+        let new_leaves = read_digests(new_leaves_pointer, memory);
+        let claimed_new_peaks = read_digests(claimed_new_peaks_pointer, memory);
+
+        let mut mmra: MmrAccumulator<VmHasher> =
+            MmrAccumulator::init(read_digests(peaks_pointer, memory), old_leaf_count);
+        for leaf in new_leaves.iter() {
+            mmra.append(*leaf);
+        }
+
+        // Update the peaks list in memory the same way that the TASM code does
+        let append = CalculateNewPeaksFromAppend {
+            list_type: self.list_type.clone(),
+            loop_bound: None,
+        };
+        let mut leaf_count = old_leaf_count;
+        for leaf in new_leaves {
+            stack.push(BFieldElement::new(leaf_count >> 32));
+            stack.push(BFieldElement::new(leaf_count & u32::MAX as u64));
+            stack.push(peaks_pointer);
+            for value in leaf.values().iter().rev() {
+                stack.push(*value);
+            }
+            append.rust_shadowing(stack, vec![], vec![], memory);
+
+            // _ *peaks *auth_path
+            stack.pop();
+            stack.pop();
+            leaf_count += 1;
+        }
+        assert_eq!(mmra.get_peaks(), read_digests(peaks_pointer, memory));
+
+        let validation_result = mmra.get_peaks() == claimed_new_peaks;
+        stack.push(BFieldElement::new(validation_result as u64));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::Zero;
+
+    use super::*;
+    use crate::test_helpers::{
+        test_rust_equivalence_given_execution_state_deprecated,
+        test_rust_equivalence_multiple_deprecated,
+    };
+
+    #[test]
+    fn verify_batch_append_test_unsafe_lists() {
+        test_rust_equivalence_multiple_deprecated(
+            &MmrVerifyBatchAppend {
+                list_type: ListType::Unsafe,
+            },
+            true,
+        );
+    }
+
+    #[test]
+    fn verify_batch_append_test_safe_lists() {
+        test_rust_equivalence_multiple_deprecated(
+            &MmrVerifyBatchAppend {
+                list_type: ListType::Safe,
+            },
+            true,
+        );
+    }
+
+    fn prop_verify_batch_append(
+        list_type: ListType,
+        old_leaf_count: usize,
+        num_new_leaves: usize,
+        corrupt_claim: bool,
+    ) {
+        let snippet = MmrVerifyBatchAppend { list_type };
+        let start_mmr: MmrAccumulator<VmHasher> =
+            MmrAccumulator::new(random_elements(old_leaf_count));
+        let new_leaves: Vec<Digest> = random_elements(num_new_leaves);
+
+        let mut end_mmr = start_mmr.clone();
+        for leaf in new_leaves.iter() {
+            end_mmr.append(*leaf);
+        }
+        let mut claimed_new_peaks = end_mmr.get_peaks();
+        if corrupt_claim {
+            let last_peak = claimed_new_peaks.last_mut().unwrap();
+            let mut values = last_peak.values();
+            values[0] += BFieldElement::one();
+            *last_peak = Digest::new(values);
+        }
+
+        let init_state = snippet.prepare_state(&start_mmr, &new_leaves, &claimed_new_peaks);
+        let vm_output_state =
+            test_rust_equivalence_given_execution_state_deprecated(&snippet, init_state);
+
+        let validation_result = *vm_output_state.final_stack.last().unwrap();
+        if corrupt_claim {
+            assert!(validation_result.is_zero());
+        } else {
+            assert!(validation_result.is_one());
+        }
+    }
+
+    #[test]
+    fn append_empty_batch() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            prop_verify_batch_append(list_type.clone(), 0, 0, false);
+            prop_verify_batch_append(list_type, 5, 0, false);
+        }
+    }
+
+    #[test]
+    fn append_single_leaf() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            for old_leaf_count in [0, 1, 2, 3, 7, 8] {
+                prop_verify_batch_append(list_type.clone(), old_leaf_count, 1, false);
+            }
+        }
+    }
+
+    #[test]
+    fn append_several_leaves() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            for (old_leaf_count, num_new_leaves) in [(0, 2), (0, 16), (1, 7), (13, 9), (64, 64)] {
+                prop_verify_batch_append(list_type.clone(), old_leaf_count, num_new_leaves, false);
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_claimed_peaks_are_rejected() {
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            prop_verify_batch_append(list_type.clone(), 0, 1, true);
+            prop_verify_batch_append(list_type.clone(), 5, 3, true);
+            prop_verify_batch_append(list_type, 31, 1, true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::snippet_bencher::bench_and_write;
+
+    #[test]
+    fn verify_batch_append_unsafe_lists_benchmark() {
+        bench_and_write(MmrVerifyBatchAppend {
+            list_type: ListType::Unsafe,
+        });
+    }
+
+    #[test]
+    fn verify_batch_append_safe_lists_benchmark() {
+        bench_and_write(MmrVerifyBatchAppend {
+            list_type: ListType::Safe,
+        });
+    }
+}
@@ -120,8 +120,10 @@ impl<P: Procedure + 'static> RustShadow for ShadowedProcedure<P> {
                 words_statically_allocated,
             );
 
-            // assert_eq!(tasm.final_sponge_state.state, rust.final_sponge_state.state);
-            // can't do this without changing the VM interface, unfortunately ...
+            assert_eq!(
+                tasm.final_sponge_state.state, rust.final_sponge_state.state,
+                "Rust shadowing and VM sponge states must agree"
+            );
 
             assert_eq!(
                 rust.output, tasm.output,
@@ -1,3 +1,4 @@
 pub mod lsb;
 pub mod neg;
+pub mod rotate;
 pub mod sub;
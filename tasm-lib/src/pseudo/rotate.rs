@@ -0,0 +1,254 @@
+use itertools::Itertools;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use triton_vm::{triton_asm, BFieldElement};
+
+use crate::{
+    closure::Closure,
+    get_init_tvm_stack,
+    snippet::{BasicSnippet, DataType},
+    snippet_bencher::BenchmarkCase,
+};
+
+/// Largest number of words that can be rotated, as `swap` reaches at most 15 words deep.
+pub const MAX_ROTATION_WIDTH: usize = 16;
+
+/// Rotate the top `n` single-word stack elements one position to the left, i.e., move
+/// the deepest of the `n` elements to the top of the stack.
+///
+/// BEFORE: _ x_{n-1} x_{n-2} ... x_1 x_0
+/// AFTER: _ x_{n-2} ... x_1 x_0 x_{n-1}
+pub struct StackRotateLeft(pub usize);
+
+/// Rotate the top `n` single-word stack elements one position to the right, i.e., move
+/// the top element to depth `n - 1`.
+///
+/// BEFORE: _ x_{n-1} x_{n-2} ... x_1 x_0
+/// AFTER: _ x_0 x_{n-1} x_{n-2} ... x_1
+pub struct StackRotateRight(pub usize);
+
+fn assert_valid_width(n: usize) {
+    assert!(
+        (2..=MAX_ROTATION_WIDTH).contains(&n),
+        "Can only rotate between 2 and {MAX_ROTATION_WIDTH} stack elements. Got: {n}"
+    );
+}
+
+fn rotation_inputs(n: usize) -> Vec<(DataType, String)> {
+    (0..n)
+        .rev()
+        .map(|i| (DataType::BFE, format!("x_{i}")))
+        .collect_vec()
+}
+
+fn rotation_initial_state(
+    n: usize,
+    seed: [u8; 32],
+    bench_case: Option<BenchmarkCase>,
+) -> Vec<BFieldElement> {
+    let elements = match bench_case {
+        Some(_) => (0..n as u64).map(BFieldElement::new).collect_vec(),
+        None => {
+            let mut rng: StdRng = SeedableRng::from_seed(seed);
+            (0..n).map(|_| rng.gen()).collect_vec()
+        }
+    };
+
+    [get_init_tvm_stack(), elements].concat()
+}
+
+impl BasicSnippet for StackRotateLeft {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        rotation_inputs(self.0)
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        let mut ret = rotation_inputs(self.0);
+        ret.rotate_left(1);
+        ret
+    }
+
+    fn entrypoint(&self) -> String {
+        format!("tasm_pseudo_stack_rotate_left_{}", self.0)
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        assert_valid_width(self.0);
+        let entrypoint = self.entrypoint();
+
+        // `swap 1`, `swap 2`, ..., `swap n-1` bubbles the deepest element to the top
+        // while shifting all other elements one position down.
+        let swaps = (1..self.0).map(|i| format!("swap {i}")).join("\n");
+
+        triton_asm!(
+            {entrypoint}:
+                {swaps}
+                return
+        )
+    }
+}
+
+impl Closure for StackRotateLeft {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        assert_valid_width(self.0);
+        let split_point = stack.len() - self.0;
+        stack[split_point..].rotate_left(1);
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        rotation_initial_state(self.0, seed, bench_case)
+    }
+}
+
+impl BasicSnippet for StackRotateRight {
+    fn inputs(&self) -> Vec<(DataType, String)> {
+        rotation_inputs(self.0)
+    }
+
+    fn outputs(&self) -> Vec<(DataType, String)> {
+        let mut ret = rotation_inputs(self.0);
+        ret.rotate_right(1);
+        ret
+    }
+
+    fn entrypoint(&self) -> String {
+        format!("tasm_pseudo_stack_rotate_right_{}", self.0)
+    }
+
+    fn code(
+        &self,
+        _library: &mut crate::library::Library,
+    ) -> Vec<triton_vm::instruction::LabelledInstruction> {
+        assert_valid_width(self.0);
+        let entrypoint = self.entrypoint();
+
+        // `swap n-1`, `swap n-2`, ..., `swap 1` sinks the top element to depth n-1
+        // while shifting all other elements one position up.
+        let swaps = (1..self.0).rev().map(|i| format!("swap {i}")).join("\n");
+
+        triton_asm!(
+            {entrypoint}:
+                {swaps}
+                return
+        )
+    }
+}
+
+impl Closure for StackRotateRight {
+    fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+        assert_valid_width(self.0);
+        let split_point = stack.len() - self.0;
+        stack[split_point..].rotate_right(1);
+    }
+
+    fn pseudorandom_initial_state(
+        &self,
+        seed: [u8; 32],
+        bench_case: Option<BenchmarkCase>,
+    ) -> Vec<BFieldElement> {
+        rotation_initial_state(self.0, seed, bench_case)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use triton_vm::NonDeterminism;
+    use twenty_first::util_types::algebraic_hasher::Domain;
+
+    use super::*;
+    use crate::closure::ShadowedClosure;
+    use crate::snippet::RustShadow;
+    use crate::test_helpers::test_rust_equivalence_given_complete_state;
+    use crate::VmHasherState;
+
+    fn bfes(values: &[u64]) -> Vec<BFieldElement> {
+        values.iter().map(|&x| BFieldElement::new(x)).collect_vec()
+    }
+
+    #[test]
+    fn stack_rotate_left_pbt() {
+        for n in 2..=MAX_ROTATION_WIDTH {
+            ShadowedClosure::new(StackRotateLeft(n)).test()
+        }
+    }
+
+    #[test]
+    fn stack_rotate_right_pbt() {
+        for n in 2..=MAX_ROTATION_WIDTH {
+            ShadowedClosure::new(StackRotateRight(n)).test()
+        }
+    }
+
+    #[test]
+    fn stack_rotate_unit_test() {
+        for (n, expected_left, expected_right) in [
+            (3, vec![2, 3, 1], vec![3, 1, 2]),
+            (5, vec![2, 3, 4, 5, 1], vec![5, 1, 2, 3, 4]),
+        ] {
+            let init_stack = [get_init_tvm_stack(), bfes(&(1..=n).collect_vec())].concat();
+            let expected_left = [get_init_tvm_stack(), bfes(&expected_left)].concat();
+            let expected_right = [get_init_tvm_stack(), bfes(&expected_right)].concat();
+
+            test_rust_equivalence_given_complete_state(
+                &ShadowedClosure::new(StackRotateLeft(n as usize)),
+                &init_stack,
+                &[],
+                &NonDeterminism::new(vec![]),
+                &HashMap::default(),
+                &VmHasherState::new(Domain::VariableLength),
+                1,
+                Some(&expected_left),
+            );
+            test_rust_equivalence_given_complete_state(
+                &ShadowedClosure::new(StackRotateRight(n as usize)),
+                &init_stack,
+                &[],
+                &NonDeterminism::new(vec![]),
+                &HashMap::default(),
+                &VmHasherState::new(Domain::VariableLength),
+                1,
+                Some(&expected_right),
+            );
+        }
+    }
+
+    #[test]
+    fn rotations_are_inverses() {
+        let n = 5;
+        let mut stack = [get_init_tvm_stack(), bfes(&[1, 2, 3, 4, 5])].concat();
+        let init_stack = stack.clone();
+        StackRotateLeft(n).rust_shadow(&mut stack);
+        StackRotateRight(n).rust_shadow(&mut stack);
+        assert_eq!(init_stack, stack);
+    }
+
+    #[should_panic]
+    #[test]
+    fn rotation_width_too_large() {
+        StackRotateLeft(MAX_ROTATION_WIDTH + 1).code(&mut crate::library::Library::new());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use crate::{closure::ShadowedClosure, snippet::RustShadow};
+
+    #[test]
+    fn stack_rotate_left_bench() {
+        ShadowedClosure::new(StackRotateLeft(MAX_ROTATION_WIDTH)).bench()
+    }
+
+    #[test]
+    fn stack_rotate_right_bench() {
+        ShadowedClosure::new(StackRotateRight(MAX_ROTATION_WIDTH)).bench()
+    }
+}
@@ -153,13 +153,10 @@ impl Algorithm for MerkleVerify {
     ) {
         {
             let mut rng: StdRng = SeedableRng::from_seed(seed);
-            let tree_height = if let Some(bench_case) = maybe_bench_case {
-                match bench_case {
-                    BenchmarkCase::CommonCase => 6,
-                    BenchmarkCase::WorstCase => 20,
-                }
-            } else {
-                rng.gen_range(0..20)
+            let tree_height = match maybe_bench_case {
+                Some(BenchmarkCase::CommonCase) => 6,
+                Some(BenchmarkCase::WorstCase) => 20,
+                Some(BenchmarkCase::Custom(_)) | None => rng.gen_range(0..20),
             };
 
             // sample unconstrained inputs directly
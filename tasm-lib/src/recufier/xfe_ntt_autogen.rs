@@ -35,6 +35,10 @@ impl BasicSnippet for XfeNtt {
         ]
     }
 
+    fn static_allocation_size(&self) -> usize {
+        2
+    }
+
     fn code(&self, library: &mut Library) -> Vec<triton_vm::instruction::LabelledInstruction> {
         let entrypoint = self.entrypoint();
         let tasm_arithmetic_u32_leadingzeros =
@@ -547,7 +551,7 @@ impl Function for XfeNtt {
         let n = match bench_case {
             Some(crate::snippet_bencher::BenchmarkCase::CommonCase) => 32,
             Some(crate::snippet_bencher::BenchmarkCase::WorstCase) => 128,
-            None => 32,
+            Some(crate::snippet_bencher::BenchmarkCase::Custom(_)) | None => 32,
         };
         let mut vector = (0..n).map(|_| rng.gen()).collect::<Vec<XFieldElement>>();
         vector[0] = XFieldElement::one();
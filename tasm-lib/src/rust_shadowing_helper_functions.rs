@@ -3,6 +3,12 @@ pub mod input;
 pub mod safe_list;
 pub mod unsafe_list;
 
+use std::collections::HashMap;
+
+use twenty_first::shared_math::b_field_element::BFieldElement;
+
+use crate::list::ListType;
+
 /// Count the number of non-leaf nodes that were inserted *prior* to
 /// the insertion of this leaf.
 pub fn non_leaf_nodes_left(leaf_index: u64) -> u64 {
@@ -28,3 +34,71 @@ pub fn non_leaf_nodes_left(leaf_index: u64) -> u64 {
 
     ret
 }
+
+/// Read all elements of a list whose elements consist of `N` words each.
+pub fn list_to_vec<const N: usize>(
+    list_pointer: BFieldElement,
+    memory: &HashMap<BFieldElement, BFieldElement>,
+    list_type: &ListType,
+) -> Vec<[BFieldElement; N]> {
+    let length = match list_type {
+        ListType::Safe => safe_list::safe_list_get_length(list_pointer, memory),
+        ListType::Unsafe => unsafe_list::unsafe_list_get_length(list_pointer, memory),
+    };
+    let list_get = match list_type {
+        ListType::Safe => safe_list::safe_list_get,
+        ListType::Unsafe => unsafe_list::unsafe_list_get,
+    };
+
+    (0..length)
+        .map(|i| list_get(list_pointer, i, memory, N).try_into().unwrap())
+        .collect()
+}
+
+/// Write a new list containing the given elements, each consisting of `N` words, to
+/// memory. The capacity is only used for safe lists.
+pub fn vec_to_list<const N: usize>(
+    list_pointer: BFieldElement,
+    capacity: u32,
+    elements: &[[BFieldElement; N]],
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+    list_type: &ListType,
+) {
+    match list_type {
+        ListType::Safe => safe_list::safe_list_new(list_pointer, capacity, memory),
+        ListType::Unsafe => unsafe_list::unsafe_list_new(list_pointer, memory),
+    }
+
+    let list_push = match list_type {
+        ListType::Safe => safe_list::safe_list_push,
+        ListType::Unsafe => unsafe_list::unsafe_list_push,
+    };
+    for element in elements {
+        list_push(list_pointer, element.to_vec(), memory, N);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::random;
+
+    use super::*;
+
+    #[test]
+    fn vec_to_list_to_vec_round_trip() {
+        let list_pointer = BFieldElement::new(random::<u32>() as u64);
+        let digests: Vec<[BFieldElement; 5]> = (0..7).map(|_| random()).collect();
+        let empty: Vec<[BFieldElement; 5]> = vec![];
+
+        for list_type in [ListType::Safe, ListType::Unsafe] {
+            for elements in [&digests, &empty] {
+                let mut memory = HashMap::default();
+                vec_to_list(list_pointer, 10, elements, &mut memory, &list_type);
+                assert_eq!(
+                    *elements,
+                    list_to_vec::<5>(list_pointer, &memory, &list_type)
+                );
+            }
+        }
+    }
+}
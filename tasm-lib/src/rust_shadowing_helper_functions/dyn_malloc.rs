@@ -15,28 +15,124 @@ pub fn rust_dyn_malloc_initialize(
     );
 }
 
+/// Mirror `DynMalloc`: reuse a freed block of exactly `size_in_words` words if one
+/// exists, and otherwise bump the allocator. Allocating zero words returns the current
+/// free pointer and leaves memory untouched.
 pub fn dynamic_allocator(
     size_in_words: usize,
     memory: &mut HashMap<BFieldElement, BFieldElement>,
 ) -> BFieldElement {
+    if let Some(block) = take_free_block(size_in_words, memory) {
+        return block;
+    }
+
     let allocator_addr = BFieldElement::new(dyn_malloc::DYN_MALLOC_ADDRESS as u64);
     let size = BFieldElement::new(size_in_words as u64);
-    let used_memory = memory
-        .entry(allocator_addr)
-        .and_modify(|e| {
-            *e = if e.is_zero() {
-                BFieldElement::one()
-            } else {
-                *e
-            }
-        })
-        .or_insert_with(BFieldElement::one);
-
     assert!(size.value() < (1u64 << 32));
 
-    let next_addr = *used_memory;
+    let next_addr = match memory.get(&allocator_addr) {
+        Some(next_addr) if !next_addr.is_zero() => *next_addr,
+        _ => BFieldElement::one(),
+    };
 
-    *used_memory += size;
+    if !size.is_zero() {
+        memory.insert(allocator_addr, next_addr + size);
+    }
 
     next_addr
 }
+
+/// Mirror `DynMallocAligned`: round the free pointer up to a multiple of `alignment`,
+/// which must be a power of two, and bump the allocator from there. The free list is
+/// not consulted. Allocating zero words leaves memory untouched.
+pub fn dynamic_allocator_aligned(
+    size_in_words: usize,
+    alignment: usize,
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+) -> BFieldElement {
+    assert!(
+        alignment.is_power_of_two(),
+        "alignment must be a power of two"
+    );
+    assert!((size_in_words as u64) < (1u64 << 32));
+
+    let allocator_addr = BFieldElement::new(dyn_malloc::DYN_MALLOC_ADDRESS as u64);
+    let free_pointer = match memory.get(&allocator_addr) {
+        Some(next_addr) if !next_addr.is_zero() => next_addr.value(),
+        _ => 1,
+    };
+
+    let mask = alignment as u64 - 1;
+    let next_addr = (free_pointer + mask) & !mask;
+    let used_memory = next_addr + size_in_words as u64;
+    assert!(used_memory < (1u64 << 32));
+
+    if size_in_words != 0 {
+        memory.insert(allocator_addr, BFieldElement::new(used_memory));
+    }
+
+    BFieldElement::new(next_addr)
+}
+
+/// Remove the first block of exactly `size_in_words` words from the free list that
+/// `DynFree` maintains, and return it.
+fn take_free_block(
+    size_in_words: usize,
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+) -> Option<BFieldElement> {
+    let read = |memory: &HashMap<BFieldElement, BFieldElement>, address| {
+        memory
+            .get(&address)
+            .copied()
+            .unwrap_or_else(BFieldElement::zero)
+    };
+
+    let mut link = BFieldElement::new(dyn_malloc::DYN_MALLOC_FREE_LIST_ADDRESS);
+    loop {
+        let block = read(memory, link);
+        if block.is_zero() {
+            return None;
+        }
+
+        let block_size = read(memory, block + BFieldElement::one());
+        if block_size.value() == size_in_words as u64 {
+            let next_block = read(memory, block);
+            memory.insert(link, next_block);
+            return Some(block);
+        }
+
+        link = block;
+    }
+}
+
+/// Mirror `DynFree`: push the block onto the free list. Blocks of fewer than two words
+/// cannot hold a list node and are not reused.
+pub fn dynamic_free(
+    pointer: BFieldElement,
+    size_in_words: usize,
+    memory: &mut HashMap<BFieldElement, BFieldElement>,
+) {
+    if size_in_words < 2 {
+        return;
+    }
+
+    let head_addr = BFieldElement::new(dyn_malloc::DYN_MALLOC_FREE_LIST_ADDRESS);
+    let old_head = memory
+        .get(&head_addr)
+        .copied()
+        .unwrap_or_else(BFieldElement::zero);
+    memory.insert(
+        pointer + BFieldElement::one(),
+        BFieldElement::new(size_in_words as u64),
+    );
+    memory.insert(pointer, old_head);
+    memory.insert(head_addr, pointer);
+}
+
+/// A random address for an input list of a snippet under test that cannot collide with
+/// the blocks the snippet obtains from `DynMalloc`. Test states allocate far fewer than
+/// 2^32 words, starting at address 1, so any address in `[2^32, 2^33)` is safe; the
+/// allocator's bookkeeping words live at addresses 0 and `BFieldElement::MAX`.
+pub fn random_pointer_above_dynamic_allocations() -> BFieldElement {
+    BFieldElement::new(rand::random::<u32>() as u64 + (1 << 32))
+}
@@ -12,9 +12,12 @@ use triton_vm::parser::{to_labelled_instructions, tokenize};
 use triton_vm::{triton_asm, NonDeterminism, Program};
 use twenty_first::shared_math::b_field_element::BFieldElement;
 
-use crate::execute_with_terminal_state;
+use crate::execute_with_terminal_state_and_trace;
 use crate::library::Library;
+use crate::linker::link_snippet;
+use crate::memory::dyn_malloc::DynMalloc;
 use crate::program_with_state_preparation;
+use crate::state_preparation_cycle_count;
 use crate::test_helpers::test_rust_equivalence_given_execution_state_deprecated;
 use crate::VmHasherState;
 use crate::{execute_bench_deprecated, ExecutionResult, VmOutputState, DIGEST_LENGTH};
@@ -40,9 +43,14 @@ impl Display for InputSource {
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub enum DataType {
     Bool,
+    U16,
     U32,
     U64,
     U128,
+    /// A signed 32-bit integer, encoded as the single field element whose value is the
+    /// integer's two's-complement bit pattern, i.e., an element in `[0, 2^32)` where
+    /// `2^31..2^32` represent the negative numbers. See [`DataType::i32_encoding`].
+    I32,
     BFE,
     XFE,
     Digest,
@@ -57,9 +65,11 @@ impl DataType {
         // This function is used to autogenerate snippets in the tasm-lang compiler
         match self {
             DataType::Bool => "DataType::Bool".to_owned(),
+            DataType::U16 => "DataType::U16".to_owned(),
             DataType::U32 => "DataType::U32".to_owned(),
             DataType::U64 => "DataType::U64".to_owned(),
             DataType::U128 => "DataType::U128".to_owned(),
+            DataType::I32 => "DataType::I32".to_owned(),
             DataType::BFE => "DataType::BFE".to_owned(),
             DataType::XFE => "DataType::XFE".to_owned(),
             DataType::Digest => "DataType::Digest".to_owned(),
@@ -83,9 +93,11 @@ impl DataType {
     pub fn big_random_generatable_type_collection() -> Vec<DataType> {
         vec![
             DataType::Bool,
+            DataType::U16,
             DataType::U32,
             DataType::U64,
             DataType::U128,
+            DataType::I32,
             DataType::BFE,
             DataType::XFE,
             DataType::Digest,
@@ -131,6 +143,9 @@ impl DataType {
                     .map(|x| vec![BFieldElement::new(*x as u64)])
                     .collect_vec()
             }
+            DataType::U16 => (0..count)
+                .map(|_| vec![BFieldElement::new(rng.gen_range(0..=u16::MAX as u64))])
+                .collect_vec(),
             DataType::U32 => (0..count)
                 .map(|_| vec![BFieldElement::new(rng.gen_range(0..=u32::MAX as u64))])
                 .collect_vec(),
@@ -144,6 +159,9 @@ impl DataType {
                 .tuples()
                 .map(|(a, b, c, d)| vec![a, b, c, d])
                 .collect_vec(),
+            DataType::I32 => (0..count)
+                .map(|_| vec![Self::i32_encoding(rng.gen())])
+                .collect_vec(),
             DataType::BFE => (0..count)
                 .map(|_| vec![BFieldElement::new(rng.gen_range(0..=BFieldElement::MAX))])
                 .collect_vec(),
@@ -169,6 +187,18 @@ impl DataType {
         let mut rng = thread_rng();
         self.seeded_random_elements(count, &mut rng)
     }
+
+    /// A random, valid encoding of a single value of this type, in memory order. The
+    /// returned vector always has length `get_size()`.
+    pub fn random_element(&self) -> Vec<BFieldElement> {
+        self.random_elements(1).pop().unwrap()
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label_friendly_name())
+    }
 }
 
 impl FromStr for DataType {
@@ -195,9 +225,11 @@ impl FromStr for DataType {
             match s {
                 "void_pointer" => VoidPointer,
                 "bool" => Bool,
+                "u16" => U16,
                 "u32" => U32,
                 "u64" => U64,
                 "u128" => U128,
+                "i32" => I32,
                 "bfe" => BFE,
                 "xfe" => XFE,
                 "digest" => Digest,
@@ -224,9 +256,11 @@ impl DataType {
             }
             DataType::VoidPointer => "void_pointer".to_string(),
             DataType::Bool => "bool".to_string(),
+            DataType::U16 => "u16".to_string(),
             DataType::U32 => "u32".to_string(),
             DataType::U64 => "u64".to_string(),
             DataType::U128 => "u128".to_string(),
+            DataType::I32 => "i32".to_string(),
             DataType::BFE => "bfe".to_string(),
             DataType::XFE => "xfe".to_string(),
             DataType::Digest => "digest".to_string(),
@@ -236,9 +270,11 @@ impl DataType {
     pub fn get_size(&self) -> usize {
         match self {
             DataType::Bool => 1,
+            DataType::U16 => 1,
             DataType::U32 => 1,
             DataType::U64 => 2,
             DataType::U128 => 4,
+            DataType::I32 => 1,
             DataType::BFE => 1,
             DataType::XFE => 3,
             DataType::Digest => DIGEST_LENGTH,
@@ -247,6 +283,23 @@ impl DataType {
             DataType::Tuple(t) => t.iter().map(|dt| dt.get_size()).sum(),
         }
     }
+
+    /// The `u16` encoded by a field element, or `None` if the element is not smaller
+    /// than `2^16`.
+    pub fn u16_from_encoding(encoding: BFieldElement) -> Option<u16> {
+        u16::try_from(encoding.value()).ok()
+    }
+
+    /// The encoding of an `i32`: its two's-complement bit pattern, read as a `u32`.
+    pub fn i32_encoding(value: i32) -> BFieldElement {
+        BFieldElement::new(value as u32 as u64)
+    }
+
+    /// The `i32` encoded by a field element, or `None` if the element is not smaller
+    /// than `2^32`. Inverse of [`i32_encoding`](Self::i32_encoding).
+    pub fn i32_from_encoding(encoding: BFieldElement) -> Option<i32> {
+        u32::try_from(encoding.value()).ok().map(|bits| bits as i32)
+    }
 }
 
 pub trait BasicSnippet {
@@ -265,6 +318,46 @@ pub trait BasicSnippet {
         }
         diff
     }
+
+    /// Where in the Rust source this snippet is defined, as set with
+    /// [`source_location!`](crate::source_location). Shown next to the snippet's
+    /// entrypoint in profiles.
+    fn source_location(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The number of words this snippet statically allocates through
+    /// [`Library::kmalloc`] when its code is generated, not counting allocations made
+    /// by the snippets it imports. Snippets that call `kmalloc` must override this.
+    fn static_allocation_size(&self) -> usize {
+        0
+    }
+
+    /// Link this snippet and everything it imports into a program that calls the
+    /// snippet's entrypoint and then halts. The arguments are whatever is on top of the
+    /// stack when the program starts.
+    fn assemble(&self) -> Program {
+        self.assemble_with_preallocated(0)
+    }
+
+    /// Like [`assemble`](Self::assemble), but with `words` words of memory statically
+    /// allocated, such that the snippet and its dependencies allocate beyond them.
+    fn assemble_with_preallocated(&self, words: usize) -> Program {
+        // Like the test runner, initialize the dynamic allocator such that it does not
+        // hand out the preallocated words.
+        let initialization = DynMalloc::get_initialization_code(words.try_into().unwrap());
+        let code = [initialization, link_snippet(self, words)].concat();
+
+        Program::new(&code)
+    }
+}
+
+/// The current file and line as a `&'static str`, for implementing `source_location`.
+#[macro_export]
+macro_rules! source_location {
+    () => {
+        concat!(file!(), ":", line!())
+    };
 }
 
 pub trait RustShadow {
@@ -317,6 +410,16 @@ pub trait DeprecatedSnippet {
 
     fn worst_case_input_state(&self) -> ExecutionState;
 
+    /// See [`BasicSnippet::source_location`].
+    fn source_location(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// See [`BasicSnippet::static_allocation_size`].
+    fn static_allocation_size(&self) -> usize {
+        0
+    }
+
     fn function_code_as_instructions(&self, library: &mut Library) -> Vec<LabelledInstruction> {
         let f_body = self.function_code(library);
 
@@ -390,15 +493,20 @@ pub trait DeprecatedSnippet {
         let code = self.link_for_isolated_run(words_allocated);
         let program =
             program_with_state_preparation(&code, stack, &mut nondeterminism, words_allocated);
-        let tvm_result = execute_with_terminal_state(&program, &std_in, &mut nondeterminism);
+        let tvm_result =
+            execute_with_terminal_state_and_trace(&program, &std_in, &nondeterminism, None);
+        let preparation_cycle_count = state_preparation_cycle_count(stack, words_allocated);
 
-        let maybe_final_state = tvm_result.map(|st| VmOutputState {
+        let maybe_final_state = tvm_result.map(|(st, aet)| VmOutputState {
             final_ram: st.ram,
             final_sponge_state: VmHasherState {
                 state: st.sponge_state,
             },
             final_stack: st.op_stack.stack,
             output: st.public_output,
+            clock_cycle_count: st.cycle_count as usize - preparation_cycle_count,
+            hash_table_height: aet.hash_trace.nrows(),
+            u32_table_height: aet.u32_table_length(),
         });
 
         if maybe_final_state.is_ok() {
@@ -410,6 +518,7 @@ pub trait DeprecatedSnippet {
                 &mut nondeterminism,
                 memory,
                 words_allocated,
+                None,
             );
         }
 
@@ -494,10 +603,19 @@ pub trait DeprecatedSnippet {
     }
 }
 
-struct DeprecatedSnippetWrapper<S: DeprecatedSnippet> {
+/// Lets a [`DeprecatedSnippet`] be used wherever a [`RustShadow`] is expected, e.g. by the
+/// generic test helpers.
+pub(crate) struct DeprecatedSnippetWrapper<S: DeprecatedSnippet> {
     pub deprecated_snippet: S,
 }
 
+#[cfg(test)]
+impl<S: DeprecatedSnippet> DeprecatedSnippetWrapper<S> {
+    pub(crate) fn new(deprecated_snippet: S) -> Self {
+        Self { deprecated_snippet }
+    }
+}
+
 impl<S: DeprecatedSnippet + Clone + 'static> RustShadow for DeprecatedSnippetWrapper<S> {
     fn rust_shadow_wrapper(
         &self,
@@ -540,6 +658,72 @@ impl<S: DeprecatedSnippet + Clone + 'static> RustShadow for DeprecatedSnippetWra
     }
 }
 
+/// Lets boxed snippets, e.g. those returned by the list factories in [`crate::list`], be
+/// imported and tested like any other snippet.
+impl DeprecatedSnippet for Box<dyn DeprecatedSnippet> {
+    fn entrypoint_name(&self) -> String {
+        (**self).entrypoint_name()
+    }
+
+    fn input_field_names(&self) -> Vec<String> {
+        (**self).input_field_names()
+    }
+
+    fn input_types(&self) -> Vec<DataType> {
+        (**self).input_types()
+    }
+
+    fn output_field_names(&self) -> Vec<String> {
+        (**self).output_field_names()
+    }
+
+    fn output_types(&self) -> Vec<DataType> {
+        (**self).output_types()
+    }
+
+    fn stack_diff(&self) -> isize {
+        (**self).stack_diff()
+    }
+
+    fn function_code(&self, library: &mut Library) -> String {
+        (**self).function_code(library)
+    }
+
+    fn crash_conditions(&self) -> Vec<String> {
+        (**self).crash_conditions()
+    }
+
+    fn gen_input_states(&self) -> Vec<ExecutionState> {
+        (**self).gen_input_states()
+    }
+
+    fn common_case_input_state(&self) -> ExecutionState {
+        (**self).common_case_input_state()
+    }
+
+    fn worst_case_input_state(&self) -> ExecutionState {
+        (**self).worst_case_input_state()
+    }
+
+    fn source_location(&self) -> Option<&'static str> {
+        DeprecatedSnippet::source_location(&**self)
+    }
+
+    fn static_allocation_size(&self) -> usize {
+        DeprecatedSnippet::static_allocation_size(&**self)
+    }
+
+    fn rust_shadowing(
+        &self,
+        stack: &mut Vec<BFieldElement>,
+        std_in: Vec<BFieldElement>,
+        secret_in: Vec<BFieldElement>,
+        memory: &mut HashMap<BFieldElement, BFieldElement>,
+    ) {
+        (**self).rust_shadowing(stack, std_in, secret_in, memory)
+    }
+}
+
 impl<S: DeprecatedSnippet> BasicSnippet for S {
     fn inputs(&self) -> Vec<(DataType, String)> {
         // Notice that the deprecated snippet trait has its input and output
@@ -592,12 +776,24 @@ impl<S: DeprecatedSnippet> BasicSnippet for S {
     fn code(&self, library: &mut Library) -> Vec<LabelledInstruction> {
         self.function_code_as_instructions(library)
     }
+
+    fn source_location(&self) -> Option<&'static str> {
+        DeprecatedSnippet::source_location(self)
+    }
+
+    fn static_allocation_size(&self) -> usize {
+        DeprecatedSnippet::static_allocation_size(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use triton_vm::PublicInput;
+
     use super::*;
     use crate::arithmetic;
+    use crate::get_init_tvm_stack;
+    use crate::memory::dyn_malloc::DynMalloc;
 
     #[test]
     fn can_return_code() {
@@ -611,6 +807,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn assembled_dyn_malloc_agrees_with_test_linker() {
+        let program = DynMalloc.assemble();
+        assert!(program
+            .run(PublicInput::new(vec![]), NonDeterminism::new(vec![]))
+            .is_ok());
+
+        let test_linked_program = Program::new(&DynMalloc.link_for_isolated_run(None));
+        let final_stacks = [program, test_linked_program].map(|program| {
+            program
+                .debug_terminal_state(
+                    PublicInput::new(vec![]),
+                    NonDeterminism::new(vec![]),
+                    None,
+                    None,
+                )
+                .unwrap()
+                .op_stack
+                .stack
+        });
+
+        // The VM starts with a zero on top of the stack, so this allocates zero words.
+        let mut expected_stack = get_init_tvm_stack();
+        DynMalloc.rust_shadowing(&mut expected_stack, vec![], vec![], &mut HashMap::default());
+        for final_stack in final_stacks {
+            assert_eq!(expected_stack.last(), final_stack.last());
+        }
+    }
+
+    #[test]
+    fn assembled_program_does_not_dynamically_allocate_preallocated_words() {
+        // The VM starts with a zero on top of the stack, so this allocates zero words and
+        // returns the address of the first free word.
+        let final_stack = DynMalloc
+            .assemble_with_preallocated(10)
+            .debug_terminal_state(
+                PublicInput::new(vec![]),
+                NonDeterminism::new(vec![]),
+                None,
+                None,
+            )
+            .unwrap()
+            .op_stack
+            .stack;
+        assert_eq!(Some(&BFieldElement::new(10)), final_stack.last());
+    }
+
     #[test]
     fn data_type_string_rep() {
         assert_eq!("DataType::Digest", DataType::Digest.variant_name());
@@ -624,6 +867,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn random_element_has_size_of_data_type() {
+        for data_type in DataType::big_random_generatable_type_collection() {
+            assert_eq!(data_type.get_size(), data_type.random_element().len());
+        }
+    }
+
+    #[test]
+    fn random_element_is_valid_encoding() {
+        for _ in 0..100 {
+            let bool_word = DataType::Bool.random_element()[0].value();
+            assert!(bool_word == 0 || bool_word == 1);
+
+            for limb in DataType::U32
+                .random_element()
+                .into_iter()
+                .chain(DataType::U64.random_element())
+                .chain(DataType::U128.random_element())
+            {
+                assert!(limb.value() <= u32::MAX as u64);
+            }
+
+            let u16_word = DataType::U16.random_element()[0];
+            assert!(DataType::u16_from_encoding(u16_word).is_some());
+            let i32_word = DataType::I32.random_element()[0];
+            assert!(DataType::i32_from_encoding(i32_word).is_some());
+        }
+    }
+
+    #[test]
+    fn narrow_integer_encodings() {
+        assert_eq!(
+            Some(u16::MAX),
+            DataType::u16_from_encoding(BFieldElement::new(u16::MAX as u64))
+        );
+        assert_eq!(
+            None,
+            DataType::u16_from_encoding(BFieldElement::new(1 << 16))
+        );
+
+        for value in [0, 1, -1, i32::MIN, i32::MAX, -(1 << 20)] {
+            let encoding = DataType::i32_encoding(value);
+            assert!(encoding.value() < (1 << 32));
+            assert_eq!(Some(value), DataType::i32_from_encoding(encoding));
+        }
+        assert_eq!(
+            BFieldElement::new(u32::MAX as u64),
+            DataType::i32_encoding(-1)
+        );
+        assert_eq!(
+            None,
+            DataType::i32_from_encoding(BFieldElement::new(1 << 32))
+        );
+    }
+
     #[test]
     fn parse_and_stringify_datatype_test() {
         assert_eq!(DataType::Digest, DataType::from_str("digest").unwrap());
@@ -638,4 +936,31 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    fn display_and_from_str_round_trip() {
+        let nested_types = DataType::big_random_generatable_type_collection()
+            .into_iter()
+            .flat_map(|data_type| {
+                [
+                    DataType::List(Box::new(data_type.clone())),
+                    DataType::List(Box::new(DataType::List(Box::new(data_type.clone())))),
+                    data_type,
+                ]
+            });
+        for data_type in nested_types {
+            assert_eq!(data_type.label_friendly_name(), data_type.to_string());
+            assert_eq!(
+                data_type,
+                data_type.to_string().parse::<DataType>().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn from_str_rejects_unknown_labels() {
+        assert!(DataType::from_str("u33").is_err());
+        assert!(DataType::from_str("list_Lu33R").is_err());
+        assert!(DataType::from_str("tuple_Lu32___fooR").is_err());
+    }
 }
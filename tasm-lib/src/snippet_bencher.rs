@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
-use serde_json::to_writer_pretty;
+use serde_json::{from_reader, to_writer_pretty};
+use std::fmt::Display;
 use std::fs::{create_dir_all, File};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use crate::snippet::DeprecatedSnippet;
+use crate::ExecutionState;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct BenchmarkResult {
@@ -14,20 +17,52 @@ pub struct BenchmarkResult {
     pub case: BenchmarkCase,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BenchmarkCase {
     CommonCase,
     WorstCase,
+
+    /// A named input state supplied by the caller, see
+    /// [`bench_and_write_with_custom_cases`]. Custom cases are one-off measurements that
+    /// never end up in a committed baseline, so they are written but not read back.
+    #[serde(skip_deserializing)]
+    Custom(&'static str),
+}
+
+impl Display for BenchmarkCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BenchmarkCase::CommonCase => write!(f, "CommonCase"),
+            BenchmarkCase::WorstCase => write!(f, "WorstCase"),
+            BenchmarkCase::Custom(name) => write!(f, "{name}"),
+        }
+    }
 }
 
 #[allow(dead_code)]
 pub fn benchmark_snippet_deprecated<T: DeprecatedSnippet>(snippet: T) -> Vec<BenchmarkResult> {
-    let mut benchmarks = Vec::with_capacity(2);
+    benchmark_snippet_with_custom_cases_deprecated(snippet, vec![])
+}
+
+/// Benchmark the common case, the worst case, and every one of the given named input
+/// states.
+pub fn benchmark_snippet_with_custom_cases_deprecated<T: DeprecatedSnippet>(
+    snippet: T,
+    custom_cases: Vec<(&'static str, ExecutionState)>,
+) -> Vec<BenchmarkResult> {
+    let mut benchmarks = Vec::with_capacity(2 + custom_cases.len());
 
-    for (case, mut execution_state) in [
+    let cases = [
         (BenchmarkCase::CommonCase, snippet.common_case_input_state()),
         (BenchmarkCase::WorstCase, snippet.worst_case_input_state()),
-    ] {
+    ]
+    .into_iter()
+    .chain(
+        custom_cases
+            .into_iter()
+            .map(|(name, state)| (BenchmarkCase::Custom(name), state)),
+    );
+    for (case, mut execution_state) in cases {
         let execution_result = snippet
             .link_and_run_tasm_from_state_for_bench(&mut execution_state)
             .unwrap();
@@ -61,9 +96,244 @@ pub fn write_benchmarks(benchmarks: Vec<BenchmarkResult>) {
     path.push(Path::new(&function_name).with_extension("json"));
     let output = File::create(&path).expect("open file for writing");
     to_writer_pretty(output, &benchmarks).expect("write json to file");
+
+    write_benchmarks_csv(&benchmarks);
+}
+
+pub const BENCHMARK_CSV_HEADER: &str = "name,case,clock_cycles,hash_table_height,u32_table_height";
+
+/// Write the benchmarks to `benchmarks/<name>.csv`, one row per benchmark. An existing
+/// file is overwritten. The CSV files are local artifacts and are not committed.
+pub fn write_benchmarks_csv(benchmarks: &[BenchmarkResult]) {
+    write_benchmarks_csv_to(Path::new("benchmarks"), benchmarks);
+}
+
+fn write_benchmarks_csv_to(directory: &Path, benchmarks: &[BenchmarkResult]) {
+    create_dir_all(directory).expect("benchmarks directory should exist");
+
+    let function_name = &benchmarks[0].name;
+    let path = directory.join(Path::new(&function_name).with_extension("csv"));
+    let mut output = File::create(path).expect("open file for writing");
+
+    writeln!(output, "{BENCHMARK_CSV_HEADER}").expect("write csv header to file");
+    for benchmark in benchmarks {
+        assert_eq!(
+            function_name, &benchmark.name,
+            "all fn names must agree for benchmark writing to disk"
+        );
+        writeln!(
+            output,
+            "{},{},{},{},{}",
+            benchmark.name,
+            benchmark.case,
+            benchmark.clock_cycle_count,
+            benchmark.hash_table_height,
+            benchmark.u32_table_height
+        )
+        .expect("write csv row to file");
+    }
 }
 
 #[allow(dead_code)]
 pub fn bench_and_write<T: DeprecatedSnippet>(snippet: T) {
     write_benchmarks(benchmark_snippet_deprecated(snippet));
 }
+
+/// Like [`bench_and_write`], but additionally benchmark the given named input states,
+/// e.g., to profile a specific problematic input. The results are written under the
+/// given names.
+pub fn bench_and_write_with_custom_cases<T: DeprecatedSnippet>(
+    snippet: T,
+    custom_cases: Vec<(&'static str, ExecutionState)>,
+) {
+    write_benchmarks(benchmark_snippet_with_custom_cases_deprecated(
+        snippet,
+        custom_cases,
+    ));
+}
+
+fn baseline_directory() -> PathBuf {
+    Path::new("benchmarks").join("baseline")
+}
+
+/// (Re)generate the committed cycle-count baseline of a snippet, stored in
+/// `benchmarks/baseline/<name>.json`. Only run this when a change in cycle count is
+/// intended.
+pub fn write_baseline<T: DeprecatedSnippet>(snippet: T) {
+    write_baseline_to(
+        &baseline_directory(),
+        &benchmark_snippet_deprecated(snippet),
+    );
+}
+
+fn write_baseline_to(directory: &Path, benchmarks: &[BenchmarkResult]) {
+    create_dir_all(directory).expect("baseline directory should exist");
+    let path = directory.join(Path::new(&benchmarks[0].name).with_extension("json"));
+    let output = File::create(path).expect("open file for writing");
+    to_writer_pretty(output, benchmarks).expect("write json to file");
+}
+
+/// Benchmark the snippet and panic if, for any benchmark case, its clock cycle count
+/// exceeds the one in the committed baseline `benchmarks/baseline/<name>.json` by more
+/// than `tolerance_pct` percent.
+pub fn assert_no_cycle_regression<T: DeprecatedSnippet>(snippet: T, tolerance_pct: f64) {
+    assert_no_cycle_regression_against(&baseline_directory(), snippet, tolerance_pct);
+}
+
+fn assert_no_cycle_regression_against<T: DeprecatedSnippet>(
+    directory: &Path,
+    snippet: T,
+    tolerance_pct: f64,
+) {
+    let name = snippet.entrypoint_name();
+    let path = directory.join(Path::new(&name).with_extension("json"));
+    let baseline_file = File::open(&path).unwrap_or_else(|_| {
+        panic!(
+            "no baseline for `{name}` at {}; generate one with `write_baseline`",
+            path.display()
+        )
+    });
+    let baseline: Vec<BenchmarkResult> = from_reader(baseline_file).expect("parse baseline");
+
+    for benchmark in benchmark_snippet_deprecated(snippet) {
+        let baseline_benchmark = baseline
+            .iter()
+            .find(|b| b.case == benchmark.case)
+            .unwrap_or_else(|| panic!("baseline for `{name}` lacks {:?}", benchmark.case));
+        let allowed_cycle_count =
+            baseline_benchmark.clock_cycle_count as f64 * (1.0 + tolerance_pct / 100.0);
+        assert!(
+            benchmark.clock_cycle_count as f64 <= allowed_cycle_count,
+            "Clock cycle regression in `{name}` ({:?}): {} cycles, baseline is {} \
+            with a tolerance of {tolerance_pct}%",
+            benchmark.case,
+            benchmark.clock_cycle_count,
+            baseline_benchmark.clock_cycle_count,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::read_to_string;
+
+    use itertools::Itertools;
+    use tempfile::TempDir;
+
+    use twenty_first::shared_math::b_field_element::BFieldElement;
+
+    use super::*;
+    use crate::arithmetic::u64::add_u64::AddU64;
+    use crate::arithmetic::u64::decr_u64::DecrU64;
+    use crate::arithmetic::u64::eq_u64::EqU64;
+    use crate::arithmetic::u64::incr_u64::IncrU64;
+    use crate::get_init_tvm_stack;
+
+    #[test]
+    fn custom_cases_are_benchmarked_under_their_name() {
+        let state_with_input = |hi: u64, lo: u64| {
+            let input = vec![BFieldElement::new(hi), BFieldElement::new(lo)];
+            ExecutionState::with_stack([get_init_tvm_stack(), input].concat())
+        };
+        let custom_cases = vec![
+            ("no_carry", state_with_input(0, 5)),
+            ("carry", state_with_input(1, 0)),
+        ];
+
+        let benchmarks = benchmark_snippet_with_custom_cases_deprecated(DecrU64, custom_cases);
+        let cases = benchmarks.iter().map(|b| b.case).collect_vec();
+        assert_eq!(
+            vec![
+                BenchmarkCase::CommonCase,
+                BenchmarkCase::WorstCase,
+                BenchmarkCase::Custom("no_carry"),
+                BenchmarkCase::Custom("carry"),
+            ],
+            cases
+        );
+
+        let json = serde_json::to_string(&benchmarks).unwrap();
+        assert!(json.contains(r#""Custom":"no_carry""#));
+        assert!(json.contains(r#""Custom":"carry""#));
+
+        let json = serde_json::to_string(&benchmarks[..2]).unwrap();
+        let deserialized: Vec<BenchmarkResult> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            cases[..2],
+            deserialized.into_iter().map(|b| b.case).collect_vec()
+        );
+    }
+
+    #[test]
+    fn benchmark_csv_has_expected_schema() {
+        let directory = tempfile::tempdir().unwrap();
+        let benchmarks = benchmark_snippet_deprecated(IncrU64);
+        let name = benchmarks[0].name.clone();
+
+        // Write twice to make sure the second write overwrites instead of appending.
+        write_benchmarks_csv_to(directory.path(), &benchmarks);
+        write_benchmarks_csv_to(directory.path(), &benchmarks);
+
+        let path = directory
+            .path()
+            .join(Path::new(&name).with_extension("csv"));
+        let csv = read_to_string(path).unwrap();
+        let lines = csv.lines().collect_vec();
+        assert_eq!(3, lines.len(), "header and one row per benchmark case");
+        assert_eq!(BENCHMARK_CSV_HEADER, lines[0]);
+
+        for (line, case) in lines[1..].iter().zip(["CommonCase", "WorstCase"]) {
+            let columns = line.split(',').collect_vec();
+            assert_eq!(5, columns.len());
+            assert_eq!(name, columns[0]);
+            assert_eq!(case, columns[1]);
+            for number in &columns[2..] {
+                number.parse::<usize>().expect("numeric column");
+            }
+            assert!(columns[2].parse::<usize>().unwrap() > 0);
+        }
+    }
+
+    /// Fabricate a baseline in which the snippet was faster than it is now, by
+    /// `cycles_saved` clock cycles per benchmark case. The directory is removed when the
+    /// returned handle is dropped.
+    fn fabricated_baseline_directory(cycles_saved: usize) -> TempDir {
+        let directory = tempfile::tempdir().unwrap();
+        let baseline = benchmark_snippet_deprecated(IncrU64)
+            .into_iter()
+            .map(|mut benchmark| {
+                benchmark.clock_cycle_count -= cycles_saved;
+                benchmark
+            })
+            .collect_vec();
+        write_baseline_to(directory.path(), &baseline);
+        directory
+    }
+
+    #[test]
+    fn committed_baselines_hold() {
+        assert_no_cycle_regression(AddU64, 0.0);
+        assert_no_cycle_regression(DecrU64, 0.0);
+        assert_no_cycle_regression(EqU64, 0.0);
+        assert_no_cycle_regression(IncrU64, 0.0);
+    }
+
+    #[test]
+    fn unchanged_cycle_count_passes_without_tolerance() {
+        let directory = fabricated_baseline_directory(0);
+        assert_no_cycle_regression_against(directory.path(), IncrU64, 0.0);
+    }
+
+    #[test]
+    fn slower_snippet_passes_within_tolerance() {
+        let directory = fabricated_baseline_directory(1);
+        assert_no_cycle_regression_against(directory.path(), IncrU64, 50.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Clock cycle regression")]
+    fn slower_snippet_fails_without_tolerance() {
+        let directory = fabricated_baseline_directory(1);
+        assert_no_cycle_regression_against(directory.path(), IncrU64, 0.0);
+    }
+}
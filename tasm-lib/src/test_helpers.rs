@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fs::{self, create_dir_all};
+use std::path::Path;
 
 use itertools::Itertools;
 use num_traits::Zero;
@@ -8,11 +10,12 @@ use twenty_first::shared_math::b_field_element::BFieldElement;
 use twenty_first::util_types::algebraic_hasher::Domain;
 
 use crate::dyn_malloc::DYN_MALLOC_ADDRESS;
-use crate::library::Library;
-use crate::snippet::{BasicSnippet, DeprecatedSnippet, RustShadow};
+use crate::library::{instruction_count, Library};
+use crate::list::ListType;
+use crate::snippet::{BasicSnippet, DataType, DeprecatedSnippet, RustShadow};
 use crate::{
-    execute_test, exported_snippets, rust_shadowing_helper_functions, ExecutionState,
-    VmHasherState, VmOutputState, DIGEST_LENGTH,
+    execute_test, execute_test_with_sponge_state, exported_snippets,
+    rust_shadowing_helper_functions, ExecutionState, VmHasherState, VmOutputState, DIGEST_LENGTH,
 };
 
 #[allow(dead_code)]
@@ -23,8 +26,8 @@ pub fn test_rust_equivalence_multiple_deprecated<T: DeprecatedSnippet>(
     // Verify that snippet can be found in `all_snippets`, so that
     // it iss visible to the outside.
     // This call will panic if snippet is not found in that
-    // function call. The data type value is a dummy value for all
-    // snippets except those that handle lists.
+    // function call. Snippets that are generic over a list element
+    // type are reconstructed from the type suffix of their entrypoint.
     if export_snippet {
         let looked_up_snippet = exported_snippets::name_to_snippet(&snippet_struct.entrypoint());
         assert_eq!(
@@ -145,6 +148,7 @@ pub fn link_and_run_tasm_for_test_deprecated<T: DeprecatedSnippet>(
         &mut NonDeterminism::new(secret_in),
         memory,
         Some(words_statically_allocated),
+        None,
     )
 }
 
@@ -303,17 +307,112 @@ pub(crate) fn test_rust_equivalence_given_complete_state_deprecated<T: Deprecate
     vm_output_state
 }
 
+/// Verify that the snippet crashes the VM on every one of the given states. Each case
+/// is labelled with the crash condition it exercises, which must be one of the conditions
+/// the snippet declares in `crash_conditions`.
+pub fn test_crash_conditions<T: DeprecatedSnippet>(
+    snippet_struct: &T,
+    cases: Vec<(ExecutionState, &str)>,
+) {
+    let entrypoint = snippet_struct.entrypoint_name();
+    let crash_conditions = snippet_struct.crash_conditions();
+    for (execution_state, condition) in cases {
+        assert!(
+            crash_conditions.iter().any(|c| c == condition),
+            "`{condition}` is not a declared crash condition of `{entrypoint}`. Declared: {}",
+            crash_conditions.join(", ")
+        );
+
+        let mut stack = execution_state.stack;
+        let mut memory = execution_state.memory;
+        let words_allocated =
+            (execution_state.words_allocated > 0).then_some(execution_state.words_allocated);
+        let result = snippet_struct.link_and_run_tasm_for_test(
+            &mut stack,
+            execution_state.std_in,
+            execution_state.nondeterminism.individual_tokens,
+            &mut memory,
+            words_allocated,
+        );
+        assert!(
+            result.is_err(),
+            "`{entrypoint}` must crash the VM when {condition}"
+        );
+    }
+}
+
+/// Snippets that can describe their valid input states as a `proptest` strategy.
+#[cfg(feature = "proptest")]
+pub trait ArbitraryInputState: DeprecatedSnippet {
+    fn arbitrary_input_state(&self) -> proptest::strategy::BoxedStrategy<ExecutionState>;
+}
+
+/// Check the rust shadowing against the TASM code on input states drawn from the
+/// snippet's `arbitrary_input_state`. A failing input is shrunk to a minimal
+/// counterexample, which is reported together with the seed that reproduces it through
+/// [`proptest_rust_equivalence_with_seed`].
+#[cfg(feature = "proptest")]
+pub fn proptest_rust_equivalence<T: ArbitraryInputState>(snippet_struct: &T) {
+    let seed: [u8; 32] = rand::random();
+    if let Err(error) = proptest_rust_equivalence_with_seed(snippet_struct, seed) {
+        panic!(
+            "Rust shadowing and TASM of `{}` disagree. Seed: {}\n{error}",
+            snippet_struct.entrypoint_name(),
+            hex::encode(seed)
+        );
+    }
+}
+
+/// Deterministic version of [`proptest_rust_equivalence`], returning the shrunk
+/// counterexample instead of panicking.
+#[cfg(feature = "proptest")]
+pub fn proptest_rust_equivalence_with_seed<T: ArbitraryInputState>(
+    snippet_struct: &T,
+    seed: [u8; 32],
+) -> Result<(), proptest::test_runner::TestError<ExecutionState>> {
+    use proptest::test_runner::{Config, RngAlgorithm, TestRng, TestRunner};
+
+    let rng = TestRng::from_seed(RngAlgorithm::ChaCha, &seed);
+    let mut runner = TestRunner::new_with_rng(Config::default(), rng);
+    runner.run(&snippet_struct.arbitrary_input_state(), |execution_state| {
+        test_rust_equivalence_given_execution_state_deprecated(snippet_struct, execution_state);
+        Ok(())
+    })
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::HashMap;
 
     use rand::random;
-    use triton_vm::{BFieldElement, NonDeterminism};
+    use triton_vm::instruction::LabelledInstruction;
+    use triton_vm::{triton_asm, BFieldElement, NonDeterminism};
     use twenty_first::shared_math::tip5::DIGEST_LENGTH;
+    use twenty_first::util_types::algebraic_hasher::Domain;
 
-    use crate::{get_init_tvm_stack, hashing::sample_indices::SampleIndices, list::ListType};
+    use crate::{
+        arithmetic::xfe::xfe_mul::XfeMul,
+        closure::{Closure, ShadowedClosure},
+        dyn_malloc::{DynMalloc, DYN_MALLOC_ADDRESS},
+        get_init_tvm_stack,
+        hashing::sample_indices::SampleIndices,
+        io::{read_secret::ReadSecret, write_to_stdout::WriteToStdout},
+        library::Library,
+        list::ListType,
+        procedure::{Procedure, ShadowedProcedure},
+        push_encodable,
+        snippet::{BasicSnippet, DataType, DeprecatedSnippetWrapper},
+        snippet_bencher::BenchmarkCase,
+        Digest, ExecutionState, VmHasherState,
+    };
 
-    use super::test_rust_equivalence_given_complete_state_deprecated;
+    use super::{
+        assert_deterministic, assert_memory_unchanged, assert_rendered_tasm_matches, format_stack,
+        render_tasm_for_snapshot, snapshot_memory, test_io_equivalence,
+        test_rust_equivalence_given_complete_state,
+        test_rust_equivalence_given_complete_state_deprecated,
+        test_rust_equivalence_with_cycle_budget,
+    };
 
     /// TIP6 sets the bottom of the stack to the program hash. While testing Snippets,
     /// which are not standalone programs and therefore do not come with a well defined
@@ -344,6 +443,282 @@ mod test {
             None,
         );
     }
+
+    #[test]
+    fn dyn_malloc_is_deterministic() {
+        let stack = [get_init_tvm_stack(), vec![BFieldElement::new(10)]].concat();
+        assert_deterministic(
+            &DeprecatedSnippetWrapper::new(DynMalloc),
+            ExecutionState::with_stack_and_memory(stack, HashMap::default(), 1),
+        );
+    }
+
+    #[test]
+    fn read_secret_is_deterministic() {
+        let secret_digest: Vec<BFieldElement> = (0..DIGEST_LENGTH).map(|_| random()).collect();
+        let state = ExecutionState {
+            stack: get_init_tvm_stack(),
+            std_in: vec![],
+            nondeterminism: NonDeterminism::new(secret_digest),
+            memory: HashMap::default(),
+            words_allocated: 0,
+        };
+        assert_deterministic(
+            &DeprecatedSnippetWrapper::new(ReadSecret(DataType::Digest)),
+            state,
+        );
+    }
+
+    fn prop_xfe_mul_cycle_budget(max_cycles: usize) {
+        let xfe_mul = ShadowedClosure::new(XfeMul);
+        let stack = XfeMul.pseudorandom_initial_state(random(), None);
+        test_rust_equivalence_with_cycle_budget(
+            &xfe_mul,
+            &stack,
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::new(),
+            &VmHasherState::new(Domain::VariableLength),
+            1,
+            max_cycles,
+        );
+    }
+
+    #[test]
+    fn generous_cycle_budget() {
+        prop_xfe_mul_cycle_budget(1000);
+    }
+
+    #[should_panic(expected = "exceeds its cycle budget")]
+    #[test]
+    fn tight_cycle_budget() {
+        // The call and the return alone take two cycles.
+        prop_xfe_mul_cycle_budget(1);
+    }
+
+    /// Pushes a single word but declares no outputs, so its declared stack diff agrees
+    /// with the observed stack growth but not with its type indicators.
+    struct MislabeledPushOne;
+
+    impl BasicSnippet for MislabeledPushOne {
+        fn inputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn outputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn entrypoint(&self) -> String {
+            "tasm_test_mislabeled_push_one".to_string()
+        }
+
+        fn code(&self, _library: &mut Library) -> Vec<LabelledInstruction> {
+            let entrypoint = self.entrypoint();
+            triton_asm!(
+                {entrypoint}:
+                    push 1
+                    return
+            )
+        }
+
+        fn stack_diff(&self) -> isize {
+            1
+        }
+    }
+
+    impl Closure for MislabeledPushOne {
+        fn rust_shadow(&self, stack: &mut Vec<BFieldElement>) {
+            stack.push(BFieldElement::new(1));
+        }
+
+        fn pseudorandom_initial_state(
+            &self,
+            _seed: [u8; 32],
+            _bench_case: Option<BenchmarkCase>,
+        ) -> Vec<BFieldElement> {
+            get_init_tvm_stack()
+        }
+    }
+
+    #[test]
+    fn write_to_stdout_io_equivalence() {
+        let value: Vec<BFieldElement> = (0..DIGEST_LENGTH).map(|_| random()).collect();
+        let stack = [get_init_tvm_stack(), value.clone()].concat();
+
+        // The top-most word is written first.
+        let expected_output: Vec<BFieldElement> = value.into_iter().rev().collect();
+        test_io_equivalence(
+            &ShadowedProcedure::new(WriteToStdout(DataType::Digest)),
+            &stack,
+            &[],
+            Some(&expected_output),
+        );
+    }
+
+    /// Echoes one word from standard in to standard out. If `off_by_one` is set, the Rust
+    /// shadowing writes the successor of the word instead, diverging from the VM.
+    struct EchoStdIn {
+        off_by_one: bool,
+    }
+
+    impl BasicSnippet for EchoStdIn {
+        fn inputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn outputs(&self) -> Vec<(DataType, String)> {
+            vec![]
+        }
+
+        fn entrypoint(&self) -> String {
+            "tasm_test_echo_stdin".to_string()
+        }
+
+        fn code(&self, _library: &mut Library) -> Vec<LabelledInstruction> {
+            let entrypoint = self.entrypoint();
+            triton_asm!(
+                {entrypoint}:
+                    read_io
+                    write_io
+                    return
+            )
+        }
+    }
+
+    impl Procedure for EchoStdIn {
+        fn rust_shadow(
+            &self,
+            _stack: &mut Vec<BFieldElement>,
+            _memory: &mut HashMap<BFieldElement, BFieldElement>,
+            _nondeterminism: &NonDeterminism<BFieldElement>,
+            public_input: &[BFieldElement],
+            _sponge_state: &mut VmHasherState,
+        ) -> Vec<BFieldElement> {
+            vec![public_input[0] + BFieldElement::new(self.off_by_one as u64)]
+        }
+
+        fn pseudorandom_initial_state(
+            &self,
+            _seed: [u8; 32],
+            _bench_case: Option<BenchmarkCase>,
+        ) -> (
+            Vec<BFieldElement>,
+            HashMap<BFieldElement, BFieldElement>,
+            NonDeterminism<BFieldElement>,
+            Vec<BFieldElement>,
+            VmHasherState,
+        ) {
+            (
+                get_init_tvm_stack(),
+                HashMap::new(),
+                NonDeterminism::new(vec![]),
+                vec![random()],
+                VmHasherState::new(Domain::VariableLength),
+            )
+        }
+    }
+
+    #[test]
+    fn echo_stdin_io_equivalence() {
+        let word: BFieldElement = random();
+        test_io_equivalence(
+            &ShadowedProcedure::new(EchoStdIn { off_by_one: false }),
+            &get_init_tvm_stack(),
+            &[word],
+            Some(&[word]),
+        );
+    }
+
+    #[should_panic(expected = "must write the same standard output")]
+    #[test]
+    fn diverging_output_is_caught() {
+        test_io_equivalence(
+            &ShadowedProcedure::new(EchoStdIn { off_by_one: true }),
+            &get_init_tvm_stack(),
+            &[random()],
+            None,
+        );
+    }
+
+    #[should_panic(expected = "must match type indicators")]
+    #[test]
+    fn wrong_stack_diff_is_caught() {
+        test_rust_equivalence_given_complete_state(
+            &ShadowedClosure::new(MislabeledPushOne),
+            &get_init_tvm_stack(),
+            &[],
+            &NonDeterminism::new(vec![]),
+            &HashMap::new(),
+            &VmHasherState::new(Domain::VariableLength),
+            0,
+            None,
+        );
+    }
+
+    #[test]
+    fn unchanged_memory_ignoring_allocator_passes() {
+        let dyn_malloc_address = BFieldElement::new(DYN_MALLOC_ADDRESS as u64);
+        let mut memory: HashMap<BFieldElement, BFieldElement> = HashMap::default();
+        memory.insert(dyn_malloc_address, BFieldElement::new(1));
+        memory.insert(BFieldElement::new(42), BFieldElement::new(7));
+
+        let before = snapshot_memory(&memory);
+        memory.insert(dyn_malloc_address, BFieldElement::new(100));
+        assert_memory_unchanged(&before, &memory, &[dyn_malloc_address]);
+    }
+
+    #[should_panic(expected = "Modified words: (42: 7 => 8)")]
+    #[test]
+    fn single_modified_word_is_caught() {
+        let dyn_malloc_address = BFieldElement::new(DYN_MALLOC_ADDRESS as u64);
+        let mut memory: HashMap<BFieldElement, BFieldElement> = HashMap::default();
+        memory.insert(dyn_malloc_address, BFieldElement::new(1));
+        memory.insert(BFieldElement::new(42), BFieldElement::new(7));
+
+        let before = snapshot_memory(&memory);
+        memory.insert(dyn_malloc_address, BFieldElement::new(100));
+        memory.insert(BFieldElement::new(42), BFieldElement::new(8));
+        assert_memory_unchanged(&before, &memory, &[dyn_malloc_address]);
+    }
+
+    #[test]
+    fn format_stack_renders_typed_values() {
+        let mut stack = vec![BFieldElement::new(7)];
+        push_encodable(&mut stack, &((1u64 << 32) + 5));
+        stack.push(BFieldElement::new(1));
+        push_encodable(
+            &mut stack,
+            &Digest::new([10, 11, 12, 13, 14].map(BFieldElement::new)),
+        );
+
+        assert_eq!(
+            "7, u64(4294967301), bool(true), digest(10, 11, 12, 13, 14)",
+            format_stack(&stack, &[DataType::U64, DataType::Bool, DataType::Digest])
+        );
+        assert_eq!(
+            "7, 1, 5, bool(true), digest(10, 11, 12, 13, 14)",
+            format_stack(&stack, &[DataType::Bool, DataType::Digest])
+        );
+        assert_eq!(
+            "u32(invalid: 4294967296)",
+            format_stack(&[BFieldElement::new(1 << 32)], &[DataType::U32])
+        );
+    }
+
+    #[should_panic(expected = "First difference in line 8")]
+    #[test]
+    fn altered_body_does_not_match_snapshot() {
+        let snapshot_path = "snapshots/tasm_memory_dyn_malloc.tasm";
+        let snapshot = std::fs::read_to_string(snapshot_path).unwrap();
+        let rendered = render_tasm_for_snapshot(&DynMalloc);
+        assert_rendered_tasm_matches(&rendered, &snapshot, snapshot_path);
+
+        // Change the constant compared against in the entrypoint
+        let altered = rendered.replacen("push 0\n", "push 1\n", 1);
+        assert_ne!(rendered, altered);
+        assert_rendered_tasm_matches(&altered, &snapshot, snapshot_path);
+    }
 }
 
 pub fn rust_final_state<T: RustShadow>(
@@ -381,6 +756,9 @@ pub fn rust_final_state<T: RustShadow>(
         final_stack: rust_stack,
         final_ram: rust_memory,
         final_sponge_state: rust_sponge,
+        clock_cycle_count: 0,
+        hash_table_height: 0,
+        u32_table_height: 0,
     }
 }
 
@@ -390,7 +768,7 @@ pub fn tasm_final_state<T: RustShadow>(
     stdin: &[BFieldElement],
     nondeterminism: &NonDeterminism<BFieldElement>,
     memory: &HashMap<BFieldElement, BFieldElement>,
-    _sponge_state: &VmHasherState,
+    sponge_state: &VmHasherState,
     words_statically_allocated: usize,
 ) -> VmOutputState {
     // allocate memory, if necessary
@@ -402,36 +780,130 @@ pub fn tasm_final_state<T: RustShadow>(
         );
     }
 
-    // run tvm
+    // run tvm, starting from the same sponge state as the rust shadow
     link_and_run_tasm_for_test(
         shadowed_snippet,
         &mut stack.to_vec(),
         stdin.to_vec(),
         &mut nondeterminism.clone(),
         &mut tasm_memory,
+        Some(sponge_state),
         words_statically_allocated,
     )
 }
 
 pub fn verify_stack_equivalence(a: &[BFieldElement], b: &[BFieldElement]) {
+    verify_stack_equivalence_with_layout(a, b, &[]);
+}
+
+/// Like [`verify_stack_equivalence`], but on mismatch, the top of both stacks is also
+/// rendered according to `layout` using [`format_stack`].
+pub fn verify_stack_equivalence_with_layout(
+    a: &[BFieldElement],
+    b: &[BFieldElement],
+    layout: &[DataType],
+) {
     // assert stacks are equal, up to program hash
     let a_skip_program_hash = a.iter().cloned().skip(DIGEST_LENGTH).collect_vec();
     let b_skip_program_hash = b.iter().cloned().skip(DIGEST_LENGTH).collect_vec();
-    assert_eq!(
-        a_skip_program_hash,
-        b_skip_program_hash,
+    if a_skip_program_hash == b_skip_program_hash {
+        return;
+    }
+
+    let layout_size: usize = layout.iter().map(|data_type| data_type.get_size()).sum();
+    let render = |stack: &[BFieldElement]| {
+        if !layout.is_empty() && stack.len() >= layout_size {
+            format!(
+                "{}\n({})",
+                stack.iter().map(|x| x.to_string()).join(","),
+                format_stack(stack, layout)
+            )
+        } else {
+            stack.iter().map(|x| x.to_string()).join(",")
+        }
+    };
+    panic!(
         "A stack must match B stack\n\nA: {}\n\nB: {}",
-        a_skip_program_hash
-            .iter()
-            .map(|x| x.to_string())
-            .collect_vec()
-            .join(","),
-        b_skip_program_hash
-            .iter()
-            .map(|x| x.to_string())
-            .collect_vec()
-            .join(","),
+        render(&a_skip_program_hash),
+        render(&b_skip_program_hash),
+    );
+}
+
+/// Render a stack for humans. The top of the stack is interpreted according to
+/// `layout`, listed from deepest to topmost, like a snippet's outputs, e.g., as
+/// `u64(12345), bool(true), digest(1, 2, 3, 4, 5)`. Elements below the layout are
+/// shown as raw field elements. Words that are no valid encoding of their type are
+/// shown raw, as in `u32(invalid: 4294967296)`.
+pub fn format_stack(stack: &[BFieldElement], layout: &[DataType]) -> String {
+    let layout_size: usize = layout.iter().map(|data_type| data_type.get_size()).sum();
+    assert!(
+        stack.len() >= layout_size,
+        "Stack of length {} is too short for a layout of size {layout_size}",
+        stack.len()
     );
+
+    let (untyped, mut typed) = stack.split_at(stack.len() - layout_size);
+    let mut rendered = untyped.iter().map(|x| x.value().to_string()).collect_vec();
+    for data_type in layout {
+        let (words, rest) = typed.split_at(data_type.get_size());
+        rendered.push(format_value(data_type, words));
+        typed = rest;
+    }
+
+    rendered.join(", ")
+}
+
+/// Render one value, given the words it occupies on the stack, from deepest to topmost.
+fn format_value(data_type: &DataType, words: &[BFieldElement]) -> String {
+    // the first word of the encoding is on top of the stack
+    let encoding = words.iter().rev().map(|x| x.value()).collect_vec();
+    let join = |values: &[u64]| values.iter().map(|x| x.to_string()).join(", ");
+    let u32_limbs = encoding
+        .iter()
+        .all(|&limb| limb <= u32::MAX as u64)
+        .then(|| {
+            encoding
+                .iter()
+                .rev()
+                .fold(0u128, |acc, &limb| (acc << 32) + limb as u128)
+        });
+
+    let value = match data_type {
+        DataType::Bool => match encoding[0] {
+            0 => Some("false".to_string()),
+            1 => Some("true".to_string()),
+            _ => None,
+        },
+        DataType::U16 => DataType::u16_from_encoding(words[0]).map(|x| x.to_string()),
+        DataType::I32 => DataType::i32_from_encoding(words[0]).map(|x| x.to_string()),
+        DataType::U32 | DataType::U64 | DataType::U128 => u32_limbs.map(|x| x.to_string()),
+        DataType::BFE
+        | DataType::XFE
+        | DataType::Digest
+        | DataType::List(_)
+        | DataType::VoidPointer => Some(join(&encoding)),
+        DataType::Tuple(elements) => {
+            let mut rest = words;
+            let mut rendered = vec![];
+            for element in elements {
+                let (element_words, remaining) = rest.split_at(element.get_size());
+                rendered.push(format_value(element, element_words));
+                rest = remaining;
+            }
+            Some(rendered.join(", "))
+        }
+    };
+
+    let name = match data_type {
+        DataType::List(_) => "*list".to_string(),
+        DataType::VoidPointer => "*void".to_string(),
+        DataType::Tuple(_) => "tuple".to_string(),
+        _ => data_type.label_friendly_name(),
+    };
+    match value {
+        Some(value) => format!("{name}({value})"),
+        None => format!("{name}(invalid: {})", join(&encoding)),
+    }
 }
 
 pub fn verify_memory_equivalence(
@@ -439,21 +911,9 @@ pub fn verify_memory_equivalence(
     b_memory: &HashMap<BFieldElement, BFieldElement>,
 ) {
     // verify equivalence of memory up to the value of dynamic allocator
-    let memory_difference = b_memory
-        .iter()
-        .filter(|(k, v)| match a_memory.get(*k) {
-            Some(b) => *b != **v,
-            None => true,
-        })
-        .chain(a_memory.iter().filter(|(k, v)| match b_memory.get(*k) {
-            Some(b) => *b != **v,
-            None => true,
-        }))
-        .collect_vec();
-    if memory_difference
-        .iter()
-        .any(|(k, _v)| **k != BFieldElement::new(DYN_MALLOC_ADDRESS as u64))
-    {
+    let dyn_malloc_address = BFieldElement::new(DYN_MALLOC_ADDRESS as u64);
+    let memory_difference = memory_difference(a_memory, b_memory, &[dyn_malloc_address]);
+    if !memory_difference.is_empty() {
         let mut a_memory_ = a_memory.iter().collect_vec();
         a_memory_.sort_unstable_by(|&a, &b| a.0.value().partial_cmp(&b.0.value()).unwrap());
         let a_mem_str = a_memory_
@@ -469,21 +929,98 @@ pub fn verify_memory_equivalence(
             .map(|x| format!("({} => {})", x.0, x.1))
             .collect_vec()
             .join(",");
-        let diff_str = memory_difference
-            .iter()
-            .map(|x| format!("({} => {})", x.0, x.1))
-            .collect_vec()
-            .join(",");
+        let diff_str = format_memory_difference(&memory_difference);
         panic!(
             "Memory for both implementations must match after execution.\n\nA: {a_mem_str}\n\nB: {b_mem_str}\n\nDifference: {diff_str}\n\n",
         );
     }
 }
 
+/// The addresses, sorted, at which the two memories hold different values, together
+/// with those values. An address missing from one memory counts as different. The
+/// addresses in `ignoring` are skipped.
+fn memory_difference(
+    a_memory: &HashMap<BFieldElement, BFieldElement>,
+    b_memory: &HashMap<BFieldElement, BFieldElement>,
+    ignoring: &[BFieldElement],
+) -> Vec<(BFieldElement, Option<BFieldElement>, Option<BFieldElement>)> {
+    a_memory
+        .keys()
+        .chain(b_memory.keys())
+        .unique()
+        .filter(|address| !ignoring.contains(address))
+        .map(|address| {
+            (
+                *address,
+                a_memory.get(address).copied(),
+                b_memory.get(address).copied(),
+            )
+        })
+        .filter(|(_, a_value, b_value)| a_value != b_value)
+        .sorted_by_key(|(address, _, _)| address.value())
+        .collect()
+}
+
+fn format_memory_difference(
+    difference: &[(BFieldElement, Option<BFieldElement>, Option<BFieldElement>)],
+) -> String {
+    let format_value = |value: &Option<BFieldElement>| match value {
+        Some(value) => value.to_string(),
+        None => "-".to_string(),
+    };
+    difference
+        .iter()
+        .map(|(address, a_value, b_value)| {
+            format!(
+                "({address}: {} => {})",
+                format_value(a_value),
+                format_value(b_value)
+            )
+        })
+        .join(",")
+}
+
+/// Take a copy of the VM's memory, e.g., before running a sub-call, for later comparison
+/// with [`assert_memory_unchanged`].
+pub fn snapshot_memory(
+    memory: &HashMap<BFieldElement, BFieldElement>,
+) -> HashMap<BFieldElement, BFieldElement> {
+    memory.clone()
+}
+
+/// Assert that `after` holds the same values as `before` at every address except those
+/// in `ignoring`, like [`DYN_MALLOC_ADDRESS`]. On failure, every modified address is
+/// listed as `(address: before => after)`, where `-` marks an unset word.
+pub fn assert_memory_unchanged(
+    before: &HashMap<BFieldElement, BFieldElement>,
+    after: &HashMap<BFieldElement, BFieldElement>,
+    ignoring: &[BFieldElement],
+) {
+    let difference = memory_difference(before, after, ignoring);
+    assert!(
+        difference.is_empty(),
+        "Memory must be unchanged. Modified words: {}",
+        format_memory_difference(&difference)
+    );
+}
+
 pub fn verify_hasher_state_equivalence(a: VmOutputState, b: VmOutputState) {
     assert_eq!(a.final_sponge_state.state, b.final_sponge_state.state);
 }
 
+/// Verify that the declared stack diff of a snippet matches the difference between
+/// the sizes of its declared outputs and inputs.
+pub fn verify_stack_diff_matches_types<T: BasicSnippet + ?Sized>(snippet: &T) {
+    let input_size: usize = snippet.inputs().iter().map(|(x, _n)| x.get_size()).sum();
+    let output_size: usize = snippet.outputs().iter().map(|(x, _n)| x.get_size()).sum();
+    assert_eq!(
+        output_size as isize - input_size as isize,
+        snippet.stack_diff(),
+        "Declared stack diff of snippet {} must match type indicators",
+        snippet.entrypoint()
+    );
+}
+
 pub fn verify_stack_growth<T: RustShadow>(
     shadowed_snippet: &T,
     initial_stack: &[BFieldElement],
@@ -541,22 +1078,67 @@ pub fn test_rust_equivalence_given_complete_state<T: RustShadow>(
         "Rust shadowing and VM std out must agree"
     );
 
-    verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+    let output_layout = shadowed_snippet
+        .inner()
+        .borrow()
+        .outputs()
+        .into_iter()
+        .map(|(data_type, _)| data_type)
+        .collect_vec();
+    verify_stack_equivalence_with_layout(&rust.final_stack, &tasm.final_stack, &output_layout);
     if let Some(expected) = expected_final_stack {
-        verify_stack_equivalence(expected, &rust.final_stack);
+        verify_stack_equivalence_with_layout(expected, &rust.final_stack, &output_layout);
     }
     verify_memory_equivalence(&rust.final_ram, &tasm.final_ram);
+    verify_stack_diff_matches_types(&*shadowed_snippet.inner().borrow());
     verify_stack_growth(shadowed_snippet, &init_stack, &tasm.final_stack);
 
     tasm
 }
 
+/// Like [`test_rust_equivalence_given_complete_state`], but additionally panic if the
+/// VM spends more than `max_cycles` clock cycles, which locks in a performance ceiling.
+#[allow(clippy::too_many_arguments)]
+pub fn test_rust_equivalence_with_cycle_budget<T: RustShadow>(
+    shadowed_snippet: &T,
+    stack: &[BFieldElement],
+    stdin: &[BFieldElement],
+    nondeterminism: &NonDeterminism<BFieldElement>,
+    memory: &HashMap<BFieldElement, BFieldElement>,
+    sponge_state: &VmHasherState,
+    words_statically_allocated: usize,
+    max_cycles: usize,
+) -> VmOutputState {
+    let tasm = test_rust_equivalence_given_complete_state(
+        shadowed_snippet,
+        stack,
+        stdin,
+        nondeterminism,
+        memory,
+        sponge_state,
+        words_statically_allocated,
+        None,
+    );
+
+    assert!(
+        tasm.clock_cycle_count <= max_cycles,
+        "`{}` exceeds its cycle budget: used {} clock cycles, budget is {max_cycles}",
+        shadowed_snippet.inner().borrow().entrypoint(),
+        tasm.clock_cycle_count,
+    );
+
+    tasm
+}
+
+/// Link the snippet and run it in the VM. If `sponge_state` is set, the VM starts with
+/// that sponge state instead of the default one.
 pub fn link_and_run_tasm_for_test<T: RustShadow>(
     snippet_struct: &T,
     stack: &mut Vec<BFieldElement>,
     std_in: Vec<BFieldElement>,
     nondeterminism: &mut NonDeterminism<BFieldElement>,
     memory: &mut HashMap<BFieldElement, BFieldElement>,
+    sponge_state: Option<&VmHasherState>,
     words_statically_allocated: usize,
 ) -> VmOutputState {
     let words_statically_allocated = if let Some(allocator) = memory.get(&BFieldElement::zero()) {
@@ -567,7 +1149,7 @@ pub fn link_and_run_tasm_for_test<T: RustShadow>(
 
     let code = link_for_isolated_run(snippet_struct, words_statically_allocated);
 
-    execute_test(
+    execute_test_with_sponge_state(
         &code,
         stack,
         snippet_struct.inner().borrow().stack_diff(),
@@ -575,6 +1157,8 @@ pub fn link_and_run_tasm_for_test<T: RustShadow>(
         nondeterminism,
         memory,
         Some(words_statically_allocated),
+        None,
+        sponge_state,
     )
 }
 
@@ -618,3 +1202,206 @@ pub fn test_rust_equivalence_given_execution_state<T: BasicSnippet + RustShadow>
         None,
     )
 }
+
+/// Run the snippet twice on the same initial state and assert that final stack, memory,
+/// and standard output agree. Catches accidental reliance on, e.g., uninitialized memory.
+pub fn assert_deterministic<T: RustShadow>(snippet: &T, state: ExecutionState) {
+    let sponge_state = VmHasherState::new(Domain::VariableLength);
+    let run = || {
+        tasm_final_state(
+            snippet,
+            &state.stack,
+            &state.std_in,
+            &state.nondeterminism,
+            &state.memory,
+            &sponge_state,
+            state.words_allocated,
+        )
+    };
+    let first = run();
+    let second = run();
+
+    assert_eq!(
+        first.output, second.output,
+        "Two runs on the same input must produce the same standard output"
+    );
+    verify_stack_equivalence(&first.final_stack, &second.final_stack);
+    verify_memory_equivalence(&first.final_ram, &second.final_ram);
+}
+
+/// Run the snippet on the given stack and standard input, both in Rust and in the VM,
+/// and assert that both write the same standard output and leave the same stack. If
+/// given, the standard output must also match `expected_output`. Unlike
+/// [`test_rust_equivalence_given_complete_state`], a mismatch in standard output is
+/// reported with both outputs in full.
+pub fn test_io_equivalence<T: RustShadow>(
+    shadowed_snippet: &T,
+    stack: &[BFieldElement],
+    stdin: &[BFieldElement],
+    expected_output: Option<&[BFieldElement]>,
+) -> VmOutputState {
+    let nondeterminism = NonDeterminism::new(vec![]);
+    let memory = HashMap::default();
+    let sponge_state = VmHasherState::new(Domain::VariableLength);
+    let rust = rust_final_state(
+        shadowed_snippet,
+        stack,
+        stdin,
+        &nondeterminism,
+        &memory,
+        &sponge_state,
+        0,
+    );
+    let tasm = tasm_final_state(
+        shadowed_snippet,
+        stack,
+        stdin,
+        &nondeterminism,
+        &memory,
+        &sponge_state,
+        0,
+    );
+
+    let entrypoint = shadowed_snippet.inner().borrow().entrypoint();
+    assert_eq!(
+        rust.output,
+        tasm.output,
+        "Rust shadowing and VM must write the same standard output for snippet {entrypoint}.\n\n\
+        Rust: [{}]\n\nVM: [{}]",
+        rust.output.iter().join(","),
+        tasm.output.iter().join(","),
+    );
+    if let Some(expected) = expected_output {
+        assert_eq!(
+            expected,
+            tasm.output,
+            "Standard output of snippet {entrypoint} must match expected output.\n\n\
+            Expected: [{}]\n\nVM: [{}]",
+            expected.iter().join(","),
+            tasm.output.iter().join(","),
+        );
+    }
+    verify_stack_equivalence(&rust.final_stack, &tasm.final_stack);
+
+    tasm
+}
+
+/// Assert that a snippet, including everything it imports, consists of at most
+/// `max_instruction_count` instructions. Catches snippets that unexpectedly grow, e.g.,
+/// through an accidental new dependency.
+pub fn assert_program_size_at_most<T: BasicSnippet>(snippet: &T, max_instruction_count: usize) {
+    let mut library = Library::new();
+    let body = snippet.code(&mut library);
+    let body_count = instruction_count(&body);
+    let imports_count = library.assembled_instruction_count();
+    let total_count = body_count + imports_count;
+    println!(
+        "{}: {body_count} instructions in body, {imports_count} instructions in imports",
+        snippet.entrypoint()
+    );
+
+    assert!(
+        total_count <= max_instruction_count,
+        "Snippet {} consists of {total_count} instructions ({body_count} in body, \
+        {imports_count} in imports), exceeding the maximum of {max_instruction_count}.",
+        snippet.entrypoint()
+    );
+}
+
+/// Render the body of a snippet into a string that only changes when its instructions
+/// or labels change: comments, indentation, and empty lines are removed. Imported
+/// snippets are not included.
+fn render_tasm_for_snapshot<T: DeprecatedSnippet>(snippet: &T) -> String {
+    snippet
+        .function_code(&mut Library::new())
+        .lines()
+        .map(|line| match line.find("//") {
+            Some(comment_start) => &line[..comment_start],
+            None => line,
+        })
+        .map(|line| line.split_whitespace().join(" "))
+        .filter(|line| !line.is_empty())
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+fn assert_rendered_tasm_matches(rendered: &str, snapshot: &str, snapshot_name: &str) {
+    if snapshot == rendered {
+        return;
+    }
+
+    let snapshot_lines = snapshot.lines().collect_vec();
+    let rendered_lines = rendered.lines().collect_vec();
+    let first_difference = (0..snapshot_lines.len().max(rendered_lines.len()))
+        .find(|&i| snapshot_lines.get(i) != rendered_lines.get(i))
+        .unwrap();
+    panic!(
+        "TASM must match snapshot {snapshot_name}. First difference in line {}:\n\
+        snapshot: {}\n\
+        code:     {}\n\
+        Run with UPDATE_TASM_SNAPSHOTS=1 if the change is intended.",
+        first_difference + 1,
+        snapshot_lines
+            .get(first_difference)
+            .unwrap_or(&"<end of file>"),
+        rendered_lines
+            .get(first_difference)
+            .unwrap_or(&"<end of file>"),
+    );
+}
+
+/// Assert that the body of a snippet matches the committed golden file at `path`,
+/// relative to the crate root. Comments and formatting are ignored. Set the environment
+/// variable `UPDATE_TASM_SNAPSHOTS` to (re)generate the file instead.
+pub fn assert_tasm_snapshot<T: DeprecatedSnippet>(snippet: &T, path: &str) {
+    let rendered = render_tasm_for_snapshot(snippet);
+    let path = Path::new(path);
+    if std::env::var("UPDATE_TASM_SNAPSHOTS").is_ok() {
+        if let Some(directory) = path.parent() {
+            create_dir_all(directory).expect("snapshot directory should exist");
+        }
+        fs::write(path, rendered).expect("write snapshot to file");
+        return;
+    }
+
+    let snapshot = fs::read_to_string(path).unwrap_or_else(|_| {
+        panic!(
+            "Snapshot {} must exist. Run with UPDATE_TASM_SNAPSHOTS=1 to create it.",
+            path.display()
+        )
+    });
+    assert_rendered_tasm_matches(&rendered, &snapshot, &path.display().to_string());
+}
+
+/// Assert that every index in the list of `u32`s at `list_pointer` is smaller than
+/// `upper_bound` and, if `expect_unique` is set, that no index occurs twice. Catches
+/// bound errors that equivalence testing misses when the Rust shadowing and the TASM
+/// code share the same mistake.
+pub fn verify_sampled_indices(
+    list_pointer: BFieldElement,
+    memory: &HashMap<BFieldElement, BFieldElement>,
+    list_type: &ListType,
+    upper_bound: u32,
+    expect_unique: bool,
+) {
+    let indices =
+        rust_shadowing_helper_functions::list_to_vec::<1>(list_pointer, memory, list_type)
+            .into_iter()
+            .map(|[index]| index.value())
+            .collect_vec();
+
+    for index in indices.iter() {
+        assert!(
+            *index < upper_bound as u64,
+            "Sampled index {index} must be smaller than upper bound {upper_bound}"
+        );
+    }
+
+    if expect_unique {
+        assert!(
+            indices.iter().all_unique(),
+            "Sampled indices must be unique. Got: {}",
+            indices.iter().join(",")
+        );
+    }
+}